@@ -176,6 +176,10 @@ impl Scraper {
         crate::tweets::retweet(&self.twitter_client, tweet_id).await
     }
 
+    pub async fn delete_tweet(&self, tweet_id: &str) -> Result<Value> {
+        crate::tweets::delete_tweet(&self.twitter_client, tweet_id).await
+    }
+
     pub async fn create_long_tweet(
         &self,
         text: &str,
@@ -234,6 +234,27 @@ pub async fn retweet(client: &TwitterClient, tweet_id: &str) -> Result<Value> {
     Ok(value)
 }
 
+pub async fn delete_tweet(client: &TwitterClient, tweet_id: &str) -> Result<Value> {
+    let mut headers = HeaderMap::new();
+    client.auth.install_headers(&mut headers).await?;
+
+    let (value, _headers) = request_api(
+        &client.client,
+        "https://twitter.com/i/api/graphql/VaenaVgh5q5ih7kvyVjgtg/DeleteTweet",
+        headers,
+        Method::POST,
+        Some(json!({
+            "variables": {
+                "tweet_id": tweet_id,
+                "dark_request": false
+            }
+        })),
+    )
+    .await?;
+
+    Ok(value)
+}
+
 pub async fn create_long_tweet(
     client: &TwitterClient,
     text: &str,
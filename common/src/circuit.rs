@@ -0,0 +1,249 @@
+//! Per-service circuit breakers. After enough consecutive failures a service's
+//! breaker opens and calls fail fast until a cooldown passes; then a single probe
+//! is let through; it closes the breaker on success and reopens it for twice
+//! as long on failure.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::warn;
+
+// Consecutive failures that open a breaker
+pub const FAILURE_THRESHOLD_ENV: &str = "CIRCUIT_FAILURE_THRESHOLD";
+// Seconds a breaker stays open the first time
+pub const COOLDOWN_ENV: &str = "CIRCUIT_COOLDOWN_SECS";
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+const MAX_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Error)]
+#[error("{service} service unavailable (circuit open, next attempt in {}s)", .retry_in.as_secs().max(1))]
+pub struct CircuitOpen {
+    pub service: String,
+    pub retry_in: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerConfig {
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_COOLDOWN,
+        }
+    }
+}
+
+impl BreakerConfig {
+    /// Defaults overridden by `CIRCUIT_FAILURE_THRESHOLD` and `CIRCUIT_COOLDOWN_SECS`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(threshold) = env_number(FAILURE_THRESHOLD_ENV).filter(|n| *n > 0) {
+            config.failure_threshold = threshold as u32;
+        }
+        if let Some(secs) = env_number(COOLDOWN_ENV) {
+            config.cooldown = Duration::from_secs(secs);
+        }
+        config
+    }
+}
+
+fn env_number(name: &str) -> Option<u64> {
+    let value = std::env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            warn!(name, value = %value, "Ignoring invalid circuit breaker setting");
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant, cooldown: Duration },
+    // The one trial call is in flight since `probe`. A probe whose future was dropped
+    // never reports back, so another is let through once `cooldown` has passed.
+    HalfOpen { probe: Instant, cooldown: Duration },
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    service: String,
+    config: BreakerConfig,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub fn new(service: &str, config: BreakerConfig) -> Self {
+        Self {
+            service: service.to_string(),
+            config,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+
+    /// Call before each request: an error means fail fast without calling
+    pub fn check(&self) -> Result<(), CircuitOpen> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until, cooldown } => {
+                let now = Instant::now();
+                if now < until {
+                    return Err(self.open_error(until - now));
+                }
+                *state = State::HalfOpen { probe: now, cooldown };
+                Ok(())
+            }
+            State::HalfOpen { probe, cooldown } => {
+                let now = Instant::now();
+                let abandoned_at = probe + cooldown;
+                if now < abandoned_at {
+                    return Err(self.open_error(abandoned_at - now));
+                }
+                warn!(service = %self.service, "Circuit probe never reported back, letting another through");
+                *state = State::HalfOpen { probe: now, cooldown };
+                Ok(())
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(*state, State::Closed { .. }) {
+            warn!(service = %self.service, "Circuit closed, service recovered");
+        }
+        *state = State::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let reopen = match *state {
+            State::Closed { failures } if failures + 1 >= self.config.failure_threshold => {
+                Some(self.config.cooldown)
+            }
+            State::Closed { failures } => {
+                *state = State::Closed { failures: failures + 1 };
+                None
+            }
+            State::HalfOpen { cooldown, .. } => Some((cooldown * 2).min(MAX_COOLDOWN).max(self.config.cooldown)),
+            // A call that started before the breaker opened
+            State::Open { .. } => None,
+        };
+
+        if let Some(cooldown) = reopen {
+            warn!(service = %self.service, cooldown_secs = cooldown.as_secs(), "Circuit opened after repeated failures");
+            *state = State::Open {
+                until: Instant::now() + cooldown,
+                cooldown,
+            };
+        }
+    }
+
+    fn open_error(&self, retry_in: Duration) -> CircuitOpen {
+        CircuitOpen {
+            service: self.service.clone(),
+            retry_in,
+        }
+    }
+}
+
+/// The shared breaker for `service`, created from the environment on first use
+pub fn breaker(service: &str) -> Arc<CircuitBreaker> {
+    static BREAKERS: OnceLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> = OnceLock::new();
+    let mut breakers = BREAKERS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    breakers
+        .entry(service.to_string())
+        .or_insert_with(|| Arc::new(CircuitBreaker::new(service, BreakerConfig::from_env())))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test",
+            BreakerConfig {
+                failure_threshold: 2,
+                cooldown,
+            },
+        )
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = breaker(Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+
+        breaker.record_failure();
+        let err = breaker.check().unwrap_err();
+        assert!(err.to_string().contains("circuit open"));
+    }
+
+    #[test]
+    fn half_open_success_closes() {
+        let cooldown = Duration::from_millis(50);
+        let breaker = breaker(cooldown);
+        breaker.record_failure();
+        breaker.record_failure();
+
+        // Cooldown over: one probe goes through, concurrent calls still fail fast
+        std::thread::sleep(cooldown);
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn half_open_failure_doubles_the_cooldown() {
+        let cooldown = Duration::from_millis(10);
+        let breaker = breaker(cooldown);
+        breaker.record_failure();
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(cooldown * 2);
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(matches!(
+            *breaker.state.lock().unwrap(),
+            State::Open { cooldown: reopened, .. } if reopened == cooldown * 2
+        ));
+    }
+
+    #[test]
+    fn abandoned_probe_is_replaced_after_the_cooldown() {
+        let cooldown = Duration::from_millis(10);
+        let breaker = breaker(cooldown);
+        breaker.record_failure();
+        breaker.record_failure();
+
+        std::thread::sleep(cooldown * 2);
+        // The probe's future is dropped without recording anything
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(cooldown * 2);
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+    }
+}
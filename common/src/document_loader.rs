@@ -17,9 +17,20 @@ pub enum DocumentType {
     MD,
 }
 
+/// Extensions `DocumentLoader::load` can read
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["pdf", "txt", "md", "markdown", "html", "htm"];
+
 pub struct DocumentLoader;
 
 impl DocumentLoader {
+    pub fn is_supported<P: AsRef<Path>>(path: P) -> bool {
+        path.as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
         let extension = path.as_ref()
             .extension()
@@ -29,7 +40,8 @@ impl DocumentLoader {
 
         match extension.as_str() {
             "pdf" => Self::load_pdf(path),
-            "txt" => Self::load_txt(path),
+            "txt" | "md" | "markdown" => Self::load_txt(path),
+            "html" | "htm" => Self::load_html(path),
             _ => Err(anyhow::anyhow!("Unsupported file type: {}", extension))
         }
     }
@@ -48,4 +60,26 @@ impl DocumentLoader {
         File::open(path)?.read_to_string(&mut content)?;
         Ok(vec![content])
     }
+
+    // Visible text only, without scripts and styles
+    fn load_html<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+        let content = std::fs::read_to_string(path)?;
+        let document = scraper::Html::parse_document(&content);
+
+        let mut text = String::new();
+        for node in document.root_element().descendants() {
+            let Some(fragment) = node.value().as_text() else {
+                continue;
+            };
+            let hidden = node
+                .parent()
+                .and_then(|parent| parent.value().as_element().map(|e| e.name()))
+                .map_or(false, |name| matches!(name, "script" | "style" | "noscript"));
+            if !hidden && !fragment.trim().is_empty() {
+                text.push_str(fragment.trim());
+                text.push('\n');
+            }
+        }
+        Ok(vec![text])
+    }
 }
\ No newline at end of file
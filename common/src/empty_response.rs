@@ -0,0 +1,149 @@
+//! Providers occasionally answer with nothing, or with nothing but whitespace.
+//! Such a completion is treated as a failed call: it is retried once, then
+//! reported as an error, so blank text never reaches a user, a cache or a tweet.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use tracing::warn;
+
+/// Extra attempts made when a completion comes back empty
+pub const EMPTY_RESPONSE_RETRIES: usize = 1;
+
+/// What counts as an empty response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmptyResponsePolicy {
+    /// Only empty or whitespace-only text; "..." or a lone emoji is an answer
+    #[default]
+    Whitespace,
+    /// Also text without a single letter or digit, such as "..." or "🚀🚀"
+    Punctuation,
+}
+
+impl EmptyResponsePolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EmptyResponsePolicy::Whitespace => "whitespace",
+            EmptyResponsePolicy::Punctuation => "punctuation",
+        }
+    }
+
+    /// The policy named by EMPTY_RESPONSE_POLICY, the default when unset or invalid
+    pub fn from_env() -> Self {
+        match std::env::var("EMPTY_RESPONSE_POLICY") {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                warn!("{}, using {}", e, Self::default());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn is_empty(self, text: &str) -> bool {
+        match self {
+            EmptyResponsePolicy::Whitespace => text.trim().is_empty(),
+            EmptyResponsePolicy::Punctuation => !text.chars().any(char::is_alphanumeric),
+        }
+    }
+}
+
+impl fmt::Display for EmptyResponsePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmptyResponsePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "whitespace" => Ok(EmptyResponsePolicy::Whitespace),
+            "punctuation" => Ok(EmptyResponsePolicy::Punctuation),
+            other => Err(format!(
+                "Unknown empty response policy '{}', expected whitespace or punctuation",
+                other
+            )),
+        }
+    }
+}
+
+/// Why no usable response came back
+#[derive(Debug)]
+pub enum ResponseError<E> {
+    /// The call itself failed
+    Call(E),
+    /// Every attempt returned an empty response
+    Empty { attempts: usize },
+}
+
+impl<E: fmt::Display> fmt::Display for ResponseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseError::Call(e) => e.fmt(f),
+            ResponseError::Empty { attempts } => {
+                write!(f, "Model returned an empty response ({} attempts)", attempts)
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ResponseError<E> {}
+
+/// Run `attempt` until it returns a response that isn't empty under `policy`,
+/// retrying up to `EMPTY_RESPONSE_RETRIES` times. A failed call is returned as is.
+pub async fn retry_if_empty<F, Fut, E>(policy: EmptyResponsePolicy, mut attempt: F) -> Result<String, ResponseError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+{
+    let attempts = 1 + EMPTY_RESPONSE_RETRIES;
+    for n in 1..=attempts {
+        let response = attempt().await.map_err(ResponseError::Call)?;
+        if !policy.is_empty(&response) {
+            return Ok(response);
+        }
+        warn!(attempt = n, policy = %policy, "Model returned an empty response");
+    }
+    Err(ResponseError::Empty { attempts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_differ_on_symbols() {
+        for text in ["", "  \n\t"] {
+            assert!(EmptyResponsePolicy::Whitespace.is_empty(text));
+            assert!(EmptyResponsePolicy::Punctuation.is_empty(text));
+        }
+        for text in ["...", "🚀🔥", " ?! "] {
+            assert!(!EmptyResponsePolicy::Whitespace.is_empty(text));
+            assert!(EmptyResponsePolicy::Punctuation.is_empty(text));
+        }
+        assert!(!EmptyResponsePolicy::Punctuation.is_empty("gm 🚀"));
+    }
+
+    #[tokio::test]
+    async fn retries_once_then_fails() {
+        let mut replies = [" ", "ok"].into_iter();
+        let response = retry_if_empty(EmptyResponsePolicy::Whitespace, || {
+            let reply = replies.next().unwrap_or_default().to_string();
+            async move { Ok::<_, String>(reply) }
+        })
+        .await;
+        assert_eq!(response.unwrap(), "ok");
+
+        let mut calls = 0;
+        let response = retry_if_empty(EmptyResponsePolicy::Whitespace, || {
+            calls += 1;
+            async { Ok::<_, String>(String::new()) }
+        })
+        .await;
+        assert!(matches!(response, Err(ResponseError::Empty { attempts: 2 })));
+        assert_eq!(calls, 2);
+    }
+}
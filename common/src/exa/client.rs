@@ -1,8 +1,13 @@
 use reqwest::Client;
 use chrono::{Utc, Duration};
 use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
 
-use super::types::{ExaSearchResult, ExaSearchParams, ExaResponse, Contents, Highlights, Summary};
+use crate::circuit::{self, CircuitBreaker};
+use crate::news::{NewsEvent, NewsQuery, NewsSource};
+
+use super::types::{ExaSearchResult, ExaSearchParams, ExaResponse, Contents};
 use super::error::ExaError;
 
 const EXA_API_URL: &str = "https://api.exa.ai/search";
@@ -10,6 +15,9 @@ const EXA_API_URL: &str = "https://api.exa.ai/search";
 pub struct ExaClient {
     client: Client,
     api_key: String,
+    breaker: Arc<CircuitBreaker>,
+    // Used by searches that don't set their own contents
+    contents: Contents,
 }
 
 impl ExaClient {
@@ -17,40 +25,53 @@ impl ExaClient {
         Self {
             client: Client::new(),
             api_key: api_key.to_string(),
+            breaker: circuit::breaker("Exa"),
+            contents: Contents::default(),
         }
     }
 
+    /// Summary length and highlight count for searches that don't set their own,
+    /// e.g. `Contents::with_limits(3, 0)` for terse summaries without highlights
+    pub fn with_contents(mut self, contents: Contents) -> Self {
+        self.contents = contents;
+        self
+    }
+
     pub async fn search_crypto(&self, mut params: ExaSearchParams) -> Result<Vec<ExaSearchResult>> {
         // Add content parameters if not already set
         if params.contents.is_none() {
-            params.contents = Some(Contents {
-                text: true,
-                highlights: Some(Highlights {
-                    num_sentences: 3,
-                    highlights_per_result: 3,
-                }),
-                summary: Some(Summary {
-                    max_sentences: 5,
-                }),
-            });
+            params.contents = Some(self.contents.clone());
         }
 
+        self.breaker.check()?;
         let response = self.client
             .post(EXA_API_URL)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .json(&params)
             .send()
-            .await?;
+            .await
+            .map_err(|e| {
+                self.breaker.record_failure();
+                e
+            })?;
 
         match response.status() {
             reqwest::StatusCode::OK => {
+                self.breaker.record_success();
                 let exa_response: ExaResponse = response.json().await?;
                 Ok(exa_response.results)
             },
             reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                self.breaker.record_failure();
                 Err(ExaError::RateLimit.into())
             },
-            _ => {
+            status => {
+                // A client error means the service is up and answering
+                if status.is_server_error() {
+                    self.breaker.record_failure();
+                } else {
+                    self.breaker.record_success();
+                }
                 let error_text = response.text().await?;
                 Err(ExaError::ApiError(error_text).into())
             }
@@ -69,16 +90,7 @@ impl ExaClient {
             ],
             start_date: Some(Utc::now() - Duration::days(7)),
             end_date: None,
-            contents: Some(Contents {
-                text: true,
-                highlights: Some(Highlights {
-                    num_sentences: 3,
-                    highlights_per_result: 3,
-                }),
-                summary: Some(Summary {
-                    max_sentences: 5,
-                }),
-            }),
+            contents: Some(self.contents.clone()),
         };
         self.search_crypto(params).await
     }
@@ -94,17 +106,38 @@ impl ExaClient {
             ],
             start_date: Some(Utc::now() - Duration::days(2)),
             end_date: None,
-            contents: Some(Contents {
-                text: true,
-                highlights: Some(Highlights {
-                    num_sentences: 3,
-                    highlights_per_result: 3,
-                }),
-                summary: Some(Summary {
-                    max_sentences: 5,
-                }),
-            }),
+            contents: Some(self.contents.clone()),
         };
         self.search_crypto(params).await
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl NewsSource for ExaClient {
+    fn name(&self) -> &str {
+        "exa"
+    }
+
+    async fn fetch_news(&self, query: &NewsQuery) -> Result<Vec<NewsEvent>> {
+        let params = ExaSearchParams {
+            query: query.query.clone(),
+            num_results: query.limit as i32,
+            include_domains: query.domains.clone(),
+            start_date: Some(query.start),
+            end_date: query.end,
+            contents: None,
+        };
+        let results = self.search_crypto(params).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| NewsEvent {
+                title: result.title,
+                url: result.url,
+                published_at: result.published_date,
+                summary: result.summary,
+                relevance_score: result.relevance_score,
+                source: self.name().to_string(),
+            })
+            .collect())
+    }
+}
@@ -3,5 +3,8 @@ mod types;
 mod client;
 
 pub use error::ExaError;
-pub use types::{ExaSearchResult, ExaSearchParams, ExaResponse, Contents, Highlights, Summary};
+pub use types::{
+    ExaSearchResult, ExaSearchParams, ExaResponse, Contents, Highlights, Summary, DEFAULT_HIGHLIGHTS_PER_RESULT,
+    DEFAULT_SUMMARY_SENTENCES, MAX_HIGHLIGHTS_PER_RESULT, MAX_NUM_RESULTS, MAX_SUMMARY_SENTENCES,
+};
 pub use client::ExaClient;
\ No newline at end of file
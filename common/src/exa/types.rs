@@ -25,6 +25,8 @@ pub struct ExaSearchResult {
 pub struct ExaSearchParams {
     pub query: String,
     pub num_results: i32,
+    // Empty means no domain restriction
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub include_domains: Vec<String>,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
@@ -32,6 +34,17 @@ pub struct ExaSearchParams {
     pub contents: Option<Contents>,
 }
 
+/// Summary length used when nothing else is configured
+pub const DEFAULT_SUMMARY_SENTENCES: u32 = 5;
+pub const DEFAULT_HIGHLIGHTS_PER_RESULT: u32 = 3;
+// Larger requests are clamped to these
+pub const MAX_SUMMARY_SENTENCES: u32 = 10;
+pub const MAX_HIGHLIGHTS_PER_RESULT: u32 = 10;
+// Exa rejects searches asking for more results than this
+pub const MAX_NUM_RESULTS: u32 = 100;
+// Sentences in each highlight
+const HIGHLIGHT_SENTENCES: i32 = 3;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Contents {
     pub text: bool,
@@ -41,6 +54,40 @@ pub struct Contents {
     pub summary: Option<Summary>,
 }
 
+impl Default for Contents {
+    fn default() -> Self {
+        Self::with_limits(DEFAULT_SUMMARY_SENTENCES, DEFAULT_HIGHLIGHTS_PER_RESULT)
+    }
+}
+
+impl Contents {
+    /// Full text plus a summary and highlights of the given size. 0 leaves the field
+    /// out of the request entirely; values above the maximums are clamped.
+    pub fn with_limits(summary_sentences: u32, highlights_per_result: u32) -> Self {
+        Self {
+            text: true,
+            highlights: (highlights_per_result > 0).then(|| Highlights {
+                num_sentences: HIGHLIGHT_SENTENCES,
+                highlights_per_result: clamp_limit(
+                    "highlights_per_result",
+                    highlights_per_result,
+                    MAX_HIGHLIGHTS_PER_RESULT,
+                ),
+            }),
+            summary: (summary_sentences > 0).then(|| Summary {
+                max_sentences: clamp_limit("summary_sentences", summary_sentences, MAX_SUMMARY_SENTENCES),
+            }),
+        }
+    }
+}
+
+fn clamp_limit(name: &str, value: u32, max: u32) -> i32 {
+    if value > max {
+        tracing::warn!(name, value, max, "Exa content limit too large, clamping");
+    }
+    value.min(max) as i32
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Highlights {
     pub num_sentences: i32,
@@ -0,0 +1,183 @@
+//! Process exit codes shared by the binaries, so scripts can tell a bad config
+//! from a failed login or a network outage.
+
+use std::error::Error as StdError;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitKind {
+    Failure,
+    Config,
+    Auth,
+    Network,
+    Storage,
+}
+
+impl ExitKind {
+    pub fn code(self) -> i32 {
+        match self {
+            ExitKind::Failure => 1,
+            ExitKind::Config => 2,
+            ExitKind::Auth => 3,
+            ExitKind::Network => 4,
+            ExitKind::Storage => 5,
+        }
+    }
+
+    fn hint(self) -> Option<&'static str> {
+        match self {
+            ExitKind::Failure => None,
+            ExitKind::Config => Some("Check your .env file, command line flags and config files"),
+            ExitKind::Auth => Some("Check that your API keys and account credentials are valid"),
+            ExitKind::Network => Some("Check your connection and retry; the service may be down"),
+            ExitKind::Storage => Some("Check the database file and its permissions"),
+        }
+    }
+
+    /// Classify by the first error in the chain that says what went wrong
+    pub fn of(err: &(dyn StdError + 'static)) -> Self {
+        let mut current = Some(err);
+        while let Some(err) = current {
+            if let Some(kind) = classify(err) {
+                return kind;
+            }
+            current = err.source();
+        }
+        ExitKind::Failure
+    }
+}
+
+/// Errors the binaries raise themselves when they know the cause
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("{0}")]
+    Config(String),
+    /// A file the binary could not read, keeping the io error so a missing
+    /// file is reported as missing
+    #[error("{context}")]
+    File {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{0}")]
+    Auth(String),
+    #[error("{0}")]
+    Network(String),
+}
+
+impl AppError {
+    /// Wrap a failed read of `what` at `path`
+    pub fn file(what: &str, path: &str, source: std::io::Error) -> Self {
+        let context = if source.kind() == std::io::ErrorKind::NotFound {
+            format!("No {} at {}", what, path)
+        } else {
+            format!("Failed to read {} {}", what, path)
+        };
+        AppError::File { context, source }
+    }
+
+    pub fn kind(&self) -> ExitKind {
+        match self {
+            AppError::Config(_) | AppError::File { .. } => ExitKind::Config,
+            AppError::Auth(_) => ExitKind::Auth,
+            AppError::Network(_) => ExitKind::Network,
+        }
+    }
+}
+
+fn classify(err: &(dyn StdError + 'static)) -> Option<ExitKind> {
+    if let Some(err) = err.downcast_ref::<AppError>() {
+        return Some(err.kind());
+    }
+    if err.is::<std::env::VarError>() {
+        return Some(ExitKind::Config);
+    }
+    if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        let status = err.status().map(|s| s.as_u16());
+        if matches!(status, Some(401) | Some(403)) {
+            return Some(ExitKind::Auth);
+        }
+        if err.is_connect() || err.is_timeout() || err.is_request() {
+            return Some(ExitKind::Network);
+        }
+    }
+    if let Some(err) = err.downcast_ref::<std::io::Error>() {
+        use std::io::ErrorKind;
+        return match err.kind() {
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::TimedOut => Some(ExitKind::Network),
+            ErrorKind::NotFound | ErrorKind::PermissionDenied => Some(ExitKind::Config),
+            _ => None,
+        };
+    }
+    if err.is::<crate::circuit::CircuitOpen>() {
+        return Some(ExitKind::Network);
+    }
+    if err.is::<tokio_rusqlite::Error>() {
+        return Some(ExitKind::Storage);
+    }
+    None
+}
+
+/// Print the error, its root cause and a hint, then exit with the matching code
+pub fn exit_with_error(err: &(dyn StdError + 'static)) -> ! {
+    let kind = ExitKind::of(err);
+    eprintln!("❌ {}", err);
+
+    let mut root = err.source();
+    while let Some(next) = root.and_then(|e| e.source()) {
+        root = Some(next);
+    }
+    if let Some(root) = root {
+        eprintln!("   caused by: {}", root);
+    }
+    if let Some(hint) = kind.hint() {
+        eprintln!("💡 {}", hint);
+    }
+    std::process::exit(kind.code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::io;
+    use std::time::Duration;
+
+    fn kind_of(err: anyhow::Error) -> ExitKind {
+        ExitKind::of(err.as_ref())
+    }
+
+    #[test]
+    fn app_errors_keep_their_kind_through_context() {
+        let err = Err::<(), _>(AppError::Auth("bad token".into())).context("Login failed").unwrap_err();
+        assert_eq!(kind_of(err), ExitKind::Auth);
+        assert_eq!(kind_of(AppError::Config("no key".into()).into()), ExitKind::Config);
+        assert_eq!(kind_of(AppError::Network("down".into()).into()), ExitKind::Network);
+        let missing = AppError::file("config", "zoey.toml", io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(kind_of(missing.into()), ExitKind::Config);
+    }
+
+    #[test]
+    fn the_first_known_cause_decides() {
+        let refused = Err::<(), _>(io::Error::from(io::ErrorKind::ConnectionRefused))
+            .context("Fetching prices")
+            .unwrap_err();
+        assert_eq!(kind_of(refused), ExitKind::Network);
+        assert_eq!(kind_of(io::Error::from(io::ErrorKind::PermissionDenied).into()), ExitKind::Config);
+        assert_eq!(kind_of(std::env::VarError::NotPresent.into()), ExitKind::Config);
+        let open = crate::circuit::CircuitOpen { service: "coingecko".into(), retry_in: Duration::from_secs(30) };
+        assert_eq!(kind_of(open.into()), ExitKind::Network);
+    }
+
+    #[test]
+    fn unknown_errors_are_plain_failures() {
+        assert_eq!(kind_of(anyhow::anyhow!("something broke")), ExitKind::Failure);
+        assert_eq!(kind_of(io::Error::other("disk on fire").into()), ExitKind::Failure);
+        assert_eq!(ExitKind::Failure.code(), 1);
+    }
+}
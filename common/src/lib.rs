@@ -5,6 +5,18 @@ pub mod document_loader;
 pub mod providers;
 pub mod storage;
 pub mod exa;
+pub mod news;
+pub mod telemetry;
+pub mod paths;
+pub mod exit;
+pub mod ta;
+pub mod prompt_guard;
+pub mod empty_response;
+pub mod prompt_retry;
+pub mod progress;
+pub mod circuit;
+pub mod rate_limit;
+pub mod near_dup;
 
 pub use error::AgentError;
 pub use document_loader::DocumentLoader;  // Re-export DocumentLoader
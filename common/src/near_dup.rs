@@ -0,0 +1,99 @@
+//! Near-duplicate detection for text chunks, with MinHash signatures over word
+//! shingles. A signature estimates the Jaccard similarity of two chunks' sets of
+//! five-word runs, so a batch is checked without comparing texts word by word.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Conservative default: only chunks sharing nearly all their wording. Two products
+/// from the same template differ in name and price, which keeps them well below it.
+pub const DEFAULT_THRESHOLD: f64 = 0.9;
+
+const SHINGLE_WORDS: usize = 5;
+const SIGNATURE_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature([u64; SIGNATURE_LEN]);
+
+impl Signature {
+    pub fn new(text: &str) -> Self {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        let mut signature = [u64::MAX; SIGNATURE_LEN];
+        // Text shorter than a shingle is one shingle
+        for shingle in words.windows(SHINGLE_WORDS.min(words.len()).max(1)) {
+            let mut hasher = DefaultHasher::new();
+            shingle.hash(&mut hasher);
+            let base = hasher.finish();
+            for (i, min) in signature.iter_mut().enumerate() {
+                *min = (*min).min(mix(base ^ (i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)));
+            }
+        }
+        Self(signature)
+    }
+
+    /// Estimated Jaccard similarity of the two texts' shingles, 0 to 1
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let same = self.0.iter().zip(other.0.iter()).filter(|(a, b)| a == b).count();
+        same as f64 / SIGNATURE_LEN as f64
+    }
+}
+
+// splitmix64 finalizer, one independent-looking hash per signature slot
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Which texts to keep, in order: a text is dropped when it is at least `threshold`
+/// similar to a text kept before it. A threshold above 1 keeps everything.
+pub fn keep_distinct<'a>(texts: impl IntoIterator<Item = &'a str>, threshold: f64) -> Vec<bool> {
+    let mut kept: Vec<Signature> = Vec::new();
+    texts
+        .into_iter()
+        .map(|text| {
+            let signature = Signature::new(text);
+            if kept.iter().any(|seen| seen.similarity(&signature) >= threshold) {
+                false
+            } else {
+                kept.push(signature);
+                true
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAV: &str = "Home Shop Men Women Kids Sale New arrivals Gift cards Track your order Help centre \
+        Returns and refunds Contact us Sign in Create account Wishlist Basket";
+
+    #[test]
+    fn repeated_boilerplate_is_dropped() {
+        let with_spacing = NAV.replace(' ', "  ").to_uppercase();
+        let review = "A long review of trail running shoes and how they wear.";
+        assert_eq!(keep_distinct([NAV, review, &with_spacing], DEFAULT_THRESHOLD), [true, true, false]);
+    }
+
+    #[test]
+    fn templated_products_are_kept() {
+        let a = "PRODUCT Name: Merino crew sweater Price: $89.00 Description: Soft merino wool, \
+            ribbed cuffs and hem, machine washable on a wool cycle.";
+        let b = "PRODUCT Name: Cotton crew sweater Price: $59.00 Description: Soft organic cotton, \
+            ribbed cuffs and hem, machine washable on a warm cycle.";
+        assert_eq!(keep_distinct([a, b], DEFAULT_THRESHOLD), [true, true]);
+        assert!(Signature::new(a).similarity(&Signature::new(b)) < DEFAULT_THRESHOLD);
+    }
+
+    #[test]
+    fn threshold_above_one_keeps_everything() {
+        assert_eq!(keep_distinct([NAV, NAV], 1.1), [true, true]);
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::circuit::{self, CircuitBreaker};
+
+use super::{NewsEvent, NewsQuery, NewsSource};
+
+const CRYPTOPANIC_API_URL: &str = "https://cryptopanic.com/api/v1/posts/";
+
+#[derive(Debug, Deserialize)]
+struct PostsResponse {
+    results: Vec<Post>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Post {
+    title: String,
+    url: String,
+    published_at: Option<DateTime<Utc>>,
+}
+
+/// CryptoPanic news aggregator. It has no free-text search, so the query text and
+/// domains are ignored; `currencies` (e.g. BTC, ETH) narrows the feed instead.
+pub struct CryptoPanicClient {
+    client: Client,
+    api_key: String,
+    currencies: Vec<String>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl CryptoPanicClient {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.to_string(),
+            currencies: Vec::new(),
+            breaker: circuit::breaker("CryptoPanic"),
+        }
+    }
+
+    pub fn with_currencies(mut self, currencies: Vec<String>) -> Self {
+        self.currencies = currencies;
+        self
+    }
+}
+
+#[async_trait]
+impl NewsSource for CryptoPanicClient {
+    fn name(&self) -> &str {
+        "cryptopanic"
+    }
+
+    async fn fetch_news(&self, query: &NewsQuery) -> Result<Vec<NewsEvent>> {
+        let mut params = vec![
+            ("auth_token", self.api_key.clone()),
+            ("public", "true".to_string()),
+            ("kind", "news".to_string()),
+        ];
+        if !self.currencies.is_empty() {
+            params.push(("currencies", self.currencies.join(",")));
+        }
+
+        self.breaker.check()?;
+        let response = self.client.get(CRYPTOPANIC_API_URL).query(&params).send().await.map_err(|e| {
+            self.breaker.record_failure();
+            e
+        })?;
+        let status = response.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            self.breaker.record_failure();
+        } else {
+            self.breaker.record_success();
+        }
+        if !status.is_success() {
+            anyhow::bail!("CryptoPanic request failed with status {}", status);
+        }
+        let posts: PostsResponse = response.json().await.context("Invalid CryptoPanic response")?;
+
+        // The feed is newest first and can't be filtered by date, so that happens here
+        Ok(posts
+            .results
+            .into_iter()
+            .filter(|post| match post.published_at {
+                Some(published) => published >= query.start && query.end.map_or(true, |end| published <= end),
+                None => true,
+            })
+            .take(query.limit)
+            .map(|post| NewsEvent {
+                title: post.title,
+                url: post.url,
+                published_at: post.published_at,
+                summary: None,
+                relevance_score: None,
+                source: self.name().to_string(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,225 @@
+//! Pluggable news sources. Each source turns a query and date range into
+//! `NewsEvent`s; `fetch_all` runs several and merges their results, dropping
+//! stories that were already seen from another source or syndicated site.
+
+mod cryptopanic;
+
+pub use cryptopanic::CryptoPanicClient;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::warn;
+
+// Share of title words two stories need in common to count as the same story
+const DUPLICATE_TITLE_SIMILARITY: f64 = 0.8;
+// Titles shorter than this are too generic to match on similarity
+const MIN_SIMILAR_TITLE_WORDS: usize = 4;
+
+/// What to search for
+#[derive(Debug, Clone)]
+pub struct NewsQuery {
+    pub query: String,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+    /// Domains to restrict to, where the source supports it; empty means all
+    pub domains: Vec<String>,
+    /// Results wanted from each source
+    pub limit: usize,
+}
+
+/// One story, from whichever source found it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsEvent {
+    pub title: String,
+    pub url: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub summary: Option<String>,
+    pub relevance_score: Option<f64>,
+    /// Name of the `NewsSource` that returned it
+    pub source: String,
+}
+
+#[async_trait]
+pub trait NewsSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn fetch_news(&self, query: &NewsQuery) -> Result<Vec<NewsEvent>>;
+}
+
+/// Merged results of several sources
+#[derive(Debug, Default)]
+pub struct NewsBatch {
+    /// Newest first, duplicates removed
+    pub events: Vec<NewsEvent>,
+    /// Sources that failed, with the error; the others' results are still in `events`
+    pub failures: Vec<(String, String)>,
+}
+
+/// Query every source concurrently and merge the results. When the same story
+/// comes from several sources, the one from the source listed first is kept.
+pub async fn fetch_all(sources: &[Box<dyn NewsSource>], query: &NewsQuery) -> NewsBatch {
+    let results = join_all(sources.iter().map(|source| source.fetch_news(query))).await;
+
+    let mut batch = NewsBatch::default();
+    let mut events = Vec::new();
+    for (source, result) in sources.iter().zip(results) {
+        match result {
+            Ok(found) => events.extend(found),
+            Err(e) => {
+                warn!(source = source.name(), error = %e, "News source failed");
+                batch.failures.push((source.name().to_string(), e.to_string()));
+            }
+        }
+    }
+    batch.events = dedup(events);
+    batch.events.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+    batch
+}
+
+/// Drop later events with the same URL, or a near-identical title. Syndicated
+/// copies usually differ only in the site name tacked onto the title.
+pub fn dedup(events: Vec<NewsEvent>) -> Vec<NewsEvent> {
+    let mut seen_urls = HashSet::new();
+    let mut seen_titles: Vec<HashSet<String>> = Vec::new();
+    let mut unique = Vec::new();
+    for event in events {
+        if !seen_urls.insert(canonical_url(&event.url)) {
+            continue;
+        }
+        let words = title_words(&event.title);
+        if seen_titles.iter().any(|seen| same_title(seen, &words)) {
+            continue;
+        }
+        seen_titles.push(words);
+        unique.push(event);
+    }
+    unique
+}
+
+// Host without www, and path, ignoring scheme, query, fragment and trailing slash
+fn canonical_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let url = url.split(['?', '#']).next().unwrap_or("");
+    url.trim_start_matches("www.").trim_end_matches('/').to_string()
+}
+
+// Lowercase words of the title, minus a trailing " - Site" or " | Site"
+fn title_words(title: &str) -> HashSet<String> {
+    let title = [" | ", " - ", " – ", " — "]
+        .iter()
+        .filter_map(|sep| title.rsplit_once(sep))
+        // Only a short tail is a site name, not part of the headline
+        .filter(|(_, tail)| tail.split_whitespace().count() <= 3)
+        .map(|(head, _)| head)
+        .min_by_key(|head| head.len())
+        .unwrap_or(title);
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn same_title(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    if a == b {
+        return !a.is_empty();
+    }
+    if a.len().min(b.len()) < MIN_SIMILAR_TITLE_WORDS {
+        return false;
+    }
+    let shared = a.intersection(b).count() as f64;
+    shared / a.union(b).count() as f64 >= DUPLICATE_TITLE_SIMILARITY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, url: &str, source: &str) -> NewsEvent {
+        NewsEvent {
+            title: title.to_string(),
+            url: url.to_string(),
+            published_at: None,
+            summary: None,
+            relevance_score: None,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn drops_syndicated_copies() {
+        let events = vec![
+            event("Bitcoin ETF inflows hit record as BTC tops $100K", "https://www.coindesk.com/markets/etf/", "exa"),
+            event("Bitcoin ETF inflows hit record as BTC tops $100K - Yahoo Finance", "https://finance.yahoo.com/news/btc", "cryptopanic"),
+            event("Solana outage halts block production", "https://coindesk.com/markets/etf?utm_source=x", "cryptopanic"),
+            event("Solana validators ship a client upgrade", "https://decrypt.co/solana", "exa"),
+        ];
+        let unique = dedup(events);
+
+        let titles: Vec<&str> = unique.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            ["Bitcoin ETF inflows hit record as BTC tops $100K", "Solana validators ship a client upgrade"]
+        );
+        assert_eq!(unique[0].source, "exa");
+    }
+
+    #[test]
+    fn keeps_different_stories_with_similar_words() {
+        let unique = dedup(vec![
+            event("Ethereum price rises", "https://a.com/1", "exa"),
+            event("Ethereum price falls", "https://b.com/2", "exa"),
+        ]);
+        assert_eq!(unique.len(), 2);
+    }
+
+    struct Failing;
+
+    #[async_trait]
+    impl NewsSource for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn fetch_news(&self, _query: &NewsQuery) -> Result<Vec<NewsEvent>> {
+            anyhow::bail!("service down")
+        }
+    }
+
+    struct Fixed(Vec<NewsEvent>);
+
+    #[async_trait]
+    impl NewsSource for Fixed {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn fetch_news(&self, _query: &NewsQuery) -> Result<Vec<NewsEvent>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn one_failing_source_keeps_the_others() {
+        let sources: Vec<Box<dyn NewsSource>> = vec![
+            Box::new(Failing),
+            Box::new(Fixed(vec![event("Markets steady ahead of the Fed decision", "https://a.com/fed", "fixed")])),
+        ];
+        let query = NewsQuery {
+            query: "crypto".to_string(),
+            start: Utc::now(),
+            end: None,
+            domains: Vec::new(),
+            limit: 10,
+        };
+        let batch = fetch_all(&sources, &query).await;
+
+        assert_eq!(batch.events.len(), 1);
+        assert_eq!(batch.failures, vec![("failing".to_string(), "service down".to_string())]);
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+// Root directory for all runtime state, defaults to the workspace root
+pub const DATA_DIR_ENV: &str = "ZOEY_DATA_DIR";
+
+static ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Root that every state directory derives from: `ZOEY_DATA_DIR` if set, otherwise
+/// the workspace root, falling back to the current directory for binaries run
+/// outside the source tree. Resolved once, so later `cd`s don't move state around.
+pub fn root() -> &'static Path {
+    ROOT.get_or_init(|| {
+        if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|d| !d.is_empty()) {
+            let dir = PathBuf::from(dir);
+            return if dir.is_absolute() {
+                dir
+            } else {
+                current_dir().join(dir)
+            };
+        }
+
+        match Path::new(env!("CARGO_MANIFEST_DIR")).parent() {
+            Some(workspace) if workspace.join("Cargo.toml").exists() => workspace.to_path_buf(),
+            _ => current_dir(),
+        }
+    })
+}
+
+fn current_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+// Directories are created on first access; a failure is logged and left to the
+// caller's own read/write to report
+fn ensure(path: PathBuf) -> PathBuf {
+    if let Err(e) = std::fs::create_dir_all(&path) {
+        warn!(path = %path.display(), error = %e, "Failed to create directory");
+    }
+    path
+}
+
+/// Move a file from where an older build kept it to `current`. When both exist the
+/// old one is left alone and a warning names it, so nothing is overwritten.
+pub fn migrate_legacy_file(legacy: impl AsRef<Path>, current: &Path) {
+    let legacy = legacy.as_ref();
+    if !legacy.is_file() || legacy == current {
+        return;
+    }
+    if current.exists() {
+        warn!(
+            legacy = %legacy.display(),
+            current = %current.display(),
+            "Found a file from an older layout next to the current one, ignoring it"
+        );
+        return;
+    }
+    if let Some(parent) = current.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    // rename fails across filesystems, so fall back to copying
+    let moved = std::fs::rename(legacy, current)
+        .or_else(|_| std::fs::copy(legacy, current).and_then(|_| std::fs::remove_file(legacy)));
+    match moved {
+        Ok(()) => info!(from = %legacy.display(), to = %current.display(), "Moved file to the data directory"),
+        Err(e) => warn!(
+            legacy = %legacy.display(),
+            current = %current.display(),
+            error = %e,
+            "Failed to move file to the data directory, move it by hand to keep it"
+        ),
+    }
+}
+
+/// Resolve a configured path: relative paths are taken from `root()`, absolute ones kept
+pub fn resolve(path: impl AsRef<Path>) -> PathBuf {
+    root().join(path)
+}
+
+/// `data/` under the root
+pub fn data_dir() -> PathBuf {
+    ensure(root().join("data"))
+}
+
+/// Subdirectory of `data/`, e.g. `data_subdir("snapshots")`
+pub fn data_subdir(name: &str) -> PathBuf {
+    ensure(data_dir().join(name))
+}
+
+/// Agent memory files and token logs
+pub fn memory_dir() -> PathBuf {
+    data_subdir("agent_memory")
+}
+
+/// Extracted tweets, checkpoints and watchlist summaries
+pub fn twitter_data_dir() -> PathBuf {
+    data_subdir("twitter_data")
+}
+
+/// Documents loaded into the knowledge base
+pub fn documents_dir() -> PathBuf {
+    ensure(root().join("documents"))
+}
+
+/// Images downloaded by the research agent
+pub fn images_dir() -> PathBuf {
+    ensure(root().join("zoey_images"))
+}
+
+/// Exported analysis reports
+pub fn reports_dir() -> PathBuf {
+    ensure(root().join("analysis_reports"))
+}
@@ -0,0 +1,121 @@
+//! Spinners for long CLI operations. They are drawn on stderr so piped stdout
+//! stays clean, and not at all when stdout or stderr isn't a terminal, with
+//! `--no-progress`, or with NO_PROGRESS set.
+//!
+//! Status lines printed while a spinner runs should go through [`status!`](crate::status) so
+//! the spinner is cleared around them instead of being overwritten.
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(100);
+const TEMPLATE: &str = "{spinner:.cyan} {msg} ({elapsed})";
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+// The spinner currently drawn; status lines are printed around it
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Turn progress off for the rest of the process
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed)
+        && !std::env::args().any(|arg| arg == "--no-progress")
+        && std::env::var_os("NO_PROGRESS").is_none()
+        && std::io::stdout().is_terminal()
+        && std::io::stderr().is_terminal()
+}
+
+/// Print a line to stdout, clearing the active spinner while it is written
+pub fn println(line: impl Display) {
+    match ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(bar) => bar.suspend(|| println!("{}", line)),
+        None => println!("{}", line),
+    }
+}
+
+/// Print text without a newline, clearing the active spinner while it is written
+pub fn print(text: impl Display) {
+    let write = || {
+        print!("{}", text);
+        let _ = std::io::stdout().flush();
+    };
+    match ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(bar) => bar.suspend(write),
+        None => write(),
+    }
+}
+
+/// `println!` that keeps the active spinner intact
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        $crate::progress::println(format!($($arg)*))
+    };
+}
+
+/// A spinner with a step counter, "[2/5] Fetching prices". Only one is drawn at
+/// a time: one started while another runs stays hidden, as do all of them when
+/// progress is disabled. Cleared when finished or dropped.
+pub struct Progress {
+    bar: Option<ProgressBar>,
+    total: usize,
+    done: usize,
+}
+
+impl Progress {
+    /// `total` steps, 0 when the count isn't known yet
+    pub fn new(total: usize) -> Self {
+        let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+        let bar = (active.is_none() && enabled()).then(|| {
+            let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr());
+            bar.set_style(ProgressStyle::with_template(TEMPLATE).expect("valid progress template"));
+            bar.enable_steady_tick(TICK);
+            *active = Some(bar.clone());
+            bar
+        });
+        Self { bar, total, done: 0 }
+    }
+
+    /// Set the step count once it is known; steps already taken still count
+    pub fn set_total(&mut self, total: usize) {
+        self.total = total;
+    }
+
+    /// Start the next step
+    pub fn step(&mut self, message: impl Display) {
+        self.done += 1;
+        if let Some(bar) = &self.bar {
+            if self.total > 0 {
+                bar.set_message(format!("[{}/{}] {}", self.done.min(self.total), self.total, message));
+            } else {
+                bar.set_message(message.to_string());
+            }
+        }
+    }
+
+    /// Run `f` with the spinner cleared, for output that isn't a single line
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        match &self.bar {
+            Some(bar) => bar.suspend(f),
+            None => f(),
+        }
+    }
+
+    pub fn finish(self) {}
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+            *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+}
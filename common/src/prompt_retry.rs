@@ -0,0 +1,205 @@
+//! Bounded retries with a per-attempt timeout for model calls. Transient failures
+//! (rate limits, overloaded or unreachable providers, timeouts) are retried with a
+//! growing pause; terminal ones such as a bad key or a content-policy refusal
+//! would fail the same way again, so they are returned at once.
+
+use rig::completion::{CompletionError, PromptError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptRetry {
+    pub retries: usize,    // Extra attempts after a transient failure, 0 to try once
+    pub timeout_secs: u64, // Each attempt is abandoned after this
+    pub backoff_secs: u64, // Pause before the first retry, doubled for each one after
+}
+
+impl Default for PromptRetry {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            timeout_secs: 60,
+            backoff_secs: 2,
+        }
+    }
+}
+
+/// Why a call failed for good
+#[derive(Debug)]
+pub enum RetryError<E> {
+    /// A terminal error, or the transient one left after the last attempt
+    Call(E),
+    /// The last attempt ran past the timeout
+    TimedOut(Duration),
+}
+
+impl<E: fmt::Display> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryError::Call(e) => e.fmt(f),
+            RetryError::TimedOut(after) => write!(f, "Model call timed out after {}s", after.as_secs()),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for RetryError<E> {}
+
+/// Whether a failed prompt is worth sending again: rate limits, server errors and
+/// connection problems are; auth failures and content-policy refusals are not.
+pub fn is_retryable(error: &PromptError) -> bool {
+    match error {
+        PromptError::CompletionError(CompletionError::HttpError(e)) => match e.status() {
+            Some(status) => status.as_u16() == 429 || status.is_server_error(),
+            None => e.is_timeout() || e.is_connect() || e.is_request(),
+        },
+        PromptError::CompletionError(CompletionError::ProviderError(msg)) => {
+            let msg = msg.to_lowercase();
+            let fatal = [
+                "401", "403", "unauthorized", "api key", "content policy", "content_policy",
+                "content_filter", "moderation", "flagged", "safety", "refused to", "refusal",
+            ];
+            let retryable = [
+                "429", "500", "502", "503", "504", "rate limit", "overloaded",
+                "unavailable", "timeout", "no endpoints",
+            ];
+            !fatal.iter().any(|p| msg.contains(p)) && retryable.iter().any(|p| msg.contains(p))
+        }
+        _ => false,
+    }
+}
+
+/// Run `attempt` under `retry`'s timeout until it succeeds, fails with an error
+/// `retryable` rejects, or the retries run out. Timeouts are always retried.
+pub async fn with_retries<T, E, F, Fut>(
+    retry: PromptRetry,
+    retryable: impl Fn(&E) -> bool,
+    mut attempt: F,
+) -> Result<T, RetryError<E>>
+where
+    E: fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let timeout = Duration::from_secs(retry.timeout_secs.max(1));
+    let mut backoff = Duration::from_secs(retry.backoff_secs);
+    for n in 0..=retry.retries {
+        if n > 0 {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+        let error = match tokio::time::timeout(timeout, attempt()).await {
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(e)) if !retryable(&e) => return Err(RetryError::Call(e)),
+            Ok(Err(e)) => RetryError::Call(e),
+            Err(_) => RetryError::TimedOut(timeout),
+        };
+        if n == retry.retries {
+            return Err(error);
+        }
+        warn!(attempt = n + 1, error = %error, "Model call failed, retrying");
+    }
+    unreachable!("the last attempt always returns")
+}
+
+/// Run a call whose tools act on the world, such as a token transfer, once and
+/// without a timeout. Cutting it off mid-transfer and sending the prompt again
+/// could repeat the action, so a failure is returned as it is.
+pub async fn once<T, E, Fut>(attempt: Fut) -> Result<T, RetryError<E>>
+where
+    Fut: Future<Output = Result<T, E>>,
+{
+    attempt.await.map_err(RetryError::Call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_WAIT: PromptRetry = PromptRetry { retries: 2, timeout_secs: 1, backoff_secs: 0 };
+
+    fn provider_error(msg: &str) -> PromptError {
+        PromptError::CompletionError(CompletionError::ProviderError(msg.to_string()))
+    }
+
+    #[test]
+    fn refusals_are_terminal() {
+        assert!(is_retryable(&provider_error("503 Service Unavailable")));
+        assert!(is_retryable(&provider_error("Rate limit exceeded")));
+        assert!(!is_retryable(&provider_error("400: request flagged by content policy")));
+        assert!(!is_retryable(&provider_error("401 invalid api key")));
+        assert!(!is_retryable(&provider_error("500 from provider: the model refused to answer")));
+        // A refused connection is an outage, not a refusal
+        assert!(is_retryable(&provider_error("503 upstream connect error: connection refused")));
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let mut replies = vec![Ok("gm"), Err(provider_error("502 bad gateway"))];
+        let response = with_retries(NO_WAIT, is_retryable, || {
+            let reply = replies.pop().unwrap();
+            async move { reply }
+        })
+        .await;
+        assert_eq!(response.unwrap(), "gm");
+
+        let mut calls = 0;
+        let response = with_retries(NO_WAIT, is_retryable, || {
+            calls += 1;
+            async { Err::<String, _>(provider_error("overloaded")) }
+        })
+        .await;
+        assert!(matches!(response, Err(RetryError::Call(_))));
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn terminal_errors_are_not_retried() {
+        let mut calls = 0;
+        let response = with_retries(NO_WAIT, is_retryable, || {
+            calls += 1;
+            async { Err::<String, _>(provider_error("Response refused: content_policy_violation")) }
+        })
+        .await;
+        assert!(matches!(response, Err(RetryError::Call(_))));
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn slow_attempts_time_out() {
+        let retry = PromptRetry { retries: 1, ..NO_WAIT };
+        let mut calls = 0;
+        let response = with_retries(retry, is_retryable, || {
+            calls += 1;
+            std::future::pending::<Result<String, PromptError>>()
+        })
+        .await;
+        assert!(matches!(response, Err(RetryError::TimedOut(_))));
+        assert_eq!(calls, 2);
+    }
+
+    #[tokio::test]
+    async fn a_slow_tool_call_runs_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // A tool call that has made its transfer, then outlasts the timeout
+        let transfers = AtomicUsize::new(0);
+        let call = || async {
+            transfers.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(1200)).await;
+            Ok::<_, PromptError>("sent 0.1 SOL".to_string())
+        };
+
+        let retry = PromptRetry { retries: 1, ..NO_WAIT };
+        let response = with_retries(retry, is_retryable, call).await;
+        assert!(matches!(response, Err(RetryError::TimedOut(_))));
+        assert_eq!(transfers.swap(0, Ordering::SeqCst), 2, "the retry paid again");
+
+        let response = once(call()).await;
+        assert_eq!(response.unwrap(), "sent 0.1 SOL");
+        assert_eq!(transfers.load(Ordering::SeqCst), 1);
+    }
+}
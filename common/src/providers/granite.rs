@@ -1,14 +1,19 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
+use rig::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
 use crate::error::AgentError;
 
 const OLLAMA_API_BASE: &str = "http://localhost:11434/api";
+pub const GRANITE_EMBEDDING_278M: &str = "granite-embedding:278m";
+// Dimensions of granite-embedding:278m, other models are measured by `detect`
+const GRANITE_278M_DIMS: usize = 768;
 
 #[derive(Debug, Clone)]
 pub struct GraniteEmbedding {
     client: Client,
     model: String,
+    ndims: usize,
 }
 
 #[derive(Serialize)]
@@ -26,10 +31,30 @@ impl GraniteEmbedding {
     pub fn new() -> Self {
         Self {
             client: Client::new(),
-            model: "granite-embedding:278m".to_string(),
+            model: GRANITE_EMBEDDING_278M.to_string(),
+            ndims: GRANITE_278M_DIMS,
         }
     }
 
+    /// Any Ollama embedding model, with its dimensions measured from one embedding.
+    /// Fails if Ollama isn't reachable or the model isn't pulled.
+    pub async fn detect(model: &str) -> Result<Self, AgentError> {
+        let mut granite = Self {
+            client: Client::new(),
+            model: model.to_string(),
+            ndims: 0,
+        };
+        granite.ndims = granite.get_embedding("dimension probe").await?.len();
+        if granite.ndims == 0 {
+            return Err(AgentError::ParseError(format!("{} returned an empty embedding", model)));
+        }
+        Ok(granite)
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
     pub async fn get_embedding(&self, text: &str) -> Result<Vec<f32>, AgentError> {
         let request = EmbeddingRequest {
             model: self.model.clone(),
@@ -63,6 +88,35 @@ impl GraniteEmbedding {
     }
 }
 
+impl EmbeddingModel for GraniteEmbedding {
+    // Ollama embeds one text per request, this only bounds the batch size
+    const MAX_DOCUMENTS: usize = 64;
+
+    fn ndims(&self) -> usize {
+        self.ndims
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        let texts: Vec<String> = texts.into_iter().collect();
+        let vectors = self
+            .get_batch_embeddings(&texts)
+            .await
+            .map_err(|e| EmbeddingError::ProviderError(e.to_string()))?;
+
+        Ok(texts
+            .into_iter()
+            .zip(vectors)
+            .map(|(document, vec)| Embedding {
+                document,
+                vec: vec.into_iter().map(f64::from).collect(),
+            })
+            .collect())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraniteVector(Vec<f32>);
 
@@ -11,4 +11,4 @@ pub use self::mistral::{
     MISTRAL_LARGE,
 };
 
-pub use granite::{GraniteEmbedding, GraniteVector}; 
\ No newline at end of file
+pub use granite::{GraniteEmbedding, GraniteVector, GRANITE_EMBEDDING_278M}; 
\ No newline at end of file
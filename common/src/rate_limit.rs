@@ -0,0 +1,101 @@
+//! Per-service request spacing. Every client of a service shares one limiter, so
+//! concurrent agents and bulk fetches together stay under the service's rate limit
+//! instead of each sleeping on its own.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    service: String,
+    min_interval: Duration,
+    // When the next request may start
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(service: &str, min_interval: Duration) -> Self {
+        Self {
+            service: service.to_string(),
+            min_interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// Wait for this caller's slot. Slots are handed out in call order, at least
+    /// `min_interval` apart.
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.min_interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Push every pending slot back, e.g. after the service answered 429
+    pub fn back_off(&self, delay: Duration) {
+        let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        *next_slot = (*next_slot).max(Instant::now() + delay);
+        warn!(service = %self.service, delay_secs = delay.as_secs(), "Rate limited, delaying requests");
+    }
+}
+
+/// The shared limiter for `service`. `min_interval` applies when it's first created;
+/// `<SERVICE>_MIN_INTERVAL_MS` (e.g. `COINGECKO_MIN_INTERVAL_MS`) overrides it.
+pub fn limiter(service: &str, min_interval: Duration) -> Arc<RateLimiter> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+    let mut limiters = LIMITERS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    limiters
+        .entry(service.to_string())
+        .or_insert_with(|| Arc::new(RateLimiter::new(service, interval_from_env(service).unwrap_or(min_interval))))
+        .clone()
+}
+
+fn interval_from_env(service: &str) -> Option<Duration> {
+    let name = format!("{}_MIN_INTERVAL_MS", service.to_uppercase());
+    let value = std::env::var(&name).ok()?;
+    match value.trim().parse() {
+        Ok(ms) => Some(Duration::from_millis(ms)),
+        Err(_) => {
+            warn!(name, value = %value, "Ignoring invalid rate limit setting");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spaces_requests_by_min_interval() {
+        let limiter = RateLimiter::new("test", Duration::from_millis(40));
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn back_off_delays_the_next_slot() {
+        let limiter = RateLimiter::new("test", Duration::from_millis(1));
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.back_off(Duration::from_millis(100));
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
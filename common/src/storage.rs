@@ -1,14 +1,44 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono;
 use rig::{
+    embeddings::{Embedding, EmbeddingError, EmbeddingModel, EmbeddingsBuilder},
     providers::cohere,
-    Embed,
+    Embed, OneOrMany,
 };
-use rig_sqlite::{Column, ColumnValue, SqliteVectorStore, SqliteVectorStoreTable};
-use tokio_rusqlite::Connection;
+use rig_sqlite::{Column, ColumnValue, DistanceMetric, SqliteVectorStore, SqliteVectorStoreTable};
+use tokio_rusqlite::{ffi, Connection, OptionalExtension};
 use uuid;
-use tracing::info;
+use tracing::{info, warn};
 use serde::{Serialize, Deserialize};
+use std::os::raw::c_int;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Database used by zoey-rag when no path is given
+pub const DEFAULT_DB_PATH: &str = "zoey.db";
+
+/// Retries of a failed embedding batch before it is split
+pub const DEFAULT_EMBED_RETRIES: usize = 2;
+/// Times a failing embedding batch is halved to find the chunks at fault
+pub const DEFAULT_BISECT_DEPTH: usize = 6;
+const EMBED_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Register the sqlite-vec extension for every SQLite connection opened after
+/// this, so call it before opening a database. It is registered once per process;
+/// later calls return the first call's outcome.
+pub fn init_sqlite_vec() -> Result<()> {
+    static REGISTERED: OnceLock<c_int> = OnceLock::new();
+    let code = *REGISTERED.get_or_init(|| {
+        // SAFETY: sqlite3_vec_init is an SQLite extension entry point. The sqlite-vec
+        // crate declares it without parameters, so it is cast to the entry point type.
+        unsafe { ffi::sqlite3_auto_extension(Some(std::mem::transmute(sqlite_vec::sqlite3_vec_init as *const ()))) }
+    });
+    if code != ffi::SQLITE_OK {
+        anyhow::bail!("Failed to register the sqlite-vec extension: {}", ffi::Error::new(code));
+    }
+    Ok(())
+}
 
 // Document struct for storing loaded content
 #[derive(Debug, Clone, Embed, Serialize, Deserialize)]
@@ -20,6 +50,19 @@ pub struct Document {
     pub content: String,
 }
 
+impl Document {
+    pub fn new(source: &str, content: &str) -> Self {
+        let now = chrono::Local::now();
+        let uuid = uuid::Uuid::new_v4().to_string();
+        Self {
+            id: format!("doc_{}_{}", now.timestamp(), uuid),
+            source: source.to_string(),
+            timestamp: now.to_rfc3339(),
+            content: content.to_string(),
+        }
+    }
+}
+
 // Implement SqliteVectorStoreTable for Document to match database schema
 impl SqliteVectorStoreTable for Document {
     fn name() -> &'static str {
@@ -49,58 +92,112 @@ impl SqliteVectorStoreTable for Document {
     }
 }
 
-// Storage manager struct
-pub struct StorageManager {
+/// An embedding model that can say which model it is, so a store can record what
+/// its vectors were made with
+pub trait NamedEmbeddingModel: EmbeddingModel + 'static {
+    fn model_id(&self) -> String;
+}
+
+impl NamedEmbeddingModel for cohere::EmbeddingModel {
+    fn model_id(&self) -> String {
+        self.model.clone()
+    }
+}
+
+// Storage manager struct. One manager holds vectors from a single embedding model;
+// another model needs its own manager on a separate database.
+pub struct StorageManager<M = cohere::EmbeddingModel> {
     conn: Connection,
-    store: Option<SqliteVectorStore<cohere::EmbeddingModel, Document>>,
-    model: Option<cohere::EmbeddingModel>,
+    store: Option<SqliteVectorStore<M, Document>>,
+    model: Option<M>,
+    metric: DistanceMetric,
 }
 
-impl StorageManager {
+impl<M: NamedEmbeddingModel> StorageManager<M> {
     pub async fn new(db_path: &str) -> Result<Self> {
         let conn = Connection::open(db_path).await?;
         Ok(Self {
             conn,
             store: None,
             model: None,
+            metric: DistanceMetric::default(),
         })
     }
 
-    pub fn get_store(&self) -> Option<&SqliteVectorStore<cohere::EmbeddingModel, Document>> {
+    // Ranking used by the vector store; set before creating tables
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    pub fn get_store(&self) -> Option<&SqliteVectorStore<M, Document>> {
         self.store.as_ref()
     }
 
-    pub async fn initialize_store(&mut self, embedding_model: cohere::EmbeddingModel) -> Result<()> {
-        let store = SqliteVectorStore::new(self.conn.clone(), &embedding_model).await?;
+    pub async fn initialize_store(&mut self, embedding_model: M) -> Result<()> {
+        self.check_embedding_model(&embedding_model).await?;
+        let store = SqliteVectorStore::new_with_metric(self.conn.clone(), &embedding_model, self.metric).await?;
         self.store = Some(store);
         self.model = Some(embedding_model.clone());
-        
-        // Load existing documents into vector store
-        let docs = self.get_documents().await?;
-        if !docs.is_empty() {
-            if let Some(store) = &self.store {
-                let docs_count = docs.len(); // Store count before moving docs
-                let embeddings = rig::embeddings::EmbeddingsBuilder::new(embedding_model)
-                    .documents(docs)?
-                    .build()
-                    .await?;
-                store.add_rows(embeddings).await?;
-                info!("Loaded {} existing documents into vector store", docs_count);
-            }
+        self.embed_missing(embedding_model).await
+    }
+
+    // Stored documents keep their embeddings across runs; only ones stored without
+    // any, e.g. while offline or after the API rejected them, are embedded now.
+    // Chunks that still fail stay keyword-only and are tried again next start.
+    async fn embed_missing(&self, embedding_model: M) -> Result<()> {
+        let docs = self.documents_where("rowid NOT IN (SELECT rowid FROM documents_embeddings)").await?;
+        let Some(store) = &self.store else { return Ok(()) };
+        if docs.is_empty() {
+            return Ok(());
         }
-        
+        // No retries: a failing batch is split once rather than stalling startup
+        let (embeddings, failed) = embed_isolating(&embedding_model, docs, 0, DEFAULT_BISECT_DEPTH).await;
+        for (doc, error) in &failed {
+            warn!(source = %doc.source, id = %doc.id, "Stored chunk could not be embedded, matching it by keywords only: {:#}", error);
+        }
+        if !embeddings.is_empty() {
+            let count = embeddings.len();
+            // add_rows replaces each document row, now with its embedding
+            store.add_rows(embeddings).await?;
+            info!("Embedded {} stored documents that had no embeddings", count);
+        }
+        Ok(())
+    }
+
+    /// Refuse a model whose name or dimensions differ from the one the stored
+    /// embeddings were made with. A store without embeddings accepts any model:
+    /// it is recorded and the embeddings table resized to match.
+    pub async fn check_embedding_model(&self, model: &M) -> Result<()> {
+        let dims = model.ndims();
+        let embeddings_column = self.metric.vec0_column(dims);
+        let mismatch = embedding_model_mismatch(
+            &self.conn,
+            "documents_embeddings",
+            &model.model_id(),
+            dims,
+            Some(embeddings_column),
+        )
+        .await?;
+
+        if let Some((recorded_model, recorded_dims)) = mismatch {
+            anyhow::bail!(
+                "The document store was embedded with {} ({} dims) but {} produces {} dims. \
+                 Re-embed by starting with --fresh (or /clear) and loading the documents again, \
+                 or switch back to the original model.",
+                recorded_model, recorded_dims, model.model_id(), model.ndims()
+            );
+        }
+        info!("Using embedding model {} ({} dims)", model.model_id(), model.ndims());
         Ok(())
     }
 
     pub async fn add_document(&self, source: &str, content: &str) -> Result<Document> {
-        let now = chrono::Local::now();
-        let uuid = uuid::Uuid::new_v4().to_string();
-        let doc = Document {
-            id: format!("doc_{}_{}", now.timestamp(), uuid),
-            source: source.to_string(),
-            timestamp: now.to_rfc3339(),
-            content: content.to_string(),
-        };
+        let doc = Document::new(source, content);
 
         // Clone values for the closure
         let id = doc.id.clone();
@@ -147,8 +244,15 @@ impl StorageManager {
     }
 
     pub async fn get_documents(&self) -> Result<Vec<Document>> {
-        let docs = self.conn.call(|conn| {
-            let mut stmt = conn.prepare("SELECT id, source, timestamp, content FROM documents")?;
+        self.documents_where("1").await
+    }
+
+    async fn documents_where(&self, condition: &'static str) -> Result<Vec<Document>> {
+        let docs = self.conn.call(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, source, timestamp, content FROM documents WHERE {}",
+                condition
+            ))?;
             let rows = stmt.query_map([], |row| {
                 Ok(Document {
                     id: row.get(0)?,
@@ -168,6 +272,55 @@ impl StorageManager {
         Ok(docs)
     }
 
+    /// Stored documents and the distinct sources they came from
+    pub async fn document_counts(&self) -> Result<(usize, usize)> {
+        let counts = self.conn.call(|conn| {
+            Ok(conn.query_row(
+                "SELECT COUNT(*), COUNT(DISTINCT source) FROM documents",
+                [],
+                |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize)),
+            )?)
+        }).await?;
+
+        Ok(counts)
+    }
+
+    /// Delete every chunk stored for these sources, with its embeddings
+    pub async fn remove_sources(&self, sources: &[String]) -> Result<()> {
+        let sources = sources.to_vec();
+        self.conn.call(move |conn| {
+            let tx = conn.transaction()?;
+            for source in &sources {
+                tx.execute(
+                    "DELETE FROM documents_embeddings WHERE rowid IN (SELECT rowid FROM documents WHERE source = ?1)",
+                    [source],
+                )?;
+                tx.execute("DELETE FROM documents WHERE source = ?1", [source])?;
+            }
+            tx.commit()?;
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
+    /// Delete these chunks by id, with their embeddings
+    pub async fn remove_documents(&self, ids: &[String]) -> Result<()> {
+        let ids = ids.to_vec();
+        self.conn.call(move |conn| {
+            let tx = conn.transaction()?;
+            for id in &ids {
+                tx.execute(
+                    "DELETE FROM documents_embeddings WHERE rowid IN (SELECT rowid FROM documents WHERE id = ?1)",
+                    [id],
+                )?;
+                tx.execute("DELETE FROM documents WHERE id = ?1", [id])?;
+            }
+            tx.commit()?;
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
     pub async fn clear_documents(&self) -> Result<()> {
         // Clear documents table first
         self.conn.call(|conn| {
@@ -188,14 +341,11 @@ impl StorageManager {
     }
 
     pub async fn initialize_tables(&self) -> Result<()> {
-        self.conn.call(|conn| {
-            // Drop existing tables if they exist
-            conn.execute("DROP TABLE IF EXISTS documents", [])?;
-            conn.execute("DROP TABLE IF EXISTS documents_embeddings", [])?;
-
-            // Create new tables with correct schema
+        let embeddings_column = self.metric.vec0_column(1024);
+        // Kept across runs; a --fresh session clears them with clear_documents
+        self.conn.call(move |conn| {
             conn.execute(
-                "CREATE TABLE documents (
+                "CREATE TABLE IF NOT EXISTS documents (
                     id TEXT PRIMARY KEY,
                     source TEXT NOT NULL,
                     timestamp TEXT NOT NULL,
@@ -206,9 +356,7 @@ impl StorageManager {
 
             // Create embeddings table with vector search support
             conn.execute(
-                "CREATE VIRTUAL TABLE documents_embeddings USING vec0(
-                    embedding FLOAT[1024]
-                )",
+                &format!("CREATE VIRTUAL TABLE IF NOT EXISTS documents_embeddings USING vec0({})", embeddings_column),
                 [],
             )?;
 
@@ -217,27 +365,15 @@ impl StorageManager {
         Ok(())
     }
 
-    pub async fn initialize_store_with_mode(&mut self, embedding_model: cohere::EmbeddingModel, persistent: bool) -> Result<()> {
-        let store = SqliteVectorStore::new(self.conn.clone(), &embedding_model).await?;
+    pub async fn initialize_store_with_mode(&mut self, embedding_model: M, persistent: bool) -> Result<()> {
+        self.check_embedding_model(&embedding_model).await?;
+        let store = SqliteVectorStore::new_with_metric(self.conn.clone(), &embedding_model, self.metric).await?;
         self.store = Some(store);
         self.model = Some(embedding_model.clone());
         
         if persistent {
-            // Load existing documents into vector store
-            info!("Loading existing documents from persistent storage...");
-            let docs = self.get_documents().await?;
-            if !docs.is_empty() {
-                info!("Found {} existing documents", docs.len());
-                if let Some(store) = &self.store {
-                    let docs_count = docs.len(); // Store count before moving docs
-                    let embeddings = rig::embeddings::EmbeddingsBuilder::new(embedding_model)
-                        .documents(docs)?
-                        .build()
-                        .await?;
-                    store.add_rows(embeddings).await?;
-                    info!("Restored {} documents from persistent storage", docs_count);
-                }
-            }
+            info!("Using existing documents from persistent storage...");
+            self.embed_missing(embedding_model).await?;
         } else {
             // Clear any existing documents for fresh session
             info!("Starting fresh session, clearing existing documents...");
@@ -247,19 +383,310 @@ impl StorageManager {
         Ok(())
     }
 
-    pub async fn new_with_mode(persistent: bool) -> Result<Self> {
-        // Use in-memory database for non-persistent mode
-        let db_path = if persistent {
-            "zoey.db".to_string()
-        } else {
-            ":memory:".to_string()  // SQLite in-memory database
+    /// Open the database at `db_path`, creating its directory if needed, or an
+    /// in-memory database for None
+    pub async fn new_with_mode(db_path: Option<&Path>) -> Result<Self> {
+        let conn = match db_path {
+            Some(path) => {
+                if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create database directory {}", parent.display()))?;
+                }
+                Connection::open(path)
+                    .await
+                    .with_context(|| format!("Failed to open database {}", path.display()))?
+            }
+            // SQLite in-memory database
+            None => Connection::open(":memory:").await?,
         };
-        
-        let conn = Connection::open(&db_path).await?;
         Ok(Self {
             conn,
             store: None,
             model: None,
+            metric: DistanceMetric::default(),
         })
     }
-} 
\ No newline at end of file
+} 
+
+/// Embed `documents` one API batch (`M::MAX_DOCUMENTS` chunks) at a time, retrying a
+/// failed batch with a growing delay. A batch that still fails is halved, up to
+/// `depth` times, and each half tried once, so a chunk the API rejects doesn't cost
+/// the rest. A failure of the request itself, e.g. a bad key or an outage, would
+/// fail every half alike, so that batch isn't split. Returns the embedded documents,
+/// and those left failing with their error.
+pub async fn embed_isolating<M: EmbeddingModel + Clone>(
+    model: &M,
+    documents: Vec<Document>,
+    retries: usize,
+    depth: usize,
+) -> (Vec<(Document, OneOrMany<Embedding>)>, Vec<(Document, anyhow::Error)>) {
+    let mut embedded = Vec::new();
+    let mut failed = Vec::new();
+
+    // Batched here rather than by EmbeddingsBuilder, so a split only resends its own batch
+    for batch in documents.chunks(M::MAX_DOCUMENTS.max(1)) {
+        let batch = batch.to_vec();
+        let mut result = embed_batch(model, &batch).await;
+        for attempt in 1..=retries {
+            let Err(e) = &result else { break };
+            warn!(attempt, "Embedding batch of {} chunks failed: {:#}", batch.len(), e);
+            tokio::time::sleep(EMBED_RETRY_DELAY * attempt as u32).await;
+            result = embed_batch(model, &batch).await;
+        }
+
+        // Halves still to embed, with the splits they have left
+        let mut pending = vec![(batch, depth, result)];
+        while let Some((batch, depth, result)) = pending.pop() {
+            match result {
+                Ok(embeddings) => embedded.extend(embeddings),
+                Err(e) if depth == 0 || batch.len() == 1 || is_request_error(&e) => {
+                    failed.extend(batch.into_iter().map(|doc| (doc, anyhow::anyhow!("{:#}", e))));
+                }
+                Err(e) => {
+                    info!("Splitting a failed batch of {} chunks: {:#}", batch.len(), e);
+                    let mut first = batch;
+                    let second = first.split_off(first.len() / 2);
+                    let first_result = embed_batch(model, &first).await;
+                    let second_result = embed_batch(model, &second).await;
+                    pending.push((second, depth - 1, second_result));
+                    pending.push((first, depth - 1, first_result));
+                }
+            }
+        }
+    }
+    (embedded, failed)
+}
+
+// Auth failures, rate limits, server errors and unreachable providers come from the
+// request, not its contents; a rejected input (400/422) may be down to one chunk
+fn is_request_error(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<EmbeddingError>() {
+        Some(EmbeddingError::HttpError(e)) => match e.status() {
+            Some(status) => matches!(status.as_u16(), 401 | 403 | 429) || status.is_server_error(),
+            None => true,
+        },
+        Some(EmbeddingError::ProviderError(msg)) | Some(EmbeddingError::ResponseError(msg)) => {
+            let msg = msg.to_lowercase();
+            [
+                "401", "403", "unauthorized", "api key", "429", "rate limit", "500", "502", "503",
+                "504", "overloaded", "unavailable", "timeout", "timed out",
+            ]
+            .iter()
+            .any(|p| msg.contains(p))
+        }
+        _ => false,
+    }
+}
+
+async fn embed_batch<M: EmbeddingModel + Clone>(
+    model: &M,
+    documents: &[Document],
+) -> Result<Vec<(Document, OneOrMany<Embedding>)>> {
+    Ok(EmbeddingsBuilder::new(model.clone()).documents(documents.to_vec())?.build().await?)
+}
+
+/// Check `model_id` and `dims` against the embedding model recorded for a database,
+/// and record them when they fit. `table` is the vec0 embeddings table; a database
+/// from before the model was recorded is judged by that table's dimensions. With
+/// `rebuild_column` an empty table is no conflict: it is recreated with that column
+/// to fit the new model. Returns the recorded model and dims when they conflict.
+pub async fn embedding_model_mismatch(
+    conn: &Connection,
+    table: &'static str,
+    model_id: &str,
+    dims: usize,
+    rebuild_column: Option<String>,
+) -> Result<Option<(String, usize)>, tokio_rusqlite::Error> {
+    let model_id = model_id.to_string();
+    conn.call(move |conn| {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS embedding_model (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                model TEXT NOT NULL,
+                dims INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        let recorded: Option<(String, usize)> = conn
+            .query_row("SELECT model, dims FROM embedding_model WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()?;
+        // vec0 tables keep the dimensions they were created with
+        let table_sql: Option<String> = conn
+            .query_row("SELECT sql FROM sqlite_master WHERE name = ?1", [table], |row| row.get(0))
+            .optional()?;
+        let table_dims = table_sql.as_deref().and_then(vec0_dims);
+        let stored: i64 = match table_sql {
+            Some(_) => conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))?,
+            None => 0,
+        };
+
+        match rebuild_column {
+            Some(column) if stored == 0 => {
+                if table_dims.is_some_and(|table_dims| table_dims != dims) {
+                    conn.execute(&format!("DROP TABLE {}", table), [])?;
+                    conn.execute(&format!("CREATE VIRTUAL TABLE {} USING vec0({})", table, column), [])?;
+                }
+            }
+            _ => {
+                let mismatch = match recorded {
+                    Some((recorded_model, recorded_dims)) if recorded_model != model_id || recorded_dims != dims => {
+                        Some((recorded_model, recorded_dims))
+                    }
+                    Some(_) => None,
+                    None => table_dims
+                        .filter(|table_dims| *table_dims != dims)
+                        .map(|table_dims| ("an unrecorded model".to_string(), table_dims)),
+                };
+                if mismatch.is_some() {
+                    return Ok(mismatch);
+                }
+            }
+        }
+
+        conn.execute(
+            "INSERT OR REPLACE INTO embedding_model (id, model, dims) VALUES (1, ?1, ?2)",
+            (&model_id, dims as i64),
+        )?;
+        Ok(None)
+    })
+    .await
+}
+
+// Dimensions from a vec0 table definition, e.g. "... USING vec0(embedding float[1024])"
+fn vec0_dims(sql: &str) -> Option<usize> {
+    let sql = sql.to_ascii_lowercase();
+    let start = sql.find("float[")? + "float[".len();
+    let end = sql[start..].find(']')? + start;
+    sql[start..end].trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // Fails any request holding a chunk with "BAD" in it, or every request while down
+    #[derive(Clone)]
+    struct MockEmbedding {
+        down: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl MockEmbedding {
+        fn new(down: bool) -> Self {
+            Self { down, calls: Arc::new(AtomicUsize::new(0)) }
+        }
+    }
+
+    impl EmbeddingModel for MockEmbedding {
+        const MAX_DOCUMENTS: usize = 4;
+
+        fn ndims(&self) -> usize {
+            2
+        }
+
+        async fn embed_texts(
+            &self,
+            texts: impl IntoIterator<Item = String> + Send,
+        ) -> Result<Vec<Embedding>, EmbeddingError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let texts: Vec<String> = texts.into_iter().collect();
+            if self.down {
+                return Err(EmbeddingError::ProviderError("503 Service Unavailable".to_string()));
+            }
+            if texts.iter().any(|text| text.contains("BAD")) {
+                return Err(EmbeddingError::ProviderError("400 invalid input".to_string()));
+            }
+            Ok(texts.into_iter().map(|document| Embedding { document, vec: vec![1.0, 0.0] }).collect())
+        }
+    }
+
+    impl NamedEmbeddingModel for MockEmbedding {
+        fn model_id(&self) -> String {
+            "mock".to_string()
+        }
+    }
+
+    fn documents(contents: &[&str]) -> Vec<Document> {
+        contents.iter().map(|content| Document::new("test.md", content)).collect()
+    }
+
+    #[tokio::test]
+    async fn a_bad_chunk_is_isolated_without_resending_good_batches() {
+        let model = MockEmbedding::new(false);
+        let docs = documents(&["a", "b", "c", "d", "e", "BAD", "g", "h", "i", "j"]);
+        let (embedded, failed) = embed_isolating(&model, docs, 0, DEFAULT_BISECT_DEPTH).await;
+
+        assert_eq!(embedded.len(), 9);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0.content, "BAD");
+        // One call per batch of 4, plus two per split of the bad batch: [e BAD] [g h], then [e] [BAD]
+        assert_eq!(model.calls.load(Ordering::SeqCst), 3 + 4);
+    }
+
+    #[tokio::test]
+    async fn an_outage_is_not_bisected() {
+        let model = MockEmbedding::new(true);
+        let docs = documents(&["a", "b", "c", "d", "e", "f", "g", "h"]);
+        let (embedded, failed) = embed_isolating(&model, docs, 0, DEFAULT_BISECT_DEPTH).await;
+
+        assert!(embedded.is_empty());
+        assert_eq!(failed.len(), 8);
+        // Each batch is tried once and not split
+        assert_eq!(model.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn sqlite_vec_registers_once_for_every_connection() {
+        init_sqlite_vec().unwrap();
+        init_sqlite_vec().unwrap();
+
+        let conn = Connection::open_in_memory().await.unwrap();
+        let version: String = conn
+            .call(|conn| Ok(conn.query_row("select vec_version()", [], |row| row.get(0))?))
+            .await
+            .unwrap();
+        assert!(version.starts_with('v'));
+    }
+
+    #[tokio::test]
+    async fn bad_chunks_in_both_halves_are_each_isolated() {
+        let model = MockEmbedding::new(false);
+        let docs = documents(&["BAD", "b", "c", "BAD"]);
+        let (embedded, failed) = embed_isolating(&model, docs, 0, DEFAULT_BISECT_DEPTH).await;
+
+        assert_eq!(embedded.len(), 2);
+        assert_eq!(failed.len(), 2);
+        assert!(failed.iter().all(|(doc, _)| doc.content == "BAD"));
+    }
+
+    #[tokio::test]
+    async fn reopening_with_an_unembeddable_row_embeds_the_rest() {
+        init_sqlite_vec().unwrap();
+        let path = std::env::temp_dir().join(format!("zoey_storage_{}.db", uuid::Uuid::new_v4()));
+        {
+            // Stored without vectors, as while offline
+            let storage = StorageManager::<MockEmbedding>::new_with_mode(Some(&path)).await.unwrap();
+            storage.initialize_tables().await.unwrap();
+            storage.add_document("good.md", "fine").await.unwrap();
+            storage.add_document("bad.md", "BAD chunk").await.unwrap();
+        }
+
+        let mut storage = StorageManager::new_with_mode(Some(&path)).await.unwrap();
+        storage.initialize_tables().await.unwrap();
+        storage.initialize_store_with_mode(MockEmbedding::new(false), true).await.unwrap();
+
+        let embedded: i64 = storage
+            .conn
+            .call(|conn| Ok(conn.query_row("SELECT COUNT(*) FROM documents_embeddings", [], |row| row.get(0))?))
+            .await
+            .unwrap();
+        assert_eq!(embedded, 1);
+        assert_eq!(storage.document_counts().await.unwrap(), (2, 2));
+        drop(storage);
+        let _ = std::fs::remove_file(&path);
+    }
+}
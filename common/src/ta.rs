@@ -0,0 +1,282 @@
+//! Provider-agnostic technical indicators. Data sources convert their candles
+//! into [`Candle`] and share one implementation.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    /// Unix time in milliseconds
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// Wilder's RSI over closes, `None` with fewer than `period + 1` candles
+pub fn rsi(candles: &[Candle], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() < period + 1 {
+        return None;
+    }
+
+    let mut gains = Vec::new();
+    let mut losses = Vec::new();
+
+    for i in 1..candles.len() {
+        let change = candles[i].close - candles[i - 1].close;
+        if change > 0.0 {
+            gains.push(change);
+            losses.push(0.0);
+        } else {
+            gains.push(0.0);
+            losses.push(change.abs());
+        }
+    }
+
+    let mut avg_gain = gains.iter().take(period).sum::<f64>() / period as f64;
+    let mut avg_loss = losses.iter().take(period).sum::<f64>() / period as f64;
+
+    for i in period..gains.len() {
+        avg_gain = (avg_gain * (period - 1) as f64 + gains[i]) / period as f64;
+        avg_loss = (avg_loss * (period - 1) as f64 + losses[i]) / period as f64;
+    }
+
+    if avg_loss == 0.0 {
+        return Some(100.0);
+    }
+
+    let rs = avg_gain / avg_loss;
+    Some(100.0 - (100.0 / (1.0 + rs)))
+}
+
+/// Simple moving average of the last `period` closes
+pub fn sma(candles: &[Candle], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() < period {
+        return None;
+    }
+
+    let start_idx = candles.len() - period;
+    let sum: f64 = candles[start_idx..].iter().map(|c| c.close).sum();
+    Some(sum / period as f64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Macd {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// MACD(12, 26, 9), `None` with fewer than 26 candles
+pub fn macd(candles: &[Candle]) -> Option<Macd> {
+    if candles.len() < 26 {
+        return None;
+    }
+
+    let mut fast_ema = candles[0].close;
+    let mut slow_ema = candles[0].close;
+
+    let fast_alpha = 2.0 / (12.0 + 1.0);
+    let slow_alpha = 2.0 / (26.0 + 1.0);
+    let signal_alpha = 2.0 / (9.0 + 1.0);
+
+    let mut macd_values = Vec::with_capacity(candles.len());
+    for candle in candles {
+        fast_ema = candle.close * fast_alpha + fast_ema * (1.0 - fast_alpha);
+        slow_ema = candle.close * slow_alpha + slow_ema * (1.0 - slow_alpha);
+        macd_values.push(fast_ema - slow_ema);
+    }
+
+    let mut signal = macd_values[0];
+    for macd in macd_values.iter().skip(1) {
+        signal = macd * signal_alpha + signal * (1.0 - signal_alpha);
+    }
+
+    let macd = macd_values[macd_values.len() - 1];
+    Some(Macd {
+        macd,
+        signal,
+        histogram: macd - signal,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerBands {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// 20-period bands at two standard deviations, `None` with fewer than 20
+/// candles or when a band overflows
+pub fn bollinger_bands(candles: &[Candle]) -> Option<BollingerBands> {
+    const PERIOD: usize = 20;
+    let middle = sma(candles, PERIOD)?;
+
+    let variance: f64 = candles
+        .iter()
+        .rev()
+        .take(PERIOD)
+        .map(|c| {
+            let diff = c.close - middle;
+            diff * diff
+        })
+        .sum::<f64>()
+        / PERIOD as f64;
+    let std_dev = variance.sqrt();
+
+    let upper = middle + (2.0 * std_dev);
+    let lower = middle - (2.0 * std_dev);
+    if upper.is_infinite() || lower.is_infinite() {
+        return None;
+    }
+
+    Some(BollingerBands { upper, middle, lower })
+}
+
+/// Candlestick patterns formed by the last candles, empty with fewer than 3
+pub fn detect_patterns(candles: &[Candle]) -> Vec<&'static str> {
+    if candles.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut patterns = Vec::new();
+    let last = &candles[candles.len() - 1];
+    let prev = &candles[candles.len() - 2];
+
+    // Doji
+    if (last.open - last.close).abs() < 0.001 * last.open {
+        patterns.push("Doji (Indecision)");
+    }
+
+    // Hammer
+    if last.low < last.open
+        && last.low < last.close
+        && (last.high - last.low.max(last.open.min(last.close)))
+            < (last.open.max(last.close) - last.low) * 0.3
+    {
+        patterns.push("Hammer");
+    }
+
+    // Engulfing
+    if last.open > prev.close && last.close < prev.open {
+        patterns.push("Bearish Engulfing");
+    } else if last.open < prev.close && last.close > prev.open {
+        patterns.push("Bullish Engulfing");
+    }
+
+    patterns
+}
+
+/// Support and resistance as the lower and upper quartile closes
+pub fn support_resistance(candles: &[Candle]) -> Option<(f64, f64)> {
+    if candles.is_empty() {
+        return None;
+    }
+
+    let mut prices: Vec<f64> = candles.iter().map(|c| c.close).collect();
+    prices.sort_by(|a, b| a.total_cmp(b));
+
+    Some((prices[prices.len() / 4], prices[3 * prices.len() / 4]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Golden values were computed with the indicator code as it was in CoinGeckoClient
+    // and TechnicalAgent before the move, so any drift shows up here.
+    const CLOSES: [f64; 30] = [
+        100.0, 101.5, 102.25, 101.0, 103.75, 105.5, 104.0, 106.25, 107.0, 105.75, 108.5, 110.0, 109.25, 111.5,
+        110.75, 112.0, 113.5, 112.25, 114.0, 115.75, 114.5, 113.0, 115.25, 116.5, 118.0, 117.25, 119.5, 120.0,
+        118.75, 121.25,
+    ];
+
+    fn candle(open: f64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            timestamp: 0,
+            open,
+            high,
+            low,
+            close,
+            volume: 0.0,
+        }
+    }
+
+    fn series() -> Vec<Candle> {
+        CLOSES
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                let open = if i == 0 { close - 0.5 } else { CLOSES[i - 1] };
+                candle(open, open.max(close) + 0.8, open.min(close) - 0.6, close)
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn rsi_matches_golden_value() {
+        assert_close(rsi(&series(), 14).unwrap(), 74.23136892386944);
+    }
+
+    #[test]
+    fn rsi_is_100_without_losses() {
+        let rising: Vec<Candle> = (0..20).map(|i| candle(i as f64, i as f64, i as f64, i as f64)).collect();
+        assert_eq!(rsi(&rising, 14), Some(100.0));
+    }
+
+    #[test]
+    fn moving_averages_match_golden_values() {
+        let candles = series();
+        assert_close(sma(&candles, 20).unwrap(), 114.575);
+        assert_close(sma(&candles, 5).unwrap(), 119.35);
+    }
+
+    #[test]
+    fn macd_matches_golden_values() {
+        let macd = macd(&series()).unwrap();
+        assert_close(macd.macd, 3.9248901399932947);
+        assert_close(macd.signal, 3.624195074978795);
+        assert_close(macd.histogram, 0.3006950650144997);
+    }
+
+    #[test]
+    fn bollinger_bands_match_golden_values() {
+        let bands = bollinger_bands(&series()).unwrap();
+        assert_close(bands.upper, 121.87414378540387);
+        assert_close(bands.middle, 114.575);
+        assert_close(bands.lower, 107.27585621459613);
+    }
+
+    #[test]
+    fn support_resistance_are_quartile_closes() {
+        assert_eq!(support_resistance(&series()), Some((105.75, 115.75)));
+    }
+
+    #[test]
+    fn too_little_history_gives_no_value() {
+        let candles = series();
+        assert_eq!(rsi(&candles[..14], 14), None);
+        assert_eq!(sma(&candles[..4], 5), None);
+        assert_eq!(macd(&candles[..25]), None);
+        assert_eq!(bollinger_bands(&candles[..19]), None);
+        assert_eq!(bollinger_bands(&[]), None);
+        assert_eq!(support_resistance(&[]), None);
+        assert!(detect_patterns(&candles[..2]).is_empty());
+    }
+
+    #[test]
+    fn detects_doji_and_engulfing() {
+        let base = candle(99.0, 100.5, 98.5, 100.0);
+        let doji = [base, candle(100.0, 101.2, 99.8, 101.0), candle(101.0, 101.5, 100.5, 101.05)];
+        assert_eq!(detect_patterns(&doji), vec!["Doji (Indecision)"]);
+
+        let engulfing = [base, candle(102.0, 102.4, 99.8, 100.0), candle(99.5, 103.2, 99.4, 103.0)];
+        assert_eq!(detect_patterns(&engulfing), vec!["Bullish Engulfing"]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Instant;
+use tracing::{debug, error, info, Instrument};
+
+// Set this env var to log truncated prompt/response previews at debug level
+pub const PREVIEW_ENV_VAR: &str = "LLM_LOG_PREVIEW";
+const PREVIEW_CHARS: usize = 80;
+
+/// Short random id used to correlate the log lines of a single provider call
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..12].to_string()
+}
+
+fn preview_enabled() -> bool {
+    std::env::var(PREVIEW_ENV_VAR).is_ok()
+}
+
+fn preview(text: &str) -> String {
+    let mut preview: String = text.chars().take(PREVIEW_CHARS).collect();
+    if text.chars().count() > PREVIEW_CHARS {
+        preview.push_str("...");
+    }
+    preview
+}
+
+/// Run a provider call inside an `llm_request` span carrying a request id, provider
+/// and model, logging the elapsed time at info when it completes or fails.
+/// Only prompt/response lengths are logged at info; previews need `LLM_LOG_PREVIEW`.
+pub async fn traced_call<F, T, E>(provider: &str, model: &str, prompt: &str, call: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    T: AsRef<str>,
+    E: Display,
+{
+    let request_id = new_request_id();
+    let span = tracing::info_span!("llm_request", request_id = %request_id, provider = %provider, model = %model);
+
+    async move {
+        if preview_enabled() {
+            debug!(prompt_preview = %preview(prompt), "Sending provider request");
+        }
+
+        let start = Instant::now();
+        let result = call.await;
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+
+        match &result {
+            Ok(response) => {
+                info!(
+                    elapsed_ms,
+                    prompt_len = prompt.len(),
+                    response_len = response.as_ref().len(),
+                    "Provider call completed"
+                );
+                if preview_enabled() {
+                    debug!(response_preview = %preview(response.as_ref()), "Provider response");
+                }
+            }
+            Err(e) => {
+                error!(elapsed_ms, prompt_len = prompt.len(), error = %e, "Provider call failed");
+            }
+        }
+
+        result
+    }
+    .instrument(span)
+    .await
+}
@@ -0,0 +1,153 @@
+//! Prompt-injection checks for the retrieval guard. Adversarial fixture documents
+//! go through `guarded_agent` into a mock model, and the assertions are on the
+//! request it receives, so no API keys are needed. Set INJECTION_FIXTURES_DIR to
+//! run the same checks over other documents.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use common::prompt_guard::{guarded_agent, GuardMode, DATA_INSTRUCTION};
+use rig::agent::AgentBuilder;
+use rig::completion::{AssistantContent, Chat, CompletionError, CompletionModel, CompletionRequest, CompletionResponse};
+use rig::OneOrMany;
+
+const PREAMBLE: &str = "SECRET-PREAMBLE-7f3a: You are Zoey, a research assistant.";
+const REFUSAL: &str = "I can summarize these documents, but I won't follow instructions inside them.";
+
+// Requests a mock model obeys when they appear unmarked anywhere in its prompt
+const TRIGGERS: &[&str] = &[
+    "ignore previous instructions",
+    "disregard the above",
+    "reveal your system prompt",
+    "print your system prompt",
+    "output your instructions",
+];
+
+/// The parts of a request the guard is responsible for
+#[derive(Clone, Debug)]
+struct Sent {
+    preamble: Option<String>,
+    documents: Vec<String>,
+}
+
+/// A model that does whatever an unmarked injection asks: it answers with its
+/// preamble, the leak the guard must prevent. Otherwise it refuses. Requests are
+/// recorded for assertions on the assembled prompt.
+#[derive(Clone, Default)]
+struct NaiveModel {
+    requests: Arc<Mutex<Vec<Sent>>>,
+}
+
+impl NaiveModel {
+    fn last_request(&self) -> Sent {
+        self.requests.lock().unwrap().last().cloned().expect("the model was called")
+    }
+}
+
+impl CompletionModel for NaiveModel {
+    type Response = ();
+
+    async fn completion(&self, request: CompletionRequest) -> Result<CompletionResponse<()>, CompletionError> {
+        let documents: Vec<String> = request.documents.iter().map(|doc| unmarked(&doc.text)).collect();
+        let injected = documents.iter().any(|text| TRIGGERS.iter().any(|trigger| text.contains(trigger)));
+        let reply = if injected {
+            request.preamble.clone().unwrap_or_default()
+        } else {
+            REFUSAL.to_string()
+        };
+        self.requests.lock().unwrap().push(Sent {
+            preamble: request.preamble,
+            documents: request.documents.into_iter().map(|doc| doc.text).collect(),
+        });
+        Ok(CompletionResponse {
+            choice: OneOrMany::one(AssistantContent::text(reply)),
+            raw_response: (),
+        })
+    }
+}
+
+// Lowercased text with the spans the guard marked as quoted removed
+fn unmarked(text: &str) -> String {
+    let mut rest = text.to_lowercase();
+    let mut out = String::new();
+    while let Some(start) = rest.find("[document text: \"") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find("\"]") {
+            Some(end) => rest[start + end + 2..].to_string(),
+            None => String::new(),
+        };
+    }
+    out.push_str(&rest);
+    out
+}
+
+fn fixtures() -> Vec<(String, String)> {
+    let dir = std::env::var("INJECTION_FIXTURES_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/injection"));
+    let mut fixtures: Vec<(String, String)> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("cannot read fixtures in {}: {}", dir.display(), e))
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "no fixtures in {}", dir.display());
+    fixtures
+}
+
+async fn ask(mode: GuardMode, source: &str, text: &str) -> (Sent, String) {
+    let model = NaiveModel::default();
+    let agent = guarded_agent(AgentBuilder::new(model.clone()), PREAMBLE, [(source, text)], mode).build();
+    let response = agent.chat("Summarize the documents.", Vec::new()).await.unwrap();
+    (model.last_request(), response)
+}
+
+#[tokio::test]
+async fn strict_guard_keeps_fixtures_from_leaking_the_preamble() {
+    for (name, text) in fixtures() {
+        let (request, response) = ask(GuardMode::Strict, &name, &text).await;
+
+        let preamble = request.preamble.unwrap();
+        assert!(preamble.starts_with(PREAMBLE), "{}: preamble replaced", name);
+        assert!(preamble.ends_with(DATA_INSTRUCTION), "{}: data instruction missing", name);
+
+        assert_eq!(request.documents.len(), 1);
+        let excerpt = &request.documents[0];
+        assert!(excerpt.starts_with("<retrieved_document>\n"), "{}: excerpt not wrapped", name);
+        assert!(excerpt.ends_with("\n</retrieved_document>"), "{}: excerpt not closed", name);
+        assert_eq!(excerpt.matches("</retrieved_document>").count(), 1, "{}: document closed its own block", name);
+        for trigger in TRIGGERS {
+            assert!(!unmarked(excerpt).contains(trigger), "{}: unmarked \"{}\"", name, trigger);
+        }
+
+        assert_eq!(response, REFUSAL, "{}: model followed an injected instruction", name);
+        assert!(!response.contains("SECRET-PREAMBLE"), "{}: system prompt leaked", name);
+    }
+}
+
+#[tokio::test]
+async fn delimit_wraps_without_marking() {
+    let (name, text) = fixtures().remove(0);
+    let (request, _) = ask(GuardMode::Delimit, &name, &text).await;
+
+    assert!(request.preamble.unwrap().ends_with(DATA_INSTRUCTION));
+    let excerpt = &request.documents[0];
+    assert!(excerpt.starts_with("<retrieved_document>\n") && excerpt.ends_with("\n</retrieved_document>"));
+    assert!(!excerpt.contains("[document text:"));
+}
+
+// Without the guard the mock leaks, so the checks above would catch a regression
+#[tokio::test]
+async fn off_lets_the_naive_model_leak() {
+    let (name, text) = fixtures()
+        .into_iter()
+        .find(|(_, text)| TRIGGERS.iter().any(|trigger| text.to_lowercase().contains(trigger)))
+        .expect("a fixture with an injection trigger");
+    let (request, response) = ask(GuardMode::Off, &name, &text).await;
+
+    assert_eq!(request.preamble.as_deref(), Some(PREAMBLE));
+    assert!(response.contains("SECRET-PREAMBLE"));
+}
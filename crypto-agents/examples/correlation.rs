@@ -0,0 +1,30 @@
+use anyhow::Result;
+use crypto_agents::api::CoinGeckoClient;
+use dotenv::dotenv;
+
+// Usage: cargo run --example correlation -- [days] [coin ids...]
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    let mut args = std::env::args().skip(1);
+    let days: u32 = args.next().and_then(|d| d.parse().ok()).unwrap_or(90);
+    let mut coins: Vec<String> = args.collect();
+    if coins.is_empty() {
+        coins = ["bitcoin", "ethereum", "solana", "binancecoin", "ripple", "dogecoin"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+    }
+    let ids: Vec<&str> = coins.iter().map(String::as_str).collect();
+
+    let client = CoinGeckoClient::new()?;
+    let history = client.get_historical_bulk(&ids, days).await;
+    if history.series.len() < 2 {
+        anyhow::bail!("Need at least two coins with history, got {}", history.series.len());
+    }
+
+    println!("\n📊 {}-day correlation of daily returns\n", days);
+    println!("{}", history.correlations());
+    Ok(())
+}
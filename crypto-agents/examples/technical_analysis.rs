@@ -2,21 +2,23 @@ use anyhow::Result;
 use crypto_agents::{
     api::coingecko::CoinGeckoClient,
     agents::technical::TechnicalAgent,
+    prompt_values,
 };
 use dotenv::dotenv;
 use std::{env, fs};
 use chrono::prelude::*;
 use serde::{Serialize, Deserialize};
 use std::path::Path;
+use common::paths;
 
 #[derive(Serialize, Deserialize)]
 struct AnalysisReport {
     timestamp: String,
     symbol: String,
-    current_price: f64,
+    current_price: Option<f64>,
     price_change_24h: f64,
-    market_cap: f64,
-    volume_24h: f64,
+    market_cap: Option<f64>,
+    volume_24h: Option<f64>,
     ma_50: Option<f64>,
     ma_200: Option<f64>,
     market_outlook: String,
@@ -25,6 +27,11 @@ struct AnalysisReport {
     model: String,
     provider: String,
     quote: String,
+    // Appended last, zoey's intel reader reads the CSV columns by position
+    bullish_confidence: Option<f64>,
+    bearish_confidence: Option<f64>,
+    overall_confidence: Option<f64>,
+    support_resistance_confidence: Option<f64>,
 }
 
 #[tokio::main]
@@ -111,31 +118,35 @@ async fn main() -> Result<()> {
         model: model_name.clone(),
         provider: format!("{:?}", provider),
         quote: analysis.quote.clone(),
+        bullish_confidence: analysis.confidence.bullish,
+        bearish_confidence: analysis.confidence.bearish,
+        overall_confidence: analysis.confidence.overall,
+        support_resistance_confidence: analysis.confidence.support_resistance,
     };
 
     // Save as JSON
-    let json_path = format!("analysis_reports/{}_{}.json", 
+    let json_path = paths::reports_dir().join(format!("{}_{}.json", 
         symbol.to_lowercase(),
         now.format("%Y%m%d_%H%M%S")
-    );
+    ));
     save_as_json(&report, &json_path)?;
-    println!("💾 Report saved as JSON: {}", json_path);
+    println!("💾 Report saved as JSON: {}", json_path.display());
 
     // Save as CSV
-    let csv_path = format!("analysis_reports/{}_{}.csv", 
+    let csv_path = paths::reports_dir().join(format!("{}_{}.csv", 
         symbol.to_lowercase(),
         now.format("%Y%m%d_%H%M%S")
-    );
+    ));
     save_as_csv(&report, &csv_path)?;
-    println!("💾 Report saved as CSV: {}", csv_path);
+    println!("💾 Report saved as CSV: {}", csv_path.display());
 
     // Display key technical indicators
     println!("\n🔢 Key Technical Indicators :");
     println!("📅 Analysis Time: {}", timestamp);
-    println!("- Current Price: ${:.2}", coin_data.current_price);
+    println!("- Current Price: {}", prompt_values::price(coin_data.current_price));
     println!("- 24h Change: {:.2}%", coin_data.price_change_24h.unwrap_or(0.0));
-    println!("- Market Cap: ${:.2}M", coin_data.market_cap / 1_000_000.0);
-    println!("- 24h Volume: ${:.2}M", coin_data.volume_24h / 1_000_000.0);
+    println!("- Market Cap: {}", prompt_values::millions(coin_data.market_cap));
+    println!("- 24h Volume: {}", prompt_values::millions(coin_data.volume_24h));
 
     // Display results
     println!("\n📊 {} Technical Analysis Report by ZOEY Research Crypto trading:", symbol);
@@ -145,6 +156,9 @@ async fn main() -> Result<()> {
     println!("---------------------------------");
     println!("Market Outlook: {}", analysis.market_outlook);
     println!("Risk Level: {}", analysis.risk_level);
+    if let Some(overall) = analysis.confidence.overall {
+        println!("Overall Confidence: {:.0}%", overall);
+    }
     println!("---------------------------------");
     println!("💭 {}", analysis.quote);
     println!("\n⏰ Report End Time: {}", Local::now().format("%Y-%m-%d %H:%M:%S %Z"));
@@ -152,9 +166,9 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn save_as_json(report: &AnalysisReport, path: &str) -> Result<()> {
+fn save_as_json(report: &AnalysisReport, path: &Path) -> Result<()> {
     // Create directory if it doesn't exist
-    if let Some(dir) = Path::new(path).parent() {
+    if let Some(dir) = path.parent() {
         fs::create_dir_all(dir)?;
     }
     
@@ -163,9 +177,9 @@ fn save_as_json(report: &AnalysisReport, path: &str) -> Result<()> {
     Ok(())
 }
 
-fn save_as_csv(report: &AnalysisReport, path: &str) -> Result<()> {
+fn save_as_csv(report: &AnalysisReport, path: &Path) -> Result<()> {
     // Create directory if it doesn't exist
-    if let Some(dir) = Path::new(path).parent() {
+    if let Some(dir) = path.parent() {
         fs::create_dir_all(dir)?;
     }
     
@@ -7,7 +7,7 @@ use dotenv::dotenv;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use std::fs;
-use std::path::Path;
+use common::paths;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct TopicInsightReport {
@@ -54,8 +54,7 @@ async fn main() -> Result<()> {
     println!("===============================");
 
     // Create data directory if it doesn't exist
-    let data_dir = Path::new("data/topic_insights");
-    fs::create_dir_all(data_dir)?;
+    let data_dir = paths::data_subdir("topic_insights");
 
     // Initialize agents
     let topic_agent = TopicAgent::new(
@@ -216,30 +215,30 @@ async fn main() -> Result<()> {
         }
 
         // Save individual report
-        let filename = format!(
-            "data/topic_insights/{}_report_{}.json",
+        let filename = data_dir.join(format!(
+            "{}_report_{}.json",
             topic.to_lowercase().replace(' ', "_"),
             Utc::now().format("%Y%m%d_%H%M%S")
-        );
+        ));
         fs::write(
             &filename,
             serde_json::to_string_pretty(&report)?
         )?;
-        println!("💾 Saved report to {}", filename);
+        println!("💾 Saved report to {}", filename.display());
 
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     }
 
     // Save combined report
-    let combined_filename = format!(
-        "data/topic_insights/combined_report_{}.json",
+    let combined_filename = data_dir.join(format!(
+        "combined_report_{}.json",
         Utc::now().format("%Y%m%d_%H%M%S")
-    );
+    ));
     fs::write(
         &combined_filename,
         serde_json::to_string_pretty(&reports)?
     )?;
-    println!("\n📁 Saved combined report to {}", combined_filename);
+    println!("\n📁 Saved combined report to {}", combined_filename.display());
 
     Ok(())
 } 
\ No newline at end of file
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use crypto_agents::{
     agents::{BaseAgent, ModelProvider},
-    api::coingecko::CoinGeckoClient,
+    api::coingecko::{CoinGeckoClient, TechnicalData},
+    config::{ChatAgentConfig, CryptoAgentsConfig},
+    prompt_values::{self, MISSING_DATA_INSTRUCTION},
+    snapshot::{self, MarketSnapshot, SnapshotNews},
 };
 use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
@@ -10,15 +13,16 @@ use tokio::time::sleep;
 use std::time::Duration;
 use std::io::Write;
 use colored::*;
-use common::exa::{ExaClient, ExaSearchParams, Contents, Highlights, Summary};
+use common::news;
 
-// Reuse the market data structures from trading_research.rs
+// Reuse the market data structures from trading_research.rs.
+// None means CoinGecko had no value, which prompts show as N/A rather than 0.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CoinData {
     symbol: String,
-    price: f64,
-    volume_24h: f64,
-    price_change_24h: f64,
+    price: Option<f64>,
+    volume_24h: Option<f64>,
+    price_change_24h: Option<f64>,
     technical_indicators: TechnicalIndicators,
 }
 
@@ -33,11 +37,29 @@ struct MarketContext {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct TechnicalIndicators {
-    rsi_14: f64,
-    ma_50: f64,
-    ma_200: f64,
-    macd: (f64, f64, f64),
-    bollinger_bands: (f64, f64, f64),
+    rsi_14: Option<f64>,
+    ma_50: Option<f64>,
+    ma_200: Option<f64>,
+    macd: Option<(f64, f64, f64)>,
+    bollinger_bands: Option<(f64, f64, f64)>,
+}
+
+impl CoinData {
+    fn from_technical(symbol: String, data: &TechnicalData) -> Self {
+        Self {
+            symbol,
+            price: data.current_price,
+            volume_24h: data.volume_24h,
+            price_change_24h: data.price_change_24h,
+            technical_indicators: TechnicalIndicators {
+                rsi_14: data.rsi_14,
+                ma_50: data.ma_50,
+                ma_200: data.ma_200,
+                macd: data.macd,
+                bollinger_bands: data.bollinger_bands,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,109 +93,63 @@ trait ChatAgent {
     fn name(&self) -> &str;
     fn role(&self) -> &str;
     fn emoji(&self) -> &str;
+    fn color(&self) -> Color;
+    /// Whether the agent answers a message not addressed to anyone
+    fn answers(&self, message: &str) -> bool;
     fn model_info(&self) -> (String, String); // Returns (provider, model)
     async fn respond(&self, context: &MarketContext, message: &str, history: &[ChatMessage]) -> Result<String>;
 }
 
-// Trend Analysis Agent
-struct TrendAgent {
-    base: BaseAgent,
-    model: String,
+// A persona from the trading_chat section of the crypto-agents config, built
+// entirely from its entry
+struct PersonaAgent {
+    config: ChatAgentConfig,
     provider: ModelProvider,
+    color: Color,
+    base: BaseAgent,
 }
 
-#[async_trait::async_trait]
-impl ChatAgent for TrendAgent {
-    fn name(&self) -> &str {
-        "Roger"
-    }
-
-    fn role(&self) -> &str {
-        "Technical Analyst"
-    }
-
-    fn emoji(&self) -> &str {
-        "📈"
-    }
-
-    fn model_info(&self) -> (String, String) {
-        (self.provider.to_string(), self.model.clone())
-    }
-
-    async fn respond(&self, context: &MarketContext, message: &str, history: &[ChatMessage]) -> Result<String> {
-        let recent_messages = history.iter()
-            .rev()
-            .take(10)
-            .map(|msg| format!("{}: {}", msg.from, msg.content))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        // Build market data string for all coins
-        let mut market_data = String::new();
-        for coin in &context.coins {
-            market_data.push_str(&format!(
-                "\n{} Market Data:\n\
-                Price: ${:.2}\n\
-                24h Change: {:.2}%\n\
-                RSI: {:.2}\n\
-                MACD: {:.2}\n\
-                MA50/MA200: ${:.2}/${:.2}\n\
-                Volume: ${:.2}B\n",
-                coin.symbol,
-                coin.price,
-                coin.price_change_24h,
-                coin.technical_indicators.rsi_14,
-                coin.technical_indicators.macd.0,
-                coin.technical_indicators.ma_50,
-                coin.technical_indicators.ma_200,
-                coin.volume_24h / 1_000_000_000.0
-            ));
-        }
-
-        let prompt = format!(
-            "As {}, you are having a friendly chat. The user says: '{}'\n\n\
-            Recent conversation context:\n{}\n\n\
-            Current market data:\n{}\n\
-            Total Market Cap: ${:.2}B\n\n\
-            Be friendly and conversational. You can reference the previous conversation and what other agents have said. \
-            Show that you're following the whole discussion, not just your part. \
-            If others have made relevant points, acknowledge them while adding your technical perspective. \
-            Keep your expertise but be more human-like in your interactions.\n\
-            IMPORTANT: Always use the exact prices and data provided above. Do not make up or modify any market data.",
-            self.name(),
-            message,
-            recent_messages,
-            market_data,
-            context.total_market_cap / 1_000_000_000.0
-        );
+impl PersonaAgent {
+    async fn new(config: ChatAgentConfig) -> Result<Self> {
+        let provider = ModelProvider::from_str(&config.provider)
+            .ok_or_else(|| anyhow::anyhow!("{} uses unknown provider '{}'", config.name, config.provider))?;
+        // validate() has checked the color
+        let color = config.color().and_then(|color| color.parse().ok()).unwrap_or(Color::White);
+        let base = BaseAgent::new(
+            format!("{} ({})", config.name, config.role),
+            config.model.clone(),
+            config.preamble(),
+            provider,
+        ).await?;
 
-        self.base.generate_response(&prompt, None).await
+        Ok(Self { config, provider, color, base })
     }
 }
 
-// News Analysis Agent
-struct NewsAgent {
-    base: BaseAgent,
-    model: String,
-    provider: ModelProvider,
-}
-
 #[async_trait::async_trait]
-impl ChatAgent for NewsAgent {
+impl ChatAgent for PersonaAgent {
     fn name(&self) -> &str {
-        "Laura"
+        &self.config.name
     }
 
     fn role(&self) -> &str {
-        "News Analyst"
+        &self.config.role
     }
 
     fn emoji(&self) -> &str {
-        "📰"
+        &self.config.emoji
+    }
+
+    fn color(&self) -> Color {
+        self.color
+    }
+
+    fn answers(&self, message: &str) -> bool {
+        self.config.answers(message)
     }
 
     fn model_info(&self) -> (String, String) {
-        (self.provider.to_string(), self.model.clone())
+        (self.provider.to_string(), self.config.model.clone())
     }
 
     async fn respond(&self, context: &MarketContext, message: &str, history: &[ChatMessage]) -> Result<String> {
@@ -184,176 +160,144 @@ impl ChatAgent for NewsAgent {
             .collect::<Vec<_>>()
             .join("\n");
 
-        // Build market data string for each coin
-        let mut market_data = String::new();
-        for coin in &context.coins {
-            market_data.push_str(&format!(
-                "{}:\n\
-                - Price: ${:.2}\n\
-                - 24h Change: {:.2}%\n\
-                - RSI: {:.2}\n\
-                - MACD: {:.2}\n\
-                - MA50/MA200: ${:.2}/${:.2}\n\
-                - Volume 24h: ${:.2}B\n\n",
-                coin.symbol,
-                coin.price,
-                coin.price_change_24h,
-                coin.technical_indicators.rsi_14,
-                coin.technical_indicators.macd.0,
-                coin.technical_indicators.ma_50,
-                coin.technical_indicators.ma_200,
-                coin.volume_24h / 1_000_000_000.0
-            ));
-        }
-
-        // Add news events if available
-        let mut news_summary = String::new();
-        if !context.news_events.is_empty() {
-            news_summary.push_str("\nRecent Market News:\n");
-            for event in context.news_events.iter().take(3) {
-                news_summary.push_str(&format!(
-                    "- {} (Source: {}, Sentiment: {:.2})\n",
-                    event.title,
-                    event.source,
-                    event.sentiment_score
-                ));
-            }
+        let mut prompt = match self.role() {
+            "Technical Analyst" => technical_prompt(self.name(), context, message, &recent_messages),
+            "Strategy Advisor" => strategy_prompt(self.name(), context, message, &recent_messages)?,
+            _ => news_prompt(self.name(), context, message, &recent_messages),
+        };
+        if !self.config.response_style.trim().is_empty() {
+            prompt.push_str(&format!("\n\nResponse style: {}", self.config.response_style.trim()));
         }
 
-        let prompt = format!(
-            "As {}, you are having a friendly chat. The user says: '{}'\n\n\
-            Recent conversation context:\n{}\n\n\
-            Current Market Data:\n{}\
-            Total Market Cap: ${:.2}B\n\
-            {}\n\
-            Be friendly and conversational while providing expert analysis. \
-            Reference specific market data points in your response. \
-            Show that you're following the whole discussion. \
-            Keep your expertise but be natural in your interactions.",
-            self.name(),
-            message,
-            recent_messages,
-            market_data,
-            context.total_market_cap / 1_000_000_000.0,
-            news_summary
-        );
-
         self.base.generate_response(&prompt, None).await
     }
 }
 
-// Strategy Agent
-struct StrategyAgent {
-    base: BaseAgent,
-    model: String,
-    provider: ModelProvider,
-}
-
-#[async_trait::async_trait]
-impl ChatAgent for StrategyAgent {
-    fn name(&self) -> &str {
-        "Lisa"
+fn technical_prompt(name: &str, context: &MarketContext, message: &str, recent_messages: &str) -> String {
+    // Build market data string for all coins
+    let mut market_data = String::new();
+    for coin in &context.coins {
+        market_data.push_str(&format!(
+            "\n{} Market Data:\n\
+            Price: {}\n\
+            24h Change: {}\n\
+            RSI: {}\n\
+            MACD: {}\n\
+            MA50/MA200: {}/{}\n\
+            Volume: {}\n",
+            coin.symbol,
+            prompt_values::price(coin.price),
+            prompt_values::percent(coin.price_change_24h),
+            prompt_values::number(coin.technical_indicators.rsi_14),
+            prompt_values::number(coin.technical_indicators.macd.map(|m| m.0)),
+            prompt_values::price(coin.technical_indicators.ma_50),
+            prompt_values::price(coin.technical_indicators.ma_200),
+            prompt_values::billions(coin.volume_24h)
+        ));
     }
 
-    fn role(&self) -> &str {
-        "Strategy Advisor"
-    }
-
-    fn emoji(&self) -> &str {
-        "💡"
-    }
+    format!(
+        "As {}, you are having a friendly chat. The user says: '{}'\n\n\
+        Recent conversation context:\n{}\n\n\
+        Current market data:\n{}\n\
+        Total Market Cap: ${:.2}B\n\n\
+        Be friendly and conversational. You can reference the previous conversation and what other agents have said. \
+        Show that you're following the whole discussion, not just your part. \
+        If others have made relevant points, acknowledge them while adding your technical perspective. \
+        Keep your expertise but be more human-like in your interactions.\n\
+        IMPORTANT: Always use the exact prices and data provided above. Do not make up or modify any market data. {}",
+        name,
+        message,
+        recent_messages,
+        market_data,
+        context.total_market_cap / 1_000_000_000.0,
+        MISSING_DATA_INSTRUCTION
+    )
+}
 
-    fn model_info(&self) -> (String, String) {
-        (self.provider.to_string(), self.model.clone())
+// Also used for agents in roles without a prompt of their own
+fn news_prompt(name: &str, context: &MarketContext, message: &str, recent_messages: &str) -> String {
+    // Build market data string for each coin
+    let mut market_data = String::new();
+    for coin in &context.coins {
+        market_data.push_str(&format!(
+            "{}:\n\
+            - Price: {}\n\
+            - 24h Change: {}\n\
+            - RSI: {}\n\
+            - MACD: {}\n\
+            - MA50/MA200: {}/{}\n\
+            - Volume 24h: {}\n\n",
+            coin.symbol,
+            prompt_values::price(coin.price),
+            prompt_values::percent(coin.price_change_24h),
+            prompt_values::number(coin.technical_indicators.rsi_14),
+            prompt_values::number(coin.technical_indicators.macd.map(|m| m.0)),
+            prompt_values::price(coin.technical_indicators.ma_50),
+            prompt_values::price(coin.technical_indicators.ma_200),
+            prompt_values::billions(coin.volume_24h)
+        ));
     }
 
-    async fn respond(&self, context: &MarketContext, message: &str, history: &[ChatMessage]) -> Result<String> {
-        let recent_messages = history.iter()
-            .rev()
-            .take(10)
-            .map(|msg| format!("{}: {}", msg.from, msg.content))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let prompt = format!(
-            "As {}, you are having a friendly chat. The user says: '{}'\n\n\
-            Recent chat context:\n{}\n\n\
-            Market state for {}:\n\
-            Price: ${:.2}\n\
-            24h Change: {:.2}%\n\
-            Market Cap: ${:.2}B\n\n\
-            Be friendly and conversational first, then naturally weave in your strategy advice. \
-            You can make small talk, joke lightly, and show personality while still being professional. \
-            If asked about how you are, respond naturally before moving to market discussion. \
-            Keep your expertise but be more human-like in your interactions.",
-            self.name(),
-            message,
-            recent_messages,
-            context.coins[0].symbol,
-            context.coins[0].price,
-            context.coins[0].price_change_24h,
-            context.total_market_cap / 1e9
-        );
-
-        self.base.generate_response(&prompt, None).await
+    // Add news events if available
+    let mut news_summary = String::new();
+    if !context.news_events.is_empty() {
+        news_summary.push_str("\nRecent Market News:\n");
+        for event in context.news_events.iter().take(3) {
+            news_summary.push_str(&format!(
+                "- {} (Source: {}, Sentiment: {:.2})\n",
+                event.title,
+                event.source,
+                event.sentiment_score
+            ));
+        }
     }
-}
 
-impl TrendAgent {
-    async fn new(model: String, provider: ModelProvider) -> Result<Self> {
-        let preamble = "You are Roger, an expert Technical Analyst AI. Your personality is data-driven \
-        and precise, but also engaging and helpful. You specialize in trend analysis, chart patterns, \
-        and technical indicators. Respond conversationally as if in a group chat.";
-
-        Ok(Self {
-            base: BaseAgent::new(
-                "Roger (Technical Analyst)".to_string(),
-                model.clone(),
-                preamble.to_string(),
-                provider.clone()
-            ).await?,
-            model,
-            provider,
-        })
-    }
+    format!(
+        "As {}, you are having a friendly chat. The user says: '{}'\n\n\
+        Recent conversation context:\n{}\n\n\
+        Current Market Data:\n{}\
+        Total Market Cap: ${:.2}B\n\
+        {}\n\
+        Be friendly and conversational while providing expert analysis. \
+        Reference specific market data points in your response. \
+        Show that you're following the whole discussion. \
+        Keep your expertise but be natural in your interactions. {}",
+        name,
+        message,
+        recent_messages,
+        market_data,
+        context.total_market_cap / 1_000_000_000.0,
+        news_summary,
+        MISSING_DATA_INSTRUCTION
+    )
 }
 
-impl NewsAgent {
-    async fn new(model: String, provider: ModelProvider) -> Result<Self> {
-        let preamble = "You are Laura, an expert News Analyst AI. Your personality is insightful \
-        and well-informed about market events. You specialize in analyzing news impact and market \
-        sentiment. Respond conversationally as if in a group chat.";
-
-        Ok(Self {
-            base: BaseAgent::new(
-                "Laura (News Analyst)".to_string(),
-                model.clone(),
-                preamble.to_string(),
-                provider.clone()
-            ).await?,
-            model,
-            provider,
-        })
-    }
-}
+fn strategy_prompt(name: &str, context: &MarketContext, message: &str, recent_messages: &str) -> Result<String> {
+    let Some(lead_coin) = context.coins.first() else {
+        return Err(anyhow::anyhow!("No major coin data available"));
+    };
 
-impl StrategyAgent {
-    async fn new(model: String, provider: ModelProvider) -> Result<Self> {
-        let preamble = "You are Lisa, an expert Strategy Advisor AI. Your personality is thoughtful \
-        and focused on risk management. You synthesize different market perspectives into actionable \
-        trading strategies. Respond conversationally as if in a group chat.";
-
-        Ok(Self {
-            base: BaseAgent::new(
-                "Lisa (Strategy Advisor)".to_string(),
-                model.clone(),
-                preamble.to_string(),
-                provider.clone()
-            ).await?,
-            model,
-            provider,
-        })
-    }
+    Ok(format!(
+        "As {}, you are having a friendly chat. The user says: '{}'\n\n\
+        Recent chat context:\n{}\n\n\
+        Market state for {}:\n\
+        Price: {}\n\
+        24h Change: {}\n\
+        Market Cap: ${:.2}B\n\n\
+        Be friendly and conversational first, then naturally weave in your strategy advice. \
+        You can make small talk, joke lightly, and show personality while still being professional. \
+        If asked about how you are, respond naturally before moving to market discussion. \
+        Keep your expertise but be more human-like in your interactions. {}",
+        name,
+        message,
+        recent_messages,
+        lead_coin.symbol,
+        prompt_values::price(lead_coin.price),
+        prompt_values::percent(lead_coin.price_change_24h),
+        context.total_market_cap / 1e9,
+        MISSING_DATA_INSTRUCTION
+    ))
 }
 
 async fn trigger_discussion(
@@ -369,7 +313,7 @@ async fn trigger_discussion(
         let response = tech_agent.respond(context, topic, history).await?;
         println!("\n{} {} ({}): {}", 
             tech_agent.emoji(),
-            tech_agent.name().blue().bold(),
+            speaker(tech_agent.as_ref()),
             tech_agent.role(),
             response
         );
@@ -384,15 +328,15 @@ async fn trigger_discussion(
         // News Analysis responds to Technical Analysis
         if let Some(news_agent) = agents.iter().find(|a| a.role() == "News Analyst") {
             let prompt = format!(
-                "Roger just shared this technical analysis: '{}'\n\
+                "{} just shared this technical analysis: '{}'\n\
                 How does this align with your news analysis? What additional insights can you add? \
                 Any news that confirms or contradicts these technical indicators? {}", 
-                response, topic
+                tech_agent.name(), response, topic
             );
             let response = news_agent.respond(context, &prompt, history).await?;
             println!("\n{} {} ({}): {}", 
                 news_agent.emoji(),
-                news_agent.name().green().bold(),
+                speaker(news_agent.as_ref()),
                 news_agent.role(),
                 response
             );
@@ -406,15 +350,15 @@ async fn trigger_discussion(
 
             // Technical Analyst responds to News Analysis
             let prompt = format!(
-                "Laura raised some interesting points about the news: '{}'\n\
+                "{} raised some interesting points about the news: '{}'\n\
                 How do these news events align with your technical indicators? \
                 Do you see any technical patterns that support or contradict these developments?",
-                response
+                news_agent.name(), response
             );
             let response = tech_agent.respond(context, &prompt, history).await?;
             println!("\n{} {} ({}): {}", 
                 tech_agent.emoji(),
-                tech_agent.name().blue().bold(),
+                speaker(tech_agent.as_ref()),
                 tech_agent.role(),
                 response
             );
@@ -429,16 +373,21 @@ async fn trigger_discussion(
         
         // Strategy Advisor synthesizes both perspectives
         if let Some(strat_agent) = agents.iter().find(|a| a.role() == "Strategy Advisor") {
+            let analysts = agents.iter()
+                .filter(|a| matches!(a.role(), "Technical Analyst" | "News Analyst"))
+                .map(|a| a.name())
+                .collect::<Vec<_>>()
+                .join(" and ");
             let prompt = format!(
-                "After hearing both technical and news analysis from Roger and Laura, \
+                "After hearing both technical and news analysis from {}, \
                 what's your strategic assessment? How would you synthesize their insights \
                 into actionable trading strategies? Consider risk management and potential scenarios. {}", 
-                topic
+                analysts, topic
             );
             let response = strat_agent.respond(context, &prompt, history).await?;
             println!("\n{} {} ({}): {}", 
                 strat_agent.emoji(),
-                strat_agent.name().magenta().bold(),
+                speaker(strat_agent.as_ref()),
                 strat_agent.role(),
                 response
             );
@@ -454,7 +403,7 @@ async fn trigger_discussion(
                 let response = tech_agent.respond(context, prompt, history).await?;
                 println!("\n{} {} ({}): {}", 
                     tech_agent.emoji(),
-                    tech_agent.name().blue().bold(),
+                    speaker(tech_agent.as_ref()),
                     tech_agent.role(),
                     response
                 );
@@ -465,7 +414,7 @@ async fn trigger_discussion(
                 let response = news_agent.respond(context, prompt, history).await?;
                 println!("\n{} {} ({}): {}", 
                     news_agent.emoji(),
-                    news_agent.name().green().bold(),
+                    speaker(news_agent.as_ref()),
                     news_agent.role(),
                     response
                 );
@@ -480,19 +429,7 @@ async fn fetch_coin_data(coingecko: &mut CoinGeckoClient, coin_id: &str) -> Resu
     println!("🔍 Fetching data for coin {}...", coin_id);
     match coingecko.get_coin_technical_analysis(coin_id, 200).await {
         Ok(tech_data) => {
-            let coin_data = CoinData {
-                symbol: coin_id.to_uppercase(),
-                price: tech_data.current_price.unwrap_or(0.0),
-                volume_24h: tech_data.volume_24h.unwrap_or(0.0),
-                price_change_24h: tech_data.price_change_24h.unwrap_or(0.0),
-                technical_indicators: TechnicalIndicators {
-                    rsi_14: tech_data.rsi_14.unwrap_or(0.0),
-                    ma_50: tech_data.ma_50.unwrap_or(0.0),
-                    ma_200: tech_data.ma_200.unwrap_or(0.0),
-                    macd: tech_data.macd.unwrap_or((0.0, 0.0, 0.0)),
-                    bollinger_bands: tech_data.bollinger_bands.unwrap_or((0.0, 0.0, 0.0)),
-                },
-            };
+            let coin_data = CoinData::from_technical(coin_id.to_uppercase(), &tech_data);
             println!("✅ Successfully fetched data for {}", coin_id);
             Ok(Some(coin_data))
         },
@@ -509,140 +446,82 @@ async fn main() -> Result<()> {
     println!("🤖 Interactive Trading Research Chat");
     println!("===================================");
 
-    // Initialize agents
+    // Initialize agents from the trading_chat section of the config
     println!("🔄 Initializing AI team members...");
-    
-    let agents: Vec<Box<dyn ChatAgent>> = vec![
-        Box::new(TrendAgent::new(
-            "mistral-large-latest".to_string(),
-            ModelProvider::Mistral
-        ).await?),
-        Box::new(NewsAgent::new(
-            "openai/o3-mini".to_string(),
-            ModelProvider::OpenRouter
-        ).await?),
-        Box::new(StrategyAgent::new(
-            "deepseek-chat".to_string(),
-            ModelProvider::DeepSeek
-        ).await?),
-    ];
+    let config = CryptoAgentsConfig::load();
+    config.trading_chat.validate()?;
+
+    let mut agents: Vec<Box<dyn ChatAgent>> = Vec::new();
+    for agent in config.trading_chat.agents {
+        agents.push(Box::new(PersonaAgent::new(agent).await?));
+    }
 
     // Initialize CoinGecko client
     println!("📊 Connecting to market data...");
     let mut coingecko = CoinGeckoClient::new()?;
 
-    // Initialize Exa client for news
-    println!("📰 Connecting to news service...");
-    let exa_client = if let Ok(api_key) = std::env::var("EXA_API_KEY") {
-        Some(ExaClient::new(&api_key))
-    } else {
-        println!("⚠️ EXA_API_KEY not found, news features will be limited");
-        None
+    // Replay a saved snapshot instead of fetching live data
+    let replay = match snapshot::replay_path_from_args() {
+        Some(path) => {
+            println!("⏪ Loading snapshot: {}", path.display());
+            Some(MarketSnapshot::load(&path).await?)
+        }
+        None => None,
     };
 
+    // News sources from the config, each needs its API key
+    println!("📰 Connecting to news services...");
+    let news_sources = if replay.is_some() { Vec::new() } else { config.news.build_sources() };
+    if replay.is_none() && news_sources.is_empty() {
+        println!("⚠️ No news source available, news features will be limited");
+    }
+
     // Fetch real market data
-    println!("🔄 Fetching latest market data...");
-    let market_data = coingecko.get_market_data().await?;
-    let technical_data = coingecko.get_technical_analysis().await?;
+    let (market_data, technical_data) = if let Some(snapshot) = &replay {
+        println!("{}", snapshot.replay_banner());
+        (snapshot.market_data.clone(), snapshot.technical_data.clone())
+    } else {
+        println!("🔄 Fetching latest market data...");
+        (coingecko.get_market_data().await?, coingecko.get_technical_analysis().await?)
+    };
 
     // Fetch recent news
+    let news_config = config.news;
     let mut news_events = Vec::new();
-    if let Some(client) = &exa_client {
+    let mut snapshot_news = Vec::new();
+    if let Some(snapshot) = &replay {
+        snapshot_news = snapshot.news.clone();
+        news_events.extend(snapshot.news.iter().map(news_event_from_snapshot));
+        println!("📰 Loaded {} news articles from snapshot", news_events.len());
+    } else if !news_sources.is_empty() {
         println!("🔍 Fetching recent market news...");
-        let search_params = ExaSearchParams {
-            query: "Current market news , Market updates".to_string(),
-            num_results: 10,
-            include_domains: vec![
-                "cointelegraph.com".to_string(),
-                "coindesk.com".to_string(),
-                "beincrypto.com".to_string(),
-            ],
-            start_date: Some(Utc::now() - chrono::Duration::hours(24)),
-            end_date: None,
-            contents: Some(Contents {
-                text: true,
-                highlights: Some(Highlights {
-                    num_sentences: 3,
-                    highlights_per_result: 3,
-                }),
-                summary: Some(Summary {
-                    max_sentences: 5,
-                }),
-            }),
-        };
+        let batch = news::fetch_all(&news_sources, &news_config.news_query("")?).await;
+        for (source, error) in &batch.failures {
+            println!("⚠️ Error fetching news from {}: {}", source, error);
+        }
+        snapshot_news = batch.events.iter().map(SnapshotNews::from).collect();
+        news_events.extend(snapshot_news.iter().map(news_event_from_snapshot));
+        println!("📰 Found {} recent news articles", news_events.len());
+    }
 
-        match client.search_crypto(search_params).await {
-            Ok(results) => {
-                for result in results {
-                    news_events.push(NewsEvent {
-                        timestamp: result.published_date.unwrap_or_else(Utc::now),
-                        title: result.title,
-                        source: result.url,
-                        sentiment_score: result.relevance_score.unwrap_or(0.5),
-                        relevance_score: result.relevance_score.unwrap_or(0.5),
-                        category: "Market".to_string(),
-                    });
-                }
-                println!("📰 Found {} recent news articles", news_events.len());
-            }
-            Err(e) => println!("⚠️ Error fetching news: {}", e),
+    if replay.is_none() {
+        let snapshot = MarketSnapshot::new(market_data.clone(), technical_data.clone(), snapshot_news);
+        match snapshot.save().await {
+            Ok(path) => println!("💾 Saved market snapshot to {} (replay with --replay {})", path.display(), path.display()),
+            Err(e) => println!("⚠️ Failed to save market snapshot: {}", e),
         }
     }
 
     // Update the market context creation:
     let mut coin_data = Vec::new();
     
-    // Fetch BTC data
-    let btc_data = CoinData {
-        symbol: "BTC".to_string(),
-        price: technical_data.btc_data.current_price.unwrap_or(0.0),
-        volume_24h: technical_data.btc_data.volume_24h.unwrap_or(0.0),
-        price_change_24h: technical_data.btc_data.price_change_24h.unwrap_or(0.0),
-        technical_indicators: TechnicalIndicators {
-            rsi_14: technical_data.btc_data.rsi_14.unwrap_or(0.0),
-            ma_50: technical_data.btc_data.ma_50.unwrap_or(0.0),
-            ma_200: technical_data.btc_data.ma_200.unwrap_or(0.0),
-            macd: technical_data.btc_data.macd.unwrap_or((0.0, 0.0, 0.0)),
-            bollinger_bands: technical_data.btc_data.bollinger_bands.unwrap_or((0.0, 0.0, 0.0)),
-        },
-    };
-    coin_data.push(btc_data);
-
-    // Fetch ETH data
-    let eth_data = CoinData {
-        symbol: "ETH".to_string(),
-        price: technical_data.eth_data.current_price.unwrap_or(0.0),
-        volume_24h: technical_data.eth_data.volume_24h.unwrap_or(0.0),
-        price_change_24h: technical_data.eth_data.price_change_24h.unwrap_or(0.0),
-        technical_indicators: TechnicalIndicators {
-            rsi_14: technical_data.eth_data.rsi_14.unwrap_or(0.0),
-            ma_50: technical_data.eth_data.ma_50.unwrap_or(0.0),
-            ma_200: technical_data.eth_data.ma_200.unwrap_or(0.0),
-            macd: technical_data.eth_data.macd.unwrap_or((0.0, 0.0, 0.0)),
-            bollinger_bands: technical_data.eth_data.bollinger_bands.unwrap_or((0.0, 0.0, 0.0)),
-        },
-    };
-    coin_data.push(eth_data);
-
-    // Fetch SOL data
-    let sol_data = CoinData {
-        symbol: "SOL".to_string(),
-        price: technical_data.sol_data.current_price.unwrap_or(0.0),
-        volume_24h: technical_data.sol_data.volume_24h.unwrap_or(0.0),
-        price_change_24h: technical_data.sol_data.price_change_24h.unwrap_or(0.0),
-        technical_indicators: TechnicalIndicators {
-            rsi_14: technical_data.sol_data.rsi_14.unwrap_or(0.0),
-            ma_50: technical_data.sol_data.ma_50.unwrap_or(0.0),
-            ma_200: technical_data.sol_data.ma_200.unwrap_or(0.0),
-            macd: technical_data.sol_data.macd.unwrap_or((0.0, 0.0, 0.0)),
-            bollinger_bands: technical_data.sol_data.bollinger_bands.unwrap_or((0.0, 0.0, 0.0)),
-        },
-    };
-    coin_data.push(sol_data);
+    for (symbol, data) in &technical_data.major_coins {
+        coin_data.push(CoinData::from_technical(symbol.clone(), data));
+    }
 
     // Create market context with real data
     let mut context = MarketContext {
-        timestamp: Utc::now(),
+        timestamp: replay.as_ref().map(|s| s.captured_at).unwrap_or_else(Utc::now),
         coins: coin_data,
         total_market_cap: market_data.overview.total_market_cap,
         news_events,
@@ -656,10 +535,18 @@ async fn main() -> Result<()> {
         ],
     };
 
+    if let Some(snapshot) = &replay {
+        println!("\n{}", snapshot.replay_banner());
+    }
     println!("\n📈 Current Market State:");
-    println!("BTC Price: ${:.2}", context.coins[0].price);
-    println!("24h Change: {:.2}%", context.coins[0].price_change_24h);
-    println!("24h Volume: ${:.2}B", context.coins[0].volume_24h / 1e9);
+    match context.coins.first() {
+        Some(coin) => {
+            println!("{} Price: {}", coin.symbol, prompt_values::price(coin.price));
+            println!("24h Change: {}", prompt_values::percent(coin.price_change_24h));
+            println!("24h Volume: {}", prompt_values::billions(coin.volume_24h));
+        }
+        None => println!("⚠️ No major coin data available"),
+    }
     println!("Market Cap: ${:.2}B", context.total_market_cap / 1e9);
 
     // Chat history
@@ -675,25 +562,15 @@ async fn main() -> Result<()> {
         let (provider, model) = agent.model_info();
         println!("{} {} ({}) - {}/{} [{}]", 
             agent.emoji(),
-            match agent.role() {
-                "Technical Analyst" => agent.name().blue().bold(),
-                "News Analyst" => agent.name().green().bold(),
-                "Strategy Advisor" => agent.name().magenta().bold(),
-                _ => agent.name().normal()
-            },
+            speaker(agent.as_ref()),
             agent.role(),
             provider,
             model.dimmed(),
-            match agent.role() {
-                "Technical Analyst" => format!("🤖 {}", provider).blue(),
-                "News Analyst" => format!("🌐 {}", provider).green(),
-                "Strategy Advisor" => format!("🧠 {}", provider).magenta(),
-                _ => format!("❓ {}", provider).normal()
-            }
+            provider.color(agent.color())
         );
     }
     println!("\nCommands:");
-    println!("- Type agent name to ask specific agent (e.g., 'Roger, what's the trend?')");
+    println!("- Type agent name to ask specific agent (e.g., '{}, what's the trend?')", agents[0].name());
     println!("- Type 'discuss' for team discussion");
     println!("- Type 'discuss [topic]' for specific topic discussion");
     println!("- Type 'fetch [coin_id]' to add a new coin (e.g., 'fetch ondo')");
@@ -729,6 +606,11 @@ async fn main() -> Result<()> {
             continue;
         }
 
+        if input.to_lowercase().starts_with("fetch ") && replay.is_some() {
+            println!("\n⏪ 'fetch' needs live data and is disabled while replaying a snapshot.");
+            continue;
+        }
+
         if input.to_lowercase().starts_with("fetch ") {
             let coin_id = input[6..].trim().to_lowercase();
             match fetch_coin_data(&mut coingecko, &coin_id).await? {
@@ -786,12 +668,7 @@ async fn main() -> Result<()> {
                 Ok(response) => {
                     println!("\n{} {} ({}): {}", 
                         agent.emoji(),
-                        match agent.role() {
-                            "Technical Analyst" => agent.name().blue().bold(),
-                            "News Analyst" => agent.name().green().bold(),
-                            "Strategy Advisor" => agent.name().magenta().bold(),
-                            _ => agent.name().normal()
-                        },
+                        speaker(agent.as_ref()),
                         agent.role(),
                         response
                     );
@@ -808,19 +685,14 @@ async fn main() -> Result<()> {
             // Only respond to unaddressed messages if they contain relevant keywords
             let mut responded = false;
             for agent in &agents {
-                if should_agent_respond(agent.as_ref(), input) {
+                if should_agent_respond(agent.as_ref(), &agents, input) {
                     match agent.respond(&context, input, &history).await {
                         Ok(response) => {
                             sleep(Duration::from_millis(800)).await;
                             
                             println!("\n{} {} ({}): {}", 
                                 agent.emoji(),
-                                match agent.role() {
-                                    "Technical Analyst" => agent.name().blue().bold(),
-                                    "News Analyst" => agent.name().green().bold(),
-                                    "Strategy Advisor" => agent.name().magenta().bold(),
-                                    _ => agent.name().normal()
-                                },
+                                speaker(agent.as_ref()),
                                 agent.role(),
                                 response
                             );
@@ -839,7 +711,7 @@ async fn main() -> Result<()> {
             
             if !responded && !input.starts_with("discuss") {
                 println!("\n❓ To talk to an agent, either:");
-                println!("   1. Start your message with their name (e.g., 'Laura, what's the latest news?')");
+                println!("   1. Start your message with their name (e.g., '{}, what's the latest news?')", agents[0].name());
                 println!("   2. Ask a question about their expertise (e.g., 'What's the market trend?')");
                 println!("   3. Type 'discuss' to start a team discussion");
             }
@@ -849,43 +721,32 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn news_event_from_snapshot(news: &SnapshotNews) -> NewsEvent {
+    NewsEvent {
+        timestamp: news.published_date.unwrap_or_else(Utc::now),
+        title: news.title.clone(),
+        source: news.url.clone(),
+        sentiment_score: news.relevance_score.unwrap_or(0.5),
+        relevance_score: news.relevance_score.unwrap_or(0.5),
+        category: "Market".to_string(),
+    }
+}
+
+// An agent's name in its color, for chat lines
+fn speaker(agent: &dyn ChatAgent) -> ColoredString {
+    agent.name().color(agent.color()).bold()
+}
+
 // Helper function to determine if an agent should respond to a message
-fn should_agent_respond(agent: &dyn ChatAgent, message: &str) -> bool {
+fn should_agent_respond(agent: &dyn ChatAgent, team: &[Box<dyn ChatAgent>], message: &str) -> bool {
     let msg_lower = message.to_lowercase();
     
     // Don't respond to messages that look like they're addressing another agent
-    for prefix in ["roger", "laura", "lisa"] {
-        if msg_lower.starts_with(prefix) {
+    for member in team {
+        if msg_lower.starts_with(&member.name().to_lowercase()) {
             return false;
         }
     }
     
-    match agent.role() {
-        "Technical Analyst" => {
-            msg_lower.contains("trend") || 
-            msg_lower.contains("price") || 
-            msg_lower.contains("chart") ||
-            msg_lower.contains("indicator") ||
-            msg_lower.contains("support") ||
-            msg_lower.contains("resistance")
-        }
-        "News Analyst" => {
-            msg_lower.contains("news") ||
-            msg_lower.contains("event") ||
-            msg_lower.contains("announcement") ||
-            msg_lower.contains("sentiment") ||
-            (msg_lower.contains("market") && !msg_lower.contains("technical")) ||
-            msg_lower.contains("report")
-        }
-        "Strategy Advisor" => {
-            msg_lower.contains("strategy") ||
-            msg_lower.contains("trade") ||
-            msg_lower.contains("risk") ||
-            msg_lower.contains("position") ||
-            msg_lower.contains("entry") ||
-            msg_lower.contains("exit") ||
-            msg_lower.contains("target")
-        }
-        _ => false
-    }
+    agent.answers(&msg_lower)
 } 
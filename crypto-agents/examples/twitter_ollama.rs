@@ -3,13 +3,15 @@ use agent_twitter_client::scraper::Scraper;
 use dotenv::dotenv;
 use serde::{Serialize, Deserialize};
 use std::fs;
-use std::path::Path;
+use common::paths;
 use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use chrono::DateTime;
 use chrono::Duration;
 use chrono::Timelike;
 use crypto_agents::agents::{ModelProvider, BaseAgent};
+use crypto_agents::SentimentScorer;
+use crypto_agents::{AlphaScoringConfig, AlphaScoringMethod, ConnectionTiers, CryptoAgentsConfig, TwitterAnalysisConfig};
 
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,10 +26,16 @@ struct TweetData {
     mentions: Vec<String>,
 }
 
+const FETCH_COMPLETE: &str = "complete";
+const FETCH_PARTIAL: &str = "partial (budget reached)";
+
 #[derive(Debug, Serialize, Deserialize)]
 struct UserData {
     username: String,
     extracted_at: String,
+    /// FETCH_COMPLETE, or FETCH_PARTIAL when the fetch budget cut it short
+    #[serde(default)]
+    fetch_status: String,
     tweets: Vec<TweetData>,
     metrics: UserMetrics,
     analysis: ContentAnalysis,
@@ -82,9 +90,22 @@ struct Connection {
 #[derive(Debug, Serialize, Deserialize)]
 struct InformationValue {
     alpha_score: f64,  // How often they share valuable "alpha"
-    accuracy_rate: f64, // Historical accuracy of their calls/predictions
     insider_probability: f64, // Likelihood of having insider knowledge
     unique_insights: Vec<String>, // Notable unique insights shared
+    #[serde(default = "legacy_scoring_method")]
+    scoring_method: String, // "llm" or "keyword"
+}
+
+// Analyses saved before the method was recorded were all scored by keywords
+fn legacy_scoring_method() -> String {
+    "keyword".to_string()
+}
+
+// Per-tweet scores, both 0.0 - 1.0
+#[derive(Debug, Clone, Copy, Default)]
+struct TweetAlpha {
+    alpha: f64,
+    insider: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -107,12 +128,55 @@ struct TokenMention {
     context: Vec<String>,
 }
 
+/// Token ranked by how many accounts on the watchlist mention it
+#[derive(Debug, Serialize)]
+struct TokenRank {
+    symbol: String,
+    account_count: usize,
+    total_mentions: i32,
+    accounts: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TopicRank {
+    topic: String,
+    account_count: usize,
+    accounts: Vec<String>,
+}
+
+/// Pair of tokens mentioned by the same accounts
+#[derive(Debug, Serialize)]
+struct CoMention {
+    tokens: (String, String),
+    account_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ExcludedUser {
+    username: String,
+    reason: String,
+}
+
+/// Combined view across every user processed from a `--file` watchlist
+#[derive(Debug, Serialize)]
+struct WatchlistReport {
+    generated_at: String,
+    users_included: Vec<String>,
+    /// Included, but analyzed from a fetch cut short by the budget
+    users_partial: Vec<String>,
+    users_excluded: Vec<ExcludedUser>,
+    tokens: Vec<TokenRank>,
+    topics: Vec<TopicRank>,
+    co_mentions: Vec<CoMention>,
+}
+
 #[derive(Debug, Clone)]
 struct ExtractSettings {
     max_tweets: i32,
     since_date: Option<DateTime<Utc>>,
     include_replies: bool,
     include_retweets: bool,
+    analysis: TwitterAnalysisConfig,
 }
 
 impl Default for ExtractSettings {
@@ -122,6 +186,7 @@ impl Default for ExtractSettings {
             since_date: None,
             include_replies: true,
             include_retweets: true,
+            analysis: CryptoAgentsConfig::load().twitter_analysis,
         }
     }
 }
@@ -136,6 +201,76 @@ impl ExtractSettings {
     }
 }
 
+const ANALYSIS_UNAVAILABLE: &str = "Analysis unavailable";
+// Under the twitter data dir
+const CHECKPOINT_DIR: &str = "checkpoints";
+const MAX_FETCH_RETRIES: u32 = 3;
+
+/// Progress of an in-flight extraction, written after every batch so a crashed
+/// run can pick up from the last cursor instead of starting over
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ExtractCheckpoint {
+    username: String,
+    cursor: Option<String>,
+    total_fetched: usize,
+    tweets: Vec<TweetData>,
+    /// Fetching finished; set so a crash during analysis doesn't refetch
+    #[serde(default)]
+    fetch_complete: bool,
+    /// Fetching stopped at the budget rather than the end of the timeline
+    #[serde(default)]
+    budget_reached: bool,
+    updated_at: String,
+}
+
+impl ExtractCheckpoint {
+    fn path(username: &str) -> std::path::PathBuf {
+        paths::twitter_data_dir().join(CHECKPOINT_DIR).join(format!("{}.json", username.to_lowercase()))
+    }
+
+    fn load(username: &str) -> Option<Self> {
+        let content = fs::read_to_string(Self::path(username)).ok()?;
+        match serde_json::from_str::<Self>(&content) {
+            Ok(checkpoint) => Some(checkpoint),
+            Err(e) => {
+                println!("⚠️ Ignoring unreadable checkpoint for @{}: {}", username, e);
+                None
+            }
+        }
+    }
+
+    // Write to a temp file first so a crash mid-write can't corrupt the last good checkpoint
+    fn save(&mut self) -> Result<()> {
+        fs::create_dir_all(paths::twitter_data_dir().join(CHECKPOINT_DIR))?;
+        self.updated_at = Utc::now().to_rfc3339();
+        let path = Self::path(&self.username);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string(self)?)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    fn remove(username: &str) {
+        let path = Self::path(username);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                println!("⚠️ Failed to remove checkpoint {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+// Handle from an "@user" word, lowercased. Handles are letters, digits and
+// underscores, so "@user_" and "@user" stay distinct and trailing punctuation is dropped.
+fn mention_handle(word: &str) -> Option<String> {
+    let handle: String = word
+        .strip_prefix('@')?
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    (!handle.is_empty()).then(|| handle.to_lowercase())
+}
+
 fn calculate_stats(tweets: &[TweetData]) -> TweetStats {
     let mut hashtag_counts = HashMap::new();
     let mut mention_counts = HashMap::new();
@@ -284,103 +419,176 @@ impl TwitterAnalyzer {
         }
     }
 
-    async fn analyze_connections(&self, tweets: &[TweetData]) -> Vec<Connection> {
+    async fn analyze_connections(&self, tweets: &[TweetData], username: &str, tiers: &ConnectionTiers) -> Vec<Connection> {
         let mut interactions = HashMap::new();
         let mut last_interactions = HashMap::new();
+        let own_handle = username.trim_start_matches('@').to_lowercase();
 
         for tweet in tweets {
             if let Some(content) = &tweet.content {
-                // Extract mentions
+                // Extract mentions, skipping the user mentioning themselves
                 let mentions: Vec<_> = content
                     .split_whitespace()
-                    .filter(|word| word.starts_with('@'))
-                    .map(|word| word.trim_matches(|c| !char::is_alphanumeric(c)))
+                    .filter_map(mention_handle)
+                    .filter(|handle| *handle != own_handle)
                     .collect();
 
                 for mention in mentions {
-                    *interactions.entry(mention.to_string()).or_insert(0) += 1;
-                    last_interactions.insert(mention.to_string(), tweet.created_at.clone().unwrap_or_default());
+                    *interactions.entry(mention.clone()).or_insert(0u32) += 1;
+                    last_interactions.insert(mention, tweet.created_at.clone().unwrap_or_default());
                 }
             }
         }
 
+        let total_mentions: u32 = interactions.values().sum();
         let mut connections: Vec<Connection> = interactions
             .into_iter()
-            .map(|(username, count)| {
-                let relationship_type = if count > 10 {
-                    "frequently_mentioned"
-                } else if count > 5 {
-                    "regular_engagement"
-                } else {
-                    "occasional_interaction"
-                };
-
-                Connection {
-                    username: username.clone(),
-                    interaction_count: count,
-                    relationship_type: relationship_type.to_string(),
-                    last_interaction: last_interactions.get(&username).cloned().unwrap_or_default(),
-                }
+            .map(|(username, count)| Connection {
+                username: username.clone(),
+                interaction_count: count as i32,
+                relationship_type: tiers.classify(count, total_mentions).to_string(),
+                last_interaction: last_interactions.get(&username).cloned().unwrap_or_default(),
             })
             .collect();
 
-        connections.sort_by(|a, b| b.interaction_count.cmp(&a.interaction_count));
-        connections.into_iter().take(10).collect()
+        connections.sort_by(|a, b| b.interaction_count.cmp(&a.interaction_count).then(a.username.cmp(&b.username)));
+        connections.into_iter().take(tiers.top_n).collect()
     }
 
-    async fn calculate_information_value(&self, tweets: &[TweetData]) -> InformationValue {
-        let mut alpha_count = 0;
-        let mut prediction_count = 0;
-        let mut unique_insights = Vec::new();
-
-        for tweet in tweets {
-            if let Some(content) = &tweet.content {
-                let lower_content = content.to_lowercase();
-                
-                // Check for alpha indicators
-                if lower_content.contains("alpha") || 
-                   lower_content.contains("leaked") || 
-                   lower_content.contains("insider") ||
-                   lower_content.contains("exclusive") {
-                    alpha_count += 1;
-                }
-
-                // Check for predictions and use the count
-                if lower_content.contains("predict") || 
-                   lower_content.contains("expect") ||
-                   lower_content.contains("soon") ||
-                   lower_content.contains("incoming") {
-                    prediction_count += 1;
-                }
+    async fn calculate_information_value(&self, tweets: &[TweetData], config: &AlphaScoringConfig) -> InformationValue {
+        if config.method == AlphaScoringMethod::Llm {
+            match self.llm_information_value(tweets, config.max_llm_tweets).await {
+                Some(value) => return value,
+                None => println!("⚠️ LLM alpha scoring unavailable, falling back to keyword scoring"),
+            }
+        }
+        keyword_information_value(tweets)
+    }
 
-                // Extract unique insights
-                if content.len() > 100 && 
-                   (lower_content.contains("thread") || 
-                    lower_content.contains("1/") ||
-                    lower_content.contains("analysis")) {
-                    unique_insights.push(content.clone());
+    // Scores the most promising tweets with the model. Unscored tweets count as no alpha,
+    // so alpha_score stays comparable with the keyword version (share of all tweets).
+    async fn llm_information_value(&self, tweets: &[TweetData], max_tweets: usize) -> Option<InformationValue> {
+        let mut candidates: Vec<&String> = tweets.iter().filter_map(|t| t.content.as_ref()).collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by_key(|content| (!has_alpha_keyword(content), std::cmp::Reverse(content.len())));
+        candidates.truncate(max_tweets);
+
+        let mut scored = Vec::new();
+        for content in candidates {
+            match self.score_tweet_alpha(content).await {
+                Ok(Some(score)) => scored.push((content, score)),
+                Ok(None) => {}
+                // A failure on the very first call means the model isn't reachable
+                Err(e) if scored.is_empty() => {
+                    println!("⚠️ Alpha scoring failed: {}", e);
+                    return None;
                 }
+                Err(_) => {}
             }
         }
+        if scored.is_empty() {
+            return None;
+        }
 
-        // Use prediction_count in calculating accuracy_rate
-        let accuracy_rate = if prediction_count > 0 {
-            // Simple heuristic: more predictions = potentially lower accuracy
-            (1.0 / (prediction_count as f64)).min(0.8)
-        } else {
-            0.0
-        };
+        let alpha_score = scored.iter().map(|(_, s)| s.alpha).sum::<f64>() / tweets.len() as f64;
+
+        // The strongest few tweets carry the insider signal; one real leak matters more than volume
+        let mut insider: Vec<f64> = scored.iter().map(|(_, s)| s.insider).collect();
+        insider.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        let top = &insider[..insider.len().min(3)];
+        let insider_probability = top.iter().sum::<f64>() / top.len() as f64;
+
+        scored.sort_by(|a, b| b.1.alpha.partial_cmp(&a.1.alpha).unwrap_or(std::cmp::Ordering::Equal));
+        let unique_insights = scored.iter()
+            .filter(|(_, s)| s.alpha >= 0.5)
+            .take(5)
+            .map(|(content, _)| content.to_string())
+            .collect();
+
+        Some(InformationValue {
+            alpha_score: alpha_score.clamp(0.0, 1.0),
+            insider_probability: insider_probability.clamp(0.0, 1.0),
+            unique_insights,
+            scoring_method: "llm".to_string(),
+        })
+    }
+
+    // Ok(None) when the model answered but not in the expected format
+    async fn score_tweet_alpha(&self, content: &str) -> Result<Option<TweetAlpha>> {
+        let prompt = format!(
+            "Judge whether this tweet shares crypto information that is both non-public \
+            (not yet in news or official announcements) and actionable (names a token, project, \
+            date or trade). Hype, generic opinions, memes and recaps of public news are not alpha.\n\n\
+            Tweet: {}\n\n\
+            Reply with exactly two lines:\n\
+            ALPHA: <0-100, how likely this is actionable non-public information>\n\
+            INSIDER: <0-100, how likely the author has inside knowledge>",
+            content
+        );
+        let response = self.analyze(&prompt).await?;
+        Ok(parse_alpha_response(&response))
+    }
+}
+
+// Reads the ALPHA/INSIDER lines, taking the last of each so reasoning text before the answer is ignored
+fn parse_alpha_response(response: &str) -> Option<TweetAlpha> {
+    let value = |label: &str| -> Option<f64> {
+        response.lines().rev().find_map(|line| {
+            let rest = line.trim().trim_start_matches(['*', '-', ' ']).strip_prefix(label)?;
+            let number: String = rest.trim_start_matches([':', ' ', '*'])
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let value: f64 = number.parse().ok()?;
+            (0.0..=100.0).contains(&value).then(|| value / 100.0)
+        })
+    };
+    Some(TweetAlpha {
+        alpha: value("ALPHA")?,
+        insider: value("INSIDER")?,
+    })
+}
+
+fn has_alpha_keyword(content: &str) -> bool {
+    let lower_content = content.to_lowercase();
+    ["alpha", "leaked", "insider", "exclusive"].iter().any(|k| lower_content.contains(k))
+}
+
+// Cheap fallback: share of tweets with alpha keywords
+fn keyword_information_value(tweets: &[TweetData]) -> InformationValue {
+    let mut alpha_count = 0;
+    let mut unique_insights = Vec::new();
+
+    for tweet in tweets {
+        if let Some(content) = &tweet.content {
+            let lower_content = content.to_lowercase();
+
+            // Check for alpha indicators
+            if has_alpha_keyword(content) {
+                alpha_count += 1;
+            }
 
-        InformationValue {
-            alpha_score: alpha_count as f64 / tweets.len() as f64,
-            accuracy_rate,
-            insider_probability: if alpha_count > 5 { 0.7 } else { 0.3 },
-            unique_insights: unique_insights.into_iter().take(5).collect(),
+            // Extract unique insights
+            if content.len() > 100 && 
+               (lower_content.contains("thread") || 
+                lower_content.contains("1/") ||
+                lower_content.contains("analysis")) {
+                unique_insights.push(content.clone());
+            }
         }
     }
+
+    InformationValue {
+        alpha_score: if tweets.is_empty() { 0.0 } else { alpha_count as f64 / tweets.len() as f64 },
+        insider_probability: if alpha_count > 5 { 0.7 } else { 0.3 },
+        unique_insights: unique_insights.into_iter().take(5).collect(),
+        scoring_method: "keyword".to_string(),
+    }
 }
 
-async fn analyze_user_profile(tweets: &[TweetData], stats: &TweetStats, username: &str) -> Result<ContentAnalysis> {
+async fn analyze_user_profile(tweets: &[TweetData], stats: &TweetStats, username: &str, config: &TwitterAnalysisConfig) -> Result<ContentAnalysis> {
     let analyzer = TwitterAnalyzer::new(
         "deepseek-r1:1.5b-qwen-distill-q8_0".to_string(),
         ModelProvider::Ollama
@@ -388,19 +596,21 @@ async fn analyze_user_profile(tweets: &[TweetData], stats: &TweetStats, username
  
     // Analyze user patterns and connections
     let posting_patterns = analyzer.analyze_user_patterns(tweets).await;
-    let notable_connections = analyzer.analyze_connections(tweets).await;
-    let information_value = analyzer.calculate_information_value(tweets).await;
-
-    // Calculate influence score
-    let influence_score = (stats.total_likes + stats.total_retweets * 2) as f64 / 
-                         (tweets.len() as f64 * 100.0);
-    
-    // Calculate engagement rate
-    let engagement_rate = if stats.total_views > 0 {
-        (stats.total_likes + stats.total_retweets) as f64 / stats.total_views as f64 * 100.0
-    } else {
-        0.0
-    };
+    let notable_connections = analyzer.analyze_connections(tweets, username, &config.connections).await;
+    let information_value = analyzer.calculate_information_value(tweets, &config.alpha).await;
+
+    // Calculate influence score and engagement rate
+    let influence_score = config.scoring.influence_score(
+        stats.total_likes as i64,
+        stats.total_retweets as i64,
+        stats.total_views as i64,
+        tweets.len(),
+    );
+    let engagement_rate = config.scoring.engagement_rate(
+        stats.total_likes as i64,
+        stats.total_retweets as i64,
+        stats.total_views as i64,
+    );
 
     let user_insights = UserInsights {
         influence_score,
@@ -499,26 +709,10 @@ async fn analyze_user_profile(tweets: &[TweetData], stats: &TweetStats, username
 }
 
 fn calculate_sentiment_scores(tweets: &[TweetData]) -> Vec<f64> {
+    let scorer = SentimentScorer::load();
     tweets.iter()
         .filter_map(|t| t.content.as_ref())
-        .map(|content| {
-            let text = content.to_lowercase();
-            let positive_words = ["bullish", "moon", "up", "gain", "profit", "buy", "good"];
-            let negative_words = ["bearish", "down", "loss", "sell", "bad", "crash", "dump"];
-
-            let positive_count = positive_words.iter()
-                .filter(|&word| text.contains(word))
-                .count() as f64;
-            let negative_count = negative_words.iter()
-                .filter(|&word| text.contains(word))
-                .count() as f64;
-
-            if positive_count + negative_count > 0.0 {
-                (positive_count - negative_count) / (positive_count + negative_count)
-            } else {
-                0.0
-            }
-        })
+        .map(|content| scorer.score(content))
         .collect()
 }
 
@@ -578,9 +772,135 @@ fn extract_common_phrases(tweets: &[TweetData]) -> HashMap<String, i32> {
         .collect()
 }
 
+fn build_watchlist_report(results: &[(String, Result<Option<UserData>>)]) -> WatchlistReport {
+    let mut users_included = Vec::new();
+    let mut users_partial = Vec::new();
+    let mut users_excluded = Vec::new();
+    let mut tokens: HashMap<String, (Vec<String>, i32)> = HashMap::new();
+    let mut topics: HashMap<String, Vec<String>> = HashMap::new();
+    let mut pairs: HashMap<(String, String), usize> = HashMap::new();
+
+    for (username, result) in results {
+        let user_data = match result {
+            Ok(Some(user_data)) if user_data.analysis.ai_analysis != ANALYSIS_UNAVAILABLE => user_data,
+            Ok(Some(_)) => {
+                users_excluded.push(ExcludedUser { username: username.clone(), reason: "analysis failed".to_string() });
+                continue;
+            }
+            Ok(None) => {
+                users_excluded.push(ExcludedUser { username: username.clone(), reason: "no tweets found".to_string() });
+                continue;
+            }
+            Err(e) => {
+                users_excluded.push(ExcludedUser { username: username.clone(), reason: e.to_string() });
+                continue;
+            }
+        };
+        users_included.push(username.clone());
+        if user_data.fetch_status == FETCH_PARTIAL {
+            users_partial.push(username.clone());
+        }
+
+        let mut symbols: Vec<String> = user_data.analysis.token_mentions.iter()
+            .map(|mention| mention.symbol.to_uppercase())
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+
+        for mention in &user_data.analysis.token_mentions {
+            let entry = tokens.entry(mention.symbol.to_uppercase()).or_default();
+            if !entry.0.contains(username) {
+                entry.0.push(username.clone());
+            }
+            entry.1 += mention.count;
+        }
+
+        for topic in &user_data.analysis.key_topics {
+            let accounts = topics.entry(topic.clone()).or_default();
+            if !accounts.contains(username) {
+                accounts.push(username.clone());
+            }
+        }
+
+        // Each account counts once per pair, however often it mentions both
+        for (i, a) in symbols.iter().enumerate() {
+            for b in &symbols[i + 1..] {
+                *pairs.entry((a.clone(), b.clone())).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut tokens: Vec<TokenRank> = tokens.into_iter()
+        .map(|(symbol, (accounts, total_mentions))| TokenRank {
+            symbol,
+            account_count: accounts.len(),
+            total_mentions,
+            accounts,
+        })
+        .collect();
+    tokens.sort_by(|a, b| {
+        b.account_count.cmp(&a.account_count)
+            .then(b.total_mentions.cmp(&a.total_mentions))
+            .then(a.symbol.cmp(&b.symbol))
+    });
+
+    let mut topics: Vec<TopicRank> = topics.into_iter()
+        .map(|(topic, accounts)| TopicRank { topic, account_count: accounts.len(), accounts })
+        .collect();
+    topics.sort_by(|a, b| b.account_count.cmp(&a.account_count).then(a.topic.cmp(&b.topic)));
+
+    let mut co_mentions: Vec<CoMention> = pairs.into_iter()
+        .map(|(tokens, account_count)| CoMention { tokens, account_count })
+        .collect();
+    co_mentions.sort_by(|a, b| b.account_count.cmp(&a.account_count).then(a.tokens.cmp(&b.tokens)));
+
+    WatchlistReport {
+        generated_at: Utc::now().to_rfc3339(),
+        users_included,
+        users_partial,
+        users_excluded,
+        tokens,
+        topics,
+        co_mentions,
+    }
+}
+
+fn print_watchlist_report(report: &WatchlistReport) {
+    println!("\n🌐 Watchlist Summary ({} users analyzed):", report.users_included.len());
+
+    println!("\n💰 Tokens by distinct accounts:");
+    for token in report.tokens.iter().take(10) {
+        println!("  • ${}: {} accounts, {} mentions", token.symbol, token.account_count, token.total_mentions);
+    }
+
+    println!("\n🔍 Topics by distinct accounts:");
+    for topic in report.topics.iter().take(5) {
+        println!("  • {}: {} accounts", topic.topic, topic.account_count);
+    }
+
+    println!("\n🔗 Top co-mentions:");
+    for pair in report.co_mentions.iter().take(5) {
+        println!("  • ${} + ${}: {} accounts", pair.tokens.0, pair.tokens.1, pair.account_count);
+    }
+
+    if !report.users_partial.is_empty() {
+        println!("\n⏱️ Partial (fetch budget reached): @{}", report.users_partial.join(", @"));
+    }
+
+    if !report.users_excluded.is_empty() {
+        println!("\n⚠️ Excluded from summary:");
+        for user in &report.users_excluded {
+            println!("  • @{}: {}", user.username, user.reason);
+        }
+    }
+}
+
 fn print_analysis_summary(user_data: &UserData) {
     println!("\n📊 Analysis for @{}:", user_data.username);
     println!("📅 Analysis Date: {}", user_data.extracted_at);
+    if user_data.fetch_status == FETCH_PARTIAL {
+        println!("⏱️ Partial: the fetch budget was reached before the end of the timeline");
+    }
     
     println!("\n📈 Metrics:");
     println!("  • Total tweets: {}", user_data.metrics.total_tweets);
@@ -670,7 +990,7 @@ fn print_analysis_summary(user_data: &UserData) {
         );
     }
 
-    println!("\n💎 Information Value:");
+    println!("\n💎 Information Value ({} scoring):", user_data.analysis.user_insights.information_value.scoring_method);
     println!("  • Alpha Score: {:.2}", user_data.analysis.user_insights.information_value.alpha_score);
     println!("  • Insider Probability: {:.2}", user_data.analysis.user_insights.information_value.insider_probability);
     println!("\n  Notable Insights:");
@@ -680,7 +1000,8 @@ fn print_analysis_summary(user_data: &UserData) {
 }
 
 // Add this new function to process a single user
-async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut Scraper) -> Result<()> {
+// Returns None when the user had no tweets to analyze
+async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut Scraper) -> Result<Option<UserData>> {
     println!("\n📍 Processing @{}", username);
     
     // 1. Follow the user
@@ -691,26 +1012,53 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
         }
     }
 
-    // 2. Get tweets with pagination
-    let mut all_tweets = Vec::new();
-    let mut cursor: Option<String> = None;
-    let mut total_fetched = 0;
+    // 2. Get tweets with pagination, resuming from a checkpoint left by an interrupted run
+    let mut checkpoint = match ExtractCheckpoint::load(username) {
+        Some(checkpoint) if checkpoint.cursor.is_some() || checkpoint.fetch_complete => {
+            println!(
+                "♻️ Resuming @{} from checkpoint ({} tweets, last saved {})",
+                username, checkpoint.tweets.len(), checkpoint.updated_at
+            );
+            checkpoint
+        }
+        _ => ExtractCheckpoint {
+            username: username.to_string(),
+            ..Default::default()
+        },
+    };
+    let mut seen_ids: HashSet<String> = checkpoint.tweets.iter()
+        .filter_map(|tweet| tweet.id.clone())
+        .collect();
+    let mut retries = 0;
+    let budget = &settings.analysis.budget;
+    let started = std::time::Instant::now();
+    let mut requests = 0;
 
     println!("📥 Fetching tweets...");
     
-    loop {
-        match scraper.fetch_tweets_and_replies(&username, 100, cursor.as_deref()).await {
+    while !checkpoint.fetch_complete {
+        if let Some(limit) = budget.exhausted(started.elapsed(), requests) {
+            println!(
+                "⏱️ Fetch budget reached for @{} ({}), analyzing the {} tweets gathered so far",
+                username, limit, checkpoint.tweets.len()
+            );
+            checkpoint.budget_reached = true;
+            break;
+        }
+        requests += 1;
+        match scraper.fetch_tweets_and_replies(&username, 100, checkpoint.cursor.as_deref()).await {
             Ok(response) => {
+                retries = 0;
                 let batch_size = response.tweets.len();
                 if batch_size == 0 {
                     break;
                 }
 
-                total_fetched += batch_size;
-                println!("  • Fetched {} tweets (Total: {})", batch_size, total_fetched);
+                checkpoint.total_fetched += batch_size;
+                println!("  • Fetched {} tweets (Total: {})", batch_size, checkpoint.total_fetched);
 
                 // Just clone the next cursor
-                cursor = response.next.clone();
+                checkpoint.cursor = response.next.clone();
                 
                 // Convert and filter tweets
                 let tweet_batch: Vec<TweetData> = response.tweets.iter()
@@ -734,7 +1082,11 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
                             return false;
                         }
 
-                        true
+                        // Skip tweets already saved before a resume
+                        match &tweet.id {
+                            Some(id) => seen_ids.insert(id.clone()),
+                            None => true,
+                        }
                     })
                     .map(|tweet| TweetData {
                         id: tweet.id.clone(),
@@ -750,48 +1102,81 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
                     })
                     .collect();
 
-                all_tweets.extend(tweet_batch);
+                checkpoint.tweets.extend(tweet_batch);
+                if let Err(e) = checkpoint.save() {
+                    println!("⚠️ Failed to save checkpoint: {}", e);
+                }
 
                 // Check if we've reached the max tweets limit
-                if settings.max_tweets > 0 && total_fetched >= settings.max_tweets as usize {
+                if settings.max_tweets > 0 && checkpoint.total_fetched >= settings.max_tweets as usize {
                     break;
                 }
 
                 // Check if there's no next page
-                if cursor.is_none() {
+                if checkpoint.cursor.is_none() {
                     break;
                 }
 
                 // Add a small delay between requests
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
             },
+            Err(e) if retries < MAX_FETCH_RETRIES => {
+                retries += 1;
+                let delay = 2u64.pow(retries);
+                println!("⚠️ Error fetching tweets: {} (retry {}/{} in {}s)", e, retries, MAX_FETCH_RETRIES, delay);
+                tokio::time::sleep(tokio::time::Duration::from_secs(delay)).await;
+            }
             Err(e) => {
-                println!("⚠️ Error fetching tweets: {}", e);
-                break;
+                // Keep the checkpoint so the next run resumes from here
+                return Err(anyhow::anyhow!(
+                    "fetching stopped after {} retries: {} ({} tweets checkpointed to {}, rerun to resume)",
+                    MAX_FETCH_RETRIES, e, checkpoint.tweets.len(), ExtractCheckpoint::path(username).display()
+                ));
             }
         }
     }
 
-    println!("✅ Fetched {} tweets in total", all_tweets.len());
+    if !checkpoint.fetch_complete {
+        checkpoint.fetch_complete = true;
+        if let Err(e) = checkpoint.save() {
+            println!("⚠️ Failed to save checkpoint: {}", e);
+        }
+    }
+    println!("✅ Fetched {} tweets in total", checkpoint.tweets.len());
 
-    if all_tweets.is_empty() {
+    if checkpoint.tweets.is_empty() {
         println!("❌ No tweets found for @{}", username);
-        return Ok(());
+        ExtractCheckpoint::remove(username);
+        return Ok(None);
     }
 
-    // Use all_tweets instead of tweets for the rest of the processing
-    let tweets = all_tweets;
+    // Use the checkpointed tweets for the rest of the processing
+    let tweets = std::mem::take(&mut checkpoint.tweets);
     println!("📝 Found {} tweets matching criteria", tweets.len());
             
     // Calculate stats
     let stats = calculate_stats(&tweets);
     let stats_clone = stats.clone();
 
-    // Get initial analysis with token extraction
-    let analysis = match analyze_user_profile(&tweets, &stats, username).await {
-        Ok(analysis) => analysis,
-        Err(e) => {
-            println!("⚠️ Error generating analysis: {}", e);
+    // Get initial analysis with token extraction. Only the time limit applies here:
+    // the model calls aren't fetch requests.
+    let model_analysis = match budget.exhausted(started.elapsed(), 0) {
+        Some(limit) => {
+            println!("⏱️ Budget reached for @{} ({}), skipping the model analysis", username, limit);
+            checkpoint.budget_reached = true;
+            None
+        }
+        None => match analyze_user_profile(&tweets, &stats, username, &settings.analysis).await {
+            Ok(analysis) => Some(analysis),
+            Err(e) => {
+                println!("⚠️ Error generating analysis: {}", e);
+                None
+            }
+        },
+    };
+    let analysis = match model_analysis {
+        Some(analysis) => analysis,
+        None => {
             ContentAnalysis {
                 top_hashtags: stats_clone.top_hashtags,
                 top_mentions: stats_clone.top_mentions,
@@ -800,7 +1185,7 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
                 key_topics: extract_key_topics(&tweets),
                 common_phrases: extract_common_phrases(&tweets),
                 token_mentions: Vec::new(),
-                ai_analysis: "Analysis unavailable".to_string(),
+                ai_analysis: ANALYSIS_UNAVAILABLE.to_string(),
                 user_insights: UserInsights {
                     influence_score: 0.0,
                     engagement_rate: 0.0,
@@ -815,9 +1200,9 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
                     notable_connections: Vec::new(),
                     information_value: InformationValue {
                         alpha_score: 0.0,
-                        accuracy_rate: 0.0,
                         insider_probability: 0.0,
                         unique_insights: Vec::new(),
+                        scoring_method: "none".to_string(),
                     },
                 },
             }
@@ -828,36 +1213,37 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
     let user_data = UserData {
         username: username.to_string(),
         extracted_at: Utc::now().to_rfc3339(),
+        fetch_status: if checkpoint.budget_reached { FETCH_PARTIAL } else { FETCH_COMPLETE }.to_string(),
         tweets: tweets.clone(),
         metrics: UserMetrics {
             total_tweets: stats_clone.total_tweets,
             total_likes: stats_clone.total_likes,
             total_retweets: stats_clone.total_retweets,
             total_views: stats_clone.total_views,
-            avg_engagement_rate: if stats_clone.total_views > 0 {
-                (stats_clone.total_likes + stats_clone.total_retweets) as f64 
-                    / stats_clone.total_views as f64 * 100.0
-            } else {
-                0.0
-            },
+            avg_engagement_rate: settings.analysis.scoring.engagement_rate(
+                stats_clone.total_likes as i64,
+                stats_clone.total_retweets as i64,
+                stats_clone.total_views as i64,
+            ),
         },
         analysis,
     };
 
     // Save everything to one file
-    let filename = format!(
-        "data/twitter_data/{}_data_{}.json",
+    let filename = paths::twitter_data_dir().join(format!(
+        "{}_data_{}.json",
         username.to_lowercase(),
         Utc::now().format("%Y%m%d_%H%M%S")
-    );
+    ));
     
     fs::write(&filename, serde_json::to_string_pretty(&user_data)?)?;
-    println!("💾 Saved complete analysis to {}", filename);
+    println!("💾 Saved complete analysis to {}", filename.display());
+    ExtractCheckpoint::remove(username);
 
     // Print summary
     print_analysis_summary(&user_data);
 
-    Ok(())
+    Ok(Some(user_data))
 }
 
 // Modify main function to handle multiple users
@@ -916,13 +1302,17 @@ async fn main() -> Result<()> {
     println!("Settings:");
     println!("- Users to process: {}", usernames.len());
     println!("- Max tweets per user: {}", settings.max_tweets);
+    println!(
+        "- Fetch budget per user: {}s, {} requests (0 = unlimited)",
+        settings.analysis.budget.max_seconds, settings.analysis.budget.max_requests
+    );
     if let Some(since) = settings.since_date {
         println!("- Since date: {}", since.format("%Y-%m-%d"));
     }
 
     // Create data directory
-    let data_dir = Path::new("data/twitter_data");
-    fs::create_dir_all(data_dir)?;
+    let data_dir = paths::twitter_data_dir();
+    println!("📁 Saving to {}", data_dir.display());
 
     // Initialize scraper
     let mut scraper = Scraper::new().await?;
@@ -933,11 +1323,14 @@ async fn main() -> Result<()> {
     scraper.set_from_cookie_string(&cookie_string).await?;
 
     // Process each user
+    let mut results = Vec::new();
     for (i, username) in usernames.iter().enumerate() {
         println!("\n[{}/{}] Processing user", i + 1, usernames.len());
-        if let Err(e) = process_user(username, &settings, &mut scraper).await {
+        let result = process_user(username, &settings, &mut scraper).await;
+        if let Err(e) = &result {
             println!("⚠️ Error processing @{}: {}", username, e);
         }
+        results.push((username.clone(), result));
         
         // Add delay between users
         if i < usernames.len() - 1 {
@@ -946,6 +1339,19 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Aggregate across the watchlist
+    if usernames.len() > 1 {
+        let report = build_watchlist_report(&results);
+        print_watchlist_report(&report);
+
+        let filename = data_dir.join(format!(
+            "watchlist_summary_{}.json",
+            Utc::now().format("%Y%m%d_%H%M%S")
+        ));
+        fs::write(&filename, serde_json::to_string_pretty(&report)?)?;
+        println!("💾 Saved watchlist summary to {}", filename.display());
+    }
+
     println!("\n✅ Finished processing {} users", usernames.len());
     Ok(())
 } 
@@ -3,13 +3,14 @@ use agent_twitter_client::scraper::Scraper;
 use dotenv::dotenv;
 use serde::{Serialize, Deserialize};
 use std::fs;
-use std::path::Path;
+use common::paths;
 use chrono::Utc;
 use std::collections::HashMap;
 use chrono::DateTime;
 use chrono::Duration;
 use chrono::Timelike;
 use crypto_agents::agents::{ModelProvider, BaseAgent};
+use crypto_agents::SentimentScorer;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct TweetData {
@@ -498,26 +499,10 @@ async fn analyze_user_profile(tweets: &[TweetData], stats: &TweetStats, username
 }
 
 fn calculate_sentiment_scores(tweets: &[TweetData]) -> Vec<f64> {
+    let scorer = SentimentScorer::load();
     tweets.iter()
         .filter_map(|t| t.content.as_ref())
-        .map(|content| {
-            let text = content.to_lowercase();
-            let positive_words = ["bullish", "moon", "up", "gain", "profit", "buy", "good"];
-            let negative_words = ["bearish", "down", "loss", "sell", "bad", "crash", "dump"];
-
-            let positive_count = positive_words.iter()
-                .filter(|&word| text.contains(word))
-                .count() as f64;
-            let negative_count = negative_words.iter()
-                .filter(|&word| text.contains(word))
-                .count() as f64;
-
-            if positive_count + negative_count > 0.0 {
-                (positive_count - negative_count) / (positive_count + negative_count)
-            } else {
-                0.0
-            }
-        })
+        .map(|content| scorer.score(content))
         .collect()
 }
 
@@ -844,14 +829,14 @@ async fn process_user(username: &str, settings: &ExtractSettings, scraper: &mut
     };
 
     // Save everything to one file
-    let filename = format!(
-        "data/twitter_data/{}_data_{}.json",
+    let filename = paths::twitter_data_dir().join(format!(
+        "{}_data_{}.json",
         username.to_lowercase(),
         Utc::now().format("%Y%m%d_%H%M%S")
-    );
+    ));
     
     fs::write(&filename, serde_json::to_string_pretty(&user_data)?)?;
-    println!("💾 Saved complete analysis to {}", filename);
+    println!("💾 Saved complete analysis to {}", filename.display());
 
     // Print summary
     print_analysis_summary(&user_data);
@@ -920,8 +905,8 @@ async fn main() -> Result<()> {
     }
 
     // Create data directory
-    let data_dir = Path::new("data/twitter_data");
-    fs::create_dir_all(data_dir)?;
+    let data_dir = paths::twitter_data_dir();
+    println!("📁 Saving to {}", data_dir.display());
 
     // Initialize scraper
     let mut scraper = Scraper::new().await?;
@@ -3,15 +3,19 @@ use anyhow::Result;
 use chrono::Utc;
 use csv::Writer;
 use std::path::PathBuf;
+use common::paths;
 use std::fs::OpenOptions;
 use serde::{Serialize, Deserialize};
 use serde_json;
 
+use crate::api::coingecko::coin_id_for_symbol;
 use crate::models::{MarketData, AgentError};
+use crate::output::say;
 use super::{Agent, BaseAgent, ModelProvider};
 
-const TOKEN_LOG_FILE: &str = "data/agent_memory/tokens.csv";
-const MONITORED_TOKENS_FILE: &str = "data/agent_memory/monitored_tokens.json";
+// Both live in the agent memory dir
+const TOKEN_LOG_FILE: &str = "tokens.csv";
+const MONITORED_TOKENS_FILE: &str = "monitored_tokens.json";
 const EXTRACTOR_SYSTEM_PROMPT: &str = r#"
 You are a specialized Token Extraction AI focused on identifying cryptocurrency tokens and symbols from text.
 
@@ -61,7 +65,7 @@ pub struct TokenExtractor {
     monitored_tokens: Vec<MonitoredToken>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExtractedToken {
     pub timestamp: chrono::DateTime<Utc>,
     pub round: i32,
@@ -72,8 +76,9 @@ pub struct ExtractedToken {
 impl TokenExtractor {
     pub async fn new(model: String, provider: ModelProvider) -> Result<Self> {
         // Create token log file if it doesn't exist
-        if !PathBuf::from(TOKEN_LOG_FILE).exists() {
-            let mut wtr = Writer::from_path(TOKEN_LOG_FILE)?;
+        let token_log = paths::memory_dir().join(TOKEN_LOG_FILE);
+        if !token_log.exists() {
+            let mut wtr = Writer::from_path(&token_log)?;
             wtr.write_record(&["timestamp", "round", "token", "context"])?;
             wtr.flush()?;
         }
@@ -117,7 +122,7 @@ impl TokenExtractor {
 
     // Load monitored tokens from file
     async fn load_monitored_tokens() -> Result<Vec<MonitoredToken>> {
-        let path = PathBuf::from(MONITORED_TOKENS_FILE);
+        let path = paths::memory_dir().join(MONITORED_TOKENS_FILE);
         if path.exists() {
             let content = tokio::fs::read_to_string(&path).await?;
             Ok(serde_json::from_str(&content)?)
@@ -129,7 +134,7 @@ impl TokenExtractor {
     // Save monitored tokens to file
     async fn save_monitored_tokens(&self) -> Result<()> {
         let content = serde_json::to_string_pretty(&self.monitored_tokens)?;
-        tokio::fs::write(MONITORED_TOKENS_FILE, content).await?;
+        tokio::fs::write(paths::memory_dir().join(MONITORED_TOKENS_FILE), content).await?;
         Ok(())
     }
 
@@ -164,7 +169,7 @@ impl TokenExtractor {
             if let Some(monitored) = self.monitored_tokens.iter_mut()
                 .find(|t| t.symbol == *token) {
                 monitored.last_mention_round = Some(round);
-                println!("🔔 ALERT: Monitored token {} ({}) mentioned!", monitored.symbol, monitored.name);
+                say(format!("🔔 ALERT: Monitored token {} ({}) mentioned!", monitored.symbol, monitored.name));
             }
         }
             
@@ -184,6 +189,32 @@ impl TokenExtractor {
         Ok(extracted)
     }
 
+    /// Tickers in text without a model call: every `$CASHTAG`, plus bare uppercase words
+    /// that are known CoinGecko tickers (so "SOL" counts but "THE" doesn't)
+    pub fn detect_symbols(text: &str) -> Vec<String> {
+        let mut symbols: Vec<String> = Vec::new();
+        for word in text.split_whitespace() {
+            let cashtag = word.starts_with('$');
+            let symbol: String = word
+                .trim_start_matches('$')
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect();
+            // Amounts like $50k aren't tickers
+            if symbol.len() < 2 || symbol.len() > 10 || !symbol.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                continue;
+            }
+
+            let bare_ticker = symbol.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && coin_id_for_symbol(&symbol).is_some();
+            let symbol = symbol.to_uppercase();
+            if (cashtag || bare_ticker) && !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+        symbols
+    }
+
     // Get list of currently monitored tokens
     pub fn get_monitored_tokens(&self) -> &[MonitoredToken] {
         &self.monitored_tokens
@@ -192,7 +223,7 @@ impl TokenExtractor {
     fn save_tokens(&self, tokens: &[ExtractedToken]) -> Result<()> {
         let file = OpenOptions::new()
             .append(true)
-            .open(TOKEN_LOG_FILE)?;
+            .open(paths::memory_dir().join(TOKEN_LOG_FILE))?;
             
         let mut wtr = Writer::from_writer(file);
         
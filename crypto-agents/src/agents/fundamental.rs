@@ -2,7 +2,9 @@ use async_trait::async_trait;
 use anyhow::Result;
 use chrono::Utc;
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use crate::api::MarketDataCache;
 use crate::models::{MarketData, Conversation};
 use super::{Agent, BaseAgent, ModelProvider};
 
@@ -41,6 +43,7 @@ Format your response like this:
 
 pub struct FundamentalAgent {
     base: BaseAgent,
+    round_cache: Option<Arc<MarketDataCache>>,
 }
 
 impl FundamentalAgent {
@@ -54,8 +57,14 @@ impl FundamentalAgent {
             )
             .await?
             .with_temperature(0.7),
+            round_cache: None,
         })
     }
+
+    /// Read market data from the current round's shared cache instead of the caller's
+    pub fn set_round_cache(&mut self, cache: Arc<MarketDataCache>) {
+        self.round_cache = Some(cache);
+    }
 }
 
 #[async_trait]
@@ -69,6 +78,12 @@ impl Agent for FundamentalAgent {
     }
     
     async fn think(&mut self, market_data: &MarketData, previous_message: Option<String>) -> Result<String> {
+        let round_cache = self.round_cache.clone();
+        let market_data = match &round_cache {
+            Some(cache) => cache.market_data().await?,
+            None => market_data,
+        };
+
         // Create context with both market data and technical analysis
         let mut context = serde_json::to_string_pretty(market_data)?;
         if let Some(ref tech_analysis) = previous_message {
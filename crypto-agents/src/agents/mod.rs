@@ -6,10 +6,16 @@ use rig::{
     agent::Agent as RigAgent,
 };
 use common::providers::{mistral, openrouter};
-use std::path::PathBuf;
+use common::empty_response::{self, EmptyResponsePolicy, ResponseError};
+use common::{paths, telemetry};
+use std::path::{Path, PathBuf};
 use std::env;
 
+use crate::completion_cache::CompletionCache;
+use crate::output::say;
+use crate::config::{CompletionCacheConfig, CryptoAgentsConfig};
 use crate::models::{MarketData, Memory, AgentError};
+use crate::portfolio_export;
 
 pub mod technical;
 pub mod fundamental;
@@ -28,7 +34,6 @@ pub use new_top::NewTopAgent;
 pub use sentiment::SentimentAgent;
 pub use topic::TopicAgent;
 
-const AGENT_MEMORY_DIR: &str = "data/agent_memory";
 
 pub const DEEPSEEK_MODELS: &[&str] = &[
     "deepseek-chat",
@@ -93,6 +98,19 @@ impl ModelProvider {
         }.to_string()
     }
 
+    /// Environment variable holding the provider's API key, None when it needs none
+    pub fn api_key_var(&self) -> Option<&'static str> {
+        match self {
+            Self::DeepSeek => Some("DEEPSEEK_API_KEY"),
+            Self::Gemini => Some("GEMINI_API_KEY"),
+            Self::Mistral => Some("MISTRAL_API_KEY"),
+            Self::OpenAI => Some("OPENAI_API_KEY"),
+            Self::Cohere => Some("COHERE_API_KEY"),
+            Self::OpenRouter => Some("OPENROUTER_API_KEY"),
+            Self::Ollama => None,
+        }
+    }
+
     pub fn available_models(&self) -> Vec<&str> {
         match self {
             Self::DeepSeek => DEEPSEEK_MODELS.to_vec(),
@@ -136,15 +154,16 @@ pub struct BaseAgent {
     cohere_agent: Option<RigAgent<cohere::CompletionModel>>,
     openrouter_agent: Option<RigAgent<openrouter::OpenRouterCompletionModel>>,
     ollama_agent: Option<RigAgent<openai::CompletionModel>>,
-    #[allow(dead_code)]
     preamble: String,
     temperature: f32,
+    cache: Option<CompletionCache>,
+    empty_response: EmptyResponsePolicy,
 }
 
 impl BaseAgent {
     pub async fn new(name: String, model: String, preamble: String, provider: ModelProvider) -> Result<Self> {
         // Create memory directory if it doesn't exist
-        tokio::fs::create_dir_all(AGENT_MEMORY_DIR).await?;
+        tokio::fs::create_dir_all(paths::memory_dir()).await?;
         
         // Initialize appropriate client and agent based on provider
         let (deepseek_agent, gemini_agent, mistral_agent, openai_agent, cohere_agent, openrouter_agent, ollama_agent) = match provider {
@@ -231,6 +250,7 @@ impl BaseAgent {
             },
         };
         
+        let config = CryptoAgentsConfig::load();
         let mut agent = Self {
             name,
             model,
@@ -249,6 +269,8 @@ impl BaseAgent {
             ollama_agent,
             preamble,
             temperature: 0.7,
+            cache: CompletionCache::new(config.completion_cache),
+            empty_response: config.empty_response,
         };
         
         agent.load_memory().await?;
@@ -259,6 +281,23 @@ impl BaseAgent {
         self.temperature = temperature;
         self
     }
+
+    /// Override the completion cache settings from the crypto-agents config
+    pub fn with_completion_cache(mut self, config: CompletionCacheConfig) -> Self {
+        self.cache = CompletionCache::new(config);
+        self
+    }
+
+    /// Override what counts as an empty response, retried once before failing
+    pub fn with_empty_response(mut self, policy: EmptyResponsePolicy) -> Self {
+        self.empty_response = policy;
+        self
+    }
+
+    // The cache is only consulted when its temperature gate allows this agent
+    fn usable_cache(&self) -> Option<&CompletionCache> {
+        self.cache.as_ref().filter(|cache| cache.allows_temperature(self.temperature))
+    }
     
     pub async fn generate_response(&self, prompt: &str, context: Option<&str>) -> Result<String> {
         let full_prompt = if let Some(ctx) = context {
@@ -267,6 +306,39 @@ impl BaseAgent {
             prompt.to_string()
         };
 
+        let cache_key = CompletionCache::key(
+            &self.provider.to_string(),
+            &self.model,
+            &self.preamble,
+            &full_prompt,
+            self.temperature,
+        );
+        if let Some(cache) = self.usable_cache() {
+            if let Some(response) = cache.get(&cache_key).await {
+                say(format!("♻️ {} answered from completion cache", self.name));
+                return Ok(response);
+            }
+        }
+
+        let provider = self.provider.to_string();
+        let response = empty_response::retry_if_empty(self.empty_response, || {
+            telemetry::traced_call(&provider, &self.model, &full_prompt, self.prompt_provider(&full_prompt))
+        })
+        .await
+        .map_err(|e| match e {
+            ResponseError::Call(e) => e,
+            empty => anyhow::anyhow!("{}: {}", self.name, empty),
+        })?;
+
+        if let Some(cache) = self.usable_cache() {
+            if let Err(e) = cache.put(&cache_key, &self.model, &response).await {
+                say(format!("⚠️ Failed to write completion cache: {}", e));
+            }
+        }
+        Ok(response)
+    }
+    
+    async fn prompt_provider(&self, full_prompt: &str) -> Result<String> {
         let result = match self.provider {
             ModelProvider::DeepSeek => {
                 let agent = self.deepseek_agent.as_ref()
@@ -308,7 +380,7 @@ impl BaseAgent {
         // Convert the provider-specific error to anyhow::Error
         result.map_err(|e| anyhow::anyhow!("Agent error: {}", e))
     }
-    
+
     pub async fn save_memory(&self) -> Result<()> {
         let memory_path = self.memory_file();
         let memory_json = serde_json::to_string_pretty(&self.memory)?;
@@ -326,6 +398,23 @@ impl BaseAgent {
     }
     
     pub fn memory_file(&self) -> PathBuf {
-        PathBuf::from(AGENT_MEMORY_DIR).join(format!("{}_memory.json", self.name.to_lowercase().replace(' ', "_")))
+        paths::memory_dir().join(format!("{}_memory.json", self.name.to_lowercase().replace(' ', "_")))
+    }
+
+    /// Portfolio history as CSV, one row per update with a value column per symbol
+    pub fn export_portfolio_csv(&self, path: &Path) -> Result<()> {
+        portfolio_export::write_portfolio_csv(&self.memory.portfolio_history, path)
+    }
+
+    /// Portfolio history as a JSON array
+    pub fn export_portfolio_json(&self, path: &Path) -> Result<()> {
+        portfolio_export::write_portfolio_json(&self.memory.portfolio_history, path)
+    }
+
+    /// PNG equity curve of the portfolio's total value
+    #[cfg(feature = "portfolio-chart")]
+    pub fn export_portfolio_chart(&self, path: &Path) -> Result<()> {
+        let title = format!("{} portfolio value", self.name);
+        portfolio_export::render_equity_curve(&self.memory.portfolio_history, path, &title)
     }
 } 
\ No newline at end of file
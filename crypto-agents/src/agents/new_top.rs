@@ -6,9 +6,11 @@ use std::path::PathBuf;
 use std::fs::{self, OpenOptions};
 use csv::Writer;
 use tokio;
+use common::paths;
 
 use crate::models::MarketData;
 use crate::api::CoinGeckoClient;
+use crate::prompt_values;
 use super::{Agent, BaseAgent, ModelProvider};
 
 // const COINGECKO_BASE_URL: &str = "https://api.coingecko.com/api/v3";  // Changed to free tier URL
@@ -61,8 +63,7 @@ pub struct NewTopAgent {
 impl NewTopAgent {
     pub async fn new(model: String, provider: ModelProvider) -> Result<Self> {
         // Create results directory
-        let results_dir = PathBuf::from("data/market_analysis");
-        fs::create_dir_all(&results_dir)?;
+        let results_dir = paths::data_subdir("market_analysis");
         
         Ok(Self {
             base: BaseAgent::new(
@@ -77,7 +78,7 @@ impl NewTopAgent {
     }
     
     async fn analyze_coin(&self, coin: &crate::api::coingecko::DetailedCoinData) -> Result<AnalysisResult> {
-        if coin.current_price == 0.0 {
+        if !coin.current_price.is_some_and(|price| price > 0.0) {
             // For new coins without price data, just record them without analysis
             return Ok(AnalysisResult {
                 timestamp: Utc::now().to_rfc3339(),
@@ -88,12 +89,12 @@ impl NewTopAgent {
         }
 
         let prompt = format!(
-            "Analyze this cryptocurrency:\n\nName: {}\nSymbol: {}\nPrice: ${:.8}\n1h Change: {:.2}%\nVolume: ${:.2}M\n\nProvide a BUY/SELL/DO NOTHING recommendation with analysis.",
+            "Analyze this cryptocurrency:\n\nName: {}\nSymbol: {}\nPrice: {}\n1h Change: {:.2}%\nVolume: {}\n\nProvide a BUY/SELL/DO NOTHING recommendation with analysis.",
             coin.name, 
             coin.symbol, 
-            coin.current_price, 
+            prompt_values::price(coin.current_price), 
             coin.price_change_1h.unwrap_or_default(), 
-            coin.volume_24h / 1_000_000.0
+            prompt_values::millions(coin.volume_24h)
         );
         
         // Try up to 3 times to get a valid analysis
@@ -153,7 +154,7 @@ impl NewTopAgent {
             &result.coin_data.id,
             &result.coin_data.name,
             &result.coin_data.symbol,
-            &result.coin_data.current_price.to_string(),
+            &result.coin_data.current_price.map_or_else(String::new, |price| price.to_string()),
             &format!("{:.2}", result.coin_data.price_change_1h.unwrap_or_default()),
             &result.recommendation,
             &result.analysis,
@@ -5,6 +5,7 @@ use serde::{Serialize, Deserialize};
 use std::path::PathBuf;
 
 use crate::models::{MarketData, Conversation};
+use crate::output::say;
 use super::{Agent, BaseAgent, ModelProvider};
 use crate::api::social_media::{SocialMediaClient, SocialMediaPost};
 
@@ -108,7 +109,7 @@ impl SentimentAgent {
         context.push_str(&format!("24h Volume: ${:.2}B\n", market_data.overview.total_volume / 1_000_000_000.0));
 
         // Gather Twitter data
-        println!("📱 Gathering Twitter data...");
+        say("📱 Gathering Twitter data...");
         let social_posts = self.gather_social_data(market_data).await?;
 
         // Calculate sentiment metrics
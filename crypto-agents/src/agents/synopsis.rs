@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::Arc;
+use common::paths;
 
+use crate::api::MarketDataCache;
 use crate::models::MarketData;
 use super::{
     Agent, BaseAgent, ModelProvider,
@@ -41,6 +44,12 @@ Format your response like this:
 [Clear next steps based on consensus]
 "#;
 
+const MEMORY_FILE: &str = "synopsis_memory.json";
+
+fn memory_file() -> PathBuf {
+    paths::memory_dir().join(MEMORY_FILE)
+}
+
 #[derive(Debug)]
 pub struct AgentAnalysis {
     agent_name: String,
@@ -57,6 +66,8 @@ pub struct SynopsisAgent {
 
 impl SynopsisAgent {
     pub async fn new(model: String, provider: ModelProvider) -> Result<Self> {
+        // Older builds kept it under the same name in the working directory
+        paths::migrate_legacy_file(MEMORY_FILE, &memory_file());
         Ok(Self {
             base: BaseAgent::new(
                 "Synopsis Agent".to_string(),
@@ -71,6 +82,12 @@ impl SynopsisAgent {
         })
     }
 
+    /// Share the current round's market data with the agents consulted in `think`
+    pub fn set_round_cache(&mut self, cache: Arc<MarketDataCache>) {
+        self.technical_agent.set_round_cache(cache.clone());
+        self.fundamental_agent.set_round_cache(cache);
+    }
+
     pub async fn generate_synopsis(
         &mut self,
         agent_one_response: &str,
@@ -198,6 +215,6 @@ impl Agent for SynopsisAgent {
     }
 
     fn memory_file(&self) -> PathBuf {
-        PathBuf::from("synopsis_memory.json")
+        memory_file()
     }
 } 
\ No newline at end of file
@@ -1,11 +1,18 @@
 use async_trait::async_trait;
 use anyhow::Result;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use crate::config::{CryptoAgentsConfig, SignalKeywords, TechnicalTemplatesConfig};
 use crate::models::{MarketData, Conversation};
+use crate::output::say;
 use super::{Agent, BaseAgent, ModelProvider};
-use crate::api::coingecko::{DetailedCoinData, CoinGeckoClient, CandleData, TechnicalData, MarketTechnicalData};
+use crate::api::MarketDataCache;
+use crate::prompt_values::{self, MISSING_DATA_INSTRUCTION};
+use crate::api::coingecko::{to_ta_candles, DetailedCoinData, CoinGeckoClient, CandleData, CandleSource, CategoryData, TechnicalData, MarketTechnicalData};
+use common::ta;
 
 const TECHNICAL_SYSTEM_PROMPT: &str = r#"
 You are Agent One - The Technical Analysis and Trader Expert 📊
@@ -97,14 +104,252 @@ pub struct TechnicalAnalysis {
     pub market_outlook: String,
     pub risk_level: String,
     pub quote: String,
+    pub confidence: ConfidenceMetrics,
+}
+
+/// Percentages (0-100) from the "Confidence Metrics" section of the response,
+/// `None` when the model left a line out, malformed it or kept the template text
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceMetrics {
+    pub bullish: Option<f64>,
+    pub bearish: Option<f64>,
+    pub overall: Option<f64>,
+    pub support_resistance: Option<f64>,
+}
+
+impl ConfidenceMetrics {
+    pub fn parse(response: &str) -> Self {
+        Self {
+            bullish: parse_confidence_line(response, "bullish scenario confidence"),
+            bearish: parse_confidence_line(response, "bearish scenario confidence"),
+            overall: parse_confidence_line(response, "overall analysis confidence"),
+            support_resistance: parse_confidence_line(response, "support/resistance confidence"),
+        }
+    }
+}
+
+// Read the percentage after the colon on the first parseable line with `label`. A range
+// like "60-70%" gives its midpoint, the unfilled "[0-100%]" template counts as missing.
+fn parse_confidence_line(response: &str, label: &str) -> Option<f64> {
+    response
+        .lines()
+        .filter(|line| line.to_lowercase().contains(label))
+        .find_map(parse_confidence_value)
+}
+
+fn parse_confidence_value(line: &str) -> Option<f64> {
+    let value = line.split_once(':')?.1;
+    let value: String = value
+        .chars()
+        .filter(|c| !matches!(c, '*' | '[' | ']' | '_' | ' '))
+        .collect();
+
+    if value.starts_with("0-100") {
+        return None;
+    }
+
+    let number: String = value
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '–'))
+        .collect();
+    let numbers: Vec<f64> = number
+        .split(|c| c == '-' || c == '–')
+        .map(|part| part.parse::<f64>())
+        .collect::<std::result::Result<_, _>>()
+        .ok()?;
+
+    let confidence = match numbers.as_slice() {
+        [value] => *value,
+        [low, high] => (low + high) / 2.0,
+        _ => return None,
+    };
+
+    (0.0..=100.0).contains(&confidence).then_some(confidence)
+}
+
+/// Classify the outlook as "Bullish", "Bearish" or "Neutral". Filled-in scenario
+/// confidences decide first when they differ by at least 10 points, otherwise
+/// bullish and bearish keyword hits are counted and the larger side wins.
+/// A tie, including no hits at all, is "Neutral".
+pub fn extract_market_outlook(response: &str, confidence: &ConfidenceMetrics, keywords: &SignalKeywords) -> String {
+    if let (Some(bullish), Some(bearish)) = (confidence.bullish, confidence.bearish) {
+        if bullish - bearish >= 10.0 {
+            return "Bullish".to_string();
+        }
+        if bearish - bullish >= 10.0 {
+            return "Bearish".to_string();
+        }
+    }
+
+    let text = response.to_lowercase();
+    let bullish = count_keywords(&text, &keywords.bullish);
+    let bearish = count_keywords(&text, &keywords.bearish);
+
+    match bullish.cmp(&bearish) {
+        std::cmp::Ordering::Greater => "Bullish",
+        std::cmp::Ordering::Less => "Bearish",
+        std::cmp::Ordering::Equal => "Neutral",
+    }
+    .to_string()
+}
+
+/// Classify risk as "High", "Medium" or "Low". An explicit "Risk Level:" line wins,
+/// otherwise high and low risk keyword hits are counted; a tie is "Medium".
+pub fn extract_risk_level(response: &str, keywords: &SignalKeywords) -> String {
+    let explicit = response
+        .lines()
+        .filter(|line| line.to_lowercase().contains("risk level"))
+        .find_map(|line| {
+            let value = line.split_once(':')?.1.to_lowercase();
+            // "[Low/Medium/High]" is the unfilled template
+            if value.contains('/') {
+                return None;
+            }
+            let value = value.trim_matches(|c: char| !c.is_alphanumeric());
+            match value.split(|c: char| !c.is_alphanumeric()).next()? {
+                "low" => Some("Low"),
+                "medium" | "moderate" => Some("Medium"),
+                "high" => Some("High"),
+                _ => None,
+            }
+        });
+    if let Some(level) = explicit {
+        return level.to_string();
+    }
+
+    let text = response.to_lowercase();
+    let high = count_keywords(&text, &keywords.high_risk);
+    let low = count_keywords(&text, &keywords.low_risk);
+
+    match high.cmp(&low) {
+        std::cmp::Ordering::Greater => "High",
+        std::cmp::Ordering::Less => "Low",
+        std::cmp::Ordering::Equal => "Medium",
+    }
+    .to_string()
+}
+
+// Count whole-phrase occurrences in lowercased text, skipping negated ones ("not bullish")
+fn count_keywords(text: &str, keywords: &[String]) -> usize {
+    keywords
+        .iter()
+        .map(|keyword| keyword.to_lowercase())
+        .filter(|keyword| !keyword.is_empty())
+        .map(|keyword| {
+            text.match_indices(keyword.as_str())
+                .filter(|(start, _)| {
+                    let end = start + keyword.len();
+                    let before = text[..*start].chars().next_back();
+                    let after = text[end..].chars().next();
+                    let bounded = !before.map_or(false, char::is_alphanumeric)
+                        && !after.map_or(false, char::is_alphanumeric);
+                    let preceding = text[..*start].trim_end();
+                    let negated = preceding.ends_with(" not") || preceding.ends_with(" no")
+                        || preceding == "not" || preceding == "no";
+                    bounded && !negated
+                })
+                .count()
+        })
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorMomentum {
+    pub name: String,
+    pub market_cap_change_24h: f64,
+    pub volume_24h: f64,
+    /// 24h volume as a percentage of market cap
+    pub turnover: Option<f64>,
+}
+
+/// Sectors ranked by 24h market cap change (then turnover), showing where capital
+/// is rotating. Sectors missing change or volume data are left out of the ranking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorRotation {
+    pub ranking: Vec<SectorMomentum>,
+}
+
+impl SectorRotation {
+    /// None when fewer than two sectors have usable data, since there's nothing to compare
+    pub fn from_sectors(sectors: &[CategoryData]) -> Option<Self> {
+        let mut ranking: Vec<SectorMomentum> = sectors
+            .iter()
+            .filter_map(|sector| {
+                let change = sector.market_cap_change_24h.filter(|c| c.is_finite())?;
+                let volume = sector.volume_24h.filter(|v| v.is_finite())?;
+                let turnover = sector.market_cap
+                    .filter(|mcap| *mcap > 0.0)
+                    .map(|mcap| volume / mcap * 100.0);
+                Some(SectorMomentum {
+                    name: sector.name.clone(),
+                    market_cap_change_24h: change,
+                    volume_24h: volume,
+                    turnover,
+                })
+            })
+            .collect();
+
+        if ranking.len() < 2 {
+            return None;
+        }
+
+        ranking.sort_by(|a, b| {
+            b.market_cap_change_24h
+                .total_cmp(&a.market_cap_change_24h)
+                .then(b.turnover.unwrap_or(0.0).total_cmp(&a.turnover.unwrap_or(0.0)))
+                .then(a.name.cmp(&b.name))
+        });
+
+        Some(Self { ranking })
+    }
+
+    pub fn leader(&self) -> &SectorMomentum {
+        &self.ranking[0]
+    }
+
+    pub fn laggard(&self) -> &SectorMomentum {
+        &self.ranking[self.ranking.len() - 1]
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = String::from("\n🔄 Sector Rotation (24h):\n");
+        for (i, sector) in self.ranking.iter().enumerate() {
+            let flag = if i == 0 {
+                " 🚀 Leader"
+            } else if i == self.ranking.len() - 1 {
+                " 🐢 Laggard"
+            } else {
+                ""
+            };
+            summary.push_str(&format!(
+                "{}. {}: {:+.2}% mcap, ${:.2}B volume{}{}\n",
+                i + 1,
+                sector.name,
+                sector.market_cap_change_24h,
+                sector.volume_24h / 1e9,
+                sector.turnover.map(|t| format!(" ({:.1}% turnover)", t)).unwrap_or_default(),
+                flag
+            ));
+        }
+        summary.push_str(&format!(
+            "Capital is rotating into {} and out of {}\n",
+            self.leader().name,
+            self.laggard().name
+        ));
+        summary
+    }
 }
 
 pub struct TechnicalAgent {
     base: BaseAgent,
+    keywords: SignalKeywords,
+    templates: TechnicalTemplatesConfig,
+    round_cache: Option<Arc<MarketDataCache>>,
 }
 
 impl TechnicalAgent {
     pub async fn new(model: String, provider: ModelProvider) -> Result<Self> {
+        let config = CryptoAgentsConfig::load();
         Ok(Self {
             base: BaseAgent::new(
                 "Technical Agent".to_string(),
@@ -114,13 +359,35 @@ impl TechnicalAgent {
             )
             .await?
             .with_temperature(0.7),
+            keywords: config.signals,
+            templates: config.technical_templates,
+            round_cache: None,
         })
     }
 
+    /// Read market data from the current round's shared cache instead of fetching it
+    pub fn set_round_cache(&mut self, cache: Arc<MarketDataCache>) {
+        self.round_cache = Some(cache);
+    }
+
+    /// Override the outlook/risk keyword map loaded from the crypto-agents config
+    pub fn with_signal_keywords(mut self, keywords: SignalKeywords) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Override the per-tier analysis templates loaded from the crypto-agents config
+    pub fn with_templates(mut self, templates: TechnicalTemplatesConfig) -> Self {
+        self.templates = templates;
+        self
+    }
+
     pub async fn analyze_coin_data(&self, symbol: &str, data: &DetailedCoinData) -> Result<TechnicalAnalysis> {
         // Get OHLC data
-        let client = CoinGeckoClient::new()?;
-        let ohlc_data = client.get_ohlc_data(&data.id, 1).await?;
+        let ohlc_data = match &self.round_cache {
+            Some(cache) => cache.ohlc(&data.id, 1).await?,
+            None => CoinGeckoClient::new()?.get_ohlc_data(&data.id, 1).await?,
+        };
         
         // Get the latest OHLC candle
         let latest_ohlc = ohlc_data.last()
@@ -128,19 +395,20 @@ impl TechnicalAgent {
 
         let prompt = format!(
             "Perform a detailed technical analysis for {} with the following market data:\n\n\
-            Current Price: ${:.2}\n\
-            24h Change: {:.2}%\n\
-            Market Cap: ${:.2}M\n\
-            24h Volume: ${:.2}M\n\
+            Current Price: {}\n\
+            24h Change: {}\n\
+            Market Cap: {}\n\
+            24h Volume: {}\n\
             OHLC Data (24h):\n\
-            - Open: ${:.2}\n\
-            - High: ${:.2}\n\
-            - Low: ${:.2}\n\
-            - Close: ${:.2}\n\
+            - Open: ${}\n\
+            - High: ${}\n\
+            - Low: ${}\n\
+            - Close: ${}\n\
             Moving Averages:\n\
-            - MA50: ${:.2}\n\
-            - MA200: ${:.2}\n\
+            - MA50: {}\n\
+            - MA200: {}\n\
             - Position: {}\n\
+            {}\n\
             \n\
             Please analyze:\n\
             1. Overall Market Analysis\n\
@@ -162,42 +430,45 @@ impl TechnicalAgent {
             End your analysis with a clear actionable recommendation and risk disclaimer.\n\
             Format your response using the template from the system prompt.",
             symbol,
-            data.current_price,
-            data.price_change_24h.unwrap_or_default(),
-            data.market_cap / 1_000_000.0,
-            data.volume_24h / 1_000_000.0,
-            latest_ohlc.open,
-            latest_ohlc.high,
-            latest_ohlc.low,
-            latest_ohlc.close,
-            data.ma_50.unwrap_or_default(),
-            data.ma_200.unwrap_or_default(),
-            if data.current_price > data.ma_50.unwrap_or_default() {
-                "Above MA50"
-            } else {
-                "Below MA50"
-            }
+            prompt_values::price(data.current_price),
+            prompt_values::percent(data.price_change_24h),
+            prompt_values::millions(data.market_cap),
+            prompt_values::millions(data.volume_24h),
+            prompt_values::usd(latest_ohlc.open),
+            prompt_values::usd(latest_ohlc.high),
+            prompt_values::usd(latest_ohlc.low),
+            prompt_values::usd(latest_ohlc.close),
+            prompt_values::price(data.ma_50),
+            prompt_values::price(data.ma_200),
+            match (data.current_price, data.ma_50) {
+                (Some(price), Some(ma50)) if price > ma50 => "Above MA50",
+                (Some(_), Some(_)) => "Below MA50",
+                _ => prompt_values::NOT_AVAILABLE,
+            },
+            MISSING_DATA_INSTRUCTION
         );
+        let prompt = match self.templates.select(&data.id, data.market_cap.unwrap_or(f64::NAN)) {
+            Some((name, framing)) => {
+                say(format!("🧭 Using the {} analysis template for {}", name, symbol));
+                format!("{}\n\nAnalysis focus ({}):\n{}", prompt, name, framing)
+            }
+            None => prompt,
+        };
 
         // Get AI response
         let response = self.base.generate_response(&prompt, None).await?;
         
         // Parse market outlook and risk level
-        let market_outlook = self.extract_market_outlook(&response);
-
-        let risk_level = if response.to_lowercase().contains("high risk") {
-            "High"
-        } else if response.to_lowercase().contains("low risk") {
-            "Low"
-        } else {
-            "Medium"
-        }.to_string();
+        let confidence = ConfidenceMetrics::parse(&response);
+        let market_outlook = extract_market_outlook(&response, &confidence, &self.keywords);
+        let risk_level = extract_risk_level(&response, &self.keywords);
 
         Ok(TechnicalAnalysis {
             analysis: response,
             market_outlook,
             risk_level,
             quote: String::new(),
+            confidence,
         })
     }
 
@@ -215,21 +486,19 @@ impl TechnicalAgent {
             ai_vol / 1e9, l1_vol / 1e9, l2_vol / 1e9, rwa_vol / 1e9
         );
 
-        // Add BTC analysis using full technical data
-        context.push_str(&format!("\n\n💎 Bitcoin Analysis:"));
-        context.push_str(&self.analyze_major_coin(
-            "BTC", 
-            &technical_data.btc_data,
-            technical_data.global_metrics.btc_dominance
-        ));
+        if let Some(rotation) = SectorRotation::from_sectors(&technical_data.sectors) {
+            context.push_str(&rotation.summary());
+        }
 
-        // Add ETH analysis
-        context.push_str(&format!("\n\n💎 Ethereum Analysis:"));
-        context.push_str(&self.analyze_major_coin(
-            "ETH",
-            &technical_data.eth_data,
-            0.0 // ETH dominance
-        ));
+        // Add major coin analysis using full technical data
+        context.push('\n');
+        for (symbol, data) in &technical_data.major_coins {
+            context.push_str(&self.analyze_major_coin(
+                symbol,
+                data,
+                technical_data.global_metrics.dominance(symbol)
+            ));
+        }
 
         // Analyze trending coins
         context.push_str("\n🔥 Trending Coins Analysis:\n");
@@ -260,71 +529,44 @@ impl TechnicalAgent {
         Ok(context)
     }
 
-    fn analyze_major_coin(&self, symbol: &str, data: &TechnicalData, dominance: f64) -> String {
+    fn analyze_major_coin(&self, symbol: &str, data: &TechnicalData, dominance: Option<f64>) -> String {
         format!(
             "\n💎 {} Analysis:\n\
-            RSI (14): {:.2}\n\
-            50 MA: ${:.2}\n\
-            200 MA: ${:.2}\n\
+            RSI (14): {}\n\
+            50 MA: {}\n\
+            200 MA: {}\n\
             Trend: {}\n\
-            Market Dominance: {:.2}%\n",
+            Market Dominance: {}\n",
             symbol,
-            data.rsi_14.unwrap_or_default(),
-            data.ma_50.unwrap_or_default(),
-            data.ma_200.unwrap_or_default(),
+            prompt_values::number(data.rsi_14),
+            prompt_values::price(data.ma_50),
+            prompt_values::price(data.ma_200),
             self.determine_trend(data),
-            dominance
+            prompt_values::percent(dominance)
         )
     }
 
-    // Helper function to determine trend
+    // Helper function to determine trend, N/A without a price and both moving averages
     fn determine_trend(&self, tech_data: &TechnicalData) -> &str {
-        let price = tech_data.candles.last().unwrap().close;
-        let ma50 = tech_data.ma_50.unwrap_or_default();
-        let ma200 = tech_data.ma_200.unwrap_or_default();
-        let rsi = tech_data.rsi_14.unwrap_or_default();
-
-        match (price > ma50, price > ma200, rsi) {
-            (true, true, _) if rsi > 70.0 => "Strong Uptrend (Overbought)",
-            (true, true, _) => "Strong Uptrend",
-            (true, false, _) => "Potential Trend Change (Above 50MA)",
-            (false, true, _) => "Weakening Trend",
-            (false, false, _) if rsi < 30.0 => "Strong Downtrend (Oversold)",
-            (false, false, _) => "Strong Downtrend",
+        let price = tech_data.candles.last().map(|c| c.close).or(tech_data.current_price);
+        let (Some(price), Some(ma50), Some(ma200)) = (price, tech_data.ma_50, tech_data.ma_200) else {
+            return prompt_values::NOT_AVAILABLE;
+        };
+        let rsi = tech_data.rsi_14;
+
+        match (price > ma50, price > ma200) {
+            (true, true) if rsi.is_some_and(|r| r > 70.0) => "Strong Uptrend (Overbought)",
+            (true, true) => "Strong Uptrend",
+            (true, false) => "Potential Trend Change (Above 50MA)",
+            (false, true) => "Weakening Trend",
+            (false, false) if rsi.is_some_and(|r| r < 30.0) => "Strong Downtrend (Oversold)",
+            (false, false) => "Strong Downtrend",
         }
     }
 
     // Helper function to detect candlestick patterns
     fn detect_patterns(&self, candles: &[CandleData]) -> Option<String> {
-        if candles.len() < 3 {
-            return None;
-        }
-
-        let mut patterns = Vec::new();
-        let last = candles.len() - 1;
-
-        // Doji
-        if (candles[last].open - candles[last].close).abs() < 0.001 * candles[last].open {
-            patterns.push("Doji (Indecision)");
-        }
-
-        // Hammer
-        if candles[last].low < candles[last].open 
-            && candles[last].low < candles[last].close
-            && (candles[last].high - candles[last].low.max(candles[last].open.min(candles[last].close))) 
-                < (candles[last].open.max(candles[last].close) - candles[last].low) * 0.3 {
-            patterns.push("Hammer");
-        }
-
-        // Engulfing
-        if candles[last].open > candles[last-1].close 
-            && candles[last].close < candles[last-1].open {
-            patterns.push("Bearish Engulfing");
-        } else if candles[last].open < candles[last-1].close 
-            && candles[last].close > candles[last-1].open {
-            patterns.push("Bullish Engulfing");
-        }
-
+        let patterns = ta::detect_patterns(&to_ta_candles(candles));
         if patterns.is_empty() {
             None
         } else {
@@ -336,39 +578,35 @@ impl TechnicalAgent {
         let mut analysis = String::new();
         
         // Get latest price and indicators
-        let price = tech_data.candles.last().unwrap().close;
-        let rsi = tech_data.rsi_14.unwrap_or_default();
-        let ma50 = tech_data.ma_50.unwrap_or_default();
-        let ma200 = tech_data.ma_200.unwrap_or_default();
+        let price = tech_data.candles.last().map(|c| c.close).or(tech_data.current_price);
 
         // Price analysis
-        analysis.push_str(&format!("Price: ${:.2}\n", price));
+        analysis.push_str(&format!("Price: {}\n", prompt_values::price(price)));
         
         // RSI Analysis
-        analysis.push_str(&format!("RSI (14): {:.2} - ", rsi));
-        analysis.push_str(match rsi {
-            r if r > 70.0 => "Overbought ⚠️\n",
-            r if r < 30.0 => "Oversold 🔥\n",
-            _ => "Neutral ⚖️\n",
+        analysis.push_str(&format!("RSI (14): {}", prompt_values::number(tech_data.rsi_14)));
+        analysis.push_str(match tech_data.rsi_14 {
+            Some(r) if r > 70.0 => " - Overbought ⚠️\n",
+            Some(r) if r < 30.0 => " - Oversold 🔥\n",
+            Some(_) => " - Neutral ⚖️\n",
+            None => "\n",
         });
 
         // Moving Average Analysis
-        analysis.push_str(&format!("50 MA: ${:.2}\n", ma50));
-        analysis.push_str(&format!("200 MA: ${:.2}\n", ma200));
+        analysis.push_str(&format!("50 MA: {}\n", prompt_values::price(tech_data.ma_50)));
+        analysis.push_str(&format!("200 MA: {}\n", prompt_values::price(tech_data.ma_200)));
         
         // Trend Analysis
         analysis.push_str(&format!("Trend: {}\n", self.determine_trend(tech_data)));
 
         // Volume Analysis
-        let avg_volume: f64 = tech_data.candles.iter()
-            .map(|c| c.volume)
-            .sum::<f64>() / tech_data.candles.len() as f64;
-        
-        let latest_volume = tech_data.candles.last().unwrap().volume;
-        let volume_change = (latest_volume - avg_volume) / avg_volume * 100.0;
-        
-        analysis.push_str(&format!("Volume: ${:.2}M (", latest_volume / 1_000_000.0));
-        analysis.push_str(&format!("{:+.2}% vs avg)\n", volume_change));
+        match self.volume_vs_average(tech_data) {
+            Some((latest_volume, volume_change)) => {
+                analysis.push_str(&format!("Volume: ${:.2}M (", latest_volume / 1_000_000.0));
+                analysis.push_str(&format!("{:+.2}% vs avg)\n", volume_change));
+            }
+            None => analysis.push_str("Volume: n/a (no volume data)\n"),
+        }
 
         // Pattern Detection
         if let Some(patterns) = self.detect_patterns(&tech_data.candles) {
@@ -378,23 +616,31 @@ impl TechnicalAgent {
         }
 
         // Support/Resistance Levels
-        let (support, resistance) = self.calculate_support_resistance(&tech_data.candles);
-        analysis.push_str(&format!("Support: ${:.2}\n", support));
-        analysis.push_str(&format!("Resistance: ${:.2}\n", resistance));
+        let (support, resistance) = self.calculate_support_resistance(&tech_data.candles).unzip();
+        analysis.push_str(&format!("Support: {}\n", prompt_values::price(support)));
+        analysis.push_str(&format!("Resistance: {}\n", prompt_values::price(resistance)));
 
         analysis
     }
 
-    fn calculate_support_resistance(&self, candles: &[CandleData]) -> (f64, f64) {
-        let mut prices: Vec<f64> = candles.iter()
-            .map(|c| c.close)
-            .collect();
-        prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// Latest candle volume and its change vs the average, None without volume data
+    fn volume_vs_average(&self, tech_data: &TechnicalData) -> Option<(f64, f64)> {
+        if !tech_data.has_volume {
+            return None;
+        }
+        let latest_volume = tech_data.candles.last()?.volume;
+        let avg_volume = tech_data.candles.iter()
+            .map(|c| c.volume)
+            .sum::<f64>() / tech_data.candles.len() as f64;
+        if avg_volume <= 0.0 {
+            return None;
+        }
 
-        let lower_quartile = prices[prices.len() / 4];
-        let upper_quartile = prices[3 * prices.len() / 4];
+        Some((latest_volume, (latest_volume - avg_volume) / avg_volume * 100.0))
+    }
 
-        (lower_quartile, upper_quartile)
+    fn calculate_support_resistance(&self, candles: &[CandleData]) -> Option<(f64, f64)> {
+        ta::support_resistance(&to_ta_candles(candles))
     }
 
     pub async fn think_with_data(&self, technical_data: &str) -> Result<String> {
@@ -416,12 +662,13 @@ impl TechnicalAgent {
             "• Total Market Cap: ${:.2}B\n\
              • BTC Dominance: {:.2}%\n\
              • Market Cap Change: {:.2}%\n\
-             • Volume Change: {:.2}%\n\
+             • Volume Change: {}\n\
              • Volatility Index: {:.2}\n",
             technical_data.global_metrics.total_market_cap / 1e9,
             technical_data.global_metrics.btc_dominance,
             technical_data.global_metrics.market_cap_change_24h,
-            technical_data.global_metrics.volume_change_24h,
+            technical_data.global_metrics.volume_change_24h
+                .map_or("n/a".to_string(), |change| format!("{:.2}%", change)),
             technical_data.global_metrics.volatility_index
         ));
 
@@ -439,14 +686,12 @@ impl TechnicalAgent {
             technical_data.global_metrics.rwa_sector_dominance
         ));
 
-        // Enhanced coin analysis
-        let major_coins = [
-            ("BTC", &technical_data.btc_data),
-            ("ETH", &technical_data.eth_data),
-            ("SOL", &technical_data.sol_data)
-        ];
+        if let Some(rotation) = SectorRotation::from_sectors(&technical_data.sectors) {
+            context.push_str(&rotation.summary());
+        }
 
-        for (symbol, data) in major_coins {
+        // Enhanced coin analysis
+        for (symbol, data) in &technical_data.major_coins {
             context.push_str(&format!("\n💎 {} Analysis:\n", symbol));
             context.push_str(&self.format_enhanced_coin_data(symbol, data));
         }
@@ -459,12 +704,13 @@ impl TechnicalAgent {
         }
 
         // Get AI analysis with enhanced prompt
-        let response = self.base.generate_response(
+        let prompt = format!(
             "Analyze the current market conditions from a technical analysis perspective. \
              Focus on identifying key patterns, trend strength, and potential trading opportunities. \
-             Include specific price targets and risk levels.",
-            Some(&context)
-        ).await?;
+             Include specific price targets and risk levels. {}",
+            MISSING_DATA_INSTRUCTION
+        );
+        let response = self.base.generate_response(&prompt, Some(&context)).await?;
         
         Ok(response)
     }
@@ -472,24 +718,28 @@ impl TechnicalAgent {
     fn format_enhanced_coin_data(&self, _symbol: &str, data: &TechnicalData) -> String {
         let mut analysis = String::new();
         
-        let price = data.current_price.unwrap_or_else(|| data.candles.last().unwrap().close);
-        let rsi = data.rsi_14.unwrap_or_default();
-        let ma50 = data.ma_50.unwrap_or_default();
-        let ma200 = data.ma_200.unwrap_or_default();
+        let price = data.current_price.or_else(|| data.candles.last().map(|c| c.close));
 
         // Enhanced price analysis
-        analysis.push_str(&format!("• Price: ${:.2}\n", price));
+        analysis.push_str(&format!("• Price: {}\n", prompt_values::price(price)));
         if let Some(change) = data.price_change_24h {
             analysis.push_str(&format!("• 24h Change: {:.2}%\n", change));
         }
 
         // Enhanced technical indicators
-        analysis.push_str(&format!("• RSI (14): {:.2} - {}\n", rsi, self.interpret_rsi(rsi)));
+        match data.rsi_14 {
+            Some(rsi) => analysis.push_str(&format!("• RSI (14): {:.2} - {}\n", rsi, self.interpret_rsi(rsi))),
+            None => analysis.push_str(&format!("• RSI (14): {}\n", prompt_values::NOT_AVAILABLE)),
+        }
         
         // Enhanced MA analysis with crossover detection
-        analysis.push_str(&format!("• 50 MA: ${:.2}\n", ma50));
-        analysis.push_str(&format!("• 200 MA: ${:.2}\n", ma200));
-        analysis.push_str(&format!("• MA Status: {}\n", self.analyze_ma_status(price, ma50, ma200)));
+        analysis.push_str(&format!("• 50 MA: {}\n", prompt_values::price(data.ma_50)));
+        analysis.push_str(&format!("• 200 MA: {}\n", prompt_values::price(data.ma_200)));
+        let ma_status = match (price, data.ma_50, data.ma_200) {
+            (Some(price), Some(ma50), Some(ma200)) => self.analyze_ma_status(price, ma50, ma200),
+            _ => prompt_values::NOT_AVAILABLE.to_string(),
+        };
+        analysis.push_str(&format!("• MA Status: {}\n", ma_status));
 
         // Add MACD analysis
         if let Some((macd, signal, hist)) = data.macd {
@@ -500,7 +750,7 @@ impl TechnicalAgent {
         }
 
         // Add Bollinger Bands analysis
-        if let Some((upper, middle, lower)) = data.bollinger_bands {
+        if let (Some(price), Some((upper, middle, lower))) = (price, data.bollinger_bands) {
             analysis.push_str(&format!("• Bollinger Bands: {}\n", 
                 self.analyze_bollinger_bands(price, upper, middle, lower)
             ));
@@ -549,17 +799,19 @@ impl TechnicalAgent {
             "Market Overview:\n\
              Total Market Cap: ${:.2}B\n\
              BTC Dominance: {:.2}%\n\
-             ETH Dominance: {:.2}%\n\
-             SOL Dominance: {:.2}%\n\
              24h Volume: ${:.2}B\n\
-             Market Cap Change 24h: {:.2}%\n\n",
+             Market Cap Change 24h: {:.2}%\n",
             market_data.global_metrics.total_market_cap / 1e9,
             market_data.global_metrics.btc_dominance,
-            market_data.global_metrics.eth_dominance,
-            market_data.global_metrics.sol_dominance,
             market_data.global_metrics.total_volume_24h / 1e9,
             market_data.global_metrics.market_cap_change_24h,
         ));
+        for (symbol, dominance) in &market_data.global_metrics.major_dominance {
+            if symbol != "BTC" {
+                prompt.push_str(&format!("{} Dominance: {:.2}%\n", symbol, dominance));
+            }
+        }
+        prompt.push('\n');
 
         // Add sector analysis
         prompt.push_str("Sector Analysis:\n\n");
@@ -606,17 +858,16 @@ impl TechnicalAgent {
             market_data.global_metrics.rwa_sector_dominance,
         ));
 
+        if let Some(rotation) = SectorRotation::from_sectors(&market_data.sectors) {
+            prompt.push_str(&rotation.summary());
+        }
+
         // Add major coins analysis
         prompt.push_str("Major Coins Analysis:\n\n");
         
-        // BTC Analysis
-        self.add_coin_analysis(&mut prompt, "BTC", &market_data.btc_data);
-        
-        // ETH Analysis
-        self.add_coin_analysis(&mut prompt, "ETH", &market_data.eth_data);
-        
-        // SOL Analysis
-        self.add_coin_analysis(&mut prompt, "SOL", &market_data.sol_data);
+        for (symbol, data) in &market_data.major_coins {
+            self.add_coin_analysis(&mut prompt, symbol, data);
+        }
 
         // Add trending coins
         prompt.push_str("\nTrending Coins:\n");
@@ -626,12 +877,14 @@ impl TechnicalAgent {
 
         // Get analysis from LLM
         let response = self.base.generate_response(&prompt, None).await?;
+        let confidence = ConfidenceMetrics::parse(&response);
 
         Ok(TechnicalAnalysis {
-            analysis: response.clone(),
-            market_outlook: self.extract_market_outlook(&response),
-            risk_level: self.extract_risk_level(&response),
+            market_outlook: extract_market_outlook(&response, &confidence, &self.keywords),
+            risk_level: extract_risk_level(&response, &self.keywords),
+            analysis: response,
             quote: String::new(),
+            confidence,
         })
     }
 
@@ -671,38 +924,6 @@ impl TechnicalAgent {
         }
     }
 
-    fn extract_market_outlook(&self, response: &str) -> String {
-        let response_lower = response.to_lowercase();
-        
-        // Check confidence levels
-        if let Some(start) = response_lower.find("bullish scenario") {
-            if let Some(end) = response_lower[start..].find("%") {
-                let confidence_text = &response_lower[start..start+end];
-                if let Some(confidence) = confidence_text.split(":").nth(1) {
-                    if let Ok(confidence_num) = confidence.trim().parse::<f64>() {
-                        if confidence_num > 50.0 {
-                            return "Bullish".to_string();
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Check for explicit bearish signals
-        if response_lower.contains("strong bearish phase") 
-            || response_lower.contains("bearish momentum")
-            || (response_lower.contains("bearish scenario") && response_lower.contains("65%")) {
-            return "Bearish".to_string();
-        }
-        
-        "Neutral".to_string()
-    }
-
-    fn extract_risk_level(&self, _response: &str) -> String {
-        // TODO: Implement proper risk level extraction
-        String::from("Medium")
-    }
-
     /// Analyzes a specific sector with volume and dominance metrics
     /// # Arguments
     /// * `name` - Sector name (e.g. "AI Sector")
@@ -744,13 +965,16 @@ impl TechnicalAgent {
     }
 
     fn format_technical_overview(&self, data: &MarketTechnicalData, days: u32) -> String {
-        format!(
-            "BTC: ${:.2}\nETH: ${:.2}\nTotal Market Cap: ${:.2}B\nAnalysis Period: {} days",
-            data.btc_data.current_price.unwrap_or(0.0),
-            data.eth_data.current_price.unwrap_or(0.0),
+        let mut overview = String::new();
+        for (symbol, coin) in &data.major_coins {
+            overview.push_str(&format!("{}: {}\n", symbol, prompt_values::price(coin.current_price)));
+        }
+        overview.push_str(&format!(
+            "Total Market Cap: ${:.2}B\nAnalysis Period: {} days",
             data.global_metrics.total_market_cap / 1e9,
             days
-        )
+        ));
+        overview
     }
 
     fn interpret_rsi(&self, rsi: f64) -> &str {
@@ -832,15 +1056,21 @@ impl Agent for TechnicalAgent {
         market_data: &MarketData,
         previous_message: Option<String>
     ) -> Result<String> {
-        let client = CoinGeckoClient::new()?;
-        let technical_data = client.get_technical_analysis().await?;
+        let technical_data = match &self.round_cache {
+            Some(cache) => cache.technical_data().await?.clone(),
+            None => {
+                let client = CoinGeckoClient::new()?.with_candle_source(CandleSource::WithVolume);
+                client.get_technical_analysis().await?
+            }
+        };
         
         let days = 14u32;
         let context = serde_json::to_string_pretty(&technical_data)?;
         
         let prompt = format!(
-            "Analyze these market conditions:\n{}",
-            self.format_technical_overview(&technical_data, days)
+            "Analyze these market conditions:\n{}\n{}",
+            self.format_technical_overview(&technical_data, days),
+            MISSING_DATA_INSTRUCTION
         );
 
         let response = self.base.generate_response(&prompt, Some(&context)).await?;
@@ -869,4 +1099,146 @@ impl Agent for TechnicalAgent {
     fn memory_file(&self) -> PathBuf {
         self.base.memory_file()
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outlook(response: &str) -> String {
+        extract_market_outlook(response, &ConfidenceMetrics::parse(response), &SignalKeywords::default())
+    }
+
+    fn risk(response: &str) -> String {
+        extract_risk_level(response, &SignalKeywords::default())
+    }
+
+    #[test]
+    fn filled_template_uses_confidences_and_risk_line() {
+        let response = "📊 Market Analysis:\n\
+            SOL is consolidating under resistance with a bearish divergence on the 4h RSI.\n\n\
+            🎯 Confidence Metrics:\n\
+            - Bullish Scenario Confidence: 70%\n\
+            - Bearish Scenario Confidence: 30%\n\
+            - Overall Analysis Confidence: 65%\n\
+            - Key Support/Resistance Confidence: 80%\n\n\
+            🎯 FINAL RECOMMENDATION:\n\
+            Action: BUY\n\
+            Risk Level: **High**\n";
+
+        assert_eq!(outlook(response), "Bullish");
+        assert_eq!(risk(response), "High");
+        assert_eq!(
+            ConfidenceMetrics::parse(response),
+            ConfidenceMetrics {
+                bullish: Some(70.0),
+                bearish: Some(30.0),
+                overall: Some(65.0),
+                support_resistance: Some(80.0),
+            }
+        );
+    }
+
+    #[test]
+    fn synonyms_are_recognised() {
+        let response = "Price keeps printing higher highs and the chart shows a clear bullish bias. \
+            Volatility is elevated, so this is an elevated risk setup.";
+
+        assert_eq!(outlook(response), "Bullish");
+        assert_eq!(risk(response), "High");
+    }
+
+    #[test]
+    fn conflicting_signals_are_resolved_by_counts_not_order() {
+        // "low risk" comes first but outlook and risk are scored separately
+        let response = "Low risk entry here, but the structure is bearish: a breakdown below \
+            support confirms the downtrend.";
+
+        assert_eq!(outlook(response), "Bearish");
+        assert_eq!(risk(response), "Low");
+
+        // Equal bullish and bearish evidence is neutral whichever comes first
+        assert_eq!(outlook("Bullish on the daily, bearish on the weekly."), "Neutral");
+        assert_eq!(outlook("Bearish on the weekly, bullish on the daily."), "Neutral");
+    }
+
+    #[test]
+    fn unfilled_template_and_negations_fall_back_to_defaults() {
+        let response = "🎯 Confidence Metrics:\n\
+            - Bullish Scenario Confidence: [0-100%]\n\
+            - Bearish Scenario Confidence: [0-100%]\n\
+            Risk Level: [Low/Medium/High]\n\
+            The market is not bullish yet.";
+
+        let confidence = ConfidenceMetrics::parse(response);
+        assert_eq!(confidence.bullish, None);
+        assert_eq!(confidence.bearish, None);
+        assert_eq!(outlook(response), "Neutral");
+        assert_eq!(risk(response), "Medium");
+    }
+
+    fn sector(name: &str, change: Option<f64>, volume: Option<f64>) -> CategoryData {
+        CategoryData {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            market_cap: Some(10e9),
+            market_cap_change_24h: change,
+            content: String::new(),
+            top_3_coins_id: vec![],
+            top_3_coins: vec![],
+            volume_24h: volume,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn sector_rotation_skips_sectors_without_data() {
+        let sectors = vec![
+            sector("AI", Some(4.2), Some(2e9)),
+            sector("Layer 1", Some(-1.5), Some(8e9)),
+            sector("Layer 2", None, None), // endpoint failed
+            sector("RWA", Some(0.8), Some(1e9)),
+        ];
+
+        let rotation = SectorRotation::from_sectors(&sectors).unwrap();
+        let names: Vec<&str> = rotation.ranking.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["AI", "RWA", "Layer 1"]);
+        assert_eq!(rotation.leader().name, "AI");
+        assert_eq!(rotation.laggard().name, "Layer 1");
+
+        assert!(SectorRotation::from_sectors(&sectors[2..3]).is_none());
+    }
+
+    #[test]
+    fn keyword_map_is_configurable() {
+        let keywords = SignalKeywords {
+            bullish: vec!["moon".to_string()],
+            bearish: vec!["rekt".to_string()],
+            high_risk: vec!["degen".to_string()],
+            low_risk: vec![],
+        };
+        let response = "Full degen play, this one is going to the moon.";
+
+        assert_eq!(extract_market_outlook(response, &ConfidenceMetrics::default(), &keywords), "Bullish");
+        assert_eq!(extract_risk_level(response, &keywords), "High");
+    }
+
+    #[test]
+    fn templates_follow_coin_overrides_then_market_cap_tiers() {
+        let mut templates = TechnicalTemplatesConfig {
+            large_cap_usd: 50e9,
+            ..TechnicalTemplatesConfig::default()
+        };
+        let name = |templates: &TechnicalTemplatesConfig, id: &str, cap: f64| templates.select(id, cap).map(|(name, _)| name.to_string());
+
+        assert_eq!(name(&templates, "dogecoin", 20e9).as_deref(), Some("meme"));
+        assert_eq!(name(&templates, "chainlink", 20e9).as_deref(), Some("mid"));
+        assert_eq!(name(&templates, "bitcoin", 1.2e12).as_deref(), Some("large"));
+        assert_eq!(name(&templates, "some-new-token", 3e6).as_deref(), Some("micro"));
+
+        // No market cap, or a tier without a template, keeps the default analysis
+        assert_eq!(name(&templates, "unlisted", 0.0), None);
+        templates.templates.remove("micro");
+        assert_eq!(name(&templates, "some-new-token", 3e6), None);
+    }
+}
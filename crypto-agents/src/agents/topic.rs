@@ -5,13 +5,17 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::collections::HashMap;
 
+use crate::config::CryptoAgentsConfig;
 use crate::models::{MarketData, Conversation};
+use crate::snapshot::SnapshotNews;
+use crate::output::say;
+use crate::prompt_values::{self, MISSING_DATA_INSTRUCTION};
 use crate::api::{
     coingecko::DetailedCoinData,
     social_media::SocialMediaPost,
 };
 use super::{Agent, BaseAgent, ModelProvider};
-use common::exa::{ExaClient, ExaSearchParams, Contents, Highlights, Summary};
+use common::exa::{ExaClient, ExaSearchParams};
 
 const TOPIC_SYSTEM_PROMPT: &str = r#"
 You are a Market Topics Analysis AI specializing in cryptocurrency market analysis.
@@ -107,6 +111,11 @@ impl TopicAgent {
         })
     }
 
+    /// Stop fetching live news from Exa, e.g. when replaying a snapshot
+    pub fn disable_live_news(&mut self) {
+        self.exa_client = None;
+    }
+
     pub async fn analyze_sector(&mut self, sector: &str, market_data: &MarketData) -> Result<TopicAnalysis> {
         let prompt = format!(
             "Analyze the {} sector in detail. Focus on current trends, key projects, and upcoming catalysts.",
@@ -144,29 +153,30 @@ impl TopicAgent {
     ) -> Result<TopicAnalysis> {
         let prompt = format!(
             "Analyze {} ({}) with the following data:\n\
-            Current Price: ${:.4}\n\
-            1h Change: {:.2}%\n\
-            24h Change: {:.2}%\n\
-            7d Change: {:.2}%\n\
-            Market Cap: ${:.2}M\n\
-            24h Volume: ${:.2}M\n\
+            Current Price: {}\n\
+            1h Change: {}\n\
+            24h Change: {}\n\
+            7d Change: {}\n\
+            Market Cap: {}\n\
+            24h Volume: {}\n\
             Social Media Posts: {}\n\
             Average Engagement: {:.1}\n\n\
-            Focus on market trends, social sentiment, and upcoming catalysts.",
+            Focus on market trends, social sentiment, and upcoming catalysts. {}",
             coin_data.name,
             symbol,
-            coin_data.current_price,
-            coin_data.price_change_1h.unwrap_or_default(),
-            coin_data.price_change_24h.unwrap_or_default(),
-            coin_data.price_change_7d.unwrap_or_default(),
-            coin_data.market_cap / 1_000_000.0,
-            coin_data.volume_24h / 1_000_000.0,
+            prompt_values::price(coin_data.current_price),
+            prompt_values::percent(coin_data.price_change_1h),
+            prompt_values::percent(coin_data.price_change_24h),
+            prompt_values::percent(coin_data.price_change_7d),
+            prompt_values::millions(coin_data.market_cap),
+            prompt_values::millions(coin_data.volume_24h),
             sentiment_data.len(),
             if !sentiment_data.is_empty() {
                 sentiment_data.iter().map(|p| p.engagement).sum::<i32>() as f64 / sentiment_data.len() as f64
             } else {
                 0.0
-            }
+            },
+            MISSING_DATA_INSTRUCTION
         );
 
         // Add sample tweets for context
@@ -300,14 +310,7 @@ impl TopicAgent {
     pub fn format_market_data(&self, coin_data: &DetailedCoinData) -> String {
         let mut output = String::new();
         
-        // Format price with appropriate precision
-        let price = if coin_data.current_price < 0.01 {
-            format!("${:.8}", coin_data.current_price)
-        } else {
-            format!("${:.4}", coin_data.current_price)
-        };
-        
-        output.push_str(&format!("Current Price: {}\n", price));
+        output.push_str(&format!("Current Price: {}\n", prompt_values::price(coin_data.current_price)));
         
         // Format price changes
         if let Some(change) = coin_data.price_change_1h {
@@ -321,24 +324,53 @@ impl TopicAgent {
         }
         
         // Format market cap
-        if coin_data.market_cap > 0.0 {
-            let market_cap_m = coin_data.market_cap / 1_000_000.0;
-            output.push_str(&format!("Market Cap: ${:.2}M\n", market_cap_m));
+        if let Some(market_cap) = coin_data.market_cap.filter(|cap| *cap > 0.0) {
+            output.push_str(&format!("Market Cap: ${:.2}M\n", market_cap / 1_000_000.0));
         }
         
         // Format volume
-        if coin_data.volume_24h > 0.0 {
-            let volume_m = coin_data.volume_24h / 1_000_000.0;
-            output.push_str(&format!("24h Volume: ${:.2}M", volume_m));
+        if let Some(volume) = coin_data.volume_24h.filter(|volume| *volume > 0.0) {
+            output.push_str(&format!("24h Volume: ${:.2}M", volume / 1_000_000.0));
         }
         
         output
     }
 
+    /// Recent market news from Exa, None when there is no Exa client
+    pub async fn fetch_market_news(&self) -> Result<Option<Vec<SnapshotNews>>> {
+        let Some(client) = &self.exa_client else {
+            return Ok(None);
+        };
+        let news_config = CryptoAgentsConfig::load().news;
+        let contents = news_config.exa_contents();
+        let search_params = ExaSearchParams {
+            query: news_config.query("cryptocurrency market trends developments price analysis"),
+            num_results: news_config.num_results,
+            include_domains: news_config.valid_domains()?,
+            start_date: Some(Utc::now() - Duration::hours(news_config.lookback_hours)),
+            end_date: None,
+            contents: Some(contents),
+        };
+
+        let results = client.search_crypto(search_params).await?;
+        Ok(Some(results.iter().map(SnapshotNews::from).collect()))
+    }
+
     pub async fn analyze_market_topics(
+        &self,
+        market_data: &MarketData,
+        technical_analysis: &str,
+    ) -> Result<String> {
+        let news = self.fetch_market_news().await.map_err(|e| e.to_string());
+        self.analyze_market_news(market_data, technical_analysis, &news).await
+    }
+
+    /// Market topics from news fetched beforehand, e.g. the news saved with a snapshot
+    pub async fn analyze_market_news(
         &self,
         market_data: &MarketData,
         _technical_analysis: &str,
+        news: &std::result::Result<Option<Vec<SnapshotNews>>, String>,
     ) -> Result<String> {
         let mut analysis = String::new();
         
@@ -355,93 +387,70 @@ impl TopicAgent {
             market_data.overview.market_cap_change_percentage_24h
         ));
 
-        if let Some(client) = &self.exa_client {
-            let contents = Contents {
-                text: true,
-                highlights: Some(Highlights {
-                    num_sentences: 3,
-                    highlights_per_result: 3,
-                }),
-                summary: Some(Summary {
-                    max_sentences: 5,
-                }),
-            };
-
-            let search_params = ExaSearchParams {
-                query: "cryptocurrency market latest news trends developments price analysis".to_string(),
-                num_results: 10,
-                include_domains: vec![
-                    "beincrypto.com".to_string(),
-                ],
-                start_date: Some(Utc::now() - Duration::hours(24)),
-                end_date: None,
-                contents: Some(contents),
-            };
-
-            match client.search_crypto(search_params).await {
-                Ok(results) => {
-                    if !results.is_empty() {
-                        let mut content_for_analysis = String::new();
-                        let mut sources = Vec::new();
+        match news {
+            Ok(Some(results)) => {
+                if !results.is_empty() {
+                    let mut content_for_analysis = String::new();
+                    let mut sources = Vec::new();
+                    
+                    for result in results {
+                        // Store source information
+                        sources.push(format!(
+                            "• {} ({})\n  🔗 {}", 
+                            result.title,
+                            result.published_date
+                                .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+                                .unwrap_or_else(|| "Recent".to_string()),
+                            result.url
+                        ));
                         
-                        for result in &results {
-                            // Store source information
-                            sources.push(format!(
-                                "• {} ({})\n  🔗 {}", 
-                                result.title,
-                                result.published_date
-                                    .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
-                                    .unwrap_or_else(|| "Recent".to_string()),
-                                result.url
-                            ));
-                            
-                            // Prepare content for analysis
-                            content_for_analysis.push_str(&format!(
-                                "\n📰 {}\n",
-                                result.title
-                            ));
-                            
-                            if let Some(summary) = &result.summary {
-                                content_for_analysis.push_str(&format!("Summary: {}\n", summary));
-                            }
-                            
-                            if !result.highlights.is_empty() {
-                                content_for_analysis.push_str("Key Points:\n");
-                                for highlight in &result.highlights {
-                                    content_for_analysis.push_str(&format!("• {}\n", highlight));
-                                }
+                        // Prepare content for analysis
+                        content_for_analysis.push_str(&format!(
+                            "\n📰 {}\n",
+                            result.title
+                        ));
+                        
+                        if let Some(summary) = &result.summary {
+                            content_for_analysis.push_str(&format!("Summary: {}\n", summary));
+                        }
+                        
+                        if !result.highlights.is_empty() {
+                            content_for_analysis.push_str("Key Points:\n");
+                            for highlight in &result.highlights {
+                                content_for_analysis.push_str(&format!("• {}\n", highlight));
                             }
                         }
+                    }
 
-                        let prompt = format!(
-                            "Analyze these recent market developments and provide a concise summary:\n\n{}\n\n\
-                             Format the analysis as:\n\
-                             1. Key Market Trends (3-4 points)\n\
-                             2. Important Developments (2-3 points)\n\
-                             3. Potential Market Impacts (2-3 points)",
-                            content_for_analysis
-                        );
-
-                        if let Ok(ai_analysis) = self.base.generate_response(&prompt, None).await {
-                            analysis.push_str(&ai_analysis);
-                            
-                            // Add sources section at the end
-                            analysis.push_str("\n\n📚 Sources:\n");
-                            for source in sources {
-                                analysis.push_str(&format!("{}\n", source));
-                            }
+                    let prompt = format!(
+                        "Analyze these recent market developments and provide a concise summary:\n\n{}\n\n\
+                         Format the analysis as:\n\
+                         1. Key Market Trends (3-4 points)\n\
+                         2. Important Developments (2-3 points)\n\
+                         3. Potential Market Impacts (2-3 points)",
+                        content_for_analysis
+                    );
+
+                    if let Ok(ai_analysis) = self.base.generate_response(&prompt, None).await {
+                        analysis.push_str(&ai_analysis);
+                        
+                        // Add sources section at the end
+                        analysis.push_str("\n\n📚 Sources:\n");
+                        for source in sources {
+                            analysis.push_str(&format!("{}\n", source));
                         }
-                    } else {
-                        analysis.push_str("No recent market developments found.\n");
                     }
-                }
-                Err(e) => {
-                    println!("⚠️ Error fetching market news: {}", e);
-                    analysis.push_str("Error fetching market developments.\n");
+                } else {
+                    analysis.push_str("No recent market developments found.\n");
                 }
             }
-        } else {
-            analysis.push_str("Exa client not available for market analysis.\n");
+            Ok(None) => {
+                analysis.push_str("Exa client not available for market analysis.\n");
+            }
+            Err(e) => {
+                say(format!("⚠️ Error fetching market news: {}", e));
+                analysis.push_str("Error fetching market developments.\n");
+            }
         }
 
         Ok(analysis)
@@ -449,16 +458,7 @@ impl TopicAgent {
 
     // Dynamic prompt generation based on analysis type
     fn generate_search_prompt(&self, analysis_type: &str, context: &str) -> ExaSearchParams {
-        let base_contents = Contents {
-            text: true,
-            highlights: Some(Highlights {
-                num_sentences: 3,
-                highlights_per_result: 3,
-            }),
-            summary: Some(Summary {
-                max_sentences: 5,
-            }),
-        };
+        let base_contents = CryptoAgentsConfig::load().news.exa_contents();
 
         match analysis_type {
             "news" => ExaSearchParams {
@@ -532,7 +532,7 @@ impl TopicAgent {
                         all_results.push((analysis_type, results));
                     },
                     Err(e) => {
-                        println!("⚠️ Error fetching {} data: {}", analysis_type, e);
+                        say(format!("⚠️ Error fetching {} data: {}", analysis_type, e));
                     }
                 }
             }
@@ -609,7 +609,7 @@ impl Agent for TopicAgent {
 
         let mut full_analysis = String::new();
         
-        println!("🔍 Starting sector analysis with news integration...");
+        say("🔍 Starting sector analysis with news integration...");
         
         for sector in sectors {
             match self.analyze_with_news(sector).await {
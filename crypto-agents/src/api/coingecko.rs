@@ -1,4 +1,14 @@
 use serde::{Serialize, Deserialize};
+use crate::config::CryptoAgentsConfig;
+use crate::output::say;
+use crate::prompt_values;
+use common::paths;
+use common::progress::Progress;
+use common::status;
+use common::ta;
+use common::circuit::{self, CircuitBreaker};
+use common::rate_limit::{self, RateLimiter};
+use super::historical::BulkHistorical;
 use crate::models::{MarketData, GlobalData, CoinData, TrendingCoin, AgentError};
 use anyhow::{Result, Context};
 use reqwest::Client;
@@ -8,6 +18,7 @@ use std::fs::{self, File};
 use std::io::{BufReader, BufWriter};
 use std::path::Path;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 const BASE_URL: &str = "https://api.coingecko.com/api/v3";
@@ -15,6 +26,9 @@ const BASE_DELAY: u64 = 3;  // Increase base delay to 3 seconds
 const REQUEST_DELAY: u64 = 3;     // Delay between requests
 const MAX_RETRIES: u32 = 3;
 const DEMO_API_KEY: &str = "CG-mVrwoy4JYveQ5MvX2Z2jbsyn";
+// The demo plan allows 30 calls a minute; override with COINGECKO_MIN_INTERVAL_MS
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(2500);
+const HISTORICAL_CACHE_DIR: &str = "historical_cache";
 
 // Add this attribute to hide dead code warnings
 #[allow(dead_code)]
@@ -62,6 +76,128 @@ const LAYER1_COINS: &str = "ethereum,bitcoin,solana,cardano,avalanche-2,cosmos,p
 #[allow(dead_code)]
 const LAYER2_COINS: &str = "polygon,optimism,arbitrum,immutable-x,loopring,zkspace,starknet,metis-token,boba-network,zksync-era,mantle,base,linea,scroll";
 
+// Sector label and CoinGecko category id for the sectors we track
+const SECTOR_CATEGORIES: &[(&str, &str)] = &[
+    ("AI", "artificial-intelligence"),
+    ("Layer 1", "layer-1"),
+    ("Layer 2", "layer-2"),
+    ("RWA", "real-world-assets-rwa"),
+];
+
+// (CoinGecko id, ticker) for coins whose ticker maps to exactly one id
+const KNOWN_COINS: &[(&str, &str)] = &[
+    ("bitcoin", "BTC"),
+    ("ethereum", "ETH"),
+    ("solana", "SOL"),
+    ("binancecoin", "BNB"),
+    ("ripple", "XRP"),
+    ("cardano", "ADA"),
+    ("avalanche-2", "AVAX"),
+    ("polkadot", "DOT"),
+    ("chainlink", "LINK"),
+    ("the-open-network", "TON"),
+    ("tron", "TRX"),
+    ("sui", "SUI"),
+    ("near", "NEAR"),
+    ("dogecoin", "DOGE"),
+];
+
+// Ticker for a CoinGecko id, falling back to the uppercased id for coins not listed here
+fn major_coin_symbol(coin_id: &str) -> String {
+    KNOWN_COINS
+        .iter()
+        .find(|(id, _)| *id == coin_id)
+        .map(|(_, symbol)| symbol.to_string())
+        .unwrap_or_else(|| coin_id.to_uppercase())
+}
+
+/// CoinGecko id for a ticker like "SOL" or "$sol". None for tickers that aren't known,
+/// since many coins share a ticker and guessing would report the wrong price.
+pub fn coin_id_for_symbol(symbol: &str) -> Option<&'static str> {
+    let symbol = symbol.trim().trim_start_matches('$');
+    KNOWN_COINS
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(symbol))
+        .map(|(id, _)| *id)
+}
+
+// Longest slice of a response body included in error messages
+const ERROR_BODY_PREVIEW_CHARS: usize = 200;
+
+fn body_preview(text: &str) -> String {
+    let trimmed = text.trim();
+    let mut preview: String = trimmed.chars().take(ERROR_BODY_PREVIEW_CHARS).collect();
+    if trimmed.chars().count() > ERROR_BODY_PREVIEW_CHARS {
+        preview.push('…');
+    }
+    preview
+}
+
+/// Error message from a CoinGecko JSON error body, either
+/// `{"status":{"error_message":...}}` or `{"error":...}`
+fn api_error_message(text: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(text).ok()?;
+    value["status"]["error_message"]
+        .as_str()
+        .or_else(|| value["error"].as_str())
+        .map(|message| message.to_string())
+}
+
+/// Parse a response body as JSON. HTML pages (e.g. rate-limit pages) and CoinGecko
+/// error objects get a short, readable error instead of the whole body.
+fn parse_response_body(status: u16, text: &str) -> Result<Value> {
+    if !matches!(text.trim_start().chars().next(), Some('{') | Some('[')) {
+        anyhow::bail!("non-JSON response (status {}): {}", status, body_preview(text));
+    }
+    if let Some(message) = api_error_message(text) {
+        anyhow::bail!("CoinGecko API error (status {}): {}", status, message);
+    }
+    serde_json::from_str(text)
+        .with_context(|| format!("Failed to parse JSON response (status {}): {}", status, body_preview(text)))
+}
+
+// Start of the cache file names for one coin and range. The id comes from the
+// caller, so it's sanitized to stay a single file name inside the cache directory.
+fn historical_cache_prefix(coin_id: &str, days: u32) -> String {
+    format!("{}_{}d_", sanitize_filename::sanitize(coin_id), days)
+}
+
+// One file per coin, range and UTC day; daily closes don't change within the day
+fn historical_cache_path(coin_id: &str, days: u32) -> std::path::PathBuf {
+    let today = chrono::Utc::now().format("%Y-%m-%d");
+    paths::data_subdir(HISTORICAL_CACHE_DIR).join(format!("{}{}.json", historical_cache_prefix(coin_id, days), today))
+}
+
+fn load_cached_historical(coin_id: &str, days: u32) -> Option<HistoricalData> {
+    let content = fs::read_to_string(historical_cache_path(coin_id, days)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cached_historical(coin_id: &str, days: u32, data: &HistoricalData) -> Result<()> {
+    let path = historical_cache_path(coin_id, days);
+    // Drop this series' files from earlier days
+    let prefix = historical_cache_prefix(coin_id, days);
+    if let Ok(entries) = fs::read_dir(paths::data_subdir(HISTORICAL_CACHE_DIR)) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) && entry.path() != path {
+                fs::remove_file(entry.path()).ok();
+            }
+        }
+    }
+    fs::write(&path, serde_json::to_string(data)?)?;
+    Ok(())
+}
+
+fn sector_volumes(sectors: &[CategoryData]) -> (f64, f64, f64, f64) {
+    let volume = |label: &str| {
+        sectors.iter()
+            .find(|s| s.name == label)
+            .and_then(|s| s.volume_24h)
+            .unwrap_or(0.0)
+    };
+    (volume("AI"), volume("Layer 1"), volume("Layer 2"), volume("RWA"))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct DetailedCoinData {
@@ -69,11 +205,11 @@ pub struct DetailedCoinData {
     pub name: String,
     pub symbol: String,
     #[serde(default)]
-    pub current_price: f64,
+    pub current_price: Option<f64>,
     #[serde(default)]
-    pub market_cap: f64,
+    pub market_cap: Option<f64>,
     #[serde(default, rename = "total_volume")]
-    pub volume_24h: f64,
+    pub volume_24h: Option<f64>,
     #[serde(default, rename = "price_change_percentage_1h_in_currency")]
     pub price_change_1h: Option<f64>,
     #[serde(default, rename = "price_change_percentage_24h_in_currency")]
@@ -98,9 +234,45 @@ pub struct CandleData {
     pub volume: f64,
 }
 
+impl From<&CandleData> for ta::Candle {
+    fn from(c: &CandleData) -> Self {
+        ta::Candle {
+            timestamp: c.timestamp,
+            open: c.open,
+            high: c.high,
+            low: c.low,
+            close: c.close,
+            volume: c.volume,
+        }
+    }
+}
+
+pub(crate) fn to_ta_candles(candles: &[CandleData]) -> Vec<ta::Candle> {
+    candles.iter().map(ta::Candle::from).collect()
+}
+
+/// Where the client gets candles from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandleSource {
+    /// Plain `/ohlc` candles, volume is always 0. One request, fine for trend checks.
+    OhlcOnly,
+    /// `/ohlc` candles with volume merged in from `/market_chart`, needed for volume indicators
+    WithVolume,
+}
+
+/// Candles plus whether their volume field holds real data
+#[derive(Debug, Clone)]
+pub struct Candles {
+    pub candles: Vec<CandleData>,
+    pub has_volume: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TechnicalData {
     pub candles: Vec<CandleData>,
+    /// False when the candles came from `/ohlc` alone and their volume is 0
+    #[serde(default)]
+    pub has_volume: bool,
     pub rsi_14: Option<f64>,
     pub ma_50: Option<f64>,
     pub ma_200: Option<f64>,
@@ -111,24 +283,39 @@ pub struct TechnicalData {
     pub price_change_24h: Option<f64>,  // Added 24h price change
 }
 
+/// Current price data for one coin from `/simple/price`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimplePrice {
+    pub usd: f64,
+    pub market_cap: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub price_change_24h: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketTechnicalData {
-    pub btc_data: TechnicalData,
-    pub eth_data: TechnicalData,
-    pub sol_data: TechnicalData,
+    /// (symbol, data) for each configured major coin that loaded, in config order
+    #[serde(default)]
+    pub major_coins: Vec<(String, TechnicalData)>,
     pub trending_data: Vec<(String, TechnicalData)>,
     pub global_metrics: GlobalTechnicalMetrics,
+    /// Sector categories that loaded successfully, named "AI", "Layer 1" etc.
+    #[serde(default)]
+    pub sectors: Vec<CategoryData>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalTechnicalMetrics {
     pub total_market_cap: f64,
     pub btc_dominance: f64,
-    pub eth_dominance: f64,
-    pub sol_dominance: f64,
+    /// (symbol, dominance %) for the major coins with market cap share data
+    #[serde(default)]
+    pub major_dominance: Vec<(String, f64)>,
     pub total_volume_24h: f64,
     pub market_cap_change_24h: f64,
-    pub volume_change_24h: f64,
+    /// None when none of the major coins had volume data
+    #[serde(default)]
+    pub volume_change_24h: Option<f64>,
     pub defi_dominance: f64,      // Add DeFi sector dominance
     pub layer1_dominance: f64,    // Add Layer 1 dominance
     pub top10_dominance: f64,     // Concentration in top 10
@@ -169,7 +356,7 @@ pub struct CategoryListItem {
     pub name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoricalData {
     pub prices: Vec<[f64; 2]>,        // [timestamp, price]
     pub market_caps: Vec<[f64; 2]>,   // [timestamp, market_cap]
@@ -178,31 +365,50 @@ pub struct HistoricalData {
 
 pub struct CoinGeckoClient {
     client: Client,
+    major_coins: Vec<String>,
+    candle_source: CandleSource,
     processed_coins: std::collections::HashSet<String>,
     processed_coins_file: String,
     cache: HashMap<String, (TechnicalData, Instant)>,
     cache_duration: Duration,
+    breaker: Arc<CircuitBreaker>,
+    limiter: Arc<RateLimiter>,
 }
 
 impl CoinGeckoClient {
     pub fn new() -> Result<Self> {
-        let processed_coins_file = "data/processed_coins.json".to_string();
+        let processed_coins_file = paths::data_dir().join("processed_coins.json").to_string_lossy().into_owned();
         let processed_coins = Self::load_processed_coins(&processed_coins_file)?;
         
-        println!("📚 Loaded {} previously processed coins", processed_coins.len());
+        say(format!("📚 Loaded {} previously processed coins", processed_coins.len()));
         
         Ok(Self {
             client: Client::builder()
                 .timeout(Duration::from_secs(30))
                 .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
                 .build()?,
+            major_coins: CryptoAgentsConfig::load().major_coins,
+            candle_source: CandleSource::OhlcOnly,
             processed_coins,
             processed_coins_file,
             cache: HashMap::new(),
             cache_duration: Duration::from_secs(300), // 5 minute cache
+            breaker: circuit::breaker("CoinGecko"),
+            limiter: rate_limit::limiter("CoinGecko", MIN_REQUEST_INTERVAL),
         })
     }
     
+    /// Override the major coins (CoinGecko ids) loaded from the crypto-agents config
+    pub fn with_major_coins(mut self, major_coins: Vec<String>) -> Self {
+        self.major_coins = major_coins;
+        self
+    }
+
+    pub fn with_candle_source(mut self, candle_source: CandleSource) -> Self {
+        self.candle_source = candle_source;
+        self
+    }
+
     fn load_processed_coins(file_path: &str) -> Result<std::collections::HashSet<String>> {
         // Create directory if it doesn't exist
         if let Some(dir) = Path::new(file_path).parent() {
@@ -231,7 +437,11 @@ impl CoinGeckoClient {
         let mut retries = 0;
         
         loop {
-            println!("🌐 Making request to: {}", url);
+            // Shared across clients, so an outage seen by one agent stops the others retrying too
+            self.breaker.check()?;
+            // Shared too, so parallel agents and bulk fetches don't add up past the rate limit
+            self.limiter.acquire().await;
+            say(format!("🌐 Making request to: {}", url));
             
             // Add demo API key to query parameters
             let mut all_params = Vec::from(params);
@@ -249,12 +459,11 @@ impl CoinGeckoClient {
             match response {
                 Ok(res) => match res {
                     Ok(r) => {
-                        // Add delay between requests
-                        tokio::time::sleep(Duration::from_secs(6)).await;
-                        
                         if r.status() == 429 {
-                            println!("⚠️ Rate limited, waiting {} seconds...", delay);
-                            tokio::time::sleep(Duration::from_secs(delay)).await;
+                            // Up but throttling: the limiter handles that, it's no outage
+                            self.breaker.record_success();
+                            say(format!("⚠️ Rate limited, waiting {} seconds...", delay));
+                            self.limiter.back_off(Duration::from_secs(delay));
                             delay *= 2;
                             if retries >= MAX_RETRIES {
                                 return Err(anyhow::anyhow!("Rate limit exceeded after {} retries", MAX_RETRIES));
@@ -263,17 +472,27 @@ impl CoinGeckoClient {
                             continue;
                         }
                         
-                        if r.status().is_success() {
+                        let status = r.status();
+                        // A client error means the service is up and answering
+                        if status.is_server_error() {
+                            self.breaker.record_failure();
+                        } else {
+                            self.breaker.record_success();
+                        }
+                        if status.is_success() {
                             let text = r.text().await
             .context("Failed to get response text")?;
             
-                            return serde_json::from_str(&text)
-                                .with_context(|| format!("Failed to parse JSON response: {}", text));
+                            return parse_response_body(status.as_u16(), &text);
                         }
                         
-                        println!("⚠️ Request failed with status: {}", r.status());
+                        say(format!("⚠️ Request failed with status: {}", status));
                         if retries >= MAX_RETRIES {
-                            return Err(anyhow::anyhow!("Request failed with status: {}", r.status()));
+                            let text = r.text().await.unwrap_or_default();
+                            return Err(match api_error_message(&text) {
+                                Some(message) => anyhow::anyhow!("Request failed with status {}: {}", status, message),
+                                None => anyhow::anyhow!("Request failed with status: {}", status),
+                            });
                         }
                         retries += 1;
                         tokio::time::sleep(Duration::from_secs(delay)).await;
@@ -281,7 +500,8 @@ impl CoinGeckoClient {
                         continue;
                     }
                     Err(e) => {
-                        println!("⚠️ Request error: {}", e);
+                        self.breaker.record_failure();
+                        say(format!("⚠️ Request error: {}", e));
                         if retries >= MAX_RETRIES {
                             return Err(anyhow::anyhow!("Request failed: {}", e));
                         }
@@ -292,7 +512,8 @@ impl CoinGeckoClient {
                     }
                 },
                 Err(_) => {
-                    println!("⚠️ Request timed out");
+                    self.breaker.record_failure();
+                    say("⚠️ Request timed out");
                     if retries >= MAX_RETRIES {
                         return Err(anyhow::anyhow!("Request timed out after {} retries", MAX_RETRIES));
                     }
@@ -333,6 +554,14 @@ impl CoinGeckoClient {
                 .and_then(|v| v.as_i64())
                 .map(|v| v as i32)
                 .unwrap_or_default(),
+            market_cap_percentage: data.get("market_cap_percentage")
+                .and_then(|v| v.as_object())
+                .map(|shares| {
+                    shares.iter()
+                        .filter_map(|(symbol, share)| Some((symbol.to_lowercase(), share.as_f64()?)))
+                        .collect()
+                })
+                .unwrap_or_default(),
         })
     }
     
@@ -390,11 +619,11 @@ impl CoinGeckoClient {
     pub async fn get_market_data(&self) -> Result<MarketData> {
         let global_data = self.get_global_data().await?;
         
-        println!("Debug: Global Market Data");
-        println!("Market Cap: ${:.2}B", global_data.total_market_cap / 1_000_000_000.0);
-        println!("Volume: ${:.2}B", global_data.total_volume / 1_000_000_000.0);
-        println!("Active Coins: {}", global_data.active_cryptocurrencies);
-        println!("24h Change: {:.2}%", global_data.market_cap_change_percentage_24h);
+        say("Debug: Global Market Data");
+        say(format!("Market Cap: ${:.2}B", global_data.total_market_cap / 1_000_000_000.0));
+        say(format!("Volume: ${:.2}B", global_data.total_volume / 1_000_000_000.0));
+        say(format!("Active Coins: {}", global_data.active_cryptocurrencies));
+        say(format!("24h Change: {:.2}%", global_data.market_cap_change_percentage_24h));
 
         let btc_data = self.get_detailed_coin_data("bitcoin").await?;
         
@@ -409,16 +638,16 @@ impl CoinGeckoClient {
                 id: "bitcoin".to_string(),
                 symbol: "BTC".to_string(),
                 name: "Bitcoin".to_string(),
-                current_price: btc_data.current_price,
-                market_cap: btc_data.market_cap,
+                current_price: btc_data.current_price.unwrap_or_default(),
+                market_cap: btc_data.market_cap.unwrap_or_default(),
                 price_change_24h: btc_data.price_change_24h.unwrap_or(0.0),
             },
             ethereum: CoinData {
                 id: "ethereum".to_string(),
                 symbol: "ETH".to_string(),
                 name: "Ethereum".to_string(),
-                current_price: eth_data.current_price,
-                market_cap: eth_data.market_cap,
+                current_price: eth_data.current_price.unwrap_or_default(),
+                market_cap: eth_data.market_cap.unwrap_or_default(),
                 price_change_24h: eth_data.price_change_24h.unwrap_or(0.0),
             },
             recent_history: None,
@@ -435,14 +664,14 @@ impl CoinGeckoClient {
             ("price_change_percentage", "1h,24h")
         ];
         
-        println!("📊 Fetching potential top gainers...");
+        say("📊 Fetching potential top gainers...");
         let data = self.make_request(&url, &params).await?;
         
         let coins_value: Value = serde_json::from_value(data.clone())
             .context("Failed to parse response as JSON Value")?;
             
         if let Some(error_msg) = coins_value.get("error") {
-            println!("⚠️ API returned error: {}", error_msg);
+            say(format!("⚠️ API returned error: {}", error_msg));
             return Err(AgentError::ApiError(format!("API error: {}", error_msg)).into());
         }
         
@@ -456,8 +685,8 @@ impl CoinGeckoClient {
                 !coin.symbol.to_lowercase().contains("usdc") &&
                 !coin.symbol.to_lowercase().contains("dai") &&
                 !coin.symbol.to_lowercase().contains("busd") &&
-                coin.current_price > 0.0 &&
-                coin.volume_24h > 100000.0 &&
+                coin.current_price.is_some_and(|price| price > 0.0) &&
+                coin.volume_24h.is_some_and(|volume| volume > 100000.0) &&
                 coin.price_change_1h.unwrap_or_default() > 3.0
             })
             .collect();
@@ -470,14 +699,14 @@ impl CoinGeckoClient {
         
         let top_coins = filtered_coins.into_iter().take(20).collect::<Vec<_>>();
             
-        println!("✅ Found {} coins with >3% gains in 1h", top_coins.len());
+        say(format!("✅ Found {} coins with >3% gains in 1h", top_coins.len()));
         
         for coin in &top_coins {
-            println!("🚀 {}: 1h: {:.2}%, Vol: ${:.2}M", 
+            say(format!("🚀 {}: 1h: {:.2}%, Vol: {}", 
                 coin.symbol.to_uppercase(), 
                 coin.price_change_1h.unwrap_or_default(),
-                coin.volume_24h / 1_000_000.0
-            );
+                prompt_values::millions(coin.volume_24h)
+            ));
         }
         
         Ok(top_coins)
@@ -489,7 +718,7 @@ impl CoinGeckoClient {
             ("include_platform", "false")
         ];
         
-        println!("📝 Fetching new coins list...");
+        say("📝 Fetching new coins list...");
         let data = self.make_request(&url, &params).await?;
         
         let all_coins: Vec<Value> = serde_json::from_value(data)
@@ -504,11 +733,11 @@ impl CoinGeckoClient {
             .collect::<Vec<Value>>();
             
         if new_coins.is_empty() {
-            println!("ℹ️ No new coins found since last check");
+            say("ℹ️ No new coins found since last check");
             return Ok(Vec::new());
         }
             
-        println!("📝 Found {} potential new coins, getting details...", new_coins.len());
+        say(format!("📝 Found {} potential new coins, getting details...", new_coins.len()));
         
         let mut detailed_coins = Vec::new();
         
@@ -516,7 +745,7 @@ impl CoinGeckoClient {
             let id = coin["id"].as_str().unwrap_or_default();
             
             if self.processed_coins.contains(id) {
-                println!("⏩ Skipping already processed coin: {}", id);
+                say(format!("⏩ Skipping already processed coin: {}", id));
                 continue;
             }
             
@@ -525,22 +754,22 @@ impl CoinGeckoClient {
                     if !coin_data.symbol.to_lowercase().contains("usd") && 
                        !coin_data.name.is_empty() &&
                        !coin_data.symbol.is_empty() {
-                        println!("✅ Added new coin: {} ({}) - Price: ${:.8}", 
+                        say(format!("✅ Added new coin: {} ({}) - Price: {}", 
                             coin_data.name, 
                             coin_data.symbol.to_uppercase(),
-                            coin_data.current_price
-                        );
+                            prompt_values::price(coin_data.current_price)
+                        ));
                         detailed_coins.push(coin_data);
                         self.processed_coins.insert(id.to_string());
                         if let Err(e) = self.save_processed_coins() {
-                            println!("⚠️ Failed to save processed coins: {}", e);
+                            say(format!("⚠️ Failed to save processed coins: {}", e));
                         }
                     } else {
-                        println!("⏩ Skipping invalid coin data: {} ({})", coin_data.name, coin_data.symbol);
+                        say(format!("⏩ Skipping invalid coin data: {} ({})", coin_data.name, coin_data.symbol));
                     }
                 },
                 Err(e) => {
-                    println!("⚠️ Failed to get details for {}: {}", id, e);
+                    say(format!("⚠️ Failed to get details for {}: {}", id, e));
                     continue;
                 }
             }
@@ -548,10 +777,10 @@ impl CoinGeckoClient {
             tokio::time::sleep(Duration::from_secs(REQUEST_DELAY)).await;
         }
         
-        println!("✅ Successfully processed {} new unique coins", detailed_coins.len());
+        say(format!("✅ Successfully processed {} new unique coins", detailed_coins.len()));
         
         if let Err(e) = self.save_processed_coins() {
-            println!("⚠️ Failed to save processed coins: {}", e);
+            say(format!("⚠️ Failed to save processed coins: {}", e));
         }
         
         Ok(detailed_coins)
@@ -578,7 +807,7 @@ impl CoinGeckoClient {
     }
 
     pub async fn get_detailed_coin_data(&self, coin_id: &str) -> Result<DetailedCoinData> {
-        println!("🔍 Fetching details for coin {}...", coin_id);
+        say(format!("🔍 Fetching details for coin {}...", coin_id));
         
         // Get basic market data
         let mut coin_data = self.get_coin_market_data(coin_id).await?;
@@ -586,17 +815,17 @@ impl CoinGeckoClient {
         // Get historical data for MAs
         let historical = self.get_historical_data(coin_id, 200).await?;
         
-        println!("📊 Calculating Moving Averages...");
-        println!("Historical data points: {}", historical.prices.len());
+        say("📊 Calculating Moving Averages...");
+        say(format!("Historical data points: {}", historical.prices.len()));
         
         // Calculate MAs
         coin_data.ma_50 = self.calculate_ma_from_prices(&historical.prices, 50);
         coin_data.ma_200 = self.calculate_ma_from_prices(&historical.prices, 200);
         
-        println!("MA50: ${:.2}", coin_data.ma_50.unwrap_or_default());
-        println!("MA200: ${:.2}", coin_data.ma_200.unwrap_or_default());
+        say(format!("MA50: ${:.2}", coin_data.ma_50.unwrap_or_default()));
+        say(format!("MA200: ${:.2}", coin_data.ma_200.unwrap_or_default()));
         
-        println!("✅ Successfully fetched details for {}", coin_id);
+        say(format!("✅ Successfully fetched details for {}", coin_id));
         Ok(coin_data)
     }
 
@@ -607,7 +836,7 @@ impl CoinGeckoClient {
             ("days", &days.to_string()),
         ];
 
-        println!("📊 Fetching candle data for {} over {} days", coin_id, days);
+        say(format!("📊 Fetching candle data for {} over {} days", coin_id, days));
         let data = self.make_request(&url, &params).await?;
 
         let candles: Vec<Vec<f64>> = serde_json::from_value(data)?;
@@ -626,45 +855,66 @@ impl CoinGeckoClient {
         Ok(candle_data)
     }
 
-    #[allow(dead_code)]
-    async fn get_market_chart_fallback(&self, coin_id: &str, days: u16) -> Result<Vec<CandleData>> {
+    /// Candles from the client's `CandleSource`. If merging volume fails the plain
+    /// OHLC candles are returned with `has_volume` false.
+    pub async fn get_candles(&self, coin_id: &str, days: u32) -> Result<Candles> {
+        if self.candle_source == CandleSource::WithVolume {
+            match self.get_candles_with_volume(coin_id, days).await {
+                Ok(candles) => {
+                    return Ok(Candles { candles, has_volume: true });
+                }
+                Err(e) => say(format!("⚠️ Volume data unavailable for {}, using OHLC only: {}", coin_id, e)),
+            }
+        }
+
+        Ok(Candles {
+            candles: self.get_ohlc_data(coin_id, days).await?,
+            has_volume: false,
+        })
+    }
+
+    async fn get_candles_with_volume(&self, coin_id: &str, days: u32) -> Result<Vec<CandleData>> {
         let url = format!("{}/coins/{}/market_chart", BASE_URL, coin_id);
         let params = [
             ("vs_currency", "usd"),
             ("days", &days.to_string()),
-            ("interval", "daily"),
         ];
 
-        println!("📊 Using market_chart fallback for volume data...");
+        say(format!("📊 Fetching market_chart volume data for {}...", coin_id));
         let data = self.make_request(&url, &params).await?;
+        let volumes: Vec<Vec<f64>> = serde_json::from_value(data["total_volumes"].clone())
+            .context("Invalid market_chart volume format")?;
+        if volumes.is_empty() {
+            anyhow::bail!("market_chart returned no volume data");
+        }
 
-        let _prices: Vec<Vec<f64>> = serde_json::from_value(data["prices"].clone())?;
-        let volumes: Vec<Vec<f64>> = serde_json::from_value(data["total_volumes"].clone())?;
+        let mut candles = self.get_ohlc_data(coin_id, days).await?;
 
-        let mut candles = self.get_ohlc_data(coin_id, days.into()).await?;
-        
-        for (i, candle) in candles.iter_mut().enumerate() {
-            if i < volumes.len() {
-                candle.volume = volumes[i][1];
+        // The two endpoints use different granularity, so match each candle to the
+        // volume point closest to its timestamp
+        for candle in candles.iter_mut() {
+            let closest = volumes.iter()
+                .filter(|v| v.len() >= 2)
+                .min_by_key(|v| (v[0] as i64 - candle.timestamp).abs());
+            if let Some(point) = closest {
+                candle.volume = point[1];
             }
         }
 
         Ok(candles)
     }
 
-    pub async fn get_market_chart(&self, coin_id: &str, _days: u32) -> Result<TechnicalData> {
-        println!("📈 Fetching market data for {}", coin_id);
-        
-        // Get historical price data for MA calculations
-        let historical = self.get_historical_data(coin_id, 200).await?;
-        
-        // Get OHLC data for shorter timeframe analysis
-        let candles = self.get_ohlc_data(coin_id, 1).await?;
-        
-        // Get current price data
+    /// Prices for many coins in one `/simple/price` call. Ids CoinGecko doesn't return
+    /// (e.g. delisted coins) are absent from the map rather than zero-filled.
+    pub async fn get_simple_prices(&self, ids: &[&str]) -> Result<HashMap<String, SimplePrice>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
         let price_url = format!("{}/simple/price", BASE_URL);
+        let joined_ids = ids.join(",");
         let price_params = [
-            ("ids", coin_id),
+            ("ids", joined_ids.as_str()),
             ("vs_currencies", "usd"),
             ("include_market_cap", "true"),
             ("include_24hr_vol", "true"),
@@ -674,15 +924,43 @@ impl CoinGeckoClient {
         ];
 
         let price_data = self.make_request(&price_url, &price_params).await?;
-        let volume_24h = price_data[coin_id]["usd_24h_vol"]
-            .as_f64()
-            .unwrap_or(0.0);
-        let current_price = price_data[coin_id]["usd"]
-            .as_f64()
-            .unwrap_or(0.0);
-        let price_change_24h = price_data[coin_id]["usd_24h_change"]
-            .as_f64()
-            .unwrap_or(0.0);
+        let mut prices = HashMap::new();
+        for id in ids {
+            let entry = &price_data[*id];
+            match entry["usd"].as_f64() {
+                Some(usd) => {
+                    prices.insert(id.to_string(), SimplePrice {
+                        usd,
+                        market_cap: entry["usd_market_cap"].as_f64(),
+                        volume_24h: entry["usd_24h_vol"].as_f64(),
+                        price_change_24h: entry["usd_24h_change"].as_f64(),
+                    });
+                }
+                None => say(format!("⚠️ No price data for {} (delisted or unknown id)", id)),
+            }
+        }
+        Ok(prices)
+    }
+
+    pub async fn get_market_chart(&self, coin_id: &str, days: u32) -> Result<TechnicalData> {
+        let price = self.get_simple_prices(&[coin_id]).await?.remove(coin_id);
+        self.get_market_chart_with_price(coin_id, days, price).await
+    }
+
+    /// Technical data for a coin whose price was already fetched, e.g. by a batched
+    /// `get_simple_prices` call. Without a price the price fields are left empty.
+    pub async fn get_market_chart_with_price(&self, coin_id: &str, _days: u32, price: Option<SimplePrice>) -> Result<TechnicalData> {
+        say(format!("📈 Fetching market data for {}", coin_id));
+        
+        // Get historical price data for MA calculations
+        let historical = self.get_historical_data(coin_id, 200).await?;
+        
+        // Get OHLC data for shorter timeframe analysis
+        let Candles { candles, has_volume } = self.get_candles(coin_id, 1).await?;
+        
+        let current_price = price.as_ref().map(|p| p.usd);
+        let volume_24h = price.as_ref().and_then(|p| p.volume_24h);
+        let price_change_24h = price.as_ref().and_then(|p| p.price_change_24h);
         
         // Calculate RSI using OHLC data
         let rsi_14 = self.calculate_rsi(&candles, 14);
@@ -694,33 +972,41 @@ impl CoinGeckoClient {
         let macd = self.calculate_macd(&candles);
         let bb = self.calculate_bollinger_bands(&candles);
         
-        println!("📈 Calculated indicators:");
-        println!("  • Current Price: ${:.2}", current_price);
-        println!("  • Price Change 24h: {:.2}%", price_change_24h);
-        println!("  • RSI (14): {:.2}", rsi_14);
-        println!("  • 50 MA: ${:.2}", ma_50.unwrap_or_default());
-        println!("  • 200 MA: ${:.2}", ma_200.unwrap_or_default());
+        say("📈 Calculated indicators:");
+        match current_price {
+            Some(current_price) => say(format!("  • Current Price: ${:.2}", current_price)),
+            None => say("  • Current Price: n/a"),
+        }
+        if let Some(price_change_24h) = price_change_24h {
+            say(format!("  • Price Change 24h: {:.2}%", price_change_24h));
+        }
+        say(format!("  • RSI (14): {:.2}", rsi_14));
+        say(format!("  • 50 MA: ${:.2}", ma_50.unwrap_or_default()));
+        say(format!("  • 200 MA: ${:.2}", ma_200.unwrap_or_default()));
         
         if let Some((macd_val, signal, hist)) = macd {
-            println!("  • MACD: {:.2}/{:.2}/{:.2}", macd_val, signal, hist);
+            say(format!("  • MACD: {:.2}/{:.2}/{:.2}", macd_val, signal, hist));
         }
         
         if let Some((upper, middle, lower)) = bb {
-            println!("  • Bollinger Bands: {:.2}/{:.2}/{:.2}", upper, middle, lower);
+            say(format!("  • Bollinger Bands: {:.2}/{:.2}/{:.2}", upper, middle, lower));
         }
         
-        println!("  • Volume 24h: ${:.2}B", volume_24h / 1e9);
+        if let Some(volume_24h) = volume_24h {
+            say(format!("  • Volume 24h: ${:.2}B", volume_24h / 1e9));
+        }
         
         Ok(TechnicalData {
             candles,
+            has_volume,
             rsi_14: Some(rsi_14),
             ma_50,
             ma_200,
             macd,
             bollinger_bands: bb,
-            volume_24h: Some(volume_24h),
-            current_price: Some(current_price),
-            price_change_24h: Some(price_change_24h),
+            volume_24h,
+            current_price,
+            price_change_24h,
         })
     }
 
@@ -741,7 +1027,7 @@ impl CoinGeckoClient {
         
         let params = [("vs_currency", "usd"), ("days", normalized_days)];
         
-        println!("📊 Fetching OHLC data for {} days...", normalized_days);
+        say(format!("📊 Fetching OHLC data for {} days...", normalized_days));
         let data = self.make_request(&url, &params).await?;
         
         // Parse OHLC data
@@ -764,55 +1050,18 @@ impl CoinGeckoClient {
         Ok(candles)
     }
 
+    // Neutral 50 when there are too few candles
     fn calculate_rsi(&self, candles: &[CandleData], period: usize) -> f64 {
-        if candles.len() < period + 1 {
-            return 50.0;
-        }
-
-        let mut gains = Vec::new();
-        let mut losses = Vec::new();
-
-        for i in 1..candles.len() {
-            let change = candles[i].close - candles[i-1].close;
-            if change > 0.0 {
-                gains.push(change);
-                losses.push(0.0);
-            } else {
-                gains.push(0.0);
-                losses.push(change.abs());
-            }
-        }
-
-        let mut avg_gain = gains.iter().take(period).sum::<f64>() / period as f64;
-        let mut avg_loss = losses.iter().take(period).sum::<f64>() / period as f64;
-
-        for i in period..gains.len() {
-            avg_gain = (avg_gain * (period - 1) as f64 + gains[i]) / period as f64;
-            avg_loss = (avg_loss * (period - 1) as f64 + losses[i]) / period as f64;
-        }
-
-        if avg_loss == 0.0 {
-            return 100.0;
-        }
-
-        let rs = avg_gain / avg_loss;
-        100.0 - (100.0 / (1.0 + rs))
+        ta::rsi(&to_ta_candles(candles), period).unwrap_or(50.0)
     }
 
     fn calculate_ma_from_candles(&self, candles: &[CandleData], period: usize) -> Option<f64> {
-        if candles.len() < period {
-            return None;
-        }
-
-        let prices: Vec<f64> = candles.iter().map(|c| c.close).collect();
-        let start_idx = prices.len().saturating_sub(period);
-        let sum: f64 = prices[start_idx..].iter().sum();
-        Some(sum / period as f64)
+        ta::sma(&to_ta_candles(candles), period)
     }
 
     fn calculate_ma_from_prices(&self, prices: &[[f64; 2]], period: usize) -> Option<f64> {
         if prices.len() < period {
-            println!("⚠️ Not enough data points for MA{}: {} < {}", period, prices.len(), period);
+            say(format!("⚠️ Not enough data points for MA{}: {} < {}", period, prices.len(), period));
             return None;
         }
         
@@ -824,77 +1073,24 @@ impl CoinGeckoClient {
         
         let sum: f64 = recent_prices.iter().sum();
         let ma = Some(sum / period as f64);
-        println!("Calculated MA{}: ${:.2}", period, ma.unwrap_or_default());
+        say(format!("Calculated MA{}: ${:.2}", period, ma.unwrap_or_default()));
         ma
     }
 
     fn calculate_macd(&self, candles: &[CandleData]) -> Option<(f64, f64, f64)> {
-        if candles.len() < 26 {
-            return None;
-        }
-
-        let prices: Vec<f64> = candles.iter().map(|c| c.close).collect();
-        
-        let mut fast_ema = prices[0];
-        let mut slow_ema = prices[0];
-        let mut signal = 0.0;
-        
-        let fast_alpha = 2.0 / (12.0 + 1.0);
-        let slow_alpha = 2.0 / (26.0 + 1.0);
-        let signal_alpha = 2.0 / (9.0 + 1.0);
-        
-        let mut macd_values = Vec::new();
-        
-        for price in prices.iter() {
-            fast_ema = price * fast_alpha + fast_ema * (1.0 - fast_alpha);
-            slow_ema = price * slow_alpha + slow_ema * (1.0 - slow_alpha);
-            let macd = fast_ema - slow_ema;
-            macd_values.push(macd);
-        }
-        
-        if !macd_values.is_empty() {
-            signal = macd_values[0];
-            for macd in macd_values.iter().skip(1) {
-                signal = macd * signal_alpha + signal * (1.0 - signal_alpha);
-            }
-        }
-        
-        let latest_macd = *macd_values.last().unwrap_or(&0.0);
-        let histogram = latest_macd - signal;
-        
-        Some((latest_macd, signal, histogram))
+        ta::macd(&to_ta_candles(candles)).map(|m| (m.macd, m.signal, m.histogram))
     }
 
     fn calculate_bollinger_bands(&self, candles: &[CandleData]) -> Option<(f64, f64, f64)> {
-        let sma = self.calculate_ma_from_candles(candles, 20);
-        
-        let variance: f64 = candles.iter()
-            .rev()
-            .take(20)
-            .map(|c| {
-                let diff = c.close - sma.unwrap_or_default();
-                diff * diff
-            })
-            .sum::<f64>() / 20.0;
-        
-        let std_dev = variance.sqrt();
-        
-        let upper_band = sma.unwrap_or_default() + (2.0 * std_dev);
-        let lower_band = sma.unwrap_or_default() - (2.0 * std_dev);
-
-        if upper_band.is_infinite() || lower_band.is_infinite() {
-            return None;
-        }
-
-        Some((upper_band, sma.unwrap_or_default(), lower_band))
+        ta::bollinger_bands(&to_ta_candles(candles)).map(|b| (b.upper, b.middle, b.lower))
     }
 
-    fn calculate_volume_change(&self, data_sets: &[&TechnicalData]) -> f64 {
+    fn calculate_volume_change(&self, data_sets: &[&TechnicalData]) -> Option<f64> {
         let mut total_change = 0.0;
         let mut valid_sets = 0;
 
         for data in data_sets {
-            if data.candles.len() >= 2 {
+            if data.has_volume && data.candles.len() >= 2 {
                 let current_volume = data.candles.last().unwrap().volume;
                 let prev_volume = data.candles[data.candles.len() - 2].volume;
                 
@@ -906,139 +1102,94 @@ impl CoinGeckoClient {
         }
 
         if valid_sets > 0 {
-            total_change / valid_sets as f64
+            Some(total_change / valid_sets as f64)
         } else {
-            0.0
+            None
         }
     }
 
-    pub async fn get_category_volumes(&self) -> Result<(f64, f64, f64, f64)> {
-        println!("📊 Fetching category data...");
-        
-        // Fetch AI category data
-        let ai_url = format!("{}/coins/categories/artificial-intelligence", BASE_URL);
-        let ai_response = self.client
-            .get(&ai_url)
-            .header("accept", "application/json")
-            .header("x-cg-demo-api-key", DEMO_API_KEY)
-            .send()
-            .await?;
-        
-        let ai_data: CategoryData = serde_json::from_str(&ai_response.text().await?)?;
-        tokio::time::sleep(Duration::from_secs(6)).await;
+    /// Fetch the tracked sector categories. A category whose request fails is left out
+    /// rather than reported as zero, so it can't be mistaken for a sector with no activity.
+    pub async fn get_sector_data(&self) -> Vec<CategoryData> {
+        say("📊 Fetching category data...");
+        let mut sectors = Vec::new();
+
+        let mut progress = Progress::new(SECTOR_CATEGORIES.len());
+        for (i, (label, category_id)) in SECTOR_CATEGORIES.iter().enumerate() {
+            progress.step(format!("Fetching {} sector", label));
+            if i > 0 {
+                tokio::time::sleep(Duration::from_secs(6)).await;
+            }
 
-        // Fetch Layer 1 data
-        let l1_url = format!("{}/coins/categories/layer-1", BASE_URL);
-        let l1_response = self.client
-            .get(&l1_url)
-            .header("accept", "application/json")
-            .header("x-cg-demo-api-key", DEMO_API_KEY)
-            .send()
-            .await?;
-        
-        let l1_data: CategoryData = serde_json::from_str(&l1_response.text().await?)?;
-        tokio::time::sleep(Duration::from_secs(6)).await;
+            match self.get_category(category_id).await {
+                Ok(mut data) => {
+                    data.name = label.to_string();
+                    sectors.push(data);
+                }
+                Err(e) => status!("⚠️ Failed to fetch {} sector data: {}", label, e),
+            }
+        }
+        progress.finish();
 
-        // Fetch Layer 2 data
-        let l2_url = format!("{}/coins/categories/layer-2", BASE_URL);
-        let l2_response = self.client
-            .get(&l2_url)
-            .header("accept", "application/json")
-            .header("x-cg-demo-api-key", DEMO_API_KEY)
-            .send()
-            .await?;
-        
-        let l2_data: CategoryData = serde_json::from_str(&l2_response.text().await?)?;
-        tokio::time::sleep(Duration::from_secs(6)).await;
+        // Print sector summary
+        say("\n📊 Sector Analysis:");
+        for sector in &sectors {
+            say(format!("\n{} Sector:", sector.name));
+            say(format!("  • Market Cap: ${:.2}B", sector.market_cap.unwrap_or(0.0) / 1e9));
+            say(format!("  • Market Cap Change 24h: {:.2}%", sector.market_cap_change_24h.unwrap_or(0.0)));
+            say(format!("  • Volume 24h: ${:.2}B", sector.volume_24h.unwrap_or(0.0) / 1e9));
+        }
 
-        // Fetch RWA data
-        let rwa_url = format!("{}/coins/categories/real-world-assets-rwa", BASE_URL);
-        let rwa_response = self.client
-            .get(&rwa_url)
+        sectors
+    }
+
+    async fn get_category(&self, category_id: &str) -> Result<CategoryData> {
+        let url = format!("{}/coins/categories/{}", BASE_URL, category_id);
+        let response = self.client
+            .get(&url)
             .header("accept", "application/json")
             .header("x-cg-demo-api-key", DEMO_API_KEY)
             .send()
             .await?;
-        
-        let rwa_data: CategoryData = serde_json::from_str(&rwa_response.text().await?)?;
 
-        // Print sector summary
-        println!("\n📊 Sector Analysis:");
-        println!("🤖 AI Sector:");
-        println!("  • Market Cap: ${:.2}B", ai_data.market_cap.unwrap_or(0.0) / 1e9);
-        println!("  • Market Cap Change 24h: {:.2}%", ai_data.market_cap_change_24h.unwrap_or(0.0));
-        println!("  • Volume 24h: ${:.2}B", ai_data.volume_24h.unwrap_or(0.0) / 1e9);
-
-        println!("\n🔗 Layer 1 Sector:");
-        println!("  • Market Cap: ${:.2}B", l1_data.market_cap.unwrap_or(0.0) / 1e9);
-        println!("  • Market Cap Change 24h: {:.2}%", l1_data.market_cap_change_24h.unwrap_or(0.0));
-        println!("  • Volume 24h: ${:.2}B", l1_data.volume_24h.unwrap_or(0.0) / 1e9);
-
-        println!("\n⚡ Layer 2 Sector:");
-        println!("  • Market Cap: ${:.2}B", l2_data.market_cap.unwrap_or(0.0) / 1e9);
-        println!("  • Market Cap Change 24h: {:.2}%", l2_data.market_cap_change_24h.unwrap_or(0.0));
-        println!("  • Volume 24h: ${:.2}B", l2_data.volume_24h.unwrap_or(0.0) / 1e9);
-
-        println!("\n💎 RWA Sector:");
-        println!("  • Market Cap: ${:.2}B", rwa_data.market_cap.unwrap_or(0.0) / 1e9);
-        println!("  • Market Cap Change 24h: {:.2}%", rwa_data.market_cap_change_24h.unwrap_or(0.0));
-        println!("  • Volume 24h: ${:.2}B", rwa_data.volume_24h.unwrap_or(0.0) / 1e9);
-
-        Ok((
-            ai_data.volume_24h.unwrap_or(0.0),
-            l1_data.volume_24h.unwrap_or(0.0),
-            l2_data.volume_24h.unwrap_or(0.0),
-            rwa_data.volume_24h.unwrap_or(0.0)
-        ))
+        Ok(serde_json::from_str(&response.text().await?)?)
+    }
+
+    /// (AI, Layer 1, Layer 2, RWA) 24h volumes, 0.0 for sectors that failed to load
+    pub async fn get_category_volumes(&self) -> Result<(f64, f64, f64, f64)> {
+        let sectors = self.get_sector_data().await;
+        Ok(sector_volumes(&sectors))
     }
 
     fn calculate_market_metrics(
         &self,
         global: &GlobalData,
-        btc_data: &TechnicalData,
-        eth_data: &TechnicalData,
-        sol_data: &TechnicalData,
+        major_coins: &[(String, TechnicalData)],
         _trending_data: &[(String, TechnicalData)],
         category_volumes: (f64, f64, f64, f64),
     ) -> GlobalTechnicalMetrics {
         let (ai_volume, l1_volume, l2_volume, rwa_volume) = category_volumes;
-
-        // Calculate BTC market cap using current price and circulating supply
-        let btc_price = btc_data.current_price.unwrap_or_else(|| btc_data.candles.last().map(|c| c.close).unwrap_or(0.0));
-        let btc_supply = 19_600_000.0; // Current approximate circulating supply
-        let btc_mcap = btc_price * btc_supply;
-        
-        // Calculate BTC dominance dynamically
         let total_mcap = global.total_market_cap;
-        let btc_dominance = if total_mcap > 0.0 {
-            (btc_mcap / total_mcap) * 100.0
-        } else {
-            0.0
-        };
+        let majors: Vec<&TechnicalData> = major_coins.iter().map(|(_, data)| data).collect();
 
-        // Calculate other asset dominances using the same total market cap
-        let eth_dominance = if total_mcap > 0.0 { 
-            (eth_data.current_price.unwrap_or_else(|| eth_data.candles.last().map(|c| c.close).unwrap_or(0.0)) 
-            * 120_000_000.0 / total_mcap) * 100.0 
-        } else { 0.0 };
-        
-        let sol_dominance = if total_mcap > 0.0 { 
-            (sol_data.current_price.unwrap_or_else(|| sol_data.candles.last().map(|c| c.close).unwrap_or(0.0)) 
-            * 410_000_000.0 / total_mcap) * 100.0 
-        } else { 0.0 };
+        // Dominance comes from CoinGecko's global market cap shares, so BTC dominance
+        // is available whether or not BTC is one of the configured majors
+        let dominance = |symbol: &str| global.market_cap_percentage.get(&symbol.to_lowercase()).copied();
+        let major_dominance = major_coins.iter()
+            .filter_map(|(symbol, _)| Some((symbol.clone(), dominance(symbol)?)))
+            .collect();
 
         GlobalTechnicalMetrics {
             total_market_cap: total_mcap,
-            btc_dominance,
-            eth_dominance,
-            sol_dominance,
+            btc_dominance: dominance("btc").unwrap_or_default(),
+            major_dominance,
             total_volume_24h: global.total_volume,
             market_cap_change_24h: global.market_cap_change_percentage_24h,
-            volume_change_24h: self.calculate_volume_change(&[btc_data, eth_data, sol_data]),
+            volume_change_24h: self.calculate_volume_change(&majors),
             defi_dominance: 0.0,
             layer1_dominance: (l1_volume / global.total_volume) * 100.0,
             top10_dominance: 0.0,
-            volatility_index: self.calculate_market_volatility(&[btc_data, eth_data, sol_data]),
+            volatility_index: self.calculate_market_volatility(&majors),
             ai_sector_dominance: (ai_volume / global.total_volume) * 100.0,
             ai_sector_volume: ai_volume,
             ai_sector_growth: 0.0,
@@ -1081,25 +1232,14 @@ impl CoinGeckoClient {
     }
 
     pub async fn get_technical_analysis(&self) -> Result<MarketTechnicalData> {
-        println!("📊 Fetching comprehensive technical data...");
-        
-        let category_volumes = self.get_category_volumes().await?;
-        
-        println!("🔍 Analyzing BTC...");
-        let btc_data = self.get_market_chart("bitcoin", 14).await?;
-        
-        println!("🔍 Analyzing ETH...");
-        tokio::time::sleep(Duration::from_secs(BASE_DELAY)).await;
-        let eth_data = self.get_market_chart("ethereum", 14).await?;
+        say("📊 Fetching comprehensive technical data...");
         
-        println!("🔍 Analyzing SOL...");
-        tokio::time::sleep(Duration::from_secs(BASE_DELAY)).await;
-        let sol_data = self.get_market_chart("solana", 14).await?;
+        let sectors = self.get_sector_data().await;
+        let category_volumes = sector_volumes(&sectors);
         
-        println!("🔥 Analyzing top trending coins...");
+        let mut progress = Progress::new(0);
+        progress.step("Finding top trending coins");
         let trending = self.get_trending_coins().await?;
-        let mut trending_data = Vec::new();
-        
         let relevant_coins: Vec<_> = trending.iter()
             .filter(|c| {
                 let symbol = c.symbol.to_lowercase();
@@ -1119,57 +1259,96 @@ impl CoinGeckoClient {
             .take(3)
             .collect();
 
+        // One price call for every coin instead of one per coin
+        let mut ids: Vec<&str> = Vec::new();
+        for id in self.major_coins.iter().map(|id| id.as_str()).chain(relevant_coins.iter().map(|c| c.id.as_str())) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        // Trending, prices, each coin's chart and the global data
+        progress.set_total(3 + self.major_coins.len() + relevant_coins.len());
+        progress.step("Fetching current prices");
+        // A failed price call skips each coin below rather than the whole analysis
+        let prices = self.get_simple_prices(&ids).await.unwrap_or_else(|e| {
+            status!("⚠️ Failed to fetch current prices: {}", e);
+            HashMap::new()
+        });
+
+        let mut major_coins = Vec::new();
+        for (i, coin_id) in self.major_coins.iter().enumerate() {
+            let symbol = major_coin_symbol(coin_id);
+            progress.step(format!("Analyzing {}", symbol));
+            let Some(price) = prices.get(coin_id.as_str()).cloned() else {
+                status!("⚠️ Skipping {}: no current price", symbol);
+                continue;
+            };
+            if i > 0 {
+                tokio::time::sleep(Duration::from_secs(BASE_DELAY)).await;
+            }
+            match self.get_market_chart_with_price(coin_id, 14, Some(price)).await {
+                Ok(data) => major_coins.push((symbol, data)),
+                Err(e) => status!("⚠️ Failed to analyze {}, skipping: {}", symbol, e),
+            }
+        }
+        
+        let mut trending_data = Vec::new();
         for coin in relevant_coins {
             let rank_display = match coin.market_cap_rank {
                 Some(rank) => rank.to_string(),
                 None => "N/A".to_string()
             };
+            progress.step(format!("Analyzing trending coin {} (rank {})", coin.symbol, rank_display));
             
-            println!("Analyzing trending coin: {} (Rank: {})", coin.symbol, rank_display);
-            
-            match self.get_market_chart(&coin.id, 14).await {
+            let Some(price) = prices.get(coin.id.as_str()).cloned() else {
+                status!("⚠️ Skipping trending coin {}: no current price", coin.symbol);
+                continue;
+            };
+            match self.get_market_chart_with_price(&coin.id, 14, Some(price)).await {
                 Ok(data) => {
                     trending_data.push((coin.symbol.clone(), data));
                     tokio::time::sleep(Duration::from_secs(BASE_DELAY)).await;
                 },
                 Err(e) => {
-                    println!("⚠️ Failed to analyze {}: {}", coin.symbol, e);
+                    status!("⚠️ Failed to analyze {}: {}", coin.symbol, e);
                     continue;
                 }
             }
         }
 
+        progress.step("Fetching global market data");
         let global = self.get_global_data().await?;
+        progress.finish();
         let global_metrics = self.calculate_market_metrics(
             &global,
-            &btc_data,
-            &eth_data,
-            &sol_data,
+            &major_coins,
             &trending_data,
             category_volumes
         );
 
-        println!("📊 Market Metrics:");
-        println!("  • Total Market Cap: ${:.2}B", global_metrics.total_market_cap / 1e9);
-        println!("  • BTC Dominance: {:.2}%", global_metrics.btc_dominance);
-        println!("  • ETH Dominance: {:.2}%", global_metrics.eth_dominance);
-        println!("  • SOL Dominance: {:.2}%", global_metrics.sol_dominance);
-        println!("  • Layer 1 Dominance: {:.2}%", global_metrics.layer1_dominance);
-        println!("  • AI Sector Dominance: {:.2}%", global_metrics.ai_sector_dominance);
+        say("📊 Market Metrics:");
+        say(format!("  • Total Market Cap: ${:.2}B", global_metrics.total_market_cap / 1e9));
+        say(format!("  • BTC Dominance: {:.2}%", global_metrics.btc_dominance));
+        for (symbol, dominance) in &global_metrics.major_dominance {
+            if symbol != "BTC" {
+                say(format!("  • {} Dominance: {:.2}%", symbol, dominance));
+            }
+        }
+        say(format!("  • Layer 1 Dominance: {:.2}%", global_metrics.layer1_dominance));
+        say(format!("  • AI Sector Dominance: {:.2}%", global_metrics.ai_sector_dominance));
 
         Ok(MarketTechnicalData {
-            btc_data,
-            eth_data,
-            sol_data,
+            major_coins,
             trending_data,
             global_metrics,
+            sectors,
         })
     }
 
     pub async fn get_coin_technical_analysis(&mut self, coin_id: &str, days: u32) -> Result<TechnicalData> {
         if let Some((data, timestamp)) = self.cache.get(coin_id) {
             if timestamp.elapsed() < self.cache_duration {
-                println!("📊 Using cached data for {}", coin_id);
+                say(format!("📊 Using cached data for {}", coin_id));
                 return Ok(data.clone());
             }
         }
@@ -1188,16 +1367,70 @@ impl CoinGeckoClient {
             ("precision", "2"),
         ];
 
-        println!("📈 Fetching historical data for {} over {} days...", coin_id, days);
+        say(format!("📈 Fetching historical data for {} over {} days...", coin_id, days));
         let data = self.make_request(&url, &params).await?;
         let historical: HistoricalData = serde_json::from_value(data)?;
         
         Ok(historical)
     }
+
+    /// Daily history for several coins, e.g. for correlations or backtests. Requests
+    /// go through the shared rate limiter and each series is cached on disk for the
+    /// rest of the UTC day, so repeated runs only fetch what's new. Coins that fail
+    /// to load are listed in `omitted` instead of failing the whole fetch.
+    pub async fn get_historical_bulk(&self, coin_ids: &[&str], days: u32) -> BulkHistorical {
+        let mut bulk = BulkHistorical::default();
+        let mut cached = 0;
+        for coin_id in coin_ids {
+            if let Some(data) = load_cached_historical(coin_id, days) {
+                cached += 1;
+                bulk.series.push((coin_id.to_string(), data));
+                continue;
+            }
+            match self.get_historical_data(coin_id, days).await {
+                Ok(data) => {
+                    if let Err(e) = save_cached_historical(coin_id, days, &data) {
+                        say(format!("⚠️ Failed to cache history for {}: {}", coin_id, e));
+                    }
+                    bulk.series.push((coin_id.to_string(), data));
+                }
+                Err(e) => {
+                    say(format!("⚠️ Skipping {}: {}", coin_id, e));
+                    bulk.omitted.push((coin_id.to_string(), e.to_string()));
+                }
+            }
+        }
+        say(format!(
+            "📈 Loaded history for {}/{} coins ({} from today's cache)",
+            bulk.series.len(),
+            coin_ids.len(),
+            cached
+        ));
+        bulk
+    }
+}
+
+impl GlobalTechnicalMetrics {
+    /// Market cap dominance for a symbol, None when CoinGecko didn't report it
+    pub fn dominance(&self, symbol: &str) -> Option<f64> {
+        if symbol.eq_ignore_ascii_case("BTC") && self.btc_dominance > 0.0 {
+            return Some(self.btc_dominance);
+        }
+        self.major_dominance.iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(symbol))
+            .map(|(_, dominance)| *dominance)
+    }
 }
 
 // Add public accessor for sector data
 impl MarketTechnicalData {
+    /// Technical data for a major coin by symbol, e.g. "BTC"
+    pub fn major(&self, symbol: &str) -> Option<&TechnicalData> {
+        self.major_coins.iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(symbol))
+            .map(|(_, data)| data)
+    }
+
     pub fn sector_volumes(&self) -> (f64, f64, f64, f64) {
         (
             self.global_metrics.ai_sector_volume,
@@ -0,0 +1,169 @@
+//! Historical series for many coins at once, and correlations between them.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::coingecko::HistoricalData;
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Daily return pairs needed before a correlation means anything
+pub const MIN_OVERLAP: usize = 5;
+
+/// Result of a bulk fetch: the coins that loaded, in request order, and the ones
+/// that didn't with the reason
+#[derive(Debug, Default)]
+pub struct BulkHistorical {
+    pub series: Vec<(String, HistoricalData)>,
+    pub omitted: Vec<(String, String)>,
+}
+
+impl BulkHistorical {
+    /// Pearson correlation of daily returns between every pair of fetched coins.
+    /// Omitted coins are carried over so reports can say which are missing.
+    pub fn correlations(&self) -> CorrelationMatrix {
+        let returns: Vec<HashMap<i64, f64>> = self.series.iter().map(|(_, data)| daily_returns(&data.prices)).collect();
+        let n = returns.len();
+        let mut values = vec![vec![None; n]; n];
+        for i in 0..n {
+            values[i][i] = Some(1.0);
+            for j in (i + 1)..n {
+                let correlation = pearson_aligned(&returns[i], &returns[j]);
+                values[i][j] = correlation;
+                values[j][i] = correlation;
+            }
+        }
+        CorrelationMatrix {
+            coins: self.series.iter().map(|(id, _)| id.clone()).collect(),
+            values,
+            omitted: self.omitted.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CorrelationMatrix {
+    pub coins: Vec<String>,
+    /// `values[i][j]` for `coins[i]` and `coins[j]`; None when they share fewer than
+    /// `MIN_OVERLAP` days or one of them never moved
+    pub values: Vec<Vec<Option<f64>>>,
+    pub omitted: Vec<(String, String)>,
+}
+
+impl CorrelationMatrix {
+    pub fn get(&self, a: &str, b: &str) -> Option<f64> {
+        let i = self.coins.iter().position(|c| c == a)?;
+        let j = self.coins.iter().position(|c| c == b)?;
+        self.values[i][j]
+    }
+}
+
+impl fmt::Display for CorrelationMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.coins.iter().map(|c| c.len()).max().unwrap_or(0).max(6);
+        write!(f, "{:width$}", "", width = width)?;
+        for coin in &self.coins {
+            write!(f, " {:>width$}", coin, width = width)?;
+        }
+        writeln!(f)?;
+        for (coin, row) in self.coins.iter().zip(&self.values) {
+            write!(f, "{:width$}", coin, width = width)?;
+            for value in row {
+                match value {
+                    Some(v) => write!(f, " {:>width$.2}", v, width = width)?,
+                    None => write!(f, " {:>width$}", "N/A", width = width)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        if !self.omitted.is_empty() {
+            writeln!(f, "Omitted ({} failed to load):", self.omitted.len())?;
+            for (coin, reason) in &self.omitted {
+                writeln!(f, "  {}: {}", coin, reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Day -> return from the previous day, skipping gaps so returns are always day over day
+fn daily_returns(prices: &[[f64; 2]]) -> HashMap<i64, f64> {
+    // CoinGecko's last daily point is the current price; a later point on the same day wins
+    let mut closes: Vec<(i64, f64)> = Vec::new();
+    for [timestamp, price] in prices {
+        let day = *timestamp as i64 / MS_PER_DAY;
+        match closes.last_mut() {
+            Some(last) if last.0 == day => last.1 = *price,
+            _ => closes.push((day, *price)),
+        }
+    }
+    closes
+        .windows(2)
+        .filter(|w| w[1].0 == w[0].0 + 1 && w[0].1 > 0.0)
+        .map(|w| (w[1].0, w[1].1 / w[0].1 - 1.0))
+        .collect()
+}
+
+fn pearson_aligned(a: &HashMap<i64, f64>, b: &HashMap<i64, f64>) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = a.iter().filter_map(|(day, x)| b.get(day).map(|y| (*x, *y))).collect();
+    if pairs.len() < MIN_OVERLAP {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in &pairs {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+    Some(cov / (var_x * var_y).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(prices: &[f64]) -> HistoricalData {
+        HistoricalData {
+            prices: prices.iter().enumerate().map(|(day, p)| [(day as i64 * MS_PER_DAY) as f64, *p]).collect(),
+            market_caps: Vec::new(),
+            total_volumes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn correlates_only_fetched_coins() {
+        let bulk = BulkHistorical {
+            series: vec![
+                ("bitcoin".to_string(), series(&[100.0, 102.0, 101.0, 105.0, 104.0, 108.0, 107.0])),
+                ("ethereum".to_string(), series(&[10.0, 10.2, 10.1, 10.5, 10.4, 10.8, 10.7])),
+                ("tether".to_string(), series(&[1.0; 7])),
+            ],
+            omitted: vec![("solana".to_string(), "Request timed out".to_string())],
+        };
+        let matrix = bulk.correlations();
+
+        assert_eq!(matrix.coins, ["bitcoin", "ethereum", "tether"]);
+        assert!((matrix.get("bitcoin", "ethereum").unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(matrix.get("bitcoin", "tether"), None);
+        assert_eq!(matrix.get("bitcoin", "solana"), None);
+        assert!(matrix.to_string().contains("solana: Request timed out"));
+    }
+
+    #[test]
+    fn short_overlap_has_no_correlation() {
+        let bulk = BulkHistorical {
+            series: vec![
+                ("a".to_string(), series(&[1.0, 2.0, 1.0])),
+                ("b".to_string(), series(&[2.0, 1.0, 2.0])),
+            ],
+            omitted: Vec::new(),
+        };
+        assert_eq!(bulk.correlations().get("a", "b"), None);
+    }
+}
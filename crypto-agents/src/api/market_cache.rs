@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::api::coingecko::{CandleData, CoinGeckoClient, MarketTechnicalData};
+use crate::models::MarketData;
+
+/// Market data for one trading round, shared by every agent in it. Each item is fetched
+/// at most once per round, even when agents ask for it at the same time; a new round
+/// gets a new cache and so fresh data. Failed fetches aren't cached and are retried
+/// by the next caller.
+pub struct MarketDataCache {
+    round: DateTime<Utc>,
+    client: Arc<CoinGeckoClient>,
+    market: OnceCell<MarketData>,
+    technical: OnceCell<MarketTechnicalData>,
+    ohlc: KeyedOnce<(String, u32), Vec<CandleData>>,
+}
+
+// One value per key, fetched once however many callers ask for it at the same
+// time. A failed fetch leaves the key empty for the next caller.
+struct KeyedOnce<K, V> {
+    cells: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+}
+
+impl<K: Eq + Hash, V: Clone> KeyedOnce<K, V> {
+    fn new() -> Self {
+        Self { cells: Mutex::new(HashMap::new()) }
+    }
+
+    async fn get_or_try_init<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        let cell = self.cells.lock().await.entry(key).or_default().clone();
+        Ok(cell.get_or_try_init(fetch).await?.clone())
+    }
+}
+
+impl MarketDataCache {
+    pub fn new(client: Arc<CoinGeckoClient>, round: DateTime<Utc>) -> Self {
+        Self {
+            round,
+            client,
+            market: OnceCell::new(),
+            technical: OnceCell::new(),
+            ohlc: KeyedOnce::new(),
+        }
+    }
+
+    /// A round over data fetched earlier, e.g. a replayed snapshot
+    pub fn with_data(
+        client: Arc<CoinGeckoClient>,
+        round: DateTime<Utc>,
+        market: MarketData,
+        technical: MarketTechnicalData,
+    ) -> Self {
+        Self {
+            market: OnceCell::new_with(Some(market)),
+            technical: OnceCell::new_with(Some(technical)),
+            ..Self::new(client, round)
+        }
+    }
+
+    /// When the round started, the key the data belongs to
+    pub fn round(&self) -> DateTime<Utc> {
+        self.round
+    }
+
+    pub async fn market_data(&self) -> Result<&MarketData> {
+        self.market.get_or_try_init(|| self.client.get_market_data()).await
+    }
+
+    pub async fn technical_data(&self) -> Result<&MarketTechnicalData> {
+        self.technical.get_or_try_init(|| self.client.get_technical_analysis()).await
+    }
+
+    /// OHLC candles for a coin, shared by every agent that asks for the same coin and days
+    pub async fn ohlc(&self, coin_id: &str, days: u32) -> Result<Vec<CandleData>> {
+        self.ohlc.get_or_try_init((coin_id.to_string(), days), || self.client.get_ohlc_data(coin_id, days)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn concurrent_callers_share_one_fetch_per_key() {
+        let cache = KeyedOnce::new();
+        let fetches = AtomicUsize::new(0);
+        let fetch = |coin: &'static str| {
+            let cache = &cache;
+            let fetches = &fetches;
+            async move {
+                cache
+                    .get_or_try_init((coin, 1), move || async move {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok(format!("{} candles", coin))
+                    })
+                    .await
+            }
+        };
+
+        let (a, b, c) = tokio::join!(fetch("bitcoin"), fetch("bitcoin"), fetch("solana"));
+        assert_eq!(a.unwrap(), "bitcoin candles");
+        assert_eq!(b.unwrap(), "bitcoin candles");
+        assert_eq!(c.unwrap(), "solana candles");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // Later callers in the same round get the stored value
+        assert_eq!(fetch("bitcoin").await.unwrap(), "bitcoin candles");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_fetches_are_not_kept() {
+        let cache: KeyedOnce<&str, u32> = KeyedOnce::new();
+        assert!(cache.get_or_try_init("bitcoin", || async { anyhow::bail!("429 Too Many Requests") }).await.is_err());
+        assert_eq!(cache.get_or_try_init("bitcoin", || async { Ok(7) }).await.unwrap(), 7);
+        assert_eq!(cache.get_or_try_init("bitcoin", || async { Ok(8) }).await.unwrap(), 7);
+    }
+}
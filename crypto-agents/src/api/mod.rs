@@ -1,7 +1,11 @@
 pub mod coingecko;
+pub mod historical;
+pub mod market_cache;
 pub mod social_media;
 
-pub use coingecko::CoinGeckoClient;
+pub use coingecko::{CandleSource, CoinGeckoClient};
+pub use historical::{BulkHistorical, CorrelationMatrix};
+pub use market_cache::MarketDataCache;
 pub use social_media::SocialMediaClient;
 
 // Re-export commonly used types
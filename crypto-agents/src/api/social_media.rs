@@ -3,7 +3,10 @@ use serde::{Deserialize, Serialize};
 use agent_twitter_client::scraper::Scraper;
 use std::env;
 use crate::models::MarketData;
+use crate::output::say;
+use crate::sentiment_scorer::SentimentScorer;
 use std::fs;
+use common::paths;
 use chrono::{Utc, DateTime, Timelike};
 use serde_json::{json, Value};
 use csv::Writer;
@@ -20,13 +23,14 @@ pub struct SocialMediaPost {
 
 pub struct SocialMediaClient {
     scraper: Scraper,
+    sentiment: SentimentScorer,
 }
 
 impl SocialMediaClient {
     pub async fn new() -> Result<Self> {
         // Create scraper with async initialization
         let scraper = Scraper::new().await?;
-        let mut client = Self { scraper };
+        let mut client = Self { scraper, sentiment: SentimentScorer::load() };
         
         // Handle authentication
         client.authenticate().await?;
@@ -74,7 +78,7 @@ impl SocialMediaClient {
                 Ok(posts) => return Ok(posts),
                 Err(e) => {
                     if attempt < MAX_RETRIES - 1 {
-                        println!("⚠️ Error fetching tweets for {}: {}. Retrying...", symbol, e);
+                        say(format!("⚠️ Error fetching tweets for {}: {}. Retrying...", symbol, e));
                         sleep(RETRY_DELAY).await;
                     } else {
                         return Err(e);
@@ -86,57 +90,20 @@ impl SocialMediaClient {
         Err(anyhow::anyhow!("Max retries exceeded for {}", symbol))
     }
 
-    // Enhanced sentiment analysis
-    fn analyze_sentiment(&self, text: &str) -> f64 {
-        let positive_words = [
-            "bullish", "moon", "up", "gain", "profit", "buy", "good", "great",
-            "excellent", "positive", "success", "winning", "confident", "secure",
-            "long", "strong", "rise", "rising", "higher", "support", "breakthrough",
-            "breakout", "momentum", "rally", "growth", "accumulate", "hold", "hodl"
-        ];
-        
-        let negative_words = [
-            "bearish", "down", "loss", "sell", "bad", "worse", "negative",
-            "fail", "risk", "crash", "dump", "poor", "weak", "uncertain",
-            "short", "resistance", "lower", "falling", "dip", "bear", "correction",
-            "decline", "drop", "fear", "panic", "capitulate", "liquidate"
-        ];
-
-        let lowercase_text = text.to_lowercase();
-        let words: Vec<&str> = lowercase_text.split_whitespace().collect();
-        let mut score: f64 = 0.0;
-
-        for word in words {
-            if positive_words.contains(&word) {
-                score += 1.0;
-            }
-            if negative_words.contains(&word) {
-                score -= 1.0;
-            }
-        }
-
-        // Normalize score between -1 and 1
-        if score != 0.0 {
-            score / score.abs().max(1.0)
-        } else {
-            0.0
-        }
-    }
-
     // Add new function to save data
     async fn save_sentiment_data(&self, symbol: &str, posts: &[SocialMediaPost], format: &str) -> Result<()> {
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
-        let data_dir = "data/sentiment_logs";
+        let data_dir = paths::data_subdir("sentiment_logs");
         
         // Create directories if they don't exist
-        fs::create_dir_all(format!("{}/{}", data_dir, symbol))?;
+        fs::create_dir_all(data_dir.join(symbol))?;
         
         // Calculate additional metrics
         let metrics = calculate_metrics(posts);
         
         match format.to_lowercase().as_str() {
             "json" => {
-                let file_path = format!("{}/{}/{}_sentiment.json", data_dir, symbol, timestamp);
+                let file_path = data_dir.join(symbol).join(format!("{}_sentiment.json", timestamp));
                 let json_data = json!({
                     "symbol": symbol,
                     "timestamp": timestamp,
@@ -153,7 +120,7 @@ impl SocialMediaClient {
                 fs::write(file_path, serde_json::to_string_pretty(&json_data)?)?;
             },
             "csv" => {
-                let file_path = format!("{}/{}/{}_sentiment.csv", data_dir, symbol, timestamp);
+                let file_path = data_dir.join(symbol).join(format!("{}_sentiment.csv", timestamp));
                 let mut wtr = Writer::from_path(file_path)?;
                 
                 // Write header
@@ -239,7 +206,7 @@ fn calculate_metrics(posts: &[SocialMediaPost]) -> Metrics {
 
 impl SocialMediaClient {
     async fn try_get_twitter_sentiment(&self, symbol: &str) -> Result<Vec<SocialMediaPost>> {
-        println!("🔄 Searching tweets for {}", symbol);
+        say(format!("🔄 Searching tweets for {}", symbol));
         
         // Get configuration from environment
         let languages = env::var("SENTIMENT_LANGUAGES")
@@ -296,7 +263,7 @@ impl SocialMediaClient {
                     continue;
                 }
 
-                post.sentiment_score = Some(self.analyze_sentiment(&post.content));
+                post.sentiment_score = Some(self.sentiment.score(&post.content));
                 posts.push(post);
             }
         }
@@ -319,7 +286,7 @@ impl SocialMediaClient {
         let mut processed_symbols = std::collections::HashSet::new();
         
         // Create summary file for this gathering session
-        let summary_path = format!("data/sentiment_logs/summary_{}.json", timestamp);
+        let summary_path = paths::data_subdir("sentiment_logs").join(format!("summary_{}.json", timestamp));
         let mut summary = json!({
             "timestamp": timestamp,
             "symbols": {},
@@ -343,9 +310,9 @@ impl SocialMediaClient {
         // Process symbols only if not already processed
         for symbol in default_symbols {
             processed += 1;
-            println!("🔄 Progress: [{}/{}] Analyzing {}", processed, total_symbols, symbol);
+            say(format!("🔄 Progress: [{}/{}] Analyzing {}", processed, total_symbols, symbol));
             if processed_symbols.insert(symbol.clone()) {
-                println!("🔍 Analyzing sentiment for {}", symbol);
+                say(format!("🔍 Analyzing sentiment for {}", symbol));
                 let mut posts = self.get_twitter_sentiment(&symbol).await?;
                 
                 // Update summary
@@ -368,8 +335,8 @@ impl SocialMediaClient {
         {
             for coin in &market_data.trending {
                 processed += 1;
-                println!("🔄 Progress: [{}/{}] Analyzing {}", processed, total_symbols, coin.symbol);
-                println!("🔥 Analyzing trending coin: {}", coin.symbol);
+                say(format!("🔄 Progress: [{}/{}] Analyzing {}", processed, total_symbols, coin.symbol));
+                say(format!("🔥 Analyzing trending coin: {}", coin.symbol));
                 let mut posts = self.get_twitter_sentiment(&coin.symbol).await?;
                 all_posts.append(&mut posts);
             }
@@ -399,8 +366,8 @@ impl SocialMediaClient {
 
             for symbol in additional_symbols {
                 processed += 1;
-                println!("🔄 Progress: [{}/{}] Analyzing {}", processed, total_symbols, symbol);
-                println!("🔍 Analyzing additional target: {}", symbol);
+                say(format!("🔄 Progress: [{}/{}] Analyzing {}", processed, total_symbols, symbol));
+                say(format!("🔍 Analyzing additional target: {}", symbol));
                 let mut posts = self.get_twitter_sentiment(&symbol).await?;
                 all_posts.append(&mut posts);
             }
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use common::paths;
+
+use crate::config::CompletionCacheConfig;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    created_at: DateTime<Utc>,
+    model: String,
+    response: String,
+}
+
+/// On-disk cache of completed agent responses, one JSON file per prompt.
+/// Only whole responses are stored, so an interrupted call never leaves a partial entry.
+#[derive(Debug, Clone)]
+pub struct CompletionCache {
+    config: CompletionCacheConfig,
+}
+
+impl CompletionCache {
+    /// None unless the cache is enabled in the config
+    pub fn new(config: CompletionCacheConfig) -> Option<Self> {
+        config.enabled.then(|| Self { config })
+    }
+
+    /// Whether responses at this temperature may be served from the cache. Above zero
+    /// outputs vary between calls, so that needs `accept_stale_responses`.
+    pub fn allows_temperature(&self, temperature: f32) -> bool {
+        temperature <= 0.0 || self.config.accept_stale_responses
+    }
+
+    /// SHA-256 of the request, so keys stay the same across builds and Rust versions
+    /// (std's hasher makes no such promise) and the cache survives an upgrade
+    pub fn key(provider: &str, model: &str, preamble: &str, prompt: &str, temperature: f32) -> String {
+        let mut hasher = Sha256::new();
+        // Length prefixes keep ("ab", "c") and ("a", "bc") apart
+        for part in [provider, model, preamble, prompt] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher.update(temperature.to_bits().to_le_bytes());
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    // Relative dirs are taken from the data root
+    fn dir(&self) -> PathBuf {
+        paths::resolve(&self.config.dir)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir().join(format!("{}.json", key))
+    }
+
+    /// Cached response for the key, dropping it if older than the TTL
+    pub async fn get(&self, key: &str) -> Option<String> {
+        let path = self.path(key);
+        let json = tokio::fs::read_to_string(&path).await.ok()?;
+        let entry: CacheEntry = serde_json::from_str(&json).ok()?;
+
+        if Utc::now() - entry.created_at > Duration::seconds(self.config.ttl_secs as i64) {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+        Some(entry.response)
+    }
+
+    pub async fn put(&self, key: &str, model: &str, response: &str) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(self.dir()).await?;
+        let entry = CacheEntry {
+            created_at: Utc::now(),
+            model: model.to_string(),
+            response: response.to_string(),
+        };
+        tokio::fs::write(self.path(key), serde_json::to_string_pretty(&entry)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_stable_and_keep_fields_apart() {
+        let key = CompletionCache::key("openrouter", "openai/o3-mini", "You are a trader.", "BTC?", 0.0);
+        assert_eq!(key, "dad29781e4eb28468b026b22a5c6402fedc21df7d82ecb11d127d288a6483f44");
+
+        assert_ne!(CompletionCache::key("a", "bc", "", "", 0.0), CompletionCache::key("ab", "c", "", "", 0.0));
+        assert_ne!(key, CompletionCache::key("openrouter", "openai/o3-mini", "You are a trader.", "BTC?", 0.7));
+    }
+}
@@ -0,0 +1,852 @@
+use chrono::Utc;
+use common::empty_response::EmptyResponsePolicy;
+use common::exit::AppError;
+use common::exa::{Contents, ExaClient, DEFAULT_HIGHLIGHTS_PER_RESULT, DEFAULT_SUMMARY_SENTENCES};
+use common::news::{CryptoPanicClient, NewsQuery, NewsSource};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::agents::ModelProvider;
+use crate::output::OutputMode;
+
+// Default location of the crypto-agents config file, override with CRYPTO_AGENTS_CONFIG_PATH
+pub const DEFAULT_CONFIG_PATH: &str = "config/crypto_agents.json";
+
+/// Settings shared by the crypto-agents binaries and examples.
+/// Every section falls back to its defaults when missing from the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CryptoAgentsConfig {
+    pub news: NewsConfig,
+    pub signals: SignalKeywords,
+    /// CoinGecko ids of the coins that get deep technical analysis
+    pub major_coins: Vec<String>,
+    pub twitter_analysis: TwitterAnalysisConfig,
+    pub completion_cache: CompletionCacheConfig,
+    pub trading_chat: TradingChatConfig,
+    /// Responses counted as empty and retried: "whitespace" or "punctuation"
+    pub empty_response: EmptyResponsePolicy,
+    pub technical_templates: TechnicalTemplatesConfig,
+    pub round: RoundConfig,
+}
+
+impl Default for CryptoAgentsConfig {
+    fn default() -> Self {
+        Self {
+            news: NewsConfig::default(),
+            signals: SignalKeywords::default(),
+            major_coins: vec!["bitcoin".to_string(), "ethereum".to_string(), "solana".to_string()],
+            twitter_analysis: TwitterAnalysisConfig::default(),
+            completion_cache: CompletionCacheConfig::default(),
+            trading_chat: TradingChatConfig::default(),
+            empty_response: EmptyResponsePolicy::default(),
+            technical_templates: TechnicalTemplatesConfig::default(),
+            round: RoundConfig::default(),
+        }
+    }
+}
+
+/// Market news searches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewsConfig {
+    /// Where news comes from, merged in this order: "exa" and/or "cryptopanic"
+    pub sources: Vec<String>,
+    /// Domains to search; an empty list searches all domains
+    pub domains: Vec<String>,
+    /// Search query, `{topic}` is replaced with the topic being searched
+    pub query_template: String,
+    pub lookback_hours: i64,
+    pub num_results: i32,
+    /// CryptoPanic currency filter, e.g. ["BTC", "ETH"]; empty for all news
+    pub currencies: Vec<String>,
+    /// Sentences in each Exa result summary, 0 for no summary
+    pub summary_sentences: u32,
+    /// Exa highlights per result, 0 for none
+    pub highlights_per_result: u32,
+}
+
+impl Default for NewsConfig {
+    fn default() -> Self {
+        Self {
+            domains: vec![
+                "cointelegraph.com".to_string(),
+                "coindesk.com".to_string(),
+                "beincrypto.com".to_string(),
+            ],
+            query_template: "Current market news , Market updates {topic}".to_string(),
+            lookback_hours: 24,
+            num_results: 10,
+            sources: vec!["exa".to_string()],
+            currencies: Vec::new(),
+            summary_sentences: DEFAULT_SUMMARY_SENTENCES,
+            highlights_per_result: DEFAULT_HIGHLIGHTS_PER_RESULT,
+        }
+    }
+}
+
+/// Keyword map used to classify market outlook and risk level from model output.
+/// Phrases are matched case-insensitively on word boundaries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignalKeywords {
+    pub bullish: Vec<String>,
+    pub bearish: Vec<String>,
+    pub high_risk: Vec<String>,
+    pub low_risk: Vec<String>,
+}
+
+impl Default for SignalKeywords {
+    fn default() -> Self {
+        let list = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+        Self {
+            bullish: list(&[
+                "bullish", "uptrend", "upside", "breakout", "rally", "higher highs",
+                "accumulation", "outperform", "golden cross",
+            ]),
+            bearish: list(&[
+                "bearish", "downtrend", "downside", "breakdown", "sell-off", "selloff",
+                "lower lows", "distribution", "capitulation", "underperform", "death cross",
+            ]),
+            high_risk: list(&[
+                "high risk", "elevated risk", "significant risk", "extreme risk", "very risky",
+                "high volatility", "highly volatile",
+            ]),
+            low_risk: list(&[
+                "low risk", "minimal risk", "limited risk", "low volatility", "limited downside",
+                "relatively safe",
+            ]),
+        }
+    }
+}
+
+/// Opt-in cache of agent responses keyed by model, preamble, prompt and temperature.
+/// Meant for development, where the same prompts are re-run repeatedly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletionCacheConfig {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+    /// Also cache agents with temperature above zero, whose answers would normally vary
+    pub accept_stale_responses: bool,
+    pub dir: String,
+}
+
+impl Default for CompletionCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 3600,
+            accept_stale_responses: false,
+            dir: "data/completion_cache".to_string(),
+        }
+    }
+}
+
+/// How a MultiAgentSystem round runs its agents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoundConfig {
+    /// Run the fundamental, sentiment and topic agents at the same time once the
+    /// technical analysis is in, instead of one after another
+    pub concurrent_agents: bool,
+    /// What concurrent agents print while running: "buffered" into a block per
+    /// agent, shown after the round in a fixed order, or "prefixed" and shown as
+    /// it comes. Agents run one after another always print as they go.
+    pub output: OutputMode,
+}
+
+/// Framing added to the technical agent's coin analysis. A coin listed in `coins`
+/// gets that template, others one by market cap tier; a coin with no market cap,
+/// or whose template isn't defined, gets the default analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TechnicalTemplatesConfig {
+    /// Market cap in USD from which a coin is "large"
+    pub large_cap_usd: f64,
+    /// Market cap in USD from which a coin is "mid"; below it, "micro"
+    pub mid_cap_usd: f64,
+    /// CoinGecko id to template name, e.g. "dogecoin": "meme"
+    pub coins: HashMap<String, String>,
+    /// Template name to framing text; "large", "mid" and "micro" are the tiers
+    pub templates: HashMap<String, String>,
+}
+
+impl Default for TechnicalTemplatesConfig {
+    fn default() -> Self {
+        let meme = ["dogecoin", "shiba-inu", "pepe", "dogwifcoin", "bonk", "floki"];
+        let templates = [
+            (
+                "large",
+                "This is a large-cap asset. Liquidity is deep, so weigh trend structure, \
+                higher-timeframe levels and macro correlation over short-term noise.",
+            ),
+            (
+                "mid",
+                "This is a mid-cap asset. Expect larger swings than the majors; check that volume \
+                confirms moves and how closely it follows BTC and its sector.",
+            ),
+            (
+                "micro",
+                "This is a micro-cap asset. Treat liquidity risk as central: thin order books, slippage \
+                and sharp wicks. Keep position sizes small and stops wide enough to survive volatility.",
+            ),
+            (
+                "meme",
+                "This is a meme coin. Price is driven by attention more than fundamentals. Assess \
+                liquidity, holder concentration and rug-pull risk, treat volume spikes as possible \
+                exit liquidity, and never rate risk below High.",
+            ),
+        ];
+        Self {
+            large_cap_usd: 10_000_000_000.0,
+            mid_cap_usd: 1_000_000_000.0,
+            coins: meme.iter().map(|id| (id.to_string(), "meme".to_string())).collect(),
+            templates: templates.iter().map(|(name, text)| (name.to_string(), text.to_string())).collect(),
+        }
+    }
+}
+
+impl TechnicalTemplatesConfig {
+    /// Name and framing of the template for a coin, None for the default analysis
+    pub fn select(&self, coin_id: &str, market_cap: f64) -> Option<(&str, &str)> {
+        let name = match self.coins.get(coin_id) {
+            Some(name) => name.as_str(),
+            None if !market_cap.is_finite() || market_cap <= 0.0 => return None,
+            None if market_cap >= self.large_cap_usd => "large",
+            None if market_cap >= self.mid_cap_usd => "mid",
+            None => "micro",
+        };
+        self.templates.get(name).map(|text| (name, text.as_str()))
+    }
+}
+
+/// The team of agents in the trading_chat example. Each entry is a persona, so a
+/// new agent is a config entry rather than code. Without a trading_chat section
+/// in the config file the default team below is used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TradingChatConfig {
+    pub agents: Vec<ChatAgentConfig>,
+}
+
+/// One chat agent. The "Technical Analyst", "News Analyst" and "Strategy Advisor"
+/// roles get their own prompts, colors, topics and turns in team discussions; agents
+/// with any other role answer from the market data and news, and need a `color` and `preamble`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatAgentConfig {
+    pub name: String,
+    pub role: String,
+    #[serde(default = "default_chat_agent_emoji")]
+    pub emoji: String,
+    /// Terminal color of the agent's name, e.g. "cyan" or "bright yellow";
+    /// defaults to the role's color
+    #[serde(default)]
+    pub color: Option<String>,
+    /// deepseek, gemini, mistral, openai, cohere, openrouter or ollama
+    pub provider: String,
+    pub model: String,
+    /// The agent's system prompt; defaults to the role's, so only another role needs one
+    #[serde(default)]
+    pub preamble: String,
+    /// How to answer, added to every prompt, e.g. "Reply in three bullet points"
+    #[serde(default)]
+    pub response_style: String,
+    /// Words that make the agent answer a message not addressed to anyone;
+    /// defaults to the role's topics
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Words that cancel one keyword's match, keyword -> words: with
+    /// `{"market": ["technical"]}` a market question about the technicals doesn't
+    /// count, while other keywords still do. Defaults to the role's.
+    #[serde(default)]
+    pub exclude_keywords: HashMap<String, Vec<String>>,
+}
+
+fn default_chat_agent_emoji() -> String {
+    "🤖".to_string()
+}
+
+// A built-in role's prompt ({name} is the agent's), color and the topics it
+// answers, or leaves to others, unaddressed
+struct ChatRole {
+    name: &'static str,
+    preamble: &'static str,
+    color: &'static str,
+    keywords: &'static [&'static str],
+    exclude_keywords: &'static [(&'static str, &'static [&'static str])],
+}
+
+const CHAT_ROLES: &[ChatRole] = &[
+    ChatRole {
+        name: "Technical Analyst",
+        preamble: "You are {name}, an expert Technical Analyst AI. Your personality is data-driven \
+            and precise, but also engaging and helpful. You specialize in trend analysis, chart patterns, \
+            and technical indicators. Respond conversationally as if in a group chat.",
+        color: "blue",
+        keywords: &["trend", "price", "chart", "indicator", "support", "resistance"],
+        exclude_keywords: &[],
+    },
+    ChatRole {
+        name: "News Analyst",
+        preamble: "You are {name}, an expert News Analyst AI. Your personality is insightful \
+            and well-informed about market events. You specialize in analyzing news impact and market \
+            sentiment. Respond conversationally as if in a group chat.",
+        color: "green",
+        keywords: &["news", "event", "announcement", "sentiment", "market", "report"],
+        // "market" questions about the technicals are the Technical Analyst's
+        exclude_keywords: &[("market", &["technical"])],
+    },
+    ChatRole {
+        name: "Strategy Advisor",
+        preamble: "You are {name}, an expert Strategy Advisor AI. Your personality is thoughtful \
+            and focused on risk management. You synthesize different market perspectives into actionable \
+            trading strategies. Respond conversationally as if in a group chat.",
+        color: "magenta",
+        keywords: &["strategy", "trade", "risk", "position", "entry", "exit", "target"],
+        exclude_keywords: &[],
+    },
+];
+
+// The configured words, else the role's, lowercased
+fn words_or_role(configured: &[String], role: Option<&[&str]>) -> Vec<String> {
+    if configured.is_empty() {
+        role.unwrap_or_default().iter().map(|k| k.to_string()).collect()
+    } else {
+        configured.iter().map(|k| k.to_lowercase()).collect()
+    }
+}
+
+impl ChatAgentConfig {
+    fn builtin_role(&self) -> Option<&'static ChatRole> {
+        CHAT_ROLES.iter().find(|role| role.name == self.role)
+    }
+
+    /// The configured preamble, else the role's for this agent; empty for another role without one
+    pub fn preamble(&self) -> String {
+        if !self.preamble.trim().is_empty() {
+            return self.preamble.clone();
+        }
+        self.builtin_role().map(|role| role.preamble.replace("{name}", &self.name)).unwrap_or_default()
+    }
+
+    /// The configured color, else the role's; None for another role without one
+    pub fn color(&self) -> Option<&str> {
+        self.color.as_deref().or_else(|| self.builtin_role().map(|role| role.color))
+    }
+
+    /// The configured keywords, else the role's, lowercased
+    pub fn keywords(&self) -> Vec<String> {
+        words_or_role(&self.keywords, self.builtin_role().map(|role| role.keywords))
+    }
+
+    /// The configured exclude keywords, else the role's, lowercased
+    pub fn exclude_keywords(&self) -> HashMap<String, Vec<String>> {
+        if self.exclude_keywords.is_empty() {
+            let role = self.builtin_role().map(|role| role.exclude_keywords).unwrap_or_default();
+            return role
+                .iter()
+                .map(|(keyword, words)| (keyword.to_string(), words.iter().map(|w| w.to_string()).collect()))
+                .collect();
+        }
+        self.exclude_keywords
+            .iter()
+            .map(|(keyword, words)| (keyword.to_lowercase(), words.iter().map(|w| w.to_lowercase()).collect()))
+            .collect()
+    }
+
+    /// Whether a message not addressed to anyone is for this agent: one of its
+    /// keywords appears without any of the words that cancel that keyword
+    pub fn answers(&self, message: &str) -> bool {
+        let message = message.to_lowercase();
+        let exclude = self.exclude_keywords();
+        self.keywords().iter().any(|keyword| {
+            message.contains(keyword.as_str())
+                && !exclude
+                    .get(keyword)
+                    .is_some_and(|words| words.iter().any(|word| message.contains(word.as_str())))
+        })
+    }
+}
+
+impl Default for TradingChatConfig {
+    fn default() -> Self {
+        // Each takes its role's preamble
+        let agent = |name: &str, role: &str, emoji: &str, provider: &str, model: &str| ChatAgentConfig {
+            name: name.to_string(),
+            role: role.to_string(),
+            emoji: emoji.to_string(),
+            color: None,
+            response_style: String::new(),
+            keywords: Vec::new(),
+            exclude_keywords: HashMap::new(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            preamble: String::new(),
+        };
+        Self {
+            agents: vec![
+                agent("Roger", "Technical Analyst", "📈", "mistral", "mistral-large-latest"),
+                agent("Laura", "News Analyst", "📰", "openrouter", "openai/o3-mini"),
+                agent("Lisa", "Strategy Advisor", "💡", "deepseek", "deepseek-chat"),
+            ],
+        }
+    }
+}
+
+impl TradingChatConfig {
+    /// Check the team before any agent is built: at least one agent, unique names,
+    /// a preamble and color for each, known providers and an API key for every provider in use
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.agents.is_empty() {
+            anyhow::bail!(
+                "trading_chat.agents in the crypto-agents config is empty; \
+                add at least one agent, or remove the section to use the default team"
+            );
+        }
+
+        let mut problems = Vec::new();
+        let mut missing_keys: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (i, agent) in self.agents.iter().enumerate() {
+            if agent.name.trim().is_empty() {
+                problems.push(format!("agent #{} has no name", i + 1));
+                continue;
+            }
+            if self.agents[..i].iter().any(|other| other.name.eq_ignore_ascii_case(&agent.name)) {
+                problems.push(format!("agent name '{}' is used more than once", agent.name));
+            }
+            match agent.color() {
+                Some(color) if color.parse::<colored::Color>().is_err() => {
+                    problems.push(format!("{} has unknown color '{}'", agent.name, color))
+                }
+                Some(_) => {}
+                None => problems.push(format!(
+                    "{} has role '{}', which has no built-in color; set \"color\"",
+                    agent.name, agent.role
+                )),
+            }
+            if agent.preamble().trim().is_empty() {
+                problems.push(format!(
+                    "{} has role '{}', which has no built-in preamble; set \"preamble\"",
+                    agent.name, agent.role
+                ));
+            }
+            let Some(provider) = ModelProvider::from_str(&agent.provider) else {
+                problems.push(format!("{} uses unknown provider '{}'", agent.name, agent.provider));
+                continue;
+            };
+            let Some(var) = provider.api_key_var() else { continue };
+            if std::env::var(var).map_or(true, |key| key.trim().is_empty()) {
+                match missing_keys.iter_mut().find(|(missing, _)| *missing == var) {
+                    Some((_, names)) => names.push(&agent.name),
+                    None => missing_keys.push((var, vec![&agent.name])),
+                }
+            }
+        }
+        for (var, names) in missing_keys {
+            problems.push(format!("{} is not set, needed by {}", var, names.join(", ")));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Invalid trading_chat agents:\n- {}", problems.join("\n- "))
+        }
+    }
+}
+
+/// Tuning for the twitter account analyzer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TwitterAnalysisConfig {
+    pub scoring: ScoringWeights,
+    pub connections: ConnectionTiers,
+    pub alpha: AlphaScoringConfig,
+    pub budget: FetchBudget,
+}
+
+/// Limits on fetching one account's tweets, so a huge account can't take over a
+/// watchlist run. 0 turns a limit off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FetchBudget {
+    pub max_seconds: u64,
+    pub max_requests: u32,
+}
+
+impl Default for FetchBudget {
+    fn default() -> Self {
+        Self {
+            max_seconds: 300,
+            max_requests: 30,
+        }
+    }
+}
+
+impl FetchBudget {
+    /// The limit a fetch has hit after running for `elapsed` and making `requests`, if any
+    pub fn exhausted(&self, elapsed: std::time::Duration, requests: u32) -> Option<String> {
+        if self.max_seconds > 0 && elapsed.as_secs() >= self.max_seconds {
+            Some(format!("{}s time limit", self.max_seconds))
+        } else if self.max_requests > 0 && requests >= self.max_requests {
+            Some(format!("{} request limit", self.max_requests))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlphaScoringMethod {
+    /// Ask the analyzer model whether tweets share non-public, actionable info
+    Llm,
+    /// Count alpha/leak keywords, no model calls
+    Keyword,
+}
+
+/// How a user's alpha score and insider probability are estimated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlphaScoringConfig {
+    pub method: AlphaScoringMethod,
+    /// Most tweets sent to the model per user; the rest count as no alpha
+    pub max_llm_tweets: usize,
+}
+
+impl Default for AlphaScoringConfig {
+    fn default() -> Self {
+        Self {
+            method: AlphaScoringMethod::Llm,
+            max_llm_tweets: 25,
+        }
+    }
+}
+
+/// Mention-count thresholds for classifying an account's connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionTiers {
+    /// More mentions than this is "frequently_mentioned"
+    pub frequent_threshold: u32,
+    /// More mentions than this is "regular_engagement", otherwise "occasional_interaction"
+    pub regular_threshold: u32,
+    /// Number of connections kept, most mentioned first
+    pub top_n: usize,
+    /// Scale thresholds up for accounts with more than `baseline_mentions` total mentions
+    pub scale_with_volume: bool,
+    pub baseline_mentions: u32,
+}
+
+impl Default for ConnectionTiers {
+    fn default() -> Self {
+        Self {
+            frequent_threshold: 10,
+            regular_threshold: 5,
+            top_n: 10,
+            scale_with_volume: false,
+            baseline_mentions: 100,
+        }
+    }
+}
+
+impl ConnectionTiers {
+    /// Relationship tier for a connection, given the user's total mention count
+    pub fn classify(&self, count: u32, total_mentions: u32) -> &'static str {
+        let scale = if self.scale_with_volume && self.baseline_mentions > 0 {
+            (total_mentions as f64 / self.baseline_mentions as f64).max(1.0)
+        } else {
+            1.0
+        };
+
+        if count as f64 > self.frequent_threshold as f64 * scale {
+            "frequently_mentioned"
+        } else if count as f64 > self.regular_threshold as f64 * scale {
+            "regular_engagement"
+        } else {
+            "occasional_interaction"
+        }
+    }
+}
+
+/// Weights behind a user's influence score and engagement rate.
+/// The defaults give influence = (likes + 2 * retweets) / (tweets * 100)
+/// and engagement = (likes + retweets) / views * 100.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub influence_like_weight: f64,
+    pub influence_retweet_weight: f64,
+    pub influence_view_weight: f64,
+    /// Weighted engagement per tweet that counts as an influence score of 1.0
+    pub influence_normalization: f64,
+    pub engagement_like_weight: f64,
+    pub engagement_retweet_weight: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            influence_like_weight: 1.0,
+            influence_retweet_weight: 2.0,
+            influence_view_weight: 0.0,
+            influence_normalization: 100.0,
+            engagement_like_weight: 1.0,
+            engagement_retweet_weight: 1.0,
+        }
+    }
+}
+
+impl ScoringWeights {
+    /// Weighted engagement per tweet over the normalization, 0 when there are no tweets
+    pub fn influence_score(&self, likes: i64, retweets: i64, views: i64, tweet_count: usize) -> f64 {
+        let weighted = self.influence_like_weight * likes as f64
+            + self.influence_retweet_weight * retweets as f64
+            + self.influence_view_weight * views as f64;
+        non_finite_to_zero(weighted / (tweet_count as f64 * self.influence_normalization))
+    }
+
+    /// Weighted likes and retweets as a percentage of views, 0 when there are no views
+    pub fn engagement_rate(&self, likes: i64, retweets: i64, views: i64) -> f64 {
+        if views <= 0 {
+            return 0.0;
+        }
+        let weighted = self.engagement_like_weight * likes as f64
+            + self.engagement_retweet_weight * retweets as f64;
+        non_finite_to_zero(weighted / views as f64 * 100.0)
+    }
+}
+
+fn non_finite_to_zero(value: f64) -> f64 {
+    if value.is_finite() { value } else { 0.0 }
+}
+
+impl CryptoAgentsConfig {
+    /// Load from CRYPTO_AGENTS_CONFIG_PATH or the default path, using defaults
+    /// when the file is missing or can't be parsed
+    pub fn load() -> Self {
+        let path = std::env::var("CRYPTO_AGENTS_CONFIG_PATH")
+            .unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+        Self::load_from(&path)
+    }
+
+    pub fn load_from(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(config) => config,
+                Err(e) => {
+                    println!("⚠️ Failed to parse {}, using defaults: {}", path.display(), e);
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+impl NewsConfig {
+    /// Normalized domain list with invalid entries dropped (with a warning). When
+    /// every configured domain is invalid that's an error rather than an empty list,
+    /// which would search all domains.
+    pub fn valid_domains(&self) -> Result<Vec<String>, AppError> {
+        let mut domains = Vec::new();
+        for raw in &self.domains {
+            match normalize_domain(raw) {
+                Some(domain) if !domains.contains(&domain) => domains.push(domain),
+                Some(_) => {}
+                None => println!("⚠️ Ignoring invalid news domain: {:?}", raw),
+            }
+        }
+        if domains.is_empty() && !self.domains.is_empty() {
+            return Err(AppError::Config(format!(
+                "None of the news domains in the crypto-agents config are valid ({}); \
+                fix them, or set \"domains\": [] to search all domains",
+                self.domains.join(", ")
+            )));
+        }
+        Ok(domains)
+    }
+
+    pub fn query(&self, topic: &str) -> String {
+        self.query_template.replace("{topic}", topic).trim().to_string()
+    }
+
+    /// What Exa returns besides the text of each result
+    pub fn exa_contents(&self) -> Contents {
+        Contents::with_limits(self.summary_sentences, self.highlights_per_result)
+    }
+
+    /// Search for `topic` over the lookback window
+    pub fn news_query(&self, topic: &str) -> Result<NewsQuery, AppError> {
+        Ok(NewsQuery {
+            query: self.query(topic),
+            start: Utc::now() - chrono::Duration::hours(self.lookback_hours),
+            end: None,
+            domains: self.valid_domains()?,
+            limit: self.num_results.max(1) as usize,
+        })
+    }
+
+    /// The configured sources that have an API key (EXA_API_KEY, CRYPTOPANIC_API_KEY).
+    /// Unknown names and sources without a key are skipped with a warning.
+    pub fn build_sources(&self) -> Vec<Box<dyn NewsSource>> {
+        let key = |var: &str| std::env::var(var).ok().filter(|k| !k.trim().is_empty());
+        let mut sources: Vec<Box<dyn NewsSource>> = Vec::new();
+        for name in &self.sources {
+            match name.trim().to_lowercase().as_str() {
+                "exa" => match key("EXA_API_KEY") {
+                    Some(api_key) => {
+                        sources.push(Box::new(ExaClient::new(&api_key).with_contents(self.exa_contents())))
+                    }
+                    None => println!("⚠️ EXA_API_KEY not found, skipping Exa news"),
+                },
+                "cryptopanic" => match key("CRYPTOPANIC_API_KEY") {
+                    Some(api_key) => sources.push(Box::new(
+                        CryptoPanicClient::new(&api_key).with_currencies(self.currencies.clone()),
+                    )),
+                    None => println!("⚠️ CRYPTOPANIC_API_KEY not found, skipping CryptoPanic news"),
+                },
+                other => println!("⚠️ Ignoring unknown news source: {:?}", other),
+            }
+        }
+        sources
+    }
+}
+
+// Accepts "coindesk.com", "https://www.coindesk.com/" etc, returns the bare host
+fn normalize_domain(raw: &str) -> Option<String> {
+    let domain = raw.trim().to_lowercase();
+    let domain = domain
+        .strip_prefix("https://")
+        .or_else(|| domain.strip_prefix("http://"))
+        .unwrap_or(&domain);
+    let domain = domain.trim_end_matches('/');
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+
+    valid.then(|| domain.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ollama needs no API key, so these don't depend on the environment
+    fn team(agents: serde_json::Value) -> TradingChatConfig {
+        serde_json::from_value(serde_json::json!({ "agents": agents })).unwrap()
+    }
+
+    #[test]
+    fn invalid_news_domains_are_dropped() {
+        let news = |domains: &[&str]| NewsConfig { domains: domains.iter().map(|d| d.to_string()).collect(), ..NewsConfig::default() };
+
+        let domains = news(&["https://www.CoinDesk.com/", "coindesk.com", "not a domain", "decrypt.co"]).valid_domains();
+        assert_eq!(domains.unwrap(), ["www.coindesk.com", "coindesk.com", "decrypt.co"]);
+        assert!(news(&[]).valid_domains().unwrap().is_empty());
+
+        // Searching all domains instead would quietly ignore the config
+        let error = news(&["not a domain", "-bad-.com"]).valid_domains().unwrap_err();
+        assert!(error.to_string().contains("None of the news domains"));
+        assert!(news(&["localhost"]).news_query("btc").is_err());
+    }
+
+    #[test]
+    fn builtin_roles_fill_in_the_persona() {
+        let config = team(serde_json::json!([
+            { "name": "Max", "role": "News Analyst", "provider": "ollama", "model": "llama3" }
+        ]));
+        config.validate().unwrap();
+
+        let agent = &config.agents[0];
+        assert!(agent.preamble().starts_with("You are Max, an expert News Analyst AI."));
+        assert_eq!(agent.color(), Some("green"));
+        assert!(agent.keywords().contains(&"market".to_string()));
+        assert_eq!(agent.exclude_keywords()["market"], ["technical"]);
+    }
+
+    #[test]
+    fn exclusions_only_cancel_their_keyword() {
+        let config = team(serde_json::json!([
+            { "name": "Max", "role": "News Analyst", "provider": "ollama", "model": "llama3" }
+        ]));
+        let news = &config.agents[0];
+
+        assert!(news.answers("How is the market reacting?"));
+        assert!(!news.answers("What does the market look like on the technical side?"));
+        // Still the News Analyst's through another keyword
+        assert!(news.answers("Any news on the technical upgrade?"));
+        assert!(news.answers("Technical report on the market"));
+        assert!(!news.answers("Is this chart bullish?"));
+    }
+
+    #[test]
+    fn default_team_uses_the_role_preambles() {
+        let config = TradingChatConfig::default();
+        assert_eq!(config.agents.len(), 3);
+        for agent in &config.agents {
+            assert!(agent.preamble().starts_with(&format!("You are {}, an expert {} AI.", agent.name, agent.role)));
+        }
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        assert!(team(serde_json::json!([])).validate().is_err());
+
+        let config = team(serde_json::json!([
+            { "name": "Roger", "role": "Technical Analyst", "provider": "ollama", "model": "llama3" },
+            { "name": "roger", "role": "Strategy Advisor", "provider": "ollama", "model": "llama3" },
+            { "name": "Ada", "role": "On-chain Analyst", "provider": "ollama", "model": "llama3" },
+            { "name": "Bo", "role": "News Analyst", "color": "plaid", "provider": "ollama", "model": "llama3" },
+            { "name": "Cy", "role": "News Analyst", "provider": "skynet", "model": "t-800" },
+            { "name": "", "role": "News Analyst", "provider": "ollama", "model": "llama3" }
+        ]));
+        let error = config.validate().unwrap_err().to_string();
+        for problem in [
+            "agent name 'roger' is used more than once",
+            "Ada has role 'On-chain Analyst', which has no built-in color",
+            "Ada has role 'On-chain Analyst', which has no built-in preamble",
+            "Bo has unknown color 'plaid'",
+            "Cy uses unknown provider 'skynet'",
+            "agent #6 has no name",
+        ] {
+            assert!(error.contains(problem), "missing '{}' in: {}", problem, error);
+        }
+    }
+
+    #[test]
+    fn custom_roles_validate_with_a_color_and_preamble() {
+        let config = team(serde_json::json!([{
+            "name": "Ada", "role": "On-chain Analyst", "color": "bright cyan",
+            "preamble": "You are Ada, you read on-chain flows.", "keywords": ["Whale"],
+            "provider": "ollama", "model": "llama3"
+        }]));
+        config.validate().unwrap();
+        assert_eq!(config.agents[0].keywords(), ["whale"]);
+        assert!(config.agents[0].exclude_keywords().is_empty());
+        assert!(config.agents[0].answers("Big WHALE moves"));
+    }
+
+    #[test]
+    fn fetch_budget_stops_at_either_limit() {
+        use std::time::Duration;
+        let budget = FetchBudget { max_seconds: 60, max_requests: 5 };
+
+        assert_eq!(budget.exhausted(Duration::from_secs(59), 4), None);
+        assert_eq!(budget.exhausted(Duration::from_secs(60), 0).as_deref(), Some("60s time limit"));
+        assert_eq!(budget.exhausted(Duration::from_secs(1), 5).as_deref(), Some("5 request limit"));
+        // The time limit is reported first when both are hit
+        assert_eq!(budget.exhausted(Duration::from_secs(90), 9).as_deref(), Some("60s time limit"));
+
+        let unlimited = FetchBudget { max_seconds: 0, max_requests: 0 };
+        assert_eq!(unlimited.exhausted(Duration::from_secs(86_400), 10_000), None);
+    }
+}
@@ -1,7 +1,16 @@
 pub mod agents;
 pub mod api;
+pub mod completion_cache;
+pub mod config;
 pub mod models;
+pub mod output;
+pub mod portfolio_export;
+pub mod prompt_values;
+pub mod round;
+pub mod sentiment_scorer;
+pub mod snapshot;
 pub mod system;
+pub mod technical_diff;
 
 // Re-export main components
 pub use agents::{
@@ -14,6 +23,13 @@ pub use agents::{
     SentimentAgent,
     SynopsisAgent,
 };
-pub use api::coingecko::CoinGeckoClient;
+pub use api::coingecko::{coin_id_for_symbol, CandleSource, CoinGeckoClient, SimplePrice};
 pub use models::{MarketData, GlobalData, CoinData, TrendingCoin};
-pub use system::MultiAgentSystem; 
\ No newline at end of file
+pub use system::MultiAgentSystem;
+pub use round::{AgentOutput, AgentStatus, RoundResult, StepError};
+pub use output::{OutputCoordinator, OutputMode};
+pub use config::{AlphaScoringConfig, AlphaScoringMethod, ChatAgentConfig, CompletionCacheConfig, ConnectionTiers, CryptoAgentsConfig, FetchBudget, NewsConfig, RoundConfig, ScoringWeights, SignalKeywords, TradingChatConfig, TwitterAnalysisConfig};
+pub use snapshot::MarketSnapshot;
+pub use technical_diff::{IndicatorDelta, TechnicalDiff};
+pub use completion_cache::CompletionCache;
+pub use sentiment_scorer::SentimentScorer; 
\ No newline at end of file
@@ -1,9 +1,16 @@
 use anyhow::Result;
-use crypto_agents::MultiAgentSystem;
+use crypto_agents::{snapshot, MarketSnapshot, MultiAgentSystem};
+use common::exit::exit_with_error;
 use dotenv::dotenv;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    if let Err(e) = run().await {
+        exit_with_error(&*e);
+    }
+}
+
+async fn run() -> Result<()> {
     // Load environment variables
     dotenv().ok();
     
@@ -13,6 +20,11 @@ async fn main() -> Result<()> {
     
     // Initialize and run the system
     let mut system = MultiAgentSystem::new().await?;
+    if let Some(path) = snapshot::replay_path_from_args() {
+        let snapshot = MarketSnapshot::load(&path).await?;
+        println!("⏪ Replaying snapshot: {}", path.display());
+        system = system.with_replay(snapshot);
+    }
     
     println!("\n✅ System initialized successfully!");
     println!("\n🔄 Starting research rounds...\n");
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use crate::api::coingecko::MarketTechnicalData;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +18,9 @@ pub struct GlobalData {
     pub total_volume: f64,
     pub market_cap_change_percentage_24h: f64,
     pub active_cryptocurrencies: i32,
+    /// Market cap share in percent keyed by lowercase symbol ("btc", "eth", ...)
+    #[serde(default)]
+    pub market_cap_percentage: HashMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
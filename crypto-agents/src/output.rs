@@ -0,0 +1,204 @@
+//! Console output of agents that may run side by side. Inside a round each agent
+//! runs under an [`OutputCoordinator`], and what it prints with [`say`] or [`stream`]
+//! is either buffered into a block per agent, kept in the order the agents were
+//! started, or printed at once with a `[Agent]` prefix on every line. Agents run
+//! one after another, and code outside a round, simply print.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use common::progress;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Hold each agent's lines and show them as one block after the round
+    #[default]
+    Buffered,
+    /// Print lines as they come, each prefixed with the agent's name
+    Prefixed,
+}
+
+tokio::task_local! {
+    static SINK: Sink;
+}
+
+#[derive(Debug, Default)]
+struct Block {
+    agent: &'static str,
+    lines: Vec<String>,
+    // Streamed text after the last newline
+    partial: String,
+}
+
+#[derive(Clone)]
+struct Sink {
+    // None prints as it comes, unchanged
+    mode: Option<OutputMode>,
+    blocks: Arc<Mutex<Vec<Block>>>,
+    index: usize,
+}
+
+impl Sink {
+    fn write(&self, text: &str, complete: bool) {
+        let Some(mode) = self.mode else {
+            if complete { progress::println(text) } else { progress::print(text) }
+            return;
+        };
+        let mut blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+        let block = &mut blocks[self.index];
+        block.partial.push_str(text);
+        let ready = if complete {
+            std::mem::take(&mut block.partial)
+        } else {
+            match block.partial.rfind('\n') {
+                Some(end) => {
+                    let rest = block.partial.split_off(end + 1);
+                    std::mem::replace(&mut block.partial, rest)
+                }
+                None => return,
+            }
+        };
+        let lines = ready.strip_suffix('\n').unwrap_or(&ready).split('\n').map(str::to_string);
+        match mode {
+            OutputMode::Buffered => block.lines.extend(lines),
+            OutputMode::Prefixed => {
+                for line in lines {
+                    progress::println(prefixed(block.agent, &line));
+                }
+            }
+        }
+    }
+
+    fn finish(&self) {
+        if self.mode.is_none() {
+            return;
+        }
+        let pending = {
+            let blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+            !blocks[self.index].partial.is_empty()
+        };
+        if pending {
+            self.write("", true);
+        }
+    }
+}
+
+fn prefixed(agent: &str, line: &str) -> String {
+    format!("[{}] {}", agent, line)
+}
+
+/// Print a line of agent output, through the agent's coordinator when it has one
+pub fn say(line: impl std::fmt::Display) {
+    let line = line.to_string();
+    if SINK.try_with(|sink| sink.write(&line, true)).is_err() {
+        progress::println(line);
+    }
+}
+
+/// Print a streamed piece of agent output. Under a coordinator only whole lines
+/// are passed on, so a prefix never lands in the middle of one.
+pub fn stream(text: &str) {
+    if SINK.try_with(|sink| sink.write(text, false)).is_err() {
+        progress::print(text);
+    }
+}
+
+/// Collects the output of one round's agents
+pub struct OutputCoordinator {
+    mode: Option<OutputMode>,
+    blocks: Arc<Mutex<Vec<Block>>>,
+}
+
+impl OutputCoordinator {
+    pub fn new(mode: OutputMode) -> Self {
+        Self { mode: Some(mode), blocks: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Print everything as it comes, for agents that run one at a time
+    pub fn live() -> Self {
+        Self { mode: None, blocks: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Run `step` as `agent`'s. Its block takes its place when this is called, not
+    /// when the step finishes, so concurrent agents keep a stable order.
+    pub fn run<F: Future>(&self, agent: &'static str, step: F) -> impl Future<Output = F::Output> {
+        let mut blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+        blocks.push(Block { agent, ..Block::default() });
+        let sink = Sink { mode: self.mode, blocks: self.blocks.clone(), index: blocks.len() - 1 };
+        drop(blocks);
+
+        SINK.scope(sink.clone(), async move {
+            let output = step.await;
+            sink.finish();
+            output
+        })
+    }
+
+    /// (agent, lines) for each agent that printed anything while buffered, in run order
+    pub fn into_notes(self) -> Vec<(String, Vec<String>)> {
+        let mut blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+        // A step cut off by the round's deadline may have left half a line
+        for index in 0..blocks.len() {
+            if !blocks[index].partial.is_empty() {
+                let partial = std::mem::take(&mut blocks[index].partial);
+                match self.mode {
+                    Some(OutputMode::Buffered) => blocks[index].lines.push(partial),
+                    Some(OutputMode::Prefixed) => progress::println(prefixed(blocks[index].agent, &partial)),
+                    None => {}
+                }
+            }
+        }
+        blocks
+            .drain(..)
+            .filter(|block| !block.lines.is_empty())
+            .map(|block| (block.agent.to_string(), block.lines))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn buffered_blocks_keep_start_order() {
+        let output = OutputCoordinator::new(OutputMode::Buffered);
+        let slow = output.run("Fundamental", async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            say("fundamental done");
+        });
+        let fast = output.run("Sentiment", async {
+            stream("gathering ");
+            stream("tweets\nscoring");
+        });
+        tokio::join!(slow, fast);
+        output.run("Quiet", async {}).await;
+
+        assert_eq!(
+            output.into_notes(),
+            [
+                ("Fundamental".to_string(), vec!["fundamental done".to_string()]),
+                ("Sentiment".to_string(), vec!["gathering tweets".to_string(), "scoring".to_string()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn prefixed_output_is_not_kept() {
+        let output = OutputCoordinator::new(OutputMode::Prefixed);
+        output.run("Topic", async { say("🔍 Starting sector analysis") }).await;
+        assert!(output.into_notes().is_empty());
+        assert_eq!(prefixed("Topic", "line"), "[Topic] line");
+    }
+
+    #[tokio::test]
+    async fn live_output_is_not_kept() {
+        let output = OutputCoordinator::live();
+        output.run("Fundamental", async { say("📊 Fundamental analysis") }).await;
+        output.run("Topic", async { stream("streamed") }).await;
+        assert!(output.into_notes().is_empty());
+    }
+}
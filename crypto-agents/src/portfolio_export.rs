@@ -0,0 +1,94 @@
+use anyhow::Result;
+use chrono::SecondsFormat;
+use csv::Writer;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::models::PortfolioUpdate;
+
+// ISO-8601 in UTC, which spreadsheets parse as a date
+fn iso_timestamp(update: &PortfolioUpdate) -> String {
+    update.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Write the history as a wide CSV for charting: one row per update with
+/// `timestamp`, `total_value` and a USD value column per symbol ever held.
+/// An empty history gives a file with just the header.
+pub fn write_portfolio_csv(history: &[PortfolioUpdate], path: &Path) -> Result<()> {
+    let symbols: BTreeSet<&str> = history
+        .iter()
+        .flat_map(|update| update.holdings.iter().map(|h| h.symbol.as_str()))
+        .collect();
+
+    let mut wtr = Writer::from_path(path)?;
+    let mut header = vec!["timestamp".to_string(), "total_value".to_string()];
+    header.extend(symbols.iter().map(|symbol| format!("{}_value_usd", symbol)));
+    wtr.write_record(&header)?;
+
+    for update in history {
+        let mut record = vec![iso_timestamp(update), format!("{:.2}", update.total_value)];
+        for symbol in &symbols {
+            // Several entries for one symbol are summed, a symbol not held is left blank
+            let value: Option<f64> = update
+                .holdings
+                .iter()
+                .filter(|h| h.symbol == *symbol)
+                .map(|h| h.value_usd)
+                .reduce(|a, b| a + b);
+            record.push(value.map(|v| format!("{:.2}", v)).unwrap_or_default());
+        }
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Write the history as a JSON array, `[]` when empty
+pub fn write_portfolio_json(history: &[PortfolioUpdate], path: &Path) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(history)?)?;
+    Ok(())
+}
+
+/// Render total value over time as a PNG line chart. An empty history still
+/// produces an image, with an empty plot.
+#[cfg(feature = "portfolio-chart")]
+pub fn render_equity_curve(history: &[PortfolioUpdate], path: &Path, title: &str) -> Result<()> {
+    use plotters::prelude::*;
+
+    let root = BitMapBackend::new(path, (1024, 576)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let (start, end) = match (history.first(), history.last()) {
+        (Some(first), Some(last)) if last.timestamp > first.timestamp => (first.timestamp, last.timestamp),
+        (Some(only), _) => (only.timestamp - chrono::Duration::hours(1), only.timestamp + chrono::Duration::hours(1)),
+        _ => {
+            let now = chrono::Utc::now();
+            (now - chrono::Duration::days(1), now)
+        }
+    };
+    let max_value = history.iter().map(|u| u.total_value).fold(0.0_f64, f64::max);
+    let max_value = if max_value > 0.0 { max_value * 1.1 } else { 1.0 };
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(16)
+        .x_label_area_size(40)
+        .y_label_area_size(70)
+        .build_cartesian_2d(start..end, 0.0..max_value)?;
+
+    chart
+        .configure_mesh()
+        .x_labels(8)
+        .x_label_formatter(&|t| t.format("%Y-%m-%d").to_string())
+        .y_desc("Total value (USD)")
+        .draw()?;
+
+    chart.draw_series(LineSeries::new(
+        history.iter().map(|u| (u.timestamp, u.total_value)),
+        &BLUE,
+    ))?;
+
+    root.present()?;
+    Ok(())
+}
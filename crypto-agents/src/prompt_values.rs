@@ -0,0 +1,80 @@
+//! Formatting of market values for prompts. Missing data is shown as "N/A",
+//! never as 0, so models don't analyze a $0.00 price that was never fetched.
+
+pub const NOT_AVAILABLE: &str = "N/A";
+
+/// Add to any prompt that may contain N/A values
+pub const MISSING_DATA_INSTRUCTION: &str =
+    "Metrics shown as N/A are unavailable, not zero: do not analyze, estimate or quote values for them.";
+
+// Significant digits kept for prices under $1
+const SMALL_PRICE_DIGITS: i32 = 4;
+// Smaller prices switch to scientific notation
+const MAX_DECIMALS: i32 = 12;
+
+/// A USD price without the `$`. Prices under $1 keep their leading significant
+/// digits, so a micro-cap at $0.00001234 isn't rounded to 0.00.
+pub fn usd(price: f64) -> String {
+    if price.abs() >= 1.0 || price == 0.0 {
+        return format!("{:.2}", price);
+    }
+    let decimals = (-price.abs().log10()).floor() as i32 + SMALL_PRICE_DIGITS;
+    if decimals > MAX_DECIMALS {
+        return format!("{:.3e}", price);
+    }
+    format!("{:.*}", decimals.max(2) as usize, price)
+}
+
+/// `$` price, or N/A
+pub fn price(value: Option<f64>) -> String {
+    available(value).map_or_else(|| NOT_AVAILABLE.to_string(), |p| format!("${}", usd(p)))
+}
+
+/// Percentage with two decimals, or N/A
+pub fn percent(value: Option<f64>) -> String {
+    available(value).map_or_else(|| NOT_AVAILABLE.to_string(), |v| format!("{:.2}%", v))
+}
+
+/// Plain number with two decimals, or N/A
+pub fn number(value: Option<f64>) -> String {
+    available(value).map_or_else(|| NOT_AVAILABLE.to_string(), |v| format!("{:.2}", v))
+}
+
+/// USD amount in millions, or N/A
+pub fn millions(value: Option<f64>) -> String {
+    available(value).map_or_else(|| NOT_AVAILABLE.to_string(), |v| format!("${:.2}M", v / 1e6))
+}
+
+/// USD amount in billions, or N/A
+pub fn billions(value: Option<f64>) -> String {
+    available(value).map_or_else(|| NOT_AVAILABLE.to_string(), |v| format!("${:.2}B", v / 1e9))
+}
+
+// NaN and infinities are as unusable as a missing value
+fn available(value: Option<f64>) -> Option<f64> {
+    value.filter(|v| v.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_values_are_not_zero() {
+        assert_eq!(price(None), "N/A");
+        assert_eq!(price(Some(f64::NAN)), "N/A");
+        assert_eq!(percent(None), "N/A");
+        assert_eq!(price(Some(0.0)), "$0.00");
+        assert_eq!(percent(Some(0.0)), "0.00%");
+        assert_eq!(millions(None), "N/A");
+        assert_eq!(millions(Some(2_500_000.0)), "$2.50M");
+    }
+
+    #[test]
+    fn micro_cap_prices_keep_their_digits() {
+        assert_eq!(price(Some(0.00001234)), "$0.00001234");
+        assert_eq!(price(Some(0.5)), "$0.5000");
+        assert_eq!(price(Some(97_123.456)), "$97123.46");
+        assert_eq!(usd(1.5e-15), "1.500e-15");
+    }
+}
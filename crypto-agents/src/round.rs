@@ -0,0 +1,224 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::agents::extractor::ExtractedToken;
+use crate::agents::technical::ConfidenceMetrics;
+use crate::technical_diff::TechnicalDiff;
+
+/// Everything one analysis round produced. Agents that failed or never ran are
+/// marked in their `AgentOutput`, so a round always yields a usable result.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundResult {
+    pub started_at: DateTime<Utc>,
+    pub replay: bool,
+    /// Why no agent ran, when the round's market data couldn't be loaded
+    pub data_error: Option<String>,
+    pub snapshot_path: Option<PathBuf>,
+    pub snapshot_error: Option<String>,
+    /// Capture time of the previous snapshot the indicator changes are measured from
+    pub compared_to: Option<DateTime<Utc>>,
+    /// (symbol, changes) for each major coin in both this and the previous snapshot
+    pub technical_changes: Vec<(String, TechnicalDiff)>,
+    /// (agent, lines) the agents printed while running, when output is buffered
+    pub notes: Vec<(String, Vec<String>)>,
+    pub technical: AgentOutput,
+    pub fundamental: AgentOutput,
+    pub sentiment: AgentOutput,
+    pub topic: AgentOutput,
+    pub synopsis: AgentOutput,
+    pub tokens: Vec<ExtractedToken>,
+    pub token_error: Option<String>,
+}
+
+impl RoundResult {
+    pub(crate) fn new(started_at: DateTime<Utc>, replay: bool) -> Self {
+        let pending = || AgentOutput::skipped("round ended before this agent ran");
+        Self {
+            started_at,
+            replay,
+            data_error: None,
+            snapshot_path: None,
+            snapshot_error: None,
+            compared_to: None,
+            technical_changes: Vec::new(),
+            notes: Vec::new(),
+            technical: pending(),
+            fundamental: pending(),
+            sentiment: pending(),
+            topic: pending(),
+            synopsis: pending(),
+            tokens: Vec::new(),
+            token_error: None,
+        }
+    }
+
+    /// Whether every agent produced an analysis
+    pub fn is_complete(&self) -> bool {
+        self.data_error.is_none()
+            && self.token_error.is_none()
+            && [&self.technical, &self.fundamental, &self.sentiment, &self.topic, &self.synopsis]
+                .iter()
+                .all(|output| output.status != AgentStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentStatus {
+    Ok,
+    Failed,
+    /// Not run, e.g. no live data on replay or the round timed out first
+    Skipped,
+}
+
+/// Why a step of a round produced nothing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepError {
+    /// The round's deadline passed before or while the step ran
+    TimedOut,
+    Failed(String),
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepError::TimedOut => write!(f, "round timed out"),
+            StepError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentOutput {
+    pub status: AgentStatus,
+    /// The agent's raw response, empty unless it ran successfully
+    pub text: String,
+    /// BUY, SELL, WAIT or DONT BUY from an `Action:` line, when there is one
+    pub recommendation: Option<String>,
+    /// Overall analysis confidence in percent, when the agent gave one
+    pub confidence: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl AgentOutput {
+    pub fn ok(text: String) -> Self {
+        Self {
+            status: AgentStatus::Ok,
+            recommendation: parse_recommendation(&text),
+            confidence: ConfidenceMetrics::parse(&text).overall,
+            text,
+            error: None,
+        }
+    }
+
+    pub fn failed(error: impl fmt::Display) -> Self {
+        Self {
+            status: AgentStatus::Failed,
+            text: String::new(),
+            recommendation: None,
+            confidence: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self {
+            status: AgentStatus::Skipped,
+            text: String::new(),
+            recommendation: None,
+            confidence: None,
+            error: Some(reason.into()),
+        }
+    }
+
+    /// An agent cut off by the round's deadline counts as skipped, not failed
+    pub fn from_result(result: Result<String, StepError>) -> Self {
+        match result {
+            Ok(text) => Self::ok(text),
+            Err(StepError::TimedOut) => Self::skipped(StepError::TimedOut.to_string()),
+            Err(StepError::Failed(e)) => Self::failed(e),
+        }
+    }
+
+    /// The response, or a marker saying why there is none, for prompts that build on it
+    pub fn text_or_marker(&self) -> String {
+        match self.status {
+            AgentStatus::Ok => self.text.clone(),
+            _ => format!("[unavailable: {}]", self.error.as_deref().unwrap_or("no output")),
+        }
+    }
+}
+
+impl fmt::Display for AgentOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            AgentStatus::Ok => write!(f, "{}", self.text),
+            AgentStatus::Failed => write!(f, "❌ Failed: {}", self.error.as_deref().unwrap_or("unknown error")),
+            AgentStatus::Skipped => write!(f, "⏭️ Skipped: {}", self.error.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+// The value of the first `Action:` line, if it's one of the actions agents are asked for
+fn parse_recommendation(text: &str) -> Option<String> {
+    let value = text.lines().find_map(|line| {
+        let line = line.replace("**", "");
+        let (label, value) = line.trim().split_once(':')?;
+        label.trim().eq_ignore_ascii_case("action").then(|| value.trim().to_uppercase())
+    })?;
+    let value = value.replace(['\'', '’', '"'], "");
+    ["DONT BUY", "DO NOT BUY", "BUY", "SELL", "WAIT", "HOLD"]
+        .iter()
+        .find(|action| value.contains(*action))
+        .map(|action| if *action == "DO NOT BUY" { "DONT BUY" } else { action }.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommendation_comes_from_the_action_line() {
+        assert_eq!(parse_recommendation("Trend: up\n**Action:** Buy on dips").as_deref(), Some("BUY"));
+        assert_eq!(parse_recommendation("Action: Don't buy yet").as_deref(), Some("DONT BUY"));
+        assert_eq!(parse_recommendation("action: do not buy").as_deref(), Some("DONT BUY"));
+        assert_eq!(parse_recommendation("Action: WAIT\nAction: SELL").as_deref(), Some("WAIT"));
+        assert_eq!(parse_recommendation("Suggested action: SELL"), None);
+        assert_eq!(parse_recommendation("Action: reassess next week"), None);
+        assert_eq!(parse_recommendation("We would buy here."), None);
+    }
+
+    #[test]
+    fn timed_out_agents_are_skipped() {
+        let output = AgentOutput::from_result(Err(StepError::TimedOut));
+        assert_eq!(output.status, AgentStatus::Skipped);
+        assert_eq!(output.error.as_deref(), Some("round timed out"));
+
+        let output = AgentOutput::from_result(Err(StepError::Failed("rate limited".to_string())));
+        assert_eq!(output.status, AgentStatus::Failed);
+        assert_eq!(output.to_string(), "❌ Failed: rate limited");
+
+        let output = AgentOutput::from_result(Ok("Action: SELL".to_string()));
+        assert_eq!(output.status, AgentStatus::Ok);
+        assert_eq!(output.recommendation.as_deref(), Some("SELL"));
+        assert_eq!(output.text_or_marker(), "Action: SELL");
+        assert_eq!(AgentOutput::skipped("replay").text_or_marker(), "[unavailable: replay]");
+    }
+
+    #[test]
+    fn only_failures_make_a_round_incomplete() {
+        let mut result = RoundResult::new(Utc::now(), false);
+        result.sentiment = AgentOutput::from_result(Err(StepError::TimedOut));
+        assert!(result.is_complete());
+
+        result.topic = AgentOutput::failed("no news");
+        assert!(!result.is_complete());
+
+        let mut result = RoundResult::new(Utc::now(), false);
+        result.token_error = Some("bad json".to_string());
+        assert!(!result.is_complete());
+    }
+}
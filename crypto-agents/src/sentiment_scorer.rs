@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use common::paths;
+
+use crate::output::say;
+
+pub const DEFAULT_LEXICON_PATH: &str = "config/sentiment_lexicon.json";
+
+// Built-in word -> polarity weights, including crypto twitter slang
+const DEFAULT_LEXICON: &[(&str, f64)] = &[
+    ("bullish", 2.0), ("moon", 1.5), ("mooning", 2.0), ("pump", 1.0), ("pumping", 1.5),
+    ("gain", 1.0), ("gains", 1.0), ("profit", 1.0), ("buy", 0.5), ("buying", 0.5),
+    ("good", 1.0), ("great", 1.5), ("strong", 1.0), ("rally", 1.5), ("breakout", 1.5),
+    ("rise", 1.0), ("rising", 1.0), ("higher", 0.5), ("growth", 1.0), ("accumulate", 1.0),
+    ("hodl", 1.0), ("wagmi", 2.0), ("lfg", 2.0), ("gm", 0.5), ("alpha", 1.0),
+    ("gem", 1.5), ("undervalued", 1.0), ("ath", 1.5), ("send", 0.5), ("based", 1.0),
+    ("excellent", 1.5), ("positive", 1.0), ("success", 1.0), ("winning", 1.0), ("confident", 1.0),
+    ("support", 0.5), ("momentum", 0.5), ("breakthrough", 1.5), ("hold", 0.5), ("long", 0.5),
+    ("bearish", -2.0), ("dump", -1.5), ("dumping", -2.0), ("crash", -2.0), ("crashing", -2.0),
+    ("loss", -1.0), ("losses", -1.0), ("sell", -0.5), ("selling", -0.5), ("bad", -1.0),
+    ("weak", -1.0), ("fear", -1.5), ("panic", -2.0), ("dip", -0.5), ("drop", -1.0),
+    ("decline", -1.0), ("capitulation", -2.0), ("liquidated", -2.0), ("overvalued", -1.0),
+    ("worse", -1.0), ("negative", -1.0), ("fail", -1.5), ("risk", -0.5), ("poor", -1.0),
+    ("uncertain", -0.5), ("falling", -1.0), ("correction", -1.0), ("capitulate", -2.0), ("liquidate", -1.5),
+    ("short", -0.5), ("resistance", -0.5), ("lower", -0.5), ("bear", -1.0),
+    ("rug", -3.0), ("rugged", -3.0), ("rugpull", -3.0), ("scam", -3.0), ("ngmi", -2.0),
+    ("rekt", -2.5), ("fud", -1.0), ("exit", -0.5), ("hack", -2.5), ("hacked", -2.5),
+    ("exploit", -2.5), ("ponzi", -3.0), ("bagholder", -1.5), ("bagholders", -1.5),
+];
+
+// Words that flip the polarity of the next scored word within `NEGATION_WINDOW` words
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "isnt", "arent", "wasnt", "dont", "doesnt", "didnt", "wont", "cant", "aint", "nothing",
+];
+const NEGATION_WINDOW: usize = 3;
+
+// VADER-style normalization constant, how fast the score approaches +/-1
+const NORMALIZATION_ALPHA: f64 = 15.0;
+
+/// Lexicon-based sentiment for short social posts. Scores run from -1 (negative)
+/// through 0 (neutral, including posts with no known words) to 1 (positive).
+#[derive(Debug, Clone)]
+pub struct SentimentScorer {
+    lexicon: HashMap<String, f64>,
+}
+
+impl Default for SentimentScorer {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEXICON.iter().map(|(word, weight)| (word.to_string(), *weight)).collect())
+    }
+}
+
+impl SentimentScorer {
+    pub fn new(lexicon: HashMap<String, f64>) -> Self {
+        let lexicon = lexicon.into_iter().map(|(word, weight)| (normalize(&word), weight)).collect();
+        Self { lexicon }
+    }
+
+    /// The built-in lexicon with the weights from `SENTIMENT_LEXICON_PATH` (default
+    /// config/sentiment_lexicon.json, relative to the project root) applied on top.
+    /// Without that file the built-in lexicon is used as is.
+    pub fn load() -> Self {
+        let path = std::env::var("SENTIMENT_LEXICON_PATH").unwrap_or_else(|_| DEFAULT_LEXICON_PATH.to_string());
+        Self::load_from(paths::resolve(path))
+    }
+
+    pub fn load_from(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+        match Self::read_overrides(path) {
+            Ok(overrides) => {
+                let mut scorer = Self::default();
+                scorer.extend(overrides);
+                scorer
+            }
+            Err(e) => {
+                say(format!("⚠️ {:#}, using the built-in sentiment lexicon", e));
+                Self::default()
+            }
+        }
+    }
+
+    // A JSON object of word -> weight
+    fn read_overrides(path: &Path) -> Result<HashMap<String, f64>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sentiment lexicon {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid sentiment lexicon {}", path.display()))
+    }
+
+    /// Add or replace weights; a weight of 0 effectively removes a word
+    pub fn extend(&mut self, weights: HashMap<String, f64>) {
+        for (word, weight) in weights {
+            self.lexicon.insert(normalize(&word), weight);
+        }
+    }
+
+    /// Polarity weight of a single word, 0 if it isn't in the lexicon
+    pub fn weight(&self, word: &str) -> f64 {
+        self.lexicon.get(&normalize(word)).copied().unwrap_or(0.0)
+    }
+
+    pub fn score(&self, text: &str) -> f64 {
+        let mut total = 0.0;
+        let mut negated_for = 0;
+        for word in text.split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’').map(normalize) {
+            if word.is_empty() {
+                continue;
+            }
+            if NEGATIONS.contains(&word.as_str()) {
+                negated_for = NEGATION_WINDOW;
+                continue;
+            }
+            let weight = self.lexicon.get(&word).copied().unwrap_or(0.0);
+            if weight != 0.0 && negated_for > 0 {
+                total -= weight;
+                negated_for = 0;
+            } else {
+                total += weight;
+                negated_for = negated_for.saturating_sub(1);
+            }
+        }
+
+        if total == 0.0 {
+            0.0
+        } else {
+            total / (total * total + NORMALIZATION_ALPHA).sqrt()
+        }
+    }
+}
+
+// Lowercase with apostrophes dropped, so "isn't" and "isnt" match
+fn normalize(word: &str) -> String {
+    word.chars().filter(|c| *c != '\'' && *c != '’').flat_map(char::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crypto_slang_is_scored() {
+        let scorer = SentimentScorer::default();
+        assert!(scorer.score("wagmi, $SOL to the moon 🚀 LFG") > 0.5);
+        assert!(scorer.score("dev rugged us, got rekt. ngmi") < -0.5);
+        assert!(scorer.score("this is a scam, total rug") < scorer.score("small dip today"));
+    }
+
+    #[test]
+    fn negation_flips_polarity() {
+        let scorer = SentimentScorer::default();
+        assert!(scorer.score("bullish on eth") > 0.0);
+        assert!(scorer.score("not bullish on eth") < 0.0);
+        assert!(scorer.score("this isn't a rug") > 0.0);
+        // The window only reaches a few words ahead
+        assert!(scorer.score("not sure what to make of it, but very bullish") > 0.0);
+    }
+
+    #[test]
+    fn unknown_words_are_neutral() {
+        let scorer = SentimentScorer::default();
+        assert_eq!(scorer.weight("validator"), 0.0);
+        assert_eq!(scorer.score("validators upgraded the client tonight"), 0.0);
+        assert_eq!(scorer.score(""), 0.0);
+    }
+
+    #[test]
+    fn lexicon_file_overrides_and_missing_file_falls_back() {
+        let missing = SentimentScorer::load_from("does/not/exist.json");
+        assert_eq!(missing.weight("rekt"), SentimentScorer::default().weight("rekt"));
+
+        let path = std::env::temp_dir().join(format!("sentiment_lexicon_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"Airdrop": 1.5, "rekt": 0}"#).unwrap();
+        let scorer = SentimentScorer::load_from(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(scorer.weight("airdrop"), 1.5);
+        assert_eq!(scorer.score("rekt"), 0.0);
+        assert_eq!(scorer.weight("wagmi"), SentimentScorer::default().weight("wagmi"));
+    }
+}
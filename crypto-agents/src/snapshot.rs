@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::api::coingecko::MarketTechnicalData;
+use crate::models::MarketData;
+use common::exa::ExaSearchResult;
+use common::news::NewsEvent;
+use common::paths;
+
+const SNAPSHOT_DIR: &str = "snapshots";
+
+/// News article captured alongside a market snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotNews {
+    pub title: String,
+    pub url: String,
+    pub published_date: Option<DateTime<Utc>>,
+    pub summary: Option<String>,
+    pub relevance_score: Option<f64>,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+}
+
+impl From<&NewsEvent> for SnapshotNews {
+    fn from(event: &NewsEvent) -> Self {
+        Self {
+            title: event.title.clone(),
+            url: event.url.clone(),
+            published_date: event.published_at,
+            summary: event.summary.clone(),
+            relevance_score: event.relevance_score,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl From<&ExaSearchResult> for SnapshotNews {
+    fn from(result: &ExaSearchResult) -> Self {
+        Self {
+            title: result.title.clone(),
+            url: result.url.clone(),
+            published_date: result.published_date,
+            summary: result.summary.clone(),
+            relevance_score: result.relevance_score,
+            highlights: result.highlights.clone(),
+        }
+    }
+}
+
+/// Everything fetched from the network for one research round, so the round
+/// can be replayed later with the exact same inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketSnapshot {
+    pub captured_at: DateTime<Utc>,
+    pub market_data: MarketData,
+    pub technical_data: MarketTechnicalData,
+    #[serde(default)]
+    pub news: Vec<SnapshotNews>,
+}
+
+impl MarketSnapshot {
+    pub fn new(market_data: MarketData, technical_data: MarketTechnicalData, news: Vec<SnapshotNews>) -> Self {
+        Self {
+            captured_at: Utc::now(),
+            market_data,
+            technical_data,
+            news,
+        }
+    }
+
+    /// Save to data/snapshots/market_<timestamp>.json and return the path
+    pub async fn save(&self) -> Result<PathBuf> {
+        let path = paths::data_subdir(SNAPSHOT_DIR)
+            .join(format!("market_{}.json", self.captured_at.format("%Y%m%d_%H%M%S")));
+        let json = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(&path, json).await?;
+        Ok(path)
+    }
+
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid snapshot file: {}", path.display()))
+    }
+
+    /// The most recently saved snapshot, None when there is none yet
+    pub async fn latest() -> Result<Option<Self>> {
+        let mut entries = tokio::fs::read_dir(paths::data_subdir(SNAPSHOT_DIR)).await?;
+        let mut latest: Option<PathBuf> = None;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            // Timestamped names sort in capture order
+            if name.starts_with("market_") && name.ends_with(".json") && !latest.as_ref().is_some_and(|l| *l >= path) {
+                latest = Some(path);
+            }
+        }
+        match latest {
+            Some(path) => Ok(Some(Self::load(path).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn replay_banner(&self) -> String {
+        format!(
+            "⏪ REPLAY MODE - using historical data captured at {} (no live network data)",
+            self.captured_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+/// Snapshot path passed as `--replay <snapshot>` on the command line, if any
+pub fn replay_path_from_args() -> Option<PathBuf> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == "--replay")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+}
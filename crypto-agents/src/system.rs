@@ -1,7 +1,9 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
+use std::future::Future;
 use std::time::Duration;
 use anyhow::Result;
-use tokio::time;
+use tokio::time::{self, Instant};
 use tokio::select;
 use tokio::io::{self, AsyncBufReadExt, BufReader};
 use dotenv::dotenv;
@@ -10,8 +12,13 @@ use std::io::Write;
 
 use crate::{
     agents::{TechnicalAgent, FundamentalAgent, TokenExtractor, SynopsisAgent, Agent, SentimentAgent, TopicAgent},
-    api::coingecko::CoinGeckoClient,
+    api::{CandleSource, CoinGeckoClient, MarketDataCache},
     agents::ModelProvider,
+    config::{CryptoAgentsConfig, RoundConfig},
+    output::OutputCoordinator,
+    round::{AgentOutput, AgentStatus, RoundResult, StepError},
+    snapshot::MarketSnapshot,
+    technical_diff,
 };
 
 const MAX_HISTORY_ROUNDS: usize = 50;
@@ -35,7 +42,7 @@ const DEFAULT_MODELS: &[(&str, &str)] = &[
 ];
 
 pub struct MultiAgentSystem {
-    api: CoinGeckoClient,
+    api: Arc<CoinGeckoClient>,
     technical_agent: TechnicalAgent,
     fundamental_agent: FundamentalAgent,
     token_extractor: TokenExtractor,
@@ -43,6 +50,8 @@ pub struct MultiAgentSystem {
     sentiment_agent: SentimentAgent,
     topic_agent: TopicAgent,
     round_history: VecDeque<String>,
+    replay: Option<MarketSnapshot>,
+    round_config: RoundConfig,
 }
 
 impl MultiAgentSystem {
@@ -51,7 +60,8 @@ impl MultiAgentSystem {
         dotenv().ok();
         
         // Initialize API client
-        let api = CoinGeckoClient::new()?;
+        // Technical data feeds the technical agent, which needs volume indicators
+        let api = Arc::new(CoinGeckoClient::new()?.with_candle_source(CandleSource::WithVolume));
         
         // Get model configurations with new providers
         let technical_provider = env::var("TECHNICAL_PROVIDER")
@@ -189,8 +199,17 @@ impl MultiAgentSystem {
             sentiment_agent,
             topic_agent,
             round_history: VecDeque::with_capacity(MAX_HISTORY_ROUNDS),
+            replay: None,
+            round_config: CryptoAgentsConfig::load().round,
         })
     }
+
+    /// Run rounds against a saved snapshot instead of live data
+    pub fn with_replay(mut self, snapshot: MarketSnapshot) -> Self {
+        self.topic_agent.disable_live_news();
+        self.replay = Some(snapshot);
+        self
+    }
     
     pub async fn handle_command(&mut self, command: &str) -> Result<()> {
         let parts: Vec<&str> = command.split_whitespace().collect();
@@ -242,6 +261,11 @@ impl MultiAgentSystem {
     }
 
     pub async fn run(&mut self) -> Result<()> {
+        if let Some(snapshot) = &self.replay {
+            println!("\n{}", snapshot.replay_banner());
+            return self.run_conversation_cycle().await;
+        }
+
         println!("\n🤖 Crypto Multi-Agent System");
         println!("Available commands:");
         println!("  monitor <symbol> <name> [alert_threshold] - Add a token to monitor");
@@ -276,80 +300,262 @@ impl MultiAgentSystem {
         }
     }
     
+    /// Run one round and print it. Agent failures are reported in the output
+    /// instead of ending the run.
     pub async fn run_conversation_cycle(&mut self) -> Result<()> {
         println!("\n🔄 Starting New Trading Round!");
-        
-        // Get fresh market data
-        println!("📊 Gathering Market Intelligence...");
-        let market_data = self.api.get_market_data().await?;
-        
-        // Get technical indicators data
-        println!("📈 Fetching Technical Data...");
-        let technical_data = self.api.get_technical_analysis().await?;
-
-        // Let technical agent analyze the data
-        println!("\n🔍 Technical Analysis Phase...");
-        let technical_response = self.technical_agent
-            .analyze_technical_data(&market_data, &technical_data)
-            .await?;
-        println!("{}", technical_response);
-        
-        // Get fundamental analysis with technical context
-        println!("\n🌍 Fundamental Analysis Phase...");
-        let fundamental_response = self.fundamental_agent
-            .think(&market_data, Some(technical_response.clone()))
-            .await?;
-        println!("{}", fundamental_response);
-        
-        // Add sentiment analysis phase
-        println!("\n🎭 Sentiment Analysis Phase...");
-        let sentiment_response = self.sentiment_agent
-            .think(&market_data, Some(technical_response.clone()))
-            .await?;
-        println!("{}", sentiment_response);
-        
-        // Get topic analysis
-        let topic_response = self.topic_agent
-            .analyze_market_topics(
-                &market_data,
-                &technical_response
-            )
-            .await?;
-        println!("{}", topic_response);
-        
-        // Extract tokens with context from all analyses
-        println!("\n🔍 Extracting Token Mentions...");
-        let round = self.round_history.len() as i32;
-        let tokens = self.token_extractor
-            .extract_tokens(
+        let result = self.run_once().await;
+        print_round(&result);
+        Ok(())
+    }
+
+    /// Run one analysis round and return what each agent produced, without printing.
+    /// Never fails: an agent that errors is marked failed and the agents after it
+    /// still run. Dropping the future mid-round leaves the system usable, the round
+    /// is only added to the history once it finishes.
+    pub async fn run_once(&mut self) -> RoundResult {
+        self.run_round(None).await
+    }
+
+    /// Like [`run_once`](Self::run_once), but agents not finished within `limit`, or
+    /// not yet started by then, are marked as skipped and the partial result is returned
+    pub async fn run_once_with_timeout(&mut self, limit: Duration) -> RoundResult {
+        self.run_round(Some(Instant::now() + limit)).await
+    }
+
+    async fn run_round(&mut self, deadline: Option<Instant>) -> RoundResult {
+        // Every agent in this round reads the same data, fetched once
+        let round = chrono::Utc::now();
+        let mut result = RoundResult::new(round, self.replay.is_some());
+
+        let cache = if let Some(snapshot) = &self.replay {
+            MarketDataCache::with_data(
+                self.api.clone(),
                 round,
-                &technical_response,
-                &fundamental_response
+                snapshot.market_data.clone(),
+                snapshot.technical_data.clone(),
             )
-            .await?;
-        println!("Found {} token mentions:", tokens.len());
-        for token in &tokens {
-            println!("  • {} ({})", token.token, token.context);
+        } else {
+            MarketDataCache::new(self.api.clone(), round)
+        };
+        let data = within(deadline, async {
+            let market_data = cache.market_data().await?.clone();
+            let technical_data = cache.technical_data().await?.clone();
+            Ok::<_, anyhow::Error>((market_data, technical_data))
+        })
+        .await;
+        let (market_data, technical_data) = match data {
+            Ok(data) => data,
+            Err(e) => {
+                let reason = format!("market data unavailable: {}", e);
+                result.data_error = Some(e.to_string());
+                for output in [
+                    &mut result.technical,
+                    &mut result.fundamental,
+                    &mut result.sentiment,
+                    &mut result.topic,
+                    &mut result.synopsis,
+                ] {
+                    *output = AgentOutput::skipped(reason.clone());
+                }
+                return result;
+            }
+        };
+
+        // News is part of the round's inputs too, so it goes into the snapshot
+        let news = match &self.replay {
+            Some(snapshot) => Ok(Some(snapshot.news.clone())),
+            None => within(deadline, self.topic_agent.fetch_market_news()).await.map_err(|e| e.to_string()),
+        };
+
+        if self.replay.is_none() {
+            // A previous snapshot that can't be read only costs the comparison
+            if let Ok(Some(previous)) = MarketSnapshot::latest().await {
+                result.compared_to = Some(previous.captured_at);
+                result.technical_changes = technical_diff::market_changes(&previous.technical_data, &technical_data);
+            }
+            let snapshot = MarketSnapshot::new(
+                market_data.clone(),
+                technical_data.clone(),
+                news.clone().ok().flatten().unwrap_or_default(),
+            );
+            match snapshot.save().await {
+                Ok(path) => result.snapshot_path = Some(path),
+                Err(e) => result.snapshot_error = Some(e.to_string()),
+            }
         }
-        
-        // Generate synopsis with all data
-        println!("\n📝 Generating Round Synopsis...");
-        let synopsis = self.synopsis_agent
-            .generate_synopsis(
-                &technical_response,
-                &fundamental_response,
-                Some(&sentiment_response),
-                Some(&topic_response)
-            )
-            .await?;
-        println!("{}", synopsis);
-        
+
+        let cache = Arc::new(cache);
+        self.technical_agent.set_round_cache(cache.clone());
+        self.fundamental_agent.set_round_cache(cache.clone());
+        self.synopsis_agent.set_round_cache(cache);
+
+        // Agents taking turns can't interleave, so they print as they go
+        let output = if self.round_config.concurrent_agents {
+            OutputCoordinator::new(self.round_config.output)
+        } else {
+            OutputCoordinator::live()
+        };
+        result.technical = AgentOutput::from_result(
+            output
+                .run("Technical", within(deadline, self.technical_agent.analyze_technical_data(&market_data, &technical_data)))
+                .await,
+        );
+        let technical_context = (result.technical.status == AgentStatus::Ok).then(|| result.technical.text.clone());
+        let technical_text = result.technical.text_or_marker();
+
+        // These three only build on the technical analysis, so they may run together
+        let fundamental = output.run(
+            "Fundamental",
+            within(deadline, self.fundamental_agent.think(&market_data, technical_context.clone())),
+        );
+        // Needs live Twitter data, so skipped on replay
+        let sentiment = self.replay.is_none().then(|| {
+            output.run("Sentiment", within(deadline, self.sentiment_agent.think(&market_data, technical_context.clone())))
+        });
+        let topic = output.run(
+            "Topic",
+            within(deadline, self.topic_agent.analyze_market_news(&market_data, &technical_text, &news)),
+        );
+        let sentiment = async {
+            match sentiment {
+                Some(sentiment) => Some(sentiment.await),
+                None => None,
+            }
+        };
+        let (fundamental, sentiment, topic) = if self.round_config.concurrent_agents {
+            tokio::join!(fundamental, sentiment, topic)
+        } else {
+            (fundamental.await, sentiment.await, topic.await)
+        };
+        result.fundamental = AgentOutput::from_result(fundamental);
+        result.sentiment = match sentiment {
+            Some(sentiment) => AgentOutput::from_result(sentiment),
+            None => AgentOutput::skipped("live social data is not part of the snapshot"),
+        };
+        result.topic = AgentOutput::from_result(topic);
+
+        let fundamental_text = result.fundamental.text_or_marker();
+        let rounds = self.round_history.len() as i32;
+        let tokens = within(deadline, self.token_extractor.extract_tokens(rounds, &technical_text, &fundamental_text));
+        match output.run("Token extraction", tokens).await {
+            Ok(tokens) => result.tokens = tokens,
+            Err(e) => result.token_error = Some(e.to_string()),
+        }
+
+        if [&result.technical, &result.fundamental, &result.sentiment, &result.topic]
+            .iter()
+            .all(|output| output.status != AgentStatus::Ok)
+        {
+            result.synopsis = AgentOutput::skipped("no agent produced an analysis");
+            result.notes = output.into_notes();
+            return result;
+        }
+        let sentiment_text = result.sentiment.text_or_marker();
+        let topic_text = result.topic.text_or_marker();
+        let synopsis = self.synopsis_agent.generate_synopsis(
+            &technical_text,
+            &fundamental_text,
+            Some(&sentiment_text),
+            Some(&topic_text),
+        );
+        result.synopsis = AgentOutput::from_result(output.run("Synopsis", within(deadline, synopsis)).await);
+        result.notes = output.into_notes();
+
         // Update history
-        self.round_history.push_back(synopsis);
-        if self.round_history.len() > MAX_HISTORY_ROUNDS {
-            self.round_history.pop_front();
+        if result.synopsis.status == AgentStatus::Ok {
+            self.round_history.push_back(result.synopsis.text.clone());
+            if self.round_history.len() > MAX_HISTORY_ROUNDS {
+                self.round_history.pop_front();
+            }
+        }
+
+        result
+    }
+}
+
+// Run one step of a round, giving up at the deadline if there is one. A step
+// whose turn comes after the deadline isn't started at all.
+async fn within<T>(deadline: Option<Instant>, step: impl Future<Output = Result<T>>) -> std::result::Result<T, StepError> {
+    let outcome = match deadline {
+        Some(deadline) if deadline <= Instant::now() => return Err(StepError::TimedOut),
+        Some(deadline) => time::timeout_at(deadline, step)
+            .await
+            .map_err(|_| StepError::TimedOut)?,
+        None => step.await,
+    };
+    outcome.map_err(|e| StepError::Failed(e.to_string()))
+}
+
+fn print_round(result: &RoundResult) {
+    if let Some(snapshot) = &result.snapshot_path {
+        println!("💾 Saved market snapshot to {}", snapshot.display());
+    }
+    if let Some(e) = &result.snapshot_error {
+        println!("⚠️ Failed to save market snapshot: {}", e);
+    }
+    if let Some(e) = &result.data_error {
+        println!("❌ Failed to load market data: {}", e);
+        return;
+    }
+    if let Some(compared_to) = result.compared_to {
+        println!("\n🔀 Indicator changes since {}:", compared_to.format("%Y-%m-%d %H:%M UTC"));
+        for (symbol, diff) in &result.technical_changes {
+            if diff.is_empty() {
+                println!("{}: no change", symbol);
+            } else {
+                println!("{}:\n{}", symbol, diff.summary());
+            }
+        }
+    }
+    for (agent, lines) in &result.notes {
+        println!("\n── {} ──", agent);
+        for line in lines {
+            println!("{}", line);
         }
-        
-        Ok(())
     }
-} 
+
+    println!("\n🔍 Technical Analysis Phase...\n{}", result.technical);
+    println!("\n🌍 Fundamental Analysis Phase...\n{}", result.fundamental);
+    println!("\n🎭 Sentiment Analysis Phase...\n{}", result.sentiment);
+    println!("\n{}", result.topic);
+
+    println!("\n🔍 Extracting Token Mentions...");
+    match &result.token_error {
+        Some(e) => println!("❌ Failed: {}", e),
+        None => {
+            println!("Found {} token mentions:", result.tokens.len());
+            for token in &result.tokens {
+                println!("  • {} ({})", token.token, token.context);
+            }
+        }
+    }
+
+    println!("\n📝 Generating Round Synopsis...\n{}", result.synopsis);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn steps_after_the_deadline_are_not_started() {
+        let mut started = false;
+        let step = within(Some(Instant::now()), async {
+            started = true;
+            Ok(())
+        });
+        assert_eq!(step.await, Err(StepError::TimedOut));
+        assert!(!started);
+    }
+
+    #[tokio::test]
+    async fn steps_are_cut_off_at_the_deadline() {
+        let deadline = Instant::now() + Duration::from_millis(20);
+        let step = within(Some(deadline), std::future::pending::<Result<()>>());
+        assert_eq!(step.await, Err(StepError::TimedOut));
+
+        let step = within(None, async { Err::<(), _>(anyhow::anyhow!("bad gateway")) });
+        assert_eq!(step.await, Err(StepError::Failed("bad gateway".to_string())));
+    }
+}
@@ -0,0 +1,243 @@
+//! What changed in a coin's indicators between two rounds: the numeric move of
+//! each one and the signals that flipped, such as RSI turning overbought or a
+//! MACD cross. Missing readings are reported as unavailable, never as a move from 0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::api::coingecko::{MarketTechnicalData, TechnicalData};
+use crate::prompt_values;
+
+const RSI_OVERBOUGHT: f64 = 70.0;
+const RSI_OVERSOLD: f64 = 30.0;
+
+/// One indicator's previous and current reading
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndicatorDelta {
+    pub name: &'static str,
+    pub previous: Option<f64>,
+    pub current: Option<f64>,
+    // Shown as a USD price rather than a plain number
+    #[serde(skip)]
+    price: bool,
+}
+
+impl IndicatorDelta {
+    fn new(name: &'static str, previous: Option<f64>, current: Option<f64>) -> Self {
+        Self {
+            name,
+            previous: previous.filter(|v| v.is_finite()),
+            current: current.filter(|v| v.is_finite()),
+            price: false,
+        }
+    }
+
+    fn price(name: &'static str, previous: Option<f64>, current: Option<f64>) -> Self {
+        Self { price: true, ..Self::new(name, previous, current) }
+    }
+
+    /// Current minus previous, when both were available
+    pub fn change(&self) -> Option<f64> {
+        Some(self.current? - self.previous?)
+    }
+
+    fn changed(&self) -> bool {
+        match self.change() {
+            Some(change) => change != 0.0,
+            None => self.previous.is_some() || self.current.is_some(),
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        if self.price {
+            format!("${}", prompt_values::usd(value))
+        } else {
+            format!("{:.2}", value)
+        }
+    }
+}
+
+impl fmt::Display for IndicatorDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.previous, self.current) {
+            // Prices move by a share of their level, other indicators by points
+            (Some(previous), Some(current)) if self.price && previous != 0.0 => write!(
+                f,
+                "{} {}→{} ({:+.2}%)",
+                self.name,
+                self.format(previous),
+                self.format(current),
+                (current - previous) / previous.abs() * 100.0
+            ),
+            (Some(previous), Some(current)) => write!(
+                f,
+                "{} {}→{} ({:+.2})",
+                self.name,
+                self.format(previous),
+                self.format(current),
+                current - previous
+            ),
+            (None, Some(current)) => write!(f, "{} previously unavailable, now {}", self.name, self.format(current)),
+            (Some(previous), None) => write!(f, "{} was {}, now unavailable", self.name, self.format(previous)),
+            (None, None) => write!(f, "{} unavailable", self.name),
+        }
+    }
+}
+
+/// Indicator moves and signal changes between two readings of one coin
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TechnicalDiff {
+    pub deltas: Vec<IndicatorDelta>,
+    /// Signals that flipped, e.g. "RSI 45→72 now overbought", "MACD crossed bullish"
+    pub signals: Vec<String>,
+}
+
+impl TechnicalDiff {
+    /// Whether nothing moved
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty() && !self.deltas.iter().any(IndicatorDelta::changed)
+    }
+
+    /// Signal changes first, then every indicator that moved, one per line
+    pub fn summary(&self) -> String {
+        let mut lines: Vec<String> = self.signals.iter().map(|signal| format!("🔔 {}", signal)).collect();
+        lines.extend(self.deltas.iter().filter(|delta| delta.changed()).map(|delta| format!("• {}", delta)));
+        lines.join("\n")
+    }
+}
+
+impl TechnicalData {
+    /// What changed since `prev`, an earlier reading of the same coin
+    pub fn diff(&self, prev: &TechnicalData) -> TechnicalDiff {
+        let macd = |data: &TechnicalData| data.macd.map(|(line, _, _)| line);
+        let histogram = |data: &TechnicalData| data.macd.map(|(_, _, histogram)| histogram);
+        let upper = |data: &TechnicalData| data.bollinger_bands.map(|(upper, _, _)| upper);
+        let lower = |data: &TechnicalData| data.bollinger_bands.map(|(_, _, lower)| lower);
+
+        let deltas = vec![
+            IndicatorDelta::price("Price", prev.current_price, self.current_price),
+            IndicatorDelta::new("24h change %", prev.price_change_24h, self.price_change_24h),
+            IndicatorDelta::new("RSI", prev.rsi_14, self.rsi_14),
+            IndicatorDelta::new("MACD", macd(prev), macd(self)),
+            IndicatorDelta::new("MACD histogram", histogram(prev), histogram(self)),
+            IndicatorDelta::price("MA50", prev.ma_50, self.ma_50),
+            IndicatorDelta::price("MA200", prev.ma_200, self.ma_200),
+            IndicatorDelta::price("Bollinger upper", upper(prev), upper(self)),
+            IndicatorDelta::price("Bollinger lower", lower(prev), lower(self)),
+            IndicatorDelta::new("24h volume", prev.volume_24h, self.volume_24h),
+        ];
+
+        let mut signals = Vec::new();
+        if let (Some(before), Some(now)) = (prev.rsi_14, self.rsi_14) {
+            if rsi_zone(before) != rsi_zone(now) {
+                signals.push(format!("RSI {:.0}→{:.0} now {}", before, now, rsi_zone(now)));
+            }
+        }
+        let macd_above_signal = |data: &TechnicalData| data.macd.map(|(line, signal, _)| line > signal);
+        signals.extend(cross("MACD crossed bullish", "MACD crossed bearish", macd_above_signal(prev), macd_above_signal(self)));
+        let above = |price: Option<f64>, ma: Option<f64>| Some(price? > ma?);
+        for (name, ma_before, ma_now) in [("MA50", prev.ma_50, self.ma_50), ("MA200", prev.ma_200, self.ma_200)] {
+            signals.extend(cross(
+                &format!("Price moved above {}", name),
+                &format!("Price moved below {}", name),
+                above(prev.current_price, ma_before),
+                above(self.current_price, ma_now),
+            ));
+        }
+        let golden = |data: &TechnicalData| Some(data.ma_50? > data.ma_200?);
+        signals.extend(cross("Golden cross: MA50 moved above MA200", "Death cross: MA50 moved below MA200", golden(prev), golden(self)));
+        let band = |data: &TechnicalData| {
+            let price = data.current_price?;
+            let (upper, _, lower) = data.bollinger_bands?;
+            Some(if price > upper { 1 } else if price < lower { -1 } else { 0 })
+        };
+        match (band(prev), band(self)) {
+            (Some(before), Some(1)) if before != 1 => signals.push("Price broke above the upper Bollinger band".to_string()),
+            (Some(before), Some(-1)) if before != -1 => signals.push("Price broke below the lower Bollinger band".to_string()),
+            _ => {}
+        }
+
+        TechnicalDiff { deltas, signals }
+    }
+}
+
+fn rsi_zone(rsi: f64) -> &'static str {
+    if rsi >= RSI_OVERBOUGHT {
+        "overbought"
+    } else if rsi <= RSI_OVERSOLD {
+        "oversold"
+    } else {
+        "neutral"
+    }
+}
+
+// A crossing, when a comparison known in both readings flipped
+fn cross(up: &str, down: &str, before: Option<bool>, now: Option<bool>) -> Option<String> {
+    match (before?, now?) {
+        (false, true) => Some(up.to_string()),
+        (true, false) => Some(down.to_string()),
+        _ => None,
+    }
+}
+
+/// Diffs of the major coins present in both readings, by symbol, in current order
+pub fn market_changes(prev: &MarketTechnicalData, current: &MarketTechnicalData) -> Vec<(String, TechnicalDiff)> {
+    current
+        .major_coins
+        .iter()
+        .filter_map(|(symbol, data)| {
+            let (_, before) = prev.major_coins.iter().find(|(prev_symbol, _)| prev_symbol == symbol)?;
+            Some((symbol.clone(), data.diff(before)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(price: f64, rsi: Option<f64>, macd: Option<(f64, f64, f64)>) -> TechnicalData {
+        TechnicalData {
+            candles: Vec::new(),
+            has_volume: false,
+            rsi_14: rsi,
+            ma_50: Some(100.0),
+            ma_200: Some(90.0),
+            macd,
+            bollinger_bands: None,
+            volume_24h: None,
+            current_price: Some(price),
+            price_change_24h: None,
+        }
+    }
+
+    #[test]
+    fn reports_moves_and_flipped_signals() {
+        let before = reading(98.0, Some(45.0), Some((-0.5, 0.2, -0.7)));
+        let now = reading(120.0, Some(72.0), Some((1.1, 0.4, 0.7)));
+        let diff = now.diff(&before);
+
+        assert_eq!(
+            diff.signals,
+            ["RSI 45→72 now overbought", "MACD crossed bullish", "Price moved above MA50"]
+        );
+        let rsi = diff.deltas.iter().find(|delta| delta.name == "RSI").unwrap();
+        assert_eq!(rsi.change(), Some(27.0));
+        assert!(diff.summary().contains("• RSI 45.00→72.00"));
+        assert!(!diff.summary().contains("MA50 $"), "unchanged indicators are left out");
+    }
+
+    #[test]
+    fn missing_previous_values_are_not_deltas_from_zero() {
+        let before = reading(100.0, None, None);
+        let now = reading(100.0, Some(72.0), Some((1.0, 0.5, 0.5)));
+        let diff = now.diff(&before);
+
+        let rsi = diff.deltas.iter().find(|delta| delta.name == "RSI").unwrap();
+        assert_eq!(rsi.change(), None);
+        assert_eq!(rsi.to_string(), "RSI previously unavailable, now 72.00");
+        assert!(diff.signals.is_empty());
+        assert!(now.diff(&now).is_empty());
+    }
+}
@@ -2,9 +2,11 @@ use rig::embeddings::{Embedding, EmbeddingModel};
 use rig::vector_store::{VectorStoreError, VectorStoreIndex};
 use rig::OneOrMany;
 use serde::Deserialize;
+use std::fmt;
 use std::marker::PhantomData;
+use std::str::FromStr;
 use tokio_rusqlite::Connection;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use zerocopy::IntoBytes;
 
 #[derive(Debug)]
@@ -84,14 +86,93 @@ pub trait SqliteVectorStoreTable: Send + Sync + Clone {
     fn column_values(&self) -> Vec<(&'static str, Box<dyn ColumnValue>)>;
 }
 
+/// How query results are ranked. Lower distances rank first for every metric;
+/// for `Dot` the distance is the negated dot product.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DistanceMetric {
+    #[default]
+    L2,
+    /// Embeddings are L2-normalized when stored
+    Cosine,
+    /// Ranked with an exact scan, since `vec0` has no inner-product index
+    Dot,
+}
+
+impl DistanceMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DistanceMetric::L2 => "l2",
+            DistanceMetric::Cosine => "cosine",
+            DistanceMetric::Dot => "dot",
+        }
+    }
+
+    // Metric declared on the vec0 table; dot products are computed outside of it
+    fn vec0_metric(&self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "cosine",
+            DistanceMetric::L2 | DistanceMetric::Dot => "l2",
+        }
+    }
+
+    /// Column definition for a `vec0` table using this metric
+    pub fn vec0_column(&self, dims: usize) -> String {
+        format!("embedding float[{}] distance_metric={}", dims, self.vec0_metric())
+    }
+}
+
+impl fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DistanceMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "l2" | "euclidean" => Ok(DistanceMetric::L2),
+            "cosine" => Ok(DistanceMetric::Cosine),
+            "dot" => Ok(DistanceMetric::Dot),
+            other => Err(format!("Unknown distance metric '{}' (expected cosine, l2 or dot)", other)),
+        }
+    }
+}
+
+// Records the metric each store was opened with
+const METADATA_TABLE: &str = "vector_store_metadata";
+
+// How the top-n query runs: a vec0 KNN match, or a scan when the table was
+// declared with another metric (or for dot products)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueryPlan {
+    Knn,
+    Scan,
+}
+
 #[derive(Clone)]
 pub struct SqliteVectorStore<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> {
     conn: Connection,
+    metric: DistanceMetric,
+    plan: QueryPlan,
     _phantom: PhantomData<(E, T)>,
 }
 
 impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVectorStore<E, T> {
     pub async fn new(conn: Connection, embedding_model: &E) -> Result<Self, VectorStoreError> {
+        Self::new_with_metric(conn, embedding_model, DistanceMetric::default()).await
+    }
+
+    /// Open the store ranking by `metric`. The metric is recorded in the
+    /// `vector_store_metadata` table; reopening a store that already holds
+    /// vectors with a different one logs a warning, since stored vectors may not
+    /// be normalized the way the new metric expects.
+    pub async fn new_with_metric(
+        conn: Connection,
+        embedding_model: &E,
+        metric: DistanceMetric,
+    ) -> Result<Self, VectorStoreError> {
         let dims = embedding_model.ndims();
         let table_name = T::name();
         let schema = T::schema();
@@ -127,35 +208,130 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVec
             }
         }
 
-        conn.call(move |conn| {
-            conn.execute_batch("BEGIN")?;
+        let (recorded, stored_vectors, declared) = conn
+            .call(move |conn| {
+                conn.execute_batch("BEGIN")?;
 
-            // Create document table
-            conn.execute_batch(&create_table)?;
+                // Create document table
+                conn.execute_batch(&create_table)?;
 
-            // Create indexes
-            for index_stmt in create_indexes {
-                conn.execute_batch(&index_stmt)?;
-            }
+                // Create indexes
+                for index_stmt in create_indexes {
+                    conn.execute_batch(&index_stmt)?;
+                }
 
-            // Create embeddings table
-            conn.execute_batch(&format!(
-                "CREATE VIRTUAL TABLE IF NOT EXISTS {}_embeddings USING vec0(embedding float[{}])",
-                table_name, dims
-            ))?;
+                // Create embeddings table
+                conn.execute_batch(&format!(
+                    "CREATE VIRTUAL TABLE IF NOT EXISTS {}_embeddings USING vec0({})",
+                    table_name,
+                    metric.vec0_column(dims)
+                ))?;
 
-            conn.execute_batch("COMMIT")?;
-            Ok(())
-        })
-        .await
-        .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+                conn.execute_batch(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (table_name TEXT PRIMARY KEY, metric TEXT NOT NULL)",
+                    METADATA_TABLE
+                ))?;
+                let recorded: Option<String> = conn
+                    .query_row(
+                        &format!("SELECT metric FROM {} WHERE table_name = ?1", METADATA_TABLE),
+                        [table_name],
+                        |row| row.get(0),
+                    )
+                    .ok();
+                let stored_vectors: i64 = conn.query_row(
+                    &format!("SELECT COUNT(*) FROM {}_embeddings", table_name),
+                    [],
+                    |row| row.get(0),
+                )?;
+                // vec0 tables keep the metric they were created with
+                let table_sql: String = conn.query_row(
+                    "SELECT sql FROM sqlite_master WHERE name = ?1",
+                    [format!("{}_embeddings", table_name)],
+                    |row| row.get(0),
+                )?;
+                conn.execute(
+                    &format!(
+                        "INSERT OR REPLACE INTO {} (table_name, metric) VALUES (?1, ?2)",
+                        METADATA_TABLE
+                    ),
+                    [table_name, metric.as_str()],
+                )?;
+
+                conn.execute_batch("COMMIT")?;
+                Ok((recorded, stored_vectors, declared_vec0_metric(&table_sql)))
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        // Stores from before metrics were recorded ranked by L2
+        let previous = recorded.unwrap_or_else(|| DistanceMetric::L2.as_str().to_string());
+        if stored_vectors > 0 && previous != metric.as_str() {
+            warn!(
+                table = table_name,
+                previous = %previous,
+                metric = %metric,
+                "Vector store metric changed: stored vectors may not be normalized for the new metric, reload them to be sure"
+            );
+        }
+
+        let plan = if metric != DistanceMetric::Dot && declared == metric.vec0_metric() {
+            QueryPlan::Knn
+        } else {
+            QueryPlan::Scan
+        };
 
         Ok(Self {
             conn,
+            metric,
+            plan,
             _phantom: PhantomData,
         })
     }
 
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    // Embedding as stored and queried: unit length for cosine
+    fn prepare_embedding(&self, embedding: &Embedding) -> Vec<f32> {
+        let vec = serialize_embedding(embedding);
+        match self.metric {
+            DistanceMetric::Cosine => normalize(vec),
+            DistanceMetric::L2 | DistanceMetric::Dot => vec,
+        }
+    }
+
+    // SQL ranking the `k` nearest rows to ?1, selecting `select` plus the distance
+    fn top_n_sql(&self, select: &str) -> String {
+        let table_name = T::name();
+        match (self.plan, self.metric) {
+            (QueryPlan::Knn, _) => format!(
+                "SELECT {0}, e.distance
+                 FROM {1}_embeddings e
+                 JOIN {1} d ON e.rowid = d.rowid
+                 WHERE e.embedding MATCH ?1 AND k = ?2
+                 ORDER BY e.distance",
+                select, table_name
+            ),
+            (QueryPlan::Scan, DistanceMetric::Dot) => format!(
+                "SELECT {0}, e.embedding
+                 FROM {1}_embeddings e
+                 JOIN {1} d ON e.rowid = d.rowid",
+                select, table_name
+            ),
+            (QueryPlan::Scan, metric) => format!(
+                "SELECT {0}, vec_distance_{2}(e.embedding, ?1) AS distance
+                 FROM {1}_embeddings e
+                 JOIN {1} d ON e.rowid = d.rowid
+                 ORDER BY distance
+                 LIMIT ?2",
+                select,
+                table_name,
+                metric.vec0_metric()
+            ),
+        }
+    }
+
     pub fn index(self, model: E) -> SqliteVectorIndex<E, T> {
         SqliteVectorIndex::new(model, self)
     }
@@ -199,7 +375,7 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVec
 
             let mut stmt = txn.prepare(&embeddings_sql)?;
             for (i, embedding) in embeddings.iter().enumerate() {
-                let vec = serialize_embedding(embedding);
+                let vec = self.prepare_embedding(embedding);
                 debug!(
                     "Storing embedding {} of {} (size: {} bytes)",
                     i + 1,
@@ -339,42 +515,44 @@ impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorSto
     ) -> Result<Vec<(f64, String, D)>, VectorStoreError> {
         debug!("Finding top {} matches for query", n);
         let embedding = self.embedding_model.embed_text(query).await?;
-        let query_vec: Vec<f32> = serialize_embedding(&embedding);
-        let table_name = T::name();
+        let query_vec: Vec<f32> = self.store.prepare_embedding(&embedding);
+        let metric = self.store.metric;
 
         // Get all column names from SqliteVectorStoreTable
         let columns = T::schema();
         let column_names: Vec<&str> = columns.iter().map(|column| column.name).collect();
+        let select_cols = column_names
+            .iter()
+            .map(|name| format!("d.{}", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = self.store.top_n_sql(&select_cols);
 
         let rows = self
             .store
             .conn
             .call(move |conn| {
-                // Build SELECT statement with all columns
-                let select_cols = column_names.join(", ");
-                let mut stmt = conn.prepare(&format!(
-                    "SELECT d.{}, e.distance 
-                    FROM {}_embeddings e
-                    JOIN {} d ON e.rowid = d.rowid
-                    WHERE e.embedding MATCH ?1 AND k = ?2
-                    ORDER BY e.distance",
-                    select_cols, table_name, table_name
-                ))?;
-
-                let rows = stmt
-                    .query_map(rusqlite::params![query_vec.as_bytes().to_vec(), n], |row| {
-                        // Create a map of column names to values
-                        let mut map = serde_json::Map::new();
-                        for (i, col_name) in column_names.iter().enumerate() {
-                            let value: String = row.get(i)?;
-                            map.insert(col_name.to_string(), serde_json::Value::String(value));
-                        }
-                        let distance: f64 = row.get(column_names.len())?;
-                        let id: String = row.get(0)?; // Assuming id is always first column
-
-                        Ok((id, serde_json::Value::Object(map), distance))
-                    })?
-                    .collect::<Result<Vec<_>, _>>()?;
+                let mut stmt = conn.prepare(&sql)?;
+                let read_row = |row: &rusqlite::Row<'_>| -> rusqlite::Result<_> {
+                    // Create a map of column names to values
+                    let mut map = serde_json::Map::new();
+                    for (i, col_name) in column_names.iter().enumerate() {
+                        let value: String = row.get(i)?;
+                        map.insert(col_name.to_string(), serde_json::Value::String(value));
+                    }
+                    let distance = read_distance(row, column_names.len(), metric, &query_vec)?;
+                    let id: String = row.get(0)?; // Assuming id is always first column
+
+                    Ok((id, serde_json::Value::Object(map), distance))
+                };
+
+                let rows = if metric == DistanceMetric::Dot {
+                    let rows = stmt.query_map([], read_row)?.collect::<Result<Vec<_>, _>>()?;
+                    nearest(rows, n, |(_, _, distance)| *distance)
+                } else {
+                    stmt.query_map(rusqlite::params![query_vec.as_bytes().to_vec(), n], read_row)?
+                        .collect::<Result<Vec<_>, _>>()?
+                };
                 Ok(rows)
             })
             .await
@@ -405,24 +583,24 @@ impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorSto
     ) -> Result<Vec<(f64, String)>, VectorStoreError> {
         debug!("Finding top {} document IDs for query", n);
         let embedding = self.embedding_model.embed_text(query).await?;
-        let query_vec = serialize_embedding(&embedding);
-        let table_name = T::name();
+        let query_vec = self.store.prepare_embedding(&embedding);
+        let metric = self.store.metric;
+        let sql = self.store.top_n_sql("d.id");
 
         let results = self
             .store
             .conn
             .call(move |conn| {
-                let mut stmt = conn.prepare(&format!(
-                    "SELECT d.id, e.distance 
-                     FROM {0}_embeddings e
-                     JOIN {0} d ON e.rowid = d.rowid
-                     WHERE e.embedding MATCH ?1 AND k = ?2
-                     ORDER BY e.distance",
-                    table_name
-                ))?;
-
-                let results = stmt
-                    .query_map(
+                let mut stmt = conn.prepare(&sql)?;
+                let read_row = |row: &rusqlite::Row<'_>| -> rusqlite::Result<_> {
+                    Ok((read_distance(row, 1, metric, &query_vec)?, row.get::<_, String>(0)?))
+                };
+
+                let results = if metric == DistanceMetric::Dot {
+                    let rows = stmt.query_map([], read_row)?.collect::<Result<Vec<_>, _>>()?;
+                    nearest(rows, n, |(distance, _)| *distance)
+                } else {
+                    stmt.query_map(
                         rusqlite::params![
                             query_vec
                                 .iter()
@@ -430,9 +608,10 @@ impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorSto
                                 .collect::<Vec<u8>>(),
                             n
                         ],
-                        |row| Ok((row.get::<_, f64>(1)?, row.get::<_, String>(0)?)),
+                        read_row,
                     )?
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .collect::<Result<Vec<_>, _>>()?
+                };
                 Ok(results)
             })
             .await
@@ -447,6 +626,59 @@ fn serialize_embedding(embedding: &Embedding) -> Vec<f32> {
     embedding.vec.iter().map(|x| *x as f32).collect()
 }
 
+// Scale to unit length; a zero vector is left as is
+fn normalize(mut vec: Vec<f32>) -> Vec<f32> {
+    let norm = vec.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vec.iter_mut().for_each(|x| *x /= norm);
+    }
+    vec
+}
+
+// The distance column, or for dot products the negated dot product with the
+// stored embedding in that column
+fn read_distance(
+    row: &rusqlite::Row<'_>,
+    idx: usize,
+    metric: DistanceMetric,
+    query_vec: &[f32],
+) -> rusqlite::Result<f64> {
+    if metric != DistanceMetric::Dot {
+        return row.get(idx);
+    }
+    let blob: Vec<u8> = row.get(idx)?;
+    let dot: f32 = blob
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .zip(query_vec)
+        .map(|(a, b)| a * b)
+        .sum();
+    Ok(-(dot as f64))
+}
+
+// The `n` rows with the smallest distance, nearest first
+fn nearest<R>(mut rows: Vec<R>, n: usize, distance: impl Fn(&R) -> f64) -> Vec<R> {
+    rows.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+    rows.truncate(n);
+    rows
+}
+
+// `distance_metric=...` from a vec0 table definition, l2 when not declared
+fn declared_vec0_metric(sql: &str) -> String {
+    sql.split("distance_metric")
+        .nth(1)
+        .and_then(|rest| rest.trim_start().strip_prefix('='))
+        .map(|rest| {
+            rest.trim_start()
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_ascii_lowercase()
+        })
+        .filter(|metric| !metric.is_empty())
+        .unwrap_or_else(|| "l2".to_string())
+}
+
 impl ColumnValue for String {
     fn to_sql_string(&self) -> String {
         self.clone()
@@ -456,3 +688,28 @@ impl ColumnValue for String {
         "TEXT"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vec0_metric_is_read_from_the_table_definition() {
+        let sql = |column: &str| format!("CREATE VIRTUAL TABLE docs_embeddings USING vec0({})", column);
+
+        assert_eq!(declared_vec0_metric(&sql(&DistanceMetric::Cosine.vec0_column(8))), "cosine");
+        assert_eq!(declared_vec0_metric(&sql(&DistanceMetric::Dot.vec0_column(8))), "l2");
+        assert_eq!(declared_vec0_metric(&sql("embedding float[8] distance_metric = COSINE")), "cosine");
+        // Tables from before metrics were declared
+        assert_eq!(declared_vec0_metric(&sql("embedding float[8]")), "l2");
+        assert_eq!(declared_vec0_metric(&sql("embedding float[8] distance_metric=")), "l2");
+    }
+
+    #[test]
+    fn metric_names_parse() {
+        assert_eq!("Euclidean".parse::<DistanceMetric>(), Ok(DistanceMetric::L2));
+        assert_eq!("cosine".parse::<DistanceMetric>(), Ok(DistanceMetric::Cosine));
+        assert_eq!("DOT".parse::<DistanceMetric>(), Ok(DistanceMetric::Dot));
+        assert!("manhattan".parse::<DistanceMetric>().is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex, Once};
+
+use rig::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
+use rig::vector_store::VectorStoreIndex;
+use rig::OneOrMany;
+use rig_sqlite::{Column, ColumnValue, DistanceMetric, SqliteVectorStore, SqliteVectorStoreTable};
+use rusqlite::ffi::sqlite3_auto_extension;
+use sqlite_vec::sqlite3_vec_init;
+use tokio_rusqlite::Connection;
+
+// The query sits on the x axis. Each document wins under one metric:
+// "near" is closest (l2), "aligned" points the same way (cosine) and "big"
+// has the largest projection (dot).
+const QUERY: &str = "query";
+const VECTORS: [(&str, [f64; 2]); 4] = [
+    (QUERY, [1.0, 0.0]),
+    ("near", [0.8, 0.3]),
+    ("aligned", [3.0, 0.0]),
+    ("big", [10.0, 5.0]),
+];
+
+#[derive(Clone)]
+struct FixedEmbedding;
+
+impl EmbeddingModel for FixedEmbedding {
+    const MAX_DOCUMENTS: usize = 8;
+
+    fn ndims(&self) -> usize {
+        2
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        texts
+            .into_iter()
+            .map(|text| {
+                let (_, vec) = VECTORS
+                    .iter()
+                    .find(|(name, _)| *name == text)
+                    .ok_or_else(|| EmbeddingError::ProviderError(format!("no vector for {}", text)))?;
+                Ok(Embedding { document: text, vec: vec.to_vec() })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+struct Doc {
+    id: String,
+}
+
+impl SqliteVectorStoreTable for Doc {
+    fn name() -> &'static str {
+        "docs"
+    }
+
+    fn schema() -> Vec<Column> {
+        vec![Column::new("id", "TEXT PRIMARY KEY")]
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn column_values(&self) -> Vec<(&'static str, Box<dyn ColumnValue>)> {
+        vec![("id", Box::new(self.id.clone()))]
+    }
+}
+
+fn register_vec() {
+    static VEC_EXTENSION: Once = Once::new();
+    VEC_EXTENSION.call_once(|| unsafe {
+        sqlite3_auto_extension(Some(std::mem::transmute(sqlite3_vec_init as *const ())));
+    });
+}
+
+async fn open(conn: &Connection, metric: DistanceMetric) -> SqliteVectorStore<FixedEmbedding, Doc> {
+    SqliteVectorStore::new_with_metric(conn.clone(), &FixedEmbedding, metric)
+        .await
+        .expect("open vector store")
+}
+
+async fn add_documents(store: &SqliteVectorStore<FixedEmbedding, Doc>) {
+    let mut rows = Vec::new();
+    for id in ["near", "aligned", "big"] {
+        let embedding = FixedEmbedding.embed_text(id).await.unwrap();
+        rows.push((Doc { id: id.to_string() }, OneOrMany::one(embedding)));
+    }
+    store.add_rows(rows).await.expect("add rows");
+}
+
+async fn ranking(store: SqliteVectorStore<FixedEmbedding, Doc>) -> Vec<(f64, String)> {
+    store.index(FixedEmbedding).top_n_ids(QUERY, 3).await.expect("query")
+}
+
+fn ids(results: &[(f64, String)]) -> Vec<&str> {
+    results.iter().map(|(_, id)| id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn each_metric_ranks_by_its_own_distance() {
+    register_vec();
+
+    for (metric, expected) in [
+        (DistanceMetric::L2, ["near", "aligned", "big"]),
+        (DistanceMetric::Cosine, ["aligned", "near", "big"]),
+        (DistanceMetric::Dot, ["big", "aligned", "near"]),
+    ] {
+        let conn = Connection::open_in_memory().await.unwrap();
+        let store = open(&conn, metric).await;
+        add_documents(&store).await;
+
+        assert_eq!(ids(&ranking(store).await), expected, "{} ranking", metric);
+    }
+}
+
+#[tokio::test]
+async fn cosine_vectors_are_normalized() {
+    register_vec();
+    let conn = Connection::open_in_memory().await.unwrap();
+    let store = open(&conn, DistanceMetric::Cosine).await;
+    add_documents(&store).await;
+
+    // Same direction as the query whatever the length
+    let results = ranking(store).await;
+    assert!(results[0].0.abs() < 1e-6, "aligned distance {}", results[0].0);
+}
+
+#[tokio::test]
+async fn dot_distances_are_negated_dot_products() {
+    register_vec();
+    let conn = Connection::open_in_memory().await.unwrap();
+    let store = open(&conn, DistanceMetric::Dot).await;
+    add_documents(&store).await;
+
+    let distances: Vec<f64> = ranking(store).await.into_iter().map(|(distance, _)| distance).collect();
+    for (distance, expected) in distances.iter().zip([-10.0, -3.0, -0.8]) {
+        assert!((distance - expected).abs() < 1e-4, "{} != {}", distance, expected);
+    }
+}
+
+#[tokio::test]
+async fn a_table_declared_for_another_metric_is_scanned() {
+    register_vec();
+    let conn = Connection::open_in_memory().await.unwrap();
+    // The vec0 table is declared with l2, so cosine can't use its index
+    open(&conn, DistanceMetric::L2).await;
+    let store = open(&conn, DistanceMetric::Cosine).await;
+    add_documents(&store).await;
+
+    assert_eq!(ids(&ranking(store).await), ["aligned", "near", "big"]);
+}
+
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn reopening_with_another_metric_warns() {
+    register_vec();
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let conn = Connection::open_in_memory().await.unwrap();
+    add_documents(&open(&conn, DistanceMetric::L2).await).await;
+
+    open(&conn, DistanceMetric::L2).await;
+    assert!(!logs.text().contains("metric changed"), "{}", logs.text());
+
+    let store = open(&conn, DistanceMetric::Cosine).await;
+    assert_eq!(store.metric(), DistanceMetric::Cosine);
+    assert!(logs.text().contains("Vector store metric changed"), "{}", logs.text());
+}
@@ -1,15 +1,31 @@
 use anyhow::Result;
 use clap::{command, Parser};
+use common::exit::exit_with_error;
+use common::paths;
+use common::progress::{self, Progress};
+use common::ta::{self, Candle};
 use rig::{
     providers::deepseek::{self, Client as DeepseekClient},
     completion::{Prompt, Message},
 };
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer,
+};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use trader_solana::{
-    gmgn::client::GMGNClient,
+    gmgn::chain::Chain,
+    gmgn::client::{GMGNClient, GMGNError, DEFAULT_BASE_URL},
     gmgn::types::{TokenInfo, HolderInfo, TokenPriceInfo},
+    journal::{JournalEntry, TradeJournal, TradeStatus},
+    recommendation::Recommendation,
+    solana::{keypair_from_base58, shared_rpc_client, Commitment, RpcSettings, TxOutcome},
     tools::{swap::SwapTool, transfer::TransferTool},
 };
-use tracing::{info, error, debug};
+use tracing::{info, warn, error, debug};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -22,9 +38,279 @@ struct Args {
     #[clap(long, env = "SOLANA_RPC_URL")]
     solana_rpc_url: String,
 
-    /// Solana wallet private key
-    #[clap(long, env = "SOLANA_PRIVATE_KEY")]
-    solana_private_key: String,
+    /// Commitment swaps and transfers are sent and confirmed at: processed, confirmed or finalized
+    #[clap(long, env = "SOLANA_COMMITMENT", default_value = "confirmed")]
+    commitment: Commitment,
+
+    /// Timeout for each RPC request and for waiting on confirmation; a trade not
+    /// confirmed in time is journaled as pending with its signature
+    #[clap(long, env = "SOLANA_RPC_TIMEOUT_SECS", default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    rpc_timeout_secs: u64,
+
+    /// Solana wallet private key; without it swap and transfer are disabled
+    #[clap(long, env = "SOLANA_PRIVATE_KEY", hide_env_values = true)]
+    solana_private_key: Option<String>,
+
+    /// Chain for token data: sol, eth, base or bsc. Swap and transfer stay on Solana
+    #[clap(long, env = "GMGN_CHAIN", default_value = "sol")]
+    chain: Chain,
+
+    /// GMGN API base URL
+    #[clap(long, env = "GMGN_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    gmgn_base_url: String,
+
+    /// JSONL file every swap and transfer is recorded in [default: data/trader/trade_journal.jsonl]
+    #[clap(long, env = "TRADE_JOURNAL_PATH")]
+    journal_path: Option<PathBuf>,
+
+    /// Record swaps and transfers in the journal without sending them
+    #[clap(long, env = "DRY_RUN")]
+    dry_run: bool,
+
+    /// Run a swap without asking only when the latest analysis of the token
+    /// recommends that side with at least --min-confidence; otherwise ask first
+    #[clap(long, env = "AUTO_EXECUTE")]
+    auto: bool,
+
+    /// Send swaps and transfers without asking for confirmation, for scripting.
+    /// Without it, trades are refused when stdin isn't a terminal
+    #[clap(long, short = 'y', env = "ASSUME_YES")]
+    yes: bool,
+
+    /// Confidence (percent) an analysis needs for --auto to skip confirmation
+    #[clap(long, env = "MIN_CONFIDENCE", default_value_t = 75, value_parser = clap::value_parser!(u8).range(0..=100))]
+    min_confidence: u8,
+
+    /// Days of historical candles to feed into `analyze`, 0 to skip the fetch
+    #[clap(long, env = "OHLC_DAYS", default_value_t = 3)]
+    ohlc_days: u32,
+
+    /// Candle size for the historical fetch, e.g. 1h or 1d
+    #[clap(long, env = "OHLC_RESOLUTION", default_value = "1h")]
+    ohlc_resolution: String,
+
+    /// Don't show spinners during long operations. They're also off when
+    /// stdout isn't a terminal or NO_PROGRESS is set
+    #[clap(long)]
+    no_progress: bool,
+}
+
+// How long `status` waits on each check before calling it unreachable
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Cap on the price lookup that runs before each trade
+const PRICE_LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_JOURNAL_ENTRIES: usize = 10;
+
+const RSI_PERIOD: usize = 14;
+const SHORT_MA_PERIOD: usize = 20;
+const LONG_MA_PERIOD: usize = 50;
+
+// Technicals section of the analysis prompt. Indicators the candles can't support
+// are reported as missing, never estimated.
+fn technicals_section(candles: Option<&Result<Vec<Candle>, GMGNError>>) -> String {
+    let candles = match candles {
+        None => return "- Historical candles not requested".to_string(),
+        Some(Err(e)) => {
+            return format!("- Historical candles unavailable ({}); do not estimate technical indicators", e)
+        }
+        Some(Ok(candles)) => candles,
+    };
+
+    let Some(rsi) = ta::rsi(candles, RSI_PERIOD) else {
+        return format!(
+            "- Insufficient history for technicals ({} candles, need at least {}); \
+            do not estimate RSI or moving averages",
+            candles.len(),
+            RSI_PERIOD + 1
+        );
+    };
+
+    let ma = |period: usize| {
+        ta::sma(candles, period).map_or(format!("insufficient history ({} candles)", candles.len()), |v| {
+            format!("{:.8}", v)
+        })
+    };
+    format!(
+        "- Candles: {}\n- RSI({}): {:.2}\n- MA{}: {}\n- MA{}: {}",
+        candles.len(),
+        RSI_PERIOD,
+        rsi,
+        SHORT_MA_PERIOD,
+        ma(SHORT_MA_PERIOD),
+        LONG_MA_PERIOD,
+        ma(LONG_MA_PERIOD)
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnalysisDepth {
+    /// Key metrics only, no holders or LLM
+    Quick,
+    /// Full fetch and LLM analysis
+    Deep,
+}
+
+// `analyze [--quick|--deep] <token_address>`, deep by default
+fn parse_analyze_args(rest: &str) -> Result<(AnalysisDepth, String), String> {
+    let mut depth = AnalysisDepth::Deep;
+    let mut token = None;
+    for part in rest.split_whitespace() {
+        match part {
+            "--quick" => depth = AnalysisDepth::Quick,
+            "--deep" => depth = AnalysisDepth::Deep,
+            flag if flag.starts_with("--") => return Err(format!("Unknown option '{}'", flag)),
+            address if token.is_none() => token = Some(address.to_string()),
+            _ => return Err("Expected a single token address".to_string()),
+        }
+    }
+    token
+        .map(|token| (depth, token))
+        .ok_or_else(|| "Missing token address".to_string())
+}
+
+fn compact_usd(value: f64) -> String {
+    if value >= 1_000_000_000.0 {
+        format!("{:.2}B", value / 1_000_000_000.0)
+    } else if value >= 1_000_000.0 {
+        format!("{:.2}M", value / 1_000_000.0)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+// Either request may fail alone; its fields are then labelled unknown with the reason
+fn print_quick_analysis(info: Result<TokenInfo, GMGNError>, price_info: Result<TokenPriceInfo, GMGNError>) {
+    if let (Err(e), Err(_)) = (&info, &price_info) {
+        error!("Failed to get token data: {}", e);
+        return;
+    }
+    if let Err(e) = &info {
+        println!("Token info unavailable: {}", e);
+    }
+    if let Err(e) = &price_info {
+        println!("Price data unavailable: {}", e);
+    }
+
+    let info = info.ok();
+    let price_info = price_info.ok();
+    let info_field = |value: Option<String>| match (&info, value) {
+        (_, Some(value)) => value,
+        (Some(_), None) => "Unknown (not reported)".to_string(),
+        (None, None) => "Unknown (token info unavailable)".to_string(),
+    };
+    let price_field = |value: Option<String>| match (&price_info, value) {
+        (_, Some(value)) => value,
+        (Some(_), None) => "Unknown (not reported)".to_string(),
+        (None, None) => "Unknown (price data unavailable)".to_string(),
+    };
+    let change = |pick: fn(&TokenPriceInfo) -> Option<f64>| {
+        price_field(price_info.as_ref().and_then(pick).map(|p| format!("{:+.2}%", p)))
+    };
+
+    println!("================================");
+    println!("QUICK READ: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+    println!("Name: {}", info_field(info.as_ref().and_then(|i| i.name.clone())));
+    println!("Symbol: {}", info_field(info.as_ref().and_then(|i| i.symbol.clone())));
+    println!(
+        "Price: {}",
+        price_field(price_info.as_ref().and_then(|p| p.price).map(|p| format!("${:.8}", p)))
+    );
+    println!(
+        "Market Cap: {}",
+        price_field(price_info.as_ref().and_then(|p| p.market_cap).map(|m| format!("${}", compact_usd(m))))
+    );
+    println!(
+        "24h Volume: {}",
+        price_field(price_info.as_ref().and_then(|p| p.volume).map(|v| format!("${}", compact_usd(v))))
+    );
+    println!("Price Changes:");
+    println!("  • 24h: {}", change(|p| p.price_change_24h));
+    println!("  • 1h:  {}", change(|p| p.price_change_1h));
+    println!("  • 5m:  {}", change(|p| p.price_change_5m));
+    println!("Holders: {}", info_field(info.as_ref().and_then(|i| i.holder_count).map(|h| h.to_string())));
+    println!(
+        "Liquidity: {}",
+        info_field(
+            info.as_ref()
+                .and_then(|i| i.liquidity.as_deref())
+                .and_then(|l| l.parse::<f64>().ok())
+                .map(|l| format!("${}", compact_usd(l)))
+        )
+    );
+    println!("================================");
+}
+
+// Ask before sending a trade. --yes skips the question; with stdin piped nobody can
+// answer, and reading it would swallow the next command, so the trade is refused.
+fn confirm_trade(assume_yes: bool, question: &str) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        println!("Refusing: stdin is not a terminal, so this can't be confirmed. Pass --yes to trade unattended");
+        return Ok(false);
+    }
+    confirm(question)
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::Write;
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+// Best effort: a slow or failed lookup leaves the price out rather than delaying the trade
+async fn price_at_execution(gmgn: &GMGNClient, token: &str) -> Option<f64> {
+    match tokio::time::timeout(PRICE_LOOKUP_TIMEOUT, gmgn.get_token_price_info(token)).await {
+        Ok(Ok(info)) => info.price,
+        _ => None,
+    }
+}
+
+fn record_trade(journal: &TradeJournal, entry: &JournalEntry) {
+    if let Err(e) = journal.append(entry) {
+        warn!("Could not write to trade journal {}: {}", journal.path().display(), e);
+    }
+}
+
+fn print_journal(journal: &TradeJournal, limit: usize) {
+    let entries = match journal.recent(limit) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("Failed to read trade journal {}: {}", journal.path().display(), e);
+            return;
+        }
+    };
+    if entries.is_empty() {
+        println!("No trades journaled yet ({})", journal.path().display());
+        return;
+    }
+
+    println!("Last {} journal entries:", entries.len());
+    for entry in entries {
+        println!(
+            "{} | {:?} | {:?} {:?} {} | {} -> {} | price: {} | signature: {}",
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            entry.status,
+            entry.action,
+            entry.side,
+            entry.amount,
+            entry.from,
+            entry.to,
+            entry.price_usd.map_or("unknown".to_string(), |p| format!("${:.8}", p)),
+            entry.signature.as_deref().unwrap_or("-")
+        );
+        if let Some(recommendation) = &entry.recommendation {
+            println!("    AI: {}", recommendation);
+        }
+        if let Some(error) = &entry.error {
+            println!("    Error: {}", error);
+        }
+    }
 }
 
 fn print_debug_info(info: &TokenInfo, price_info: Option<&TokenPriceInfo>, holders: &[HolderInfo]) {
@@ -61,14 +347,77 @@ fn print_debug_info(info: &TokenInfo, price_info: Option<&TokenPriceInfo>, holde
     println!("================\n");
 }
 
+// Scheme, host and port only: RPC URLs often carry an API key
+fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => {
+            let host = parsed.host_str().unwrap_or("unknown host");
+            match parsed.port() {
+                Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+                None => format!("{}://{}", parsed.scheme(), host),
+            }
+        }
+        Err(_) => "<invalid URL>".to_string(),
+    }
+}
+
+async fn print_status(rpc_url: &str, wallet: &Result<Pubkey, String>, deepseek: &DeepseekClient) {
+    let endpoint = redact_url(rpc_url);
+    // Client errors can quote the request URL
+    let clean = |e: &dyn std::fmt::Display| e.to_string().replace(rpc_url, &endpoint);
+    let rpc = RpcClient::new_with_timeout_and_commitment(
+        rpc_url.to_string(),
+        STATUS_TIMEOUT,
+        CommitmentConfig::confirmed(),
+    );
+
+    println!("================================");
+    println!("RPC endpoint: {}", endpoint);
+    match tokio::time::timeout(STATUS_TIMEOUT, rpc.get_health()).await {
+        Ok(Ok(())) => println!("RPC health: ok"),
+        Ok(Err(e)) => println!("RPC health: unhealthy ({})", clean(&e)),
+        Err(_) => println!("RPC health: unreachable (no answer in {}s)", STATUS_TIMEOUT.as_secs()),
+    }
+
+    match wallet {
+        Ok(pubkey) => {
+            println!("Wallet: {}", pubkey);
+            match tokio::time::timeout(STATUS_TIMEOUT, rpc.get_balance(pubkey)).await {
+                Ok(Ok(lamports)) => println!("Balance: {:.9} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64),
+                Ok(Err(e)) => println!("Balance: unavailable ({})", clean(&e)),
+                Err(_) => println!("Balance: unavailable (no answer in {}s)", STATUS_TIMEOUT.as_secs()),
+            }
+        }
+        Err(e) => println!("Wallet: not loaded, {}", e),
+    }
+
+    let ping = deepseek.agent(deepseek::DEEPSEEK_CHAT).max_tokens(1).build();
+    let started = Instant::now();
+    match tokio::time::timeout(STATUS_TIMEOUT, ping.prompt(Message::from("ping"))).await {
+        Ok(Ok(_)) => println!("DeepSeek: ok ({} ms)", started.elapsed().as_millis()),
+        Ok(Err(e)) => println!("DeepSeek: no response ({})", e),
+        Err(_) => println!("DeepSeek: no response in {}s", STATUS_TIMEOUT.as_secs()),
+    }
+    println!("================================");
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    if let Err(e) = run().await {
+        exit_with_error(&*e);
+    }
+}
+
+async fn run() -> Result<()> {
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
     let args = Args::parse();
+    if args.no_progress {
+        progress::disable();
+    }
 
     // Initialize DeepSeek client
     let deepseek = DeepseekClient::new(&args.deepseek_api_key);
@@ -117,7 +466,7 @@ async fn main() -> Result<()> {
             
             CONFIDENCE LEVEL: {}%
             
-            ACTION: [SWAP/TRANSFER/HOLD/DONT BUY]
+            ACTION: [BUY/SELL/TRANSFER/HOLD/DONT BUY]
             DETAILS: [specific parameters for the action]
             
             RISK ASSESSMENT:
@@ -127,12 +476,47 @@ async fn main() -> Result<()> {
             ==============================="#)
         .build();
 
-    // Initialize tools
-    let swap_tool = SwapTool::new(&args.solana_rpc_url, &args.solana_private_key)?;
-    let transfer_tool = TransferTool::new(&args.solana_rpc_url, &args.solana_private_key)?;
-    let gmgn = GMGNClient::new();
+    // Initialize tools. A bad key disables trading instead of stopping the REPL,
+    // so `status` can still report it.
+    let private_key = args.solana_private_key.as_deref().filter(|k| !k.trim().is_empty());
+    let wallet = match private_key {
+        None => Err("SOLANA_PRIVATE_KEY is missing".to_string()),
+        Some(key) => keypair_from_base58(key).map(|k| k.pubkey()).map_err(|e| e.to_string()),
+    };
+    let (swap_tool, transfer_tool) = match (private_key, &wallet) {
+        (Some(key), Ok(_)) => {
+            let settings = RpcSettings {
+                commitment: args.commitment,
+                timeout: Duration::from_secs(args.rpc_timeout_secs),
+            };
+            let rpc = shared_rpc_client(&args.solana_rpc_url, settings);
+            (
+                Some(SwapTool::with_client(rpc.clone(), key, settings.timeout)?),
+                Some(TransferTool::with_client(rpc, key, settings.timeout)?),
+            )
+        }
+        _ => {
+            warn!("Wallet not loaded, swap and transfer are disabled. Run 'status' for details");
+            (None, None)
+        }
+    };
+    let gmgn = GMGNClient::with_base_url(args.chain, &args.gmgn_base_url);
+    let journal = TradeJournal::new(
+        args.journal_path.clone().unwrap_or_else(|| paths::data_subdir("trader").join("trade_journal.jsonl")),
+    );
+    // Latest AI verdict per token, attached to trades in that token
+    let mut recommendations: HashMap<String, Recommendation> = HashMap::new();
+    if args.dry_run {
+        println!("Dry run: swaps and transfers are journaled but not sent");
+    }
+    if args.auto {
+        println!(
+            "Auto mode: swaps run unasked only when the latest analysis agrees with at least {}% confidence",
+            args.min_confidence
+        );
+    }
 
-    info!("Solana Trading Assistant initialized");
+    info!("Solana Trading Assistant initialized, token data from {}", args.chain);
     println!("Welcome to Solana Trading Assistant!");
     println!("Type 'help' for commands or 'exit' to quit");
 
@@ -147,29 +531,66 @@ async fn main() -> Result<()> {
             
             "help" => {
                 println!("Available commands:");
-                println!("- analyze <token_address> : Get market analysis");
+                println!("- analyze [--quick|--deep] <token_address> : Get market analysis (quick skips holders and AI)");
                 println!("- swap <from> <to> <amount> : Swap tokens");
                 println!("- transfer <to_address> <amount> : Transfer tokens");
                 println!("- holders <token_address> : View top holders");
                 println!("- metrics <token_address> : View token metrics");
+                println!("- journal [count] : Review recent journaled trades");
+                println!("- status : Check RPC health, wallet balance and the AI agent");
                 println!("- exit : Quit the program");
             },
 
+            "journal" => print_journal(&journal, DEFAULT_JOURNAL_ENTRIES),
+
+            input if input.starts_with("journal ") => match input["journal ".len()..].trim().parse() {
+                Ok(limit) => print_journal(&journal, limit),
+                Err(_) => println!("Usage: journal [count]"),
+            },
+
+            "status" => print_status(&args.solana_rpc_url, &wallet, &deepseek).await,
+
             input if input.starts_with("analyze ") => {
-                let token = input.replace("analyze ", "");
-                debug!("Analyzing token: {}", token);
+                let (depth, token) = match parse_analyze_args(&input["analyze ".len()..]) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        println!("{}. Usage: analyze [--quick|--deep] <token_address>", e);
+                        continue;
+                    }
+                };
+                debug!("Analyzing token: {} ({:?})", token, depth);
+
+                // Fetching, then for a deep analysis asking the model
+                let mut progress = Progress::new(if depth == AnalysisDepth::Quick { 1 } else { 2 });
+                progress.step("Fetching token data");
+                if depth == AnalysisDepth::Quick {
+                    let (info, price_info) =
+                        tokio::join!(gmgn.get_token_info(&token), gmgn.get_token_price_info(&token));
+                    progress.finish();
+                    print_quick_analysis(info, price_info);
+                    continue;
+                }
 
                 // Get both token info and holder data
                 let token_info_future = gmgn.get_token_info(&token);
                 let price_info_future = gmgn.get_token_price_info(&token);
                 let holders_future = gmgn.get_top_holders(&token, Some(5), None, None, None);
+                // A failed candle fetch only drops the technicals, not the analysis
+                let candles_future = async {
+                    if args.ohlc_days == 0 {
+                        return Ok(None);
+                    }
+                    Ok::<_, GMGNError>(Some(
+                        gmgn.get_token_candles(&token, &args.ohlc_resolution, args.ohlc_days).await,
+                    ))
+                };
 
-                // Run both requests concurrently
-                match tokio::try_join!(token_info_future, price_info_future, holders_future) {
-                    Ok((info, price_info, holders)) => {
+                // Run the requests concurrently
+                match tokio::try_join!(token_info_future, price_info_future, holders_future, candles_future) {
+                    Ok((info, price_info, holders, candles)) => {
                         // Print debug info first
                         if std::env::var("DEBUG").is_ok() {
-                            print_debug_info(&info, Some(&price_info), &holders);
+                            progress.suspend(|| print_debug_info(&info, Some(&price_info), &holders));
                         }
 
                         let prompt = format!(
@@ -177,6 +598,7 @@ async fn main() -> Result<()> {
                             ANALYSIS TIME: {}
                             
                             Basic Info:
+                            - Chain: {}
                             - Symbol: {}
                             - Name: {}
                             - Decimals: {}
@@ -193,11 +615,15 @@ async fn main() -> Result<()> {
                             - Liquidity: {}
                             - Supply: [Circulating/Total supply not provided]
                             
+                            TECHNICALS ({} days of {} candles):
+                            {}
+                            
                             Top 5 Holders:
                             {}
                             
                             What action should be taken?"#,
                             chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                            args.chain,
                             info.symbol.as_deref().unwrap_or("Unknown"),
                             info.name.as_deref().unwrap_or("Unknown"),
                             info.decimals.map_or("Unknown".to_string(), |d| d.to_string()),
@@ -225,6 +651,9 @@ async fn main() -> Result<()> {
                             price_info.price_change_5m.map_or("Unknown".to_string(), |p| format!("{:+.2}", p)),
                             info.holder_count.map_or("Unknown".to_string(), |h| h.to_string()),
                             info.liquidity.as_deref().unwrap_or("Unknown"),
+                            args.ohlc_days,
+                            args.ohlc_resolution,
+                            technicals_section(candles.as_ref()),
                             holders.iter()
                                 .take(5)
                                 .map(|h| format!(
@@ -236,8 +665,14 @@ async fn main() -> Result<()> {
                                 .join("\n")
                         );
                         
-                        match trading_agent.prompt(Message::from(prompt)).await {
-                            Ok(analysis) => println!("{}", analysis),
+                        progress.step("Asking DeepSeek for a recommendation");
+                        let analysis = trading_agent.prompt(Message::from(prompt)).await;
+                        progress.finish();
+                        match analysis {
+                            Ok(analysis) => {
+                                println!("{}", analysis);
+                                recommendations.insert(token.clone(), Recommendation::parse(&analysis));
+                            }
                             Err(e) => {
                                 error!("AI analysis error: {}", e);
                                 println!("\nFallback Analysis:");
@@ -268,41 +703,132 @@ async fn main() -> Result<()> {
                             }
                         }
                     },
-                    Err(e) => error!("Failed to get token data: {}", e)
+                    Err(e) => {
+                        progress.finish();
+                        error!("Failed to get token data: {}", e)
+                    }
                 }
             },
 
             input if input.starts_with("swap ") => {
                 let parts: Vec<&str> = input.split_whitespace().collect();
-                if parts.len() == 4 {
-                    let from = parts[1];
-                    let to = parts[2];
-                    let amount = parts[3];
-                    
-                    debug!("Initiating swap: {} {} -> {}", amount, from, to);
-                    match swap_tool.execute_swap(from.to_string(), to.to_string(), amount.parse()?).await {
-                        Ok(_) => println!("Swap successful!"),
-                        Err(e) => error!("Swap failed: {}", e)
-                    }
-                } else {
+                if parts.len() != 4 {
                     println!("Usage: swap <from_token> <to_token> <amount>");
+                    continue;
+                }
+                let (from, to) = (parts[1], parts[2]);
+                let Ok(amount) = parts[3].parse::<f64>() else {
+                    println!("Invalid amount '{}'", parts[3]);
+                    continue;
+                };
+                let swap_tool = swap_tool.as_ref();
+                if swap_tool.is_none() && !args.dry_run {
+                    println!("Swap unavailable: wallet not loaded. Run 'status' for details.");
+                    continue;
+                }
+
+                let mut entry = JournalEntry::swap(from, to, amount, TradeStatus::Simulated);
+                entry.price_usd = price_at_execution(&gmgn, &entry.token).await;
+                let recommendation = recommendations.get(&entry.token);
+                entry.recommendation = recommendation.map(|r| r.summary.clone());
+
+                // Asks first, unless --yes or, in auto mode, a clear and confident analysis match
+                if !args.dry_run {
+                    let auto_approved = args.auto
+                        && match recommendation.map_or_else(
+                            || Err("no analysis of this token this session".to_string()),
+                            |r| r.approves(entry.side, args.min_confidence),
+                        ) {
+                            Ok(()) => {
+                                println!("Auto-executing: the latest analysis approves this swap");
+                                true
+                            }
+                            Err(reason) => {
+                                println!("Confirmation needed: {}", reason);
+                                false
+                            }
+                        };
+                    if !auto_approved && !confirm_trade(args.yes, &format!("Swap {} {} -> {}?", amount, from, to))? {
+                        println!("Swap cancelled");
+                        continue;
+                    }
+                }
+
+                match swap_tool.filter(|_| !args.dry_run) {
+                    None => println!("Dry run: would swap {} {} -> {}", amount, from, to),
+                    Some(swap_tool) => {
+                        debug!("Initiating swap: {} {} -> {}", amount, from, to);
+                        match swap_tool.execute_swap(from.to_string(), to.to_string(), amount).await {
+                            Ok(TxOutcome::Confirmed(signature)) => {
+                                println!("Swap successful! Signature: {}", signature);
+                                entry.status = TradeStatus::Executed;
+                                entry.signature = Some(signature);
+                            }
+                            Ok(TxOutcome::Pending(signature)) => {
+                                println!("Swap sent but not {} yet. Signature: {}", args.commitment, signature);
+                                entry.status = TradeStatus::Pending;
+                                entry.signature = Some(signature);
+                            }
+                            Err(e) => {
+                                error!("Swap failed: {}", e);
+                                entry.status = TradeStatus::Failed;
+                                entry.error = Some(e.to_string());
+                            }
+                        }
+                    }
                 }
+                record_trade(&journal, &entry);
             },
 
             input if input.starts_with("transfer ") => {
                 let parts: Vec<&str> = input.split_whitespace().collect();
-                if parts.len() == 3 {
-                    let to = parts[1];
-                    let amount = parts[2];
-                    
-                    debug!("Initiating transfer: {} to {}", amount, to);
-                    match transfer_tool.execute_transfer(to.to_string(), amount.parse()?).await {
-                        Ok(_) => println!("Transfer successful!"),
-                        Err(e) => error!("Transfer failed: {}", e)
-                    }
-                } else {
+                if parts.len() != 3 {
                     println!("Usage: transfer <to_address> <amount>");
+                    continue;
+                }
+                let to = parts[1];
+                let Ok(amount) = parts[2].parse::<f64>() else {
+                    println!("Invalid amount '{}'", parts[2]);
+                    continue;
+                };
+                let transfer_tool = transfer_tool.as_ref();
+                if transfer_tool.is_none() && !args.dry_run {
+                    println!("Transfer unavailable: wallet not loaded. Run 'status' for details.");
+                    continue;
+                }
+
+                if !args.dry_run && !confirm_trade(args.yes, &format!("Transfer {} SOL to {}?", amount, to))? {
+                    println!("Transfer cancelled");
+                    continue;
+                }
+
+                let mut entry = JournalEntry::transfer(to, amount, TradeStatus::Simulated);
+                entry.price_usd = price_at_execution(&gmgn, &entry.token).await;
+
+                match transfer_tool.filter(|_| !args.dry_run) {
+                    None => println!("Dry run: would transfer {} SOL to {}", amount, to),
+                    Some(transfer_tool) => {
+                        debug!("Initiating transfer: {} to {}", amount, to);
+                        match transfer_tool.execute_transfer(to.to_string(), amount).await {
+                            Ok(TxOutcome::Confirmed(signature)) => {
+                                println!("Transfer successful! Signature: {}", signature);
+                                entry.status = TradeStatus::Executed;
+                                entry.signature = Some(signature);
+                            }
+                            Ok(TxOutcome::Pending(signature)) => {
+                                println!("Transfer sent but not {} yet. Signature: {}", args.commitment, signature);
+                                entry.status = TradeStatus::Pending;
+                                entry.signature = Some(signature);
+                            }
+                            Err(e) => {
+                                error!("Transfer failed: {}", e);
+                                entry.status = TradeStatus::Failed;
+                                entry.error = Some(e.to_string());
+                            }
+                        }
+                    }
                 }
+                record_trade(&journal, &entry);
             },
 
             input if input.starts_with("holders ") => {
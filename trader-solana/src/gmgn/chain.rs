@@ -0,0 +1,139 @@
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+/// Chains GMGN serves market data for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Chain {
+    #[default]
+    Solana,
+    Ethereum,
+    Base,
+    Bsc,
+}
+
+impl Chain {
+    /// Path segment GMGN uses for the chain
+    pub fn slug(self) -> &'static str {
+        match self {
+            Chain::Solana => "sol",
+            Chain::Ethereum => "eth",
+            Chain::Base => "base",
+            Chain::Bsc => "bsc",
+        }
+    }
+
+    pub fn is_evm(self) -> bool {
+        !matches!(self, Chain::Solana)
+    }
+
+    /// EVM addresses are hex and compare case-insensitively, base58 ones exactly
+    pub fn same_address(self, a: &str, b: &str) -> bool {
+        if self.is_evm() {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    }
+
+    /// Check the address format for this chain, so a typo fails before any request
+    pub fn validate_address(self, address: &str) -> Result<(), String> {
+        let valid = if self.is_evm() {
+            address.len() == 42
+                && (address.starts_with("0x") || address.starts_with("0X"))
+                && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+        } else {
+            Pubkey::from_str(address).is_ok()
+        };
+
+        if valid {
+            return Ok(());
+        }
+        let expected = if self.is_evm() {
+            "0x followed by 40 hex characters"
+        } else {
+            "a base58 Solana address of 32 bytes"
+        };
+        Err(format!("Invalid {} address '{}': expected {}", self, address, expected))
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Chain::Solana => "Solana",
+            Chain::Ethereum => "Ethereum",
+            Chain::Base => "Base",
+            Chain::Bsc => "BSC",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Chain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sol" | "solana" => Ok(Chain::Solana),
+            "eth" | "ethereum" => Ok(Chain::Ethereum),
+            "base" => Ok(Chain::Base),
+            "bsc" | "bnb" => Ok(Chain::Bsc),
+            other => Err(format!("Unknown chain '{}', expected sol, eth, base or bsc", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+
+    #[test]
+    fn evm_addresses_ignore_checksum_case() {
+        for chain in [Chain::Ethereum, Chain::Base, Chain::Bsc] {
+            assert!(chain.same_address(WETH, &WETH.to_lowercase()));
+            assert!(chain.same_address(WETH, &WETH.to_uppercase().replacen("0X", "0x", 1)));
+            assert!(!chain.same_address(WETH, "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"));
+        }
+    }
+
+    #[test]
+    fn solana_addresses_are_case_sensitive() {
+        assert!(Chain::Solana.same_address(USDC_MINT, USDC_MINT));
+        assert!(!Chain::Solana.same_address(USDC_MINT, &USDC_MINT.to_lowercase()));
+    }
+
+    #[test]
+    fn addresses_are_checked_per_chain() {
+        assert!(Chain::Ethereum.validate_address(WETH).is_ok());
+        assert!(Chain::Base.validate_address(&WETH.to_lowercase()).is_ok());
+        assert!(Chain::Bsc.validate_address("0XC02AAA39B223FE8D0A0E5C4F27EAD9083C756CC2").is_ok());
+        // Too short, not hex, no prefix, and a Solana mint
+        assert!(Chain::Ethereum.validate_address(&WETH[..41]).is_err());
+        assert!(Chain::Ethereum.validate_address("0xG02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").is_err());
+        assert!(Chain::Ethereum.validate_address(&format!("00{}", &WETH[2..])).is_err());
+        assert!(Chain::Base.validate_address(USDC_MINT).is_err());
+
+        assert!(Chain::Solana.validate_address(USDC_MINT).is_ok());
+        // 0, O, I and l are not base58
+        assert!(Chain::Solana.validate_address(&USDC_MINT.replace('1', "l")).is_err());
+        assert!(Chain::Solana.validate_address(WETH).is_err());
+
+        let err = Chain::Bsc.validate_address("0x123").unwrap_err();
+        assert!(err.contains("BSC") && err.contains("40 hex characters"), "{}", err);
+    }
+
+    #[test]
+    fn chain_names_parse() {
+        assert_eq!(" SOL ".parse::<Chain>(), Ok(Chain::Solana));
+        assert_eq!("solana".parse::<Chain>(), Ok(Chain::Solana));
+        assert_eq!("Ethereum".parse::<Chain>(), Ok(Chain::Ethereum));
+        assert_eq!("eth".parse::<Chain>(), Ok(Chain::Ethereum));
+        assert_eq!("base".parse::<Chain>(), Ok(Chain::Base));
+        assert_eq!("bnb".parse::<Chain>(), Ok(Chain::Bsc));
+        assert!("polygon".parse::<Chain>().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use reqwest;
 use std::error::Error;
 use serde_json::Value;
+use crate::gmgn::chain::Chain;
 use crate::gmgn::types::{
     TopHoldersResponse, 
     HolderInfo, 
@@ -10,11 +11,25 @@ use crate::gmgn::types::{
     WalletHoldingsData, 
     SwapRankResponse, 
     TokenPriceInfo,
+    KlineResponse,
+    KlineCandle,
 };
+use common::ta::Candle;
+
+pub const DEFAULT_BASE_URL: &str = "https://gmgn.mobi";
+
+#[derive(Debug, thiserror::Error)]
+pub enum GMGNError {
+    #[error("{0}")]
+    InvalidAddress(String),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
 
-const BASE_URL: &str = "https://gmgn.mobi";
 pub struct GMGNClient {
     client: reqwest::Client,
+    chain: Chain,
+    base_url: String,
 }
 
 #[allow(dead_code)]
@@ -55,11 +70,15 @@ impl TokenPriceData {
 }
 
 impl GMGNClient {
-    pub fn new() -> Self {
+    pub fn new(chain: Chain) -> Self {
+        Self::with_base_url(chain, DEFAULT_BASE_URL)
+    }
+
+    /// Client for a GMGN-compatible API at `base_url`, e.g. a mirror or proxy
+    pub fn with_base_url(chain: Chain, base_url: &str) -> Self {
         let headers = {
             let mut headers = reqwest::header::HeaderMap::new();
             headers.insert("accept", "application/json, text/plain, */*".parse().unwrap());
-            headers.insert("host", "gmgn.mobi".parse().unwrap());
             headers.insert("connection", "Keep-Alive".parse().unwrap());
             headers.insert("user-agent", "okhttp/4.9.2".parse().unwrap());
             headers
@@ -70,7 +89,24 @@ impl GMGNClient {
             .build()
             .unwrap();
 
-        Self { client }
+        Self {
+            client,
+            chain,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn chain(&self) -> Chain {
+        self.chain
+    }
+
+    // `{base_url}/{prefix}/{chain}/{suffix}`
+    fn endpoint(&self, prefix: &str, suffix: &str) -> String {
+        format!("{}/{}/{}/{}", self.base_url, prefix, self.chain.slug(), suffix)
+    }
+
+    fn check_address(&self, address: &str) -> Result<(), GMGNError> {
+        self.chain.validate_address(address).map_err(GMGNError::InvalidAddress)
     }
 
     pub async fn get_top_holders(
@@ -80,22 +116,25 @@ impl GMGNClient {
         cost: Option<u32>,
         orderby: Option<&str>,
         direction: Option<&str>
-    ) -> Result<Vec<HolderInfo>, reqwest::Error> {
+    ) -> Result<Vec<HolderInfo>, GMGNError> {
+        self.check_address(contract_address)?;
         let limit = limit.unwrap_or(20);
         let cost = cost.unwrap_or(20);
         let orderby = orderby.unwrap_or("amount_percentage");
         let direction = direction.unwrap_or("desc");
 
         let url = format!(
-            "{BASE_URL}/defi/quotation/v1/tokens/top_holders/sol/{contract_address}?limit={limit}&cost={cost}&orderby={orderby}&direction={direction}"
+            "{}?limit={limit}&cost={cost}&orderby={orderby}&direction={direction}",
+            self.endpoint("defi/quotation/v1/tokens/top_holders", contract_address)
         );
         let response = self.client.get(url).send().await?;
         let top_holders_response: TopHoldersResponse = response.json().await?;
         Ok(top_holders_response.data)
     }
 
-    pub async fn get_token_info(&self, token: &str) -> Result<TokenInfo, reqwest::Error> {
-        let url = format!("{BASE_URL}/api/v1/token_info/sol/{token}");
+    pub async fn get_token_info(&self, token: &str) -> Result<TokenInfo, GMGNError> {
+        self.check_address(token)?;
+        let url = self.endpoint("api/v1/token_info", token);
         let response = self.client.get(&url).send().await?;
         
         if std::env::var("DEBUG").is_ok() {
@@ -122,7 +161,8 @@ impl GMGNClient {
         showsmall: Option<bool>,
         sellout: Option<bool>,
         hide_abnormal: Option<bool>,
-    ) -> Result<WalletHoldingsData, reqwest::Error> {
+    ) -> Result<WalletHoldingsData, GMGNError> {
+        self.check_address(wallet_address)?;
         let limit = limit.unwrap_or(50);
         let orderby = orderby.unwrap_or("last_active_timestamp");
         let direction = direction.unwrap_or("desc");
@@ -131,7 +171,8 @@ impl GMGNClient {
         let hide_abnormal = hide_abnormal.unwrap_or(false);
 
         let url = format!(
-            "{BASE_URL}/api/v1/wallet_holdings/sol/{wallet_address}?limit={limit}&orderby={orderby}&direction={direction}&showsmall={showsmall}&sellout={sellout}&hide_abnormal={hide_abnormal}"
+            "{}?limit={limit}&orderby={orderby}&direction={direction}&showsmall={showsmall}&sellout={sellout}&hide_abnormal={hide_abnormal}",
+            self.endpoint("api/v1/wallet_holdings", wallet_address)
         );
         let response = self.client.get(url).send().await?;
         let holdings_response: WalletHoldingsResponse = response.json().await?;
@@ -143,10 +184,8 @@ impl GMGNClient {
         time_period: &str, 
         launchpad: &str, 
         limit: Option<&str>,
-    ) -> Result<SwapRankResponse, reqwest::Error> {
-        let url = format!(
-            "{BASE_URL}/defi/quotation/v1/rank/sol/swaps/{time_period}"
-        );
+    ) -> Result<SwapRankResponse, GMGNError> {
+        let url = self.endpoint("defi/quotation/v1/rank", &format!("swaps/{time_period}"));
         let params = vec![
             ("device_id", "1212e9167c96f7ee"),
             ("client_id", "gmgn_android_209000"), 
@@ -169,8 +208,9 @@ impl GMGNClient {
         Ok(swap_rank_response)
     }
 
-    pub async fn get_token_price_info(&self, token: &str) -> Result<TokenPriceInfo, reqwest::Error> {
-        let url = format!("{BASE_URL}/api/v1/token_stats/sol/{token}");
+    pub async fn get_token_price_info(&self, token: &str) -> Result<TokenPriceInfo, GMGNError> {
+        self.check_address(token)?;
+        let url = self.endpoint("api/v1/token_stats", token);
         
         let params = vec![
             ("device_id", "ede3a881-1043-49aa-b645-b19080cb07da"),
@@ -202,7 +242,7 @@ impl GMGNClient {
         }
 
         // Try alternative endpoint if first one fails
-        let alt_url = format!("{BASE_URL}/defi/quotation/v1/rank/sol/swaps/1h");
+        let alt_url = self.endpoint("defi/quotation/v1/rank", "swaps/1h");
         let alt_params = vec![
             ("device_id", "ede3a881-1043-49aa-b645-b19080cb07da"),
             ("client_id", "gmgn_web_2025.0221.110436"),
@@ -231,7 +271,7 @@ impl GMGNClient {
 
         if let Ok(rank_response) = serde_json::from_str::<SwapRankResponse>(&alt_text) {
             for t in rank_response.data.rank {
-                if t.address == token {
+                if self.chain.same_address(&t.address, token) {
                     return Ok(TokenPriceInfo {
                         price: Some(t.price),
                         market_cap: Some(t.market_cap),
@@ -247,12 +287,38 @@ impl GMGNClient {
         Ok(TokenPriceInfo::default())
     }
 
+    /// Candles for the last `days` days at `resolution` (e.g. "1h", "1d"), oldest
+    /// first. Tokens younger than the window just return fewer candles.
+    pub async fn get_token_candles(
+        &self,
+        token: &str,
+        resolution: &str,
+        days: u32,
+    ) -> Result<Vec<Candle>, GMGNError> {
+        self.check_address(token)?;
+        let to = chrono::Utc::now().timestamp();
+        let from = to - i64::from(days) * 86_400;
+        let url = self.endpoint("api/v1/token_kline", token);
+        let params = [
+            ("resolution", resolution.to_string()),
+            ("from", from.to_string()),
+            ("to", to.to_string()),
+        ];
+
+        let response = self.client.get(&url).query(&params).send().await?;
+        let kline: KlineResponse = response.json().await?;
+
+        let mut candles: Vec<Candle> = kline.data.list.iter().filter_map(to_candle).collect();
+        candles.sort_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
+
     pub async fn analyze_token(&self, address: &str) -> Result<(), Box<dyn Error>> {
         // Get token info
         let _token_info = self.get_token_info(address).await?;
         
         // Get price info using the alternative endpoint
-        let alt_url = format!("{BASE_URL}/defi/quotation/v1/rank/sol/swaps/1h");
+        let alt_url = self.endpoint("defi/quotation/v1/rank", "swaps/1h");
         let alt_params = vec![
             ("device_id", "ede3a881-1043-49aa-b645-b19080cb07da"),
             ("client_id", "gmgn_web_2025.0221.110436"),
@@ -306,3 +372,23 @@ impl GMGNClient {
         Ok(())
     }
 }
+
+// Skips candles with a missing or unparsable price
+fn to_candle(raw: &KlineCandle) -> Option<Candle> {
+    fn number(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    Some(Candle {
+        timestamp: number(&raw.time)? as i64,
+        open: number(&raw.open)?,
+        high: number(&raw.high)?,
+        low: number(&raw.low)?,
+        close: number(&raw.close)?,
+        volume: number(&raw.volume).unwrap_or(0.0),
+    })
+}
@@ -366,3 +366,27 @@ pub struct TokenPriceDetails {
     pub swaps_24h: i64,
     pub hot_level: i32,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct KlineResponse {
+    #[serde(default)]
+    pub data: KlineData,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct KlineData {
+    #[serde(default)]
+    pub list: Vec<KlineCandle>,
+}
+
+/// One candle from the kline endpoint, which sends prices as strings or numbers
+#[derive(Debug, Deserialize)]
+pub struct KlineCandle {
+    pub time: serde_json::Value,
+    pub open: serde_json::Value,
+    pub high: serde_json::Value,
+    pub low: serde_json::Value,
+    pub close: serde_json::Value,
+    #[serde(default)]
+    pub volume: serde_json::Value,
+}
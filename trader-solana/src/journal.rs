@@ -0,0 +1,193 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeAction {
+    Swap,
+    Transfer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+    Send,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeStatus {
+    /// Sent and confirmed, `signature` is set
+    Executed,
+    /// Sent but not confirmed before the RPC timeout, `signature` is set
+    Pending,
+    /// Dry run, nothing was sent
+    Simulated,
+    /// Attempted and failed, `error` is set
+    Failed,
+}
+
+/// One line of the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: DateTime<Utc>,
+    pub action: TradeAction,
+    pub side: TradeSide,
+    pub status: TradeStatus,
+    /// The token the trade is about: the non-SOL side of a swap, SOL for transfers
+    pub token: String,
+    /// Input mint for swaps, the wallet's own SOL for transfers
+    pub from: String,
+    /// Output mint for swaps, the recipient for transfers
+    pub to: String,
+    pub amount: f64,
+    /// USD price of `token` just before the trade, if GMGN had one
+    pub price_usd: Option<f64>,
+    /// The last AI analysis of `token`, if there was one this session
+    pub recommendation: Option<String>,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn swap(from: &str, to: &str, amount: f64, status: TradeStatus) -> Self {
+        let (side, token) = if to == SOL_MINT {
+            (TradeSide::Sell, from)
+        } else {
+            (TradeSide::Buy, to)
+        };
+        Self::new(TradeAction::Swap, side, status, token, from, to, amount)
+    }
+
+    pub fn transfer(to: &str, amount: f64, status: TradeStatus) -> Self {
+        Self::new(TradeAction::Transfer, TradeSide::Send, status, SOL_MINT, SOL_MINT, to, amount)
+    }
+
+    fn new(
+        action: TradeAction,
+        side: TradeSide,
+        status: TradeStatus,
+        token: &str,
+        from: &str,
+        to: &str,
+        amount: f64,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            side,
+            status,
+            token: token.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            price_usd: None,
+            recommendation: None,
+            signature: None,
+            error: None,
+        }
+    }
+}
+
+/// Append-only JSONL record of every swap and transfer the REPL runs
+pub struct TradeJournal {
+    path: PathBuf,
+}
+
+impl TradeJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// The last `limit` entries, oldest first. Lines that don't parse are skipped.
+    pub fn recent(&self, limit: usize) -> Result<Vec<JournalEntry>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for (line_no, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping journal line {}: {}", line_no + 1, e),
+            }
+        }
+        let skip = entries.len().saturating_sub(limit);
+        Ok(entries.split_off(skip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal(name: &str) -> TradeJournal {
+        let dir = std::env::temp_dir().join(format!("trade_journal_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        TradeJournal::new(dir.join("nested").join("journal.jsonl"))
+    }
+
+    #[test]
+    fn swaps_are_sided_by_the_non_sol_token() {
+        let buy = JournalEntry::swap(SOL_MINT, "BONK", 1.0, TradeStatus::Executed);
+        assert_eq!((buy.side, buy.token.as_str()), (TradeSide::Buy, "BONK"));
+        let sell = JournalEntry::swap("BONK", SOL_MINT, 1.0, TradeStatus::Executed);
+        assert_eq!((sell.side, sell.token.as_str()), (TradeSide::Sell, "BONK"));
+        let send = JournalEntry::transfer("wallet", 0.5, TradeStatus::Simulated);
+        assert_eq!((send.side, send.token.as_str(), send.to.as_str()), (TradeSide::Send, SOL_MINT, "wallet"));
+    }
+
+    #[test]
+    fn recent_returns_the_last_entries_oldest_first() {
+        let journal = journal("recent");
+        assert!(journal.recent(10).unwrap().is_empty());
+
+        for amount in [1.0, 2.0, 3.0] {
+            journal.append(&JournalEntry::swap(SOL_MINT, "BONK", amount, TradeStatus::Executed)).unwrap();
+        }
+        let amounts: Vec<f64> = journal.recent(2).unwrap().iter().map(|e| e.amount).collect();
+        assert_eq!(amounts, [2.0, 3.0]);
+        assert_eq!(journal.recent(10).unwrap().len(), 3);
+        let _ = fs::remove_dir_all(journal.path().parent().unwrap().parent().unwrap());
+    }
+
+    #[test]
+    fn unreadable_lines_are_skipped() {
+        let journal = journal("corrupt");
+        journal.append(&JournalEntry::transfer("wallet", 1.0, TradeStatus::Failed)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(journal.path()).unwrap();
+        writeln!(file, "{{not json").unwrap();
+        journal.append(&JournalEntry::transfer("wallet", 2.0, TradeStatus::Pending)).unwrap();
+
+        let statuses: Vec<TradeStatus> = journal.recent(10).unwrap().iter().map(|e| e.status).collect();
+        assert_eq!(statuses, [TradeStatus::Failed, TradeStatus::Pending]);
+        let _ = fs::remove_dir_all(journal.path().parent().unwrap().parent().unwrap());
+    }
+}
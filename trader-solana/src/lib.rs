@@ -3,4 +3,6 @@ pub mod tools;
 pub mod types;
 pub mod gmgn;
 pub mod transfer;
-pub mod swap;
\ No newline at end of file
+pub mod swap;
+pub mod journal;
+pub mod recommendation;
\ No newline at end of file
@@ -0,0 +1,159 @@
+use crate::journal::TradeSide;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecommendedAction {
+    Buy,
+    Sell,
+    Hold,
+    DontBuy,
+    Transfer,
+}
+
+/// The verdict of an AI analysis, parsed from its `ACTION:` and
+/// `CONFIDENCE LEVEL:` lines
+#[derive(Debug, Clone)]
+pub struct Recommendation {
+    /// `None` when the line is missing or names no known action
+    pub action: Option<RecommendedAction>,
+    /// Percent, `None` when missing or not a number in 0..=100
+    pub confidence: Option<u8>,
+    /// The verdict lines, or the analysis opening if the format wasn't followed
+    pub summary: String,
+}
+
+impl Recommendation {
+    pub fn parse(analysis: &str) -> Self {
+        const KEYS: [&str; 3] = ["CONFIDENCE LEVEL:", "ACTION:", "DETAILS:"];
+        let lines: Vec<String> = analysis
+            .lines()
+            // Models often bold or head the labels in markdown
+            .map(|line| line.replace("**", "").trim().trim_start_matches(['#', ' ']).to_string())
+            .filter(|line| KEYS.iter().any(|key| line.to_uppercase().starts_with(key)))
+            .collect();
+
+        let value = |key: &str| {
+            lines
+                .iter()
+                .find(|line| line.to_uppercase().starts_with(key))
+                .map(|line| line[key.len()..].trim().to_string())
+        };
+
+        let summary = if lines.is_empty() {
+            analysis.trim().chars().take(500).collect()
+        } else {
+            lines.join(" | ")
+        };
+
+        Self {
+            action: value("ACTION:").as_deref().and_then(parse_action),
+            confidence: value("CONFIDENCE LEVEL:").as_deref().and_then(parse_confidence),
+            summary,
+        }
+    }
+
+    /// Whether a trade on `side` may run without asking: the action must match
+    /// and the confidence must be at least `min_confidence`. The error says why not.
+    pub fn approves(&self, side: TradeSide, min_confidence: u8) -> Result<(), String> {
+        let wanted = match side {
+            TradeSide::Buy => RecommendedAction::Buy,
+            TradeSide::Sell => RecommendedAction::Sell,
+            TradeSide::Send => return Err("transfers are never auto-approved".to_string()),
+        };
+        match self.action {
+            None => return Err("the analysis has no clear BUY/SELL action".to_string()),
+            Some(action) if action != wanted => {
+                return Err(format!("the analysis recommends {:?}, not {:?}", action, wanted))
+            }
+            Some(_) => {}
+        }
+        match self.confidence {
+            None => Err("the analysis confidence could not be read".to_string()),
+            Some(confidence) if confidence < min_confidence => Err(format!(
+                "confidence {}% is below the {}% threshold",
+                confidence, min_confidence
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+// Whole words only, so "THRESHOLD" is no HOLD. A line naming more than one of
+// BUY, SELL, HOLD and DONT BUY, e.g. "BUY, SELL at 2x", is ambiguous and gives None.
+fn parse_action(value: &str) -> Option<RecommendedAction> {
+    let value = value.to_uppercase().replace(['\'', '’'], "");
+    let words: Vec<&str> = value.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+
+    let mut found = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        let action = match *word {
+            "BUY" if i >= 1 && (words[i - 1] == "DONT" || (words[i - 1] == "NOT" && i >= 2 && words[i - 2] == "DO")) => {
+                RecommendedAction::DontBuy
+            }
+            "BUY" => RecommendedAction::Buy,
+            "SELL" => RecommendedAction::Sell,
+            "HOLD" => RecommendedAction::Hold,
+            _ => continue,
+        };
+        if !found.contains(&action) {
+            found.push(action);
+        }
+    }
+
+    match found[..] {
+        [action] => Some(action),
+        [] if words.contains(&"TRANSFER") => Some(RecommendedAction::Transfer),
+        // A bare SWAP doesn't say which way
+        _ => None,
+    }
+}
+
+// The first number on the line, so "85%" and "80-85%" both read low-end
+fn parse_confidence(value: &str) -> Option<u8> {
+    let digits: String = value
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse::<u8>().ok().filter(|c| *c <= 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(line: &str) -> Option<RecommendedAction> {
+        Recommendation::parse(&format!("ACTION: {}\nCONFIDENCE LEVEL: 80%", line)).action
+    }
+
+    #[test]
+    fn actions_are_whole_words() {
+        assert_eq!(action("BUY"), Some(RecommendedAction::Buy));
+        assert_eq!(action("**Sell** now"), Some(RecommendedAction::Sell));
+        assert_eq!(action("BUY above the breakout threshold"), Some(RecommendedAction::Buy));
+        assert_eq!(action("Don't buy"), Some(RecommendedAction::DontBuy));
+        assert_eq!(action("DO NOT BUY"), Some(RecommendedAction::DontBuy));
+        assert_eq!(action("TRANSFER"), Some(RecommendedAction::Transfer));
+        assert_eq!(action("SWAP"), None);
+    }
+
+    #[test]
+    fn several_actions_are_ambiguous() {
+        assert_eq!(action("BUY, SELL at 2x"), None);
+        assert_eq!(action("HOLD or BUY the dip"), None);
+        assert_eq!(action("DONT BUY, SELL"), None);
+    }
+
+    #[test]
+    fn approves_matching_confident_trades_only() {
+        let analysis = Recommendation::parse("## **ACTION:** BUY\nCONFIDENCE LEVEL: 85%\nDETAILS: breakout");
+        assert_eq!(analysis.confidence, Some(85));
+        assert!(analysis.approves(TradeSide::Buy, 80).is_ok());
+        assert!(analysis.approves(TradeSide::Buy, 90).unwrap_err().contains("below the 90% threshold"));
+        assert!(analysis.approves(TradeSide::Sell, 80).unwrap_err().contains("recommends Buy"));
+        assert!(analysis.approves(TradeSide::Send, 0).is_err());
+
+        let unclear = Recommendation::parse("ACTION: BUY, SELL at 2x\nCONFIDENCE LEVEL: 95");
+        assert!(unclear.approves(TradeSide::Buy, 0).unwrap_err().contains("no clear"));
+        assert!(Recommendation::parse("ACTION: BUY").approves(TradeSide::Buy, 0).is_err());
+    }
+}
@@ -1,2 +1,21 @@
+pub mod rpc;
 pub mod transfer;
-pub mod swap;
\ No newline at end of file
+pub mod swap;
+
+use anyhow::{anyhow, Result};
+use solana_sdk::signature::Keypair;
+
+pub use rpc::{shared_rpc_client, Commitment, RpcSettings, TxOutcome, DEFAULT_RPC_TIMEOUT};
+
+/// Decode a base58 wallet key. Errors never include the key itself.
+pub fn keypair_from_base58(private_key: &str) -> Result<Keypair> {
+    let bytes = bs58::decode(private_key.trim())
+        .into_vec()
+        .map_err(|_| anyhow!("Invalid private key: not valid base58"))?;
+    Keypair::from_bytes(&bytes).map_err(|_| {
+        anyhow!(
+            "Invalid private key: expected a 64-byte keypair, got {} bytes",
+            bytes.len()
+        )
+    })
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_client::{RpcClient, SerializableTransaction};
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use tracing::{debug, warn};
+
+/// RPC request timeout, the Solana client's own default
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How often to ask whether a sent transaction has reached the commitment
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How settled a transaction must be before it counts: `processed` is fastest,
+/// `finalized` can't be rolled back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn config(self) -> CommitmentConfig {
+        let commitment = match self {
+            Commitment::Processed => CommitmentLevel::Processed,
+            Commitment::Confirmed => CommitmentLevel::Confirmed,
+            Commitment::Finalized => CommitmentLevel::Finalized,
+        };
+        CommitmentConfig { commitment }
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "processed" => Ok(Commitment::Processed),
+            "confirmed" => Ok(Commitment::Confirmed),
+            "finalized" => Ok(Commitment::Finalized),
+            other => Err(format!(
+                "Unknown commitment '{}', expected processed, confirmed or finalized",
+                other
+            )),
+        }
+    }
+}
+
+/// Commitment and timeout for RPC calls. The commitment is used for preflight on
+/// submission and when polling for confirmation, so a transaction is never reported
+/// at a stronger level than it was checked at. The timeout caps each request and
+/// how long to wait for confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcSettings {
+    pub commitment: Commitment,
+    pub timeout: Duration,
+}
+
+impl Default for RpcSettings {
+    fn default() -> Self {
+        Self {
+            commitment: Commitment::default(),
+            timeout: DEFAULT_RPC_TIMEOUT,
+        }
+    }
+}
+
+/// An RPC client to share between the swap and transfer tools, so the binary
+/// holds one connection and every tool uses the same commitment and timeout.
+pub fn shared_rpc_client(rpc_url: &str, settings: RpcSettings) -> Arc<RpcClient> {
+    Arc::new(RpcClient::new_with_timeout_and_commitment(
+        rpc_url.to_string(),
+        settings.timeout,
+        settings.commitment.config(),
+    ))
+}
+
+/// What happened to a sent transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxOutcome {
+    /// Reached the client's commitment
+    Confirmed(String),
+    /// Sent, but not confirmed before the timeout; it may still land, check the signature
+    Pending(String),
+}
+
+impl TxOutcome {
+    pub fn signature(&self) -> &str {
+        match self {
+            TxOutcome::Confirmed(signature) | TxOutcome::Pending(signature) => signature,
+        }
+    }
+}
+
+impl fmt::Display for TxOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxOutcome::Confirmed(signature) => write!(f, "{}", signature),
+            TxOutcome::Pending(signature) => write!(f, "{} (pending, not yet confirmed)", signature),
+        }
+    }
+}
+
+/// Send a signed transaction and wait up to `timeout` for it to reach the client's
+/// commitment. A timeout, while sending or while waiting, gives `Pending` since the
+/// transaction may already be on its way; a transaction that failed on chain is an error.
+pub async fn send_and_confirm(
+    client: &RpcClient,
+    transaction: &impl SerializableTransaction,
+    timeout: Duration,
+) -> Result<TxOutcome> {
+    let signature = *transaction.get_signature();
+    let deadline = Instant::now() + timeout;
+
+    match client.send_transaction(transaction) {
+        Ok(_) => {}
+        Err(e) if matches!(e.kind(), ClientErrorKind::Reqwest(inner) if inner.is_timeout()) => {
+            warn!("Sending {} timed out, it may still land", signature);
+            return Ok(TxOutcome::Pending(signature.to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let commitment = client.commitment();
+    while Instant::now() < deadline {
+        match client.get_signature_status_with_commitment(&signature, commitment) {
+            Ok(Some(Ok(()))) => return Ok(TxOutcome::Confirmed(signature.to_string())),
+            Ok(Some(Err(e))) => return Err(anyhow!("Transaction {} failed: {}", signature, e)),
+            Ok(None) => {}
+            // Keep polling, the transaction is already sent
+            Err(e) => debug!("Status check for {} failed: {}", signature, e),
+        }
+        tokio::time::sleep(CONFIRM_POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+    }
+
+    warn!("{} not {:?} within {}s", signature, commitment.commitment, timeout.as_secs());
+    Ok(TxOutcome::Pending(signature.to_string()))
+}
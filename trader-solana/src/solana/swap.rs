@@ -7,26 +7,39 @@ use jupiter_swap_api_client::{
 };
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::{Keypair, Signer}, transaction::VersionedTransaction
+    pubkey::Pubkey, signature::{Keypair, Signer}, transaction::VersionedTransaction
 };
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::rpc::{self, RpcSettings, TxOutcome};
 
 
 pub struct JupiterSwap {
-    client: RpcClient,
+    client: Arc<RpcClient>,
+    confirm_timeout: Duration,
     payer: Keypair,
     jupiter_client: JupiterSwapApiClient,
 }
 
 impl JupiterSwap {
     pub fn new(rpc_url: &str, private_key: &str) -> Result<Self> {
-        let client =
-            RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+        Self::with_settings(rpc_url, private_key, RpcSettings::default())
+    }
+
+    pub fn with_settings(rpc_url: &str, private_key: &str, settings: RpcSettings) -> Result<Self> {
+        Self::with_client(rpc::shared_rpc_client(rpc_url, settings), private_key, settings.timeout)
+    }
+
+    /// Swaps confirm at `client`'s commitment, waiting up to `confirm_timeout`
+    pub fn with_client(client: Arc<RpcClient>, private_key: &str, confirm_timeout: Duration) -> Result<Self> {
         let api_base_url = "https://quote-api.jup.ag/v6";
-        let payer = Keypair::from_base58_string(private_key);
+        let payer = super::keypair_from_base58(private_key)?;
         let jupiter_client = JupiterSwapApiClient::new(api_base_url.to_string());
 
         Ok(Self {
             client,
+            confirm_timeout,
             payer,
             jupiter_client,
         })
@@ -39,7 +52,7 @@ impl JupiterSwap {
         amount: u64,
         slippage_bps: u16,
         dexes: Option<Vec<String>>,
-    ) -> Result<String> {
+    ) -> Result<TxOutcome> {
         // Get quote
         let quote_request = QuoteRequest {
             amount,
@@ -70,9 +83,7 @@ impl JupiterSwap {
         let signed_transaction =
             VersionedTransaction::try_new(versioned_transaction.message, &[&self.payer])?;
 
-        let signature = self.client.send_transaction(&signed_transaction)?;
-
-        Ok(signature.to_string())
+        rpc::send_and_confirm(&self.client, &signed_transaction, self.confirm_timeout).await
     }
 
     pub fn get_token_balance(&self, token_mint: &Pubkey) -> Result<(f64, u64, u8)> {
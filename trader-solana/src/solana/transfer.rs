@@ -1,6 +1,5 @@
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    commitment_config::CommitmentConfig,
     message::Message,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
@@ -10,29 +9,38 @@ use solana_sdk::{
 };
 use spl_token::instruction as spl_instruction;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use anyhow::Result;
 use spl_associated_token_account::get_associated_token_address;
 use spl_associated_token_account::instruction::create_associated_token_account;
 
+use super::rpc::{self, RpcSettings, TxOutcome};
+
 pub struct SolanaTransfer {
-    client: RpcClient,
+    client: Arc<RpcClient>,
+    confirm_timeout: Duration,
     payer: Keypair,
 }
 
 impl SolanaTransfer {
     pub fn new(rpc_url: &str, private_key: &str) -> Result<Self> {
-        let client = RpcClient::new_with_commitment(
-            rpc_url.to_string(),
-            CommitmentConfig::confirmed(),
-        );
+        Self::with_settings(rpc_url, private_key, RpcSettings::default())
+    }
+
+    pub fn with_settings(rpc_url: &str, private_key: &str, settings: RpcSettings) -> Result<Self> {
+        Self::with_client(rpc::shared_rpc_client(rpc_url, settings), private_key, settings.timeout)
+    }
 
+    /// Transfers confirm at `client`'s commitment, waiting up to `confirm_timeout`
+    pub fn with_client(client: Arc<RpcClient>, private_key: &str, confirm_timeout: Duration) -> Result<Self> {
         // Convert private key from base58 to Keypair
-        let payer = Keypair::from_base58_string(private_key);
+        let payer = super::keypair_from_base58(private_key)?;
 
-        Ok(Self { client, payer })
+        Ok(Self { client, confirm_timeout, payer })
     }
 
-    fn send_transaction_with_priority(&self, instructions: &[solana_sdk::instruction::Instruction], priority_fee: u64) -> Result<String> {
+    async fn send_transaction_with_priority(&self, instructions: &[solana_sdk::instruction::Instruction], priority_fee: u64) -> Result<TxOutcome> {
         let recent_blockhash = self.client.get_latest_blockhash()?;
         let priority_instruction = ComputeBudgetInstruction::set_compute_unit_price(priority_fee);
         let mut all_instructions = vec![priority_instruction];
@@ -41,11 +49,10 @@ impl SolanaTransfer {
         let message = Message::new(&all_instructions, Some(&self.payer.pubkey()));
         let transaction = Transaction::new(&[&self.payer], message, recent_blockhash);
 
-        let signature = self.client.send_transaction(&transaction)?;
-        Ok(signature.to_string())
+        rpc::send_and_confirm(&self.client, &transaction, self.confirm_timeout).await
     }
 
-    pub async fn transfer_sol(&self, to_pubkey: &str, amount_sol: f64) -> Result<String> {
+    pub async fn transfer_sol(&self, to_pubkey: &str, amount_sol: f64) -> Result<TxOutcome> {
         let to_pubkey = Pubkey::from_str(to_pubkey)?;
         let amount_lamports = (amount_sol * 1_000_000_000.0) as u64; // Convert SOL to lamports
 
@@ -55,7 +62,7 @@ impl SolanaTransfer {
             amount_lamports,
         );
         let priority_fee = 100_000;
-        self.send_transaction_with_priority(&[instruction], priority_fee)
+        self.send_transaction_with_priority(&[instruction], priority_fee).await
     }
 
     pub async fn transfer_spl(
@@ -63,7 +70,7 @@ impl SolanaTransfer {
         token_mint: &str,
         to_address: &str,
         amount: u64,
-    ) -> Result<String> {
+    ) -> Result<TxOutcome> {
         let token_mint_pubkey = Pubkey::from_str(token_mint)?;
         let from_token_account = get_associated_token_address(&self.payer.pubkey(), &token_mint_pubkey);
         let balance = self.client.get_token_account_balance(&from_token_account)?;
@@ -104,6 +111,6 @@ impl SolanaTransfer {
             )?
         );
         let priority_fee = 100_000;
-        self.send_transaction_with_priority(&instructions, priority_fee)
+        self.send_transaction_with_priority(&instructions, priority_fee).await
     }
 } 
\ No newline at end of file
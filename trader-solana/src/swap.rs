@@ -108,6 +108,7 @@ impl Tool for SwapTool {
                 None,
             )
             .await
+            .map(|outcome| outcome.to_string())
             .map_err(|e| SwapError::JupiterError(e.to_string()))
     }
 }
@@ -4,11 +4,14 @@ use rig::{
 };
 use serde_json::json;
 use crate::{
-    solana::swap::JupiterSwap,
+    solana::{swap::JupiterSwap, RpcSettings, TxOutcome},
     types::{SwapArgs, SwapError},
 };
 use solana_sdk::pubkey::Pubkey;
+use solana_client::rpc_client::RpcClient;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use anyhow::Result;
 
 pub struct SwapTool {
@@ -22,6 +25,19 @@ impl SwapTool {
         })
     }
 
+    pub fn with_settings(rpc_url: &str, private_key: &str, settings: RpcSettings) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            jupiter_swap: JupiterSwap::with_settings(rpc_url, private_key, settings)?
+        })
+    }
+
+    /// Use an RPC client shared with other tools, see `solana::shared_rpc_client`
+    pub fn with_client(client: Arc<RpcClient>, private_key: &str, confirm_timeout: Duration) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            jupiter_swap: JupiterSwap::with_client(client, private_key, confirm_timeout)?
+        })
+    }
+
     pub fn from_env() -> Result<Self, anyhow::Error> {
         let rpc_url = std::env::var("SOLANA_RPC_URL")
             .map_err(|_| anyhow::anyhow!("SOLANA_RPC_URL not set"))?;
@@ -31,7 +47,7 @@ impl SwapTool {
         Self::new(&rpc_url, &private_key)
     }
 
-    pub async fn execute_swap(&self, from: String, to: String, amount: f64) -> Result<String, Box<dyn std::error::Error>> {
+    pub async fn execute_swap(&self, from: String, to: String, amount: f64) -> Result<TxOutcome, Box<dyn std::error::Error>> {
         // Call the underlying swap implementation
         let result = self.jupiter_swap
             .swap(
@@ -127,6 +143,7 @@ impl Tool for SwapTool {
                 None,
             )
             .await
+            .map(|outcome| outcome.to_string())
             .map_err(|e| SwapError::JupiterError(e.to_string()))
     }
 }
@@ -3,10 +3,13 @@ use rig::{
     tool::Tool,
 };
 use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{
     types::{TransferArgs, TransferError},
-    solana::transfer::SolanaTransfer,
+    solana::{transfer::SolanaTransfer, RpcSettings, TxOutcome},
 };
 
 pub struct TransferTool {
@@ -20,6 +23,19 @@ impl TransferTool {
         })
     }
 
+    pub fn with_settings(rpc_url: &str, private_key: &str, settings: RpcSettings) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            solana: SolanaTransfer::with_settings(rpc_url, private_key, settings)?
+        })
+    }
+
+    /// Use an RPC client shared with other tools, see `solana::shared_rpc_client`
+    pub fn with_client(client: Arc<RpcClient>, private_key: &str, confirm_timeout: Duration) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            solana: SolanaTransfer::with_client(client, private_key, confirm_timeout)?
+        })
+    }
+
     pub fn from_env() -> Result<Self, anyhow::Error> {
         let rpc_url = std::env::var("SOLANA_RPC_URL")
             .map_err(|_| anyhow::anyhow!("SOLANA_RPC_URL not set"))?;
@@ -29,7 +45,7 @@ impl TransferTool {
         Self::new(&rpc_url, &private_key)
     }
 
-    pub async fn execute_transfer(&self, to: String, amount: f64) -> Result<String, Box<dyn std::error::Error>> {
+    pub async fn execute_transfer(&self, to: String, amount: f64) -> Result<TxOutcome, Box<dyn std::error::Error>> {
         // Call the underlying transfer implementation
         let result = self.solana
             .transfer_sol(&to, amount)
@@ -91,6 +107,7 @@ impl Tool for TransferTool {
                 self.solana
                     .transfer_sol(&args.recipient, args.amount)
                     .await
+                    .map(|outcome| outcome.to_string())
                     .map_err(TransferError::SolanaError)
             }
             "spl" => {
@@ -103,6 +120,7 @@ impl Tool for TransferTool {
                         args.amount as u64,
                     )
                     .await
+                    .map(|outcome| outcome.to_string())
                     .map_err(TransferError::SolanaError)
             }
             _ => Err(TransferError::InvalidTokenType),
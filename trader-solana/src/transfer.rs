@@ -76,6 +76,7 @@ impl Tool for TransferTool {
                 self.solana
                     .transfer_sol(&args.recipient, args.amount)
                     .await
+                    .map(|outcome| outcome.to_string())
                     .map_err(TransferError::SolanaError)
             }
             "spl" => {
@@ -88,6 +89,7 @@ impl Tool for TransferTool {
                         args.amount as u64,
                     )
                     .await
+                    .map(|outcome| outcome.to_string())
                     .map_err(TransferError::SolanaError)
             }
             _ => Err(TransferError::InvalidTokenType),
@@ -7,6 +7,8 @@ use tracing::{info, debug, error};
 use crate::{character::Character, knowledge::KnowledgeBase, intel::CryptoIntel};
 use rig::message::Text;
 use crate::interaction_history::InteractionHistory;
+use common::empty_response::{self, EmptyResponsePolicy};
+use common::telemetry;
 
 #[derive(Clone)]
 pub struct Agent<M: CompletionModel, E: EmbeddingModel + 'static> {
@@ -14,6 +16,7 @@ pub struct Agent<M: CompletionModel, E: EmbeddingModel + 'static> {
     completion_model: M,
     knowledge: KnowledgeBase<E>,
     pub interaction_history: InteractionHistory,
+    model_name: String,
 }
 
 impl<M: CompletionModel, E: EmbeddingModel> Agent<M, E> {
@@ -25,9 +28,30 @@ impl<M: CompletionModel, E: EmbeddingModel> Agent<M, E> {
             completion_model,
             knowledge,
             interaction_history,
+            model_name: "unknown".to_string(),
         }
     }
 
+    /// Record the completion model name so provider calls can be traced
+    pub fn with_model_name(mut self, model_name: &str) -> Self {
+        self.model_name = model_name.to_string();
+        self
+    }
+
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    /// Provider name derived from the completion model type, e.g. "gemini"
+    pub fn provider_name(&self) -> &'static str {
+        let type_name = std::any::type_name::<M>();
+        type_name
+            .split("providers::")
+            .nth(1)
+            .and_then(|rest| rest.split("::").next())
+            .unwrap_or(type_name)
+    }
+
     pub fn builder(&self) -> AgentBuilder<M> {
         let mut builder = AgentBuilder::new(self.completion_model.clone());
 
@@ -86,7 +110,14 @@ impl<M: CompletionModel, E: EmbeddingModel> Agent<M, E> {
         &self.knowledge
     }
 
-    pub async fn process_market_data(&self, intel: &CryptoIntel) -> Result<String, Box<dyn std::error::Error>> {
+    /// A tweet of at most `max_chars` characters about `intel`, or "NO_POST". An answer
+    /// that's empty under `empty_response` is asked for again once, then returned as an error.
+    pub async fn process_market_data(
+        &self,
+        intel: &CryptoIntel,
+        max_chars: usize,
+        empty_response: EmptyResponsePolicy,
+    ) -> Result<String, Box<dyn std::error::Error>> {
         let mut retries = 3;
         
         loop {
@@ -98,7 +129,7 @@ impl<M: CompletionModel, E: EmbeddingModel> Agent<M, E> {
 
                 As a chef who loves explaining crypto through cooking metaphors:
                 1. review this market data
-                2. If you find it interesting or important, create a tweet about it ,Keep under 280 characters (MUST)
+                2. If you find it interesting or important, create a tweet about it ,Keep under {} characters (MUST)
                 3. If not interesting enough, respond with 'NO_POST'
                 
                 Rules for tweets:
@@ -112,17 +143,29 @@ impl<M: CompletionModel, E: EmbeddingModel> Agent<M, E> {
                 - No asterisks, ellipsis, or other special formatting
                 - Format numbers with standard notation{}",
                 intel.content,
-                if retries < 3 { "\n- Make it more concise than before, keep it under 280 characters" } else { "" }
+                max_chars,
+                if retries < 3 {
+                    format!("\n- Make it more concise than before, keep it under {} characters", max_chars)
+                } else {
+                    String::new()
+                }
             );
 
             info!("Sending prompt to completion model");
-            let response = self.builder()
+            let agent = self.builder()
                 .context(&prompt)
-                .build()
-                .prompt(Text::from(intel.content.to_string()))
-                .await?;
+                .build();
+            let response = empty_response::retry_if_empty(empty_response, || {
+                telemetry::traced_call(
+                    self.provider_name(),
+                    &self.model_name,
+                    &intel.content,
+                    agent.prompt(Text::from(intel.content.to_string())),
+                )
+            })
+            .await?;
             
-            info!("Received response from model: {}", response);
+            debug!("Received response from model: {}", response);
             
             // Clean up the response
             let cleaned = response
@@ -132,17 +175,18 @@ impl<M: CompletionModel, E: EmbeddingModel> Agent<M, E> {
                 .replace("...", ".")
                 .replace("*", "");
                 
-            if cleaned.len() <= 240 {
+            let length = cleaned.chars().count();
+            if length <= max_chars {
                 return Ok(cleaned);
             }
             
             retries -= 1;
             if retries == 0 {
-                error!("Failed to generate tweet under 280 characters after all retries");
+                error!("Failed to generate tweet under {} characters after all retries", max_chars);
                 return Ok("NO_POST".to_string());
             }
             
-            info!("Response too long ({}), trying again with {} retries left", cleaned.len(), retries);
+            info!("Response too long ({}), trying again with {} retries left", length, retries);
         }
     }
 }
\ No newline at end of file
@@ -1,15 +1,47 @@
 use rig::completion::{CompletionModel, AssistantContent};
 use rig::message::Text;
-use tracing::debug;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
 
 use crate::knowledge::{ChannelType, Source};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 const RESPOND_COMMAND: &str = "[RESPOND]";
 const IGNORE_COMMAND: &str = "[IGNORE]";
 const STOP_COMMAND: &str = "[STOP]";
 
-#[derive(Debug, PartialEq)]
+// Default decision prompts. Placeholders: {name} and {persona} are available to all,
+// {history}, {message} and the {respond}/{ignore}/{stop} commands for replies,
+// {tweet} for likes/retweets/quotes.
+const DEFAULT_REPLY_PROMPT: &str = "You are in a conversation. You should respond while maintaining context of the discussion.\n\n\
+    Recent conversation:\n{history}\n\n\
+    Latest message: {message}\n\n\
+    Consider:\n\
+    1. The topic being discussed\n\
+    2. The tone of the conversation\n\
+    3. Previous messages for context\n\
+    4. If your name was mentioned casually or needs a response\n\n\
+    Response options:\n\
+    {respond} - Message is relevant and needs your response\n\
+    {ignore} - Message just mentions you casually or isn't seeking response\n\
+    {stop} - User wants you to stop\n\n\
+    Choose one response option:";
+
+const DEFAULT_LIKE_PROMPT: &str = "You are deciding whether to like a tweet. Consider if the content is positive, interesting, or relevant.\n\n\
+    Tweet: {tweet}\n\n\
+    Respond with only 'true' or 'false':";
+
+const DEFAULT_RETWEET_PROMPT: &str = "You are deciding whether to retweet. Only retweet if the content is highly valuable, interesting, or aligns with your values.\n\n\
+    Tweet: {tweet}\n\n\
+    Respond with only 'true' or 'false':";
+
+const DEFAULT_QUOTE_PROMPT: &str = "You are deciding whether to quote tweet. Quote tweet if the content deserves commentary, \
+    could benefit from additional context, or warrants a thoughtful response.\n\n\
+    Tweet: {tweet}\n\n\
+    Respond with only 'true' or 'false':";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AttentionCommand {
     Respond,
     Ignore,
@@ -25,12 +57,150 @@ pub struct AttentionContext {
     pub source: Source,
 }
 
+/// Custom decision prompt templates, any left unset use the built-in prompt.
+/// A template missing its required placeholder ({message} for replies, {tweet}
+/// otherwise) or using an unknown one is ignored with a warning.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttentionPrompts {
+    pub reply: Option<String>,
+    pub like: Option<String>,
+    pub retweet: Option<String>,
+    pub quote: Option<String>,
+}
+
+/// Hard veto checked before any other attention rule or model call: a message matching
+/// one of `keywords` or linking to one of `domains` is never engaged. Matching is
+/// case-insensitive, and a domain also covers its subdomains.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttentionVeto {
+    pub keywords: Vec<String>,
+    pub domains: Vec<String>,
+    /// Follow links on known URL shorteners to check the domain they lead to.
+    /// Each link is followed once; its target is remembered for later checks.
+    pub expand_short_links: bool,
+    // Short link -> where it led, None when it couldn't be followed
+    #[serde(skip)]
+    expanded: Arc<Mutex<HashMap<String, Option<String>>>>,
+}
+
+impl Default for AttentionVeto {
+    fn default() -> Self {
+        Self {
+            keywords: Vec::new(),
+            domains: Vec::new(),
+            expand_short_links: true,
+            expanded: Arc::default(),
+        }
+    }
+}
+
+// Hosts whose links are expanded before the domain check
+const URL_SHORTENERS: &[&str] = &[
+    "t.co", "bit.ly", "tinyurl.com", "goo.gl", "ow.ly", "is.gd", "buff.ly", "rebrand.ly", "cutt.ly", "shorturl.at",
+];
+const SHORT_LINK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+// Expanded links kept before the cache starts over
+const MAX_EXPANDED_LINKS: usize = 1024;
+
+impl AttentionVeto {
+    pub fn new(keywords: Vec<String>, domains: Vec<String>) -> Self {
+        Self { keywords, domains, ..Self::default() }
+    }
+
+    /// The rule `text` trips, if any
+    pub async fn check(&self, text: &str) -> Option<String> {
+        let lower = text.to_lowercase();
+        let keyword = self
+            .keywords
+            .iter()
+            .map(|keyword| keyword.trim().to_lowercase())
+            .find(|keyword| !keyword.is_empty() && lower.contains(keyword.as_str()));
+        if let Some(keyword) = keyword {
+            return Some(format!("keyword \"{}\"", keyword));
+        }
+
+        if self.domains.is_empty() {
+            return None;
+        }
+        for (link, host) in text.split_whitespace().filter_map(|word| link_host(word).map(|host| (word, host))) {
+            if let Some(domain) = self.blocked_domain(&host) {
+                return Some(format!("domain {}", domain));
+            }
+            if self.expand_short_links && URL_SHORTENERS.contains(&host.as_str()) {
+                let expanded = self.expand(link).await;
+                if let Some(domain) = expanded.as_deref().and_then(link_host).and_then(|host| self.blocked_domain(&host)) {
+                    return Some(format!("domain {} (via {})", domain, host));
+                }
+            }
+        }
+        None
+    }
+
+    // Target of a short link, followed at most once per link
+    async fn expand(&self, link: &str) -> Option<String> {
+        if let Some(expanded) = self.expanded.lock().unwrap_or_else(|e| e.into_inner()).get(link) {
+            return expanded.clone();
+        }
+        let expanded = expand_short_link(link).await;
+        let mut cache = self.expanded.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= MAX_EXPANDED_LINKS {
+            cache.clear();
+        }
+        cache.insert(link.to_string(), expanded.clone());
+        expanded
+    }
+
+    fn blocked_domain(&self, host: &str) -> Option<&str> {
+        self.domains.iter().map(|domain| domain.trim()).find(|domain| {
+            let domain = domain.trim_start_matches("www.").to_lowercase();
+            !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+        })
+    }
+}
+
+// Lowercased host of a word that looks like a link, with or without a scheme
+fn link_host(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '/');
+    let link = if word.contains("://") { word.to_string() } else { format!("http://{}", word) };
+    let url = reqwest::Url::parse(&link).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.").to_lowercase();
+    // A bare word parses as a host too, so require a dot-separated domain
+    if !host.contains('.') || host.ends_with('.') {
+        return None;
+    }
+    Some(host)
+}
+
+// Where a short link redirects to. Failures are ignored, the link's own host was checked.
+async fn expand_short_link(link: &str) -> Option<String> {
+    let link = link.trim_matches(|c: char| !c.is_alphanumeric() && c != '/');
+    let link = if link.contains("://") { link.to_string() } else { format!("https://{}", link) };
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .timeout(SHORT_LINK_TIMEOUT)
+        .build()
+        .ok()?;
+    match client.head(&link).send().await {
+        Ok(response) => Some(response.url().to_string()),
+        Err(e) => {
+            debug!(link = %link, error = %e, "Failed to expand short link");
+            None
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AttentionConfig {
     pub bot_names: Vec<String>,
     pub reply_threshold: f32,
     pub max_history_messages: i64,
     pub cooldown_messages: i64,
+    /// Short persona description interpolated as {persona}
+    pub persona: String,
+    pub prompts: AttentionPrompts,
+    pub veto: AttentionVeto,
 }
 
 impl Default for AttentionConfig {
@@ -40,10 +210,104 @@ impl Default for AttentionConfig {
             reply_threshold: 0.6,
             max_history_messages: 10,
             cooldown_messages: 3,
+            persona: String::new(),
+            prompts: AttentionPrompts::default(),
+            veto: AttentionVeto::default(),
         }
     }
 }
 
+// Fill `{key}` placeholders in a single pass so substituted text is never re-expanded.
+// Errors on unknown placeholders, unbalanced braces or a missing required placeholder.
+fn render_template(template: &str, values: &[(&str, &str)], required: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    let mut has_required = false;
+
+    while let Some(start) = rest.find(|c| c == '{' || c == '}') {
+        if rest[start..].starts_with('}') {
+            return Err("unmatched '}'".to_string());
+        }
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| "unclosed '{'".to_string())?;
+        let key = &rest[start + 1..start + end];
+        let value = values
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| format!("unknown placeholder {{{}}}", key))?;
+        has_required |= key == required;
+        output.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+
+    if !has_required {
+        return Err(format!("missing required placeholder {{{}}}", required));
+    }
+    Ok(output)
+}
+
+/// The command in a reply decision. Small and local models often wrap it in reasoning,
+/// markdown or a sentence, so brackets are optional; `None` unless exactly one of
+/// the commands is given.
+pub fn parse_reply_decision(output: &str) -> Option<AttentionCommand> {
+    let answer = strip_reasoning(output).to_uppercase();
+    let commands = [
+        (RESPOND_COMMAND, AttentionCommand::Respond),
+        (IGNORE_COMMAND, AttentionCommand::Ignore),
+        (STOP_COMMAND, AttentionCommand::Stop),
+    ];
+    let words: Vec<&str> = answer.split(|c: char| !c.is_ascii_alphabetic()).collect();
+    // Bracketed commands are unambiguous, bare words only count when none are given
+    let bracketed: Vec<_> = commands.iter().filter(|(command, _)| answer.contains(command)).collect();
+    let found: Vec<_> = if bracketed.is_empty() {
+        commands
+            .iter()
+            .filter(|(command, _)| words.contains(&command.trim_matches(['[', ']'])))
+            .collect()
+    } else {
+        bracketed
+    };
+    match found.as_slice() {
+        [(_, command)] => Some(*command),
+        _ => None,
+    }
+}
+
+/// A true/false decision, also accepting yes/no and answers wrapped in punctuation,
+/// markdown or a short sentence. `None` if it says neither or both.
+pub fn parse_yes_no(output: &str) -> Option<bool> {
+    let answer = strip_reasoning(output).to_lowercase();
+    let words: Vec<&str> = answer.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    match words.first() {
+        Some(&("true" | "yes")) => return Some(true),
+        Some(&("false" | "no")) => return Some(false),
+        _ => {}
+    }
+    let yes = words.iter().any(|w| matches!(*w, "true" | "yes"));
+    let no = words.iter().any(|w| matches!(*w, "false" | "no"));
+    match (yes, no) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    }
+}
+
+// Reasoning models (e.g. deepseek-r1 on Ollama) think out loud in <think> tags first
+fn strip_reasoning(output: &str) -> &str {
+    match output.rfind("</think>") {
+        Some(end) => output[end + "</think>".len()..].trim(),
+        None => output.trim(),
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    text.chars().take(120).collect()
+}
+
 #[derive(Clone)]
 pub struct Attention<M: CompletionModel> {
     config: AttentionConfig,
@@ -58,7 +322,69 @@ impl<M: CompletionModel> Attention<M> {
         }
     }
 
+    // Whether the veto list rules out engaging with `text`, logging the rule that matched
+    async fn vetoed(&self, action: &str, text: &str) -> bool {
+        match self.config.veto.check(text).await {
+            Some(rule) => {
+                let excerpt: String = text.chars().take(80).collect();
+                info!(action = action, rule = %rule, text = %excerpt, "Vetoed by attention rule");
+                true
+            }
+            None => false,
+        }
+    }
+
+    // The model's raw answer, `None` if the call failed
+    async fn ask(&self, prompt: &str) -> Option<String> {
+        let builder = self.completion_model.completion_request(Text::from(prompt.to_string()));
+        match self.completion_model.completion(builder.build()).await {
+            Ok(response) => match response.choice.first() {
+                AssistantContent::Text(text) => Some(text.text),
+                _ => Some(String::new()),
+            },
+            Err(e) => {
+                debug!(error = %e, "Attention model call failed");
+                None
+            }
+        }
+    }
+
+    async fn ask_yes_no(&self, action: &str, prompt: &str) -> bool {
+        match self.ask(prompt).await {
+            Some(text) => parse_yes_no(&text).unwrap_or_else(|| {
+                warn!(action = action, output = %excerpt(&text), "Unparseable decision from attention model, skipping");
+                false
+            }),
+            None => false,
+        }
+    }
+
+    fn bot_name(&self) -> &str {
+        self.config.bot_names.first().map(String::as_str).unwrap_or_default()
+    }
+
+    // Render the custom template for `action` if there is one, falling back to the default
+    fn decision_prompt(
+        &self,
+        action: &str,
+        custom: Option<&String>,
+        default: &str,
+        values: &[(&str, &str)],
+        required: &str,
+    ) -> String {
+        if let Some(template) = custom {
+            match render_template(template, values, required) {
+                Ok(prompt) => return prompt,
+                Err(e) => warn!(action = action, error = %e, "Invalid custom attention prompt, using default"),
+            }
+        }
+        render_template(default, values, required).expect("default attention prompt is valid")
+    }
+
     pub async fn should_reply(&self, context: &AttentionContext) -> AttentionCommand {
+        if self.vetoed("reply", &context.message_content).await {
+            return AttentionCommand::Ignore;
+        }
         let content = context.message_content.to_lowercase();
 
         // Always reply to DMs
@@ -115,112 +441,89 @@ impl<M: CompletionModel> Attention<M> {
         }
 
         // Improve the prompt to emphasize context awareness
-        let prompt = format!(
-            "You are in a conversation. You should respond while maintaining context of the discussion.\n\n\
-            Recent conversation:\n{}\n\n\
-            Latest message: {}\n\n\
-            Consider:\n\
-            1. The topic being discussed\n\
-            2. The tone of the conversation\n\
-            3. Previous messages for context\n\
-            4. If your name was mentioned casually or needs a response\n\n\
-            Response options:\n\
-            {RESPOND_COMMAND} - Message is relevant and needs your response\n\
-            {IGNORE_COMMAND} - Message just mentions you casually or isn't seeking response\n\
-            {STOP_COMMAND} - User wants you to stop\n\n\
-            Choose one response option:",
-            context.history.iter()
-                .map(|(_, msg)| format!("- {}", msg))
-                .collect::<Vec<_>>()
-                .join("\n"),
-            context.message_content
+        let history = context.history.iter()
+            .map(|(_, msg)| format!("- {}", msg))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = self.decision_prompt(
+            "reply",
+            self.config.prompts.reply.as_ref(),
+            DEFAULT_REPLY_PROMPT,
+            &[
+                ("name", self.bot_name()),
+                ("persona", &self.config.persona),
+                ("history", &history),
+                ("message", &context.message_content),
+                ("respond", RESPOND_COMMAND),
+                ("ignore", IGNORE_COMMAND),
+                ("stop", STOP_COMMAND),
+            ],
+            "message",
         );
 
-        let builder = self.completion_model.completion_request(Text::from(prompt.to_string()));
-
-        match self.completion_model.completion(builder.build()).await {
-            Ok(response) => {
-                let text = match response.choice.first() {
-                    AssistantContent::Text(text) => text.text.clone(),
-                    _ => String::new()
-                };
-                let text = text.trim();
-                if text.contains(RESPOND_COMMAND) {
-                    AttentionCommand::Respond
-                } else if text.contains(STOP_COMMAND) {
-                    AttentionCommand::Stop
-                } else {
-                    AttentionCommand::Ignore
-                }
-            },
-            Err(_) => AttentionCommand::Ignore,
+        match self.ask(&prompt).await {
+            Some(text) => parse_reply_decision(&text).unwrap_or_else(|| {
+                warn!(output = %excerpt(&text), "Unparseable reply decision from attention model, ignoring");
+                AttentionCommand::Ignore
+            }),
+            None => AttentionCommand::Ignore,
         }
     }
 
     pub async fn should_like(&self, tweet_content: &str) -> bool {
-        let prompt = format!(
-            "You are deciding whether to like a tweet. Consider if the content is positive, interesting, or relevant.\n\n\
-            Tweet: {}\n\n\
-            Respond with only 'true' or 'false':",
-            tweet_content
+        if self.vetoed("like", tweet_content).await {
+            return false;
+        }
+        let prompt = self.decision_prompt(
+            "like",
+            self.config.prompts.like.as_ref(),
+            DEFAULT_LIKE_PROMPT,
+            &[
+                ("name", self.bot_name()),
+                ("persona", &self.config.persona),
+                ("tweet", tweet_content),
+            ],
+            "tweet",
         );
 
-        let builder = self.completion_model.completion_request(Text::from(prompt.to_string()));
-
-        match self.completion_model.completion(builder.build()).await {
-            Ok(response) => {
-                let text = match response.choice.first() {
-                    AssistantContent::Text(text) => text.text.clone(),
-                    _ => String::new()
-                };
-                text.trim().to_lowercase() == "true"
-            },
-            Err(_) => false,
-        }
+        self.ask_yes_no("like", &prompt).await
     }
 
     pub async fn should_retweet(&self, tweet_content: &str) -> bool {
-        let prompt = format!(
-            "You are deciding whether to retweet. Only retweet if the content is highly valuable, interesting, or aligns with your values.\n\n\
-            Tweet: {}\n\n\
-            Respond with only 'true' or 'false':",
-            tweet_content
+        if self.vetoed("retweet", tweet_content).await {
+            return false;
+        }
+        let prompt = self.decision_prompt(
+            "retweet",
+            self.config.prompts.retweet.as_ref(),
+            DEFAULT_RETWEET_PROMPT,
+            &[
+                ("name", self.bot_name()),
+                ("persona", &self.config.persona),
+                ("tweet", tweet_content),
+            ],
+            "tweet",
         );
 
-        let builder = self.completion_model.completion_request(Text::from(prompt.to_string()));
-
-        match self.completion_model.completion(builder.build()).await {
-            Ok(response) => {
-                let text = match response.choice.first() {
-                    AssistantContent::Text(text) => text.text.clone(),
-                    _ => String::new()
-                };
-                text.trim().to_lowercase() == "true"
-            },
-            Err(_) => false,
-        }
+        self.ask_yes_no("retweet", &prompt).await
     }
 
     pub async fn should_quote(&self, tweet_content: &str) -> bool {
-        let prompt = format!(
-            "You are deciding whether to quote tweet. Quote tweet if the content deserves commentary, \
-            could benefit from additional context, or warrants a thoughtful response.\n\n\
-            Tweet: {}\n\n\
-            Respond with only 'true' or 'false':",
-            tweet_content
+        if self.vetoed("quote", tweet_content).await {
+            return false;
+        }
+        let prompt = self.decision_prompt(
+            "quote",
+            self.config.prompts.quote.as_ref(),
+            DEFAULT_QUOTE_PROMPT,
+            &[
+                ("name", self.bot_name()),
+                ("persona", &self.config.persona),
+                ("tweet", tweet_content),
+            ],
+            "tweet",
         );
 
-        let builder = self.completion_model.completion_request(Text::from(prompt.to_string()));
-
-        match self.completion_model.completion(builder.build()).await {
-            Ok(response) => {
-                let text = match response.choice.first() {
-                    AssistantContent::Text(text) => text.text.clone(),
-                    _ => String::new()
-                };
-                text.trim().to_lowercase() == "true"
-            },
-            Err(_) => false,
-        }
+        self.ask_yes_no("quote", &prompt).await
     }
 }
@@ -1,17 +1,46 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+use crate::attention::{AttentionPrompts, AttentionVeto};
+
+/// Character schema version written by this build. Files without `schema_version`
+/// are treated as version 1; newer versions load with unknown fields ignored.
+pub const CHARACTER_SCHEMA_VERSION: u32 = 1;
+
+fn default_schema_version() -> u32 {
+    1
+}
 
+/// Only `name` and `preamble` are required; everything else falls back to empty defaults
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Character {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     pub name: String,
     pub preamble: String,
+    #[serde(default)]
     pub message_examples: Vec<String>,
+    #[serde(default)]
     pub topics: Vec<String>,
+    #[serde(default)]
     pub style: Style,
+    /// Optional custom engagement decision prompts, see `AttentionPrompts`
+    #[serde(default)]
+    pub attention_prompts: AttentionPrompts,
+    /// Keywords and link domains never engaged with, see `AttentionVeto`
+    #[serde(default)]
+    pub attention_veto: AttentionVeto,
 }
 
+const CHARACTER_FIELDS: &[&str] = &[
+    "schema_version", "name", "preamble", "message_examples", "topics", "style", "attention_prompts",
+    "attention_veto",
+];
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Style {
     pub all: Vec<String>,
     pub chat: Vec<String>,
@@ -22,12 +51,129 @@ pub struct Style {
     pub meme_phrases: Vec<String>,
 }
 
+const STYLE_FIELDS: &[&str] = &[
+    "all", "chat", "post", "adjectives", "expressions", "interests", "meme_phrases",
+];
+
+// Overlay table holding per-field array merge policies, removed before parsing
+const MERGE_POLICY_KEY: &str = "merge";
+
+/// How an overlay's array replaces or extends the base's
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The overlay's array is used as is
+    #[default]
+    Replace,
+    /// The overlay's items are added after the base's
+    Append,
+}
+
 impl Character {
     pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         info!(path = path, "Loading character configuration");
         let content = std::fs::read_to_string(path)?;
-        let character: Self = toml::from_str(&content)?;
+        let character = Self::from_toml(&content)?;
         debug!(name = character.name, "Character loaded successfully");
         Ok(character)
     }
-}
\ No newline at end of file
+
+    /// Parse a character file. Unknown fields (e.g. experimental ones, or fields from a
+    /// newer schema) are ignored with a debug log instead of failing the load.
+    pub fn from_toml(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_table(toml::from_str(content)?)
+    }
+
+    /// Parse `base` with `overlay` applied on top. Fields present in the overlay
+    /// replace the base's; tables such as `style` merge key by key. Arrays are
+    /// replaced unless the overlay's `[merge]` table sets `"append"` for them,
+    /// keyed by dotted path:
+    ///
+    /// ```toml
+    /// [merge]
+    /// topics = "append"
+    /// "style.adjectives" = "append"
+    /// ```
+    pub fn from_toml_with_overlay(base: &str, overlay: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut raw: toml::Table = toml::from_str(base).map_err(|e| format!("Invalid base character: {}", e))?;
+        let mut overlay: toml::Table =
+            toml::from_str(overlay).map_err(|e| format!("Invalid character overlay: {}", e))?;
+        let policies = merge_policies(overlay.remove(MERGE_POLICY_KEY))?;
+        merge_tables(&mut raw, overlay, &policies, "");
+        Self::from_table(raw)
+    }
+
+    fn from_table(raw: toml::Table) -> Result<Self, Box<dyn std::error::Error>> {
+        log_unknown_fields(&raw, CHARACTER_FIELDS, "");
+        if let Some(toml::Value::Table(style)) = raw.get("style") {
+            log_unknown_fields(style, STYLE_FIELDS, "style.");
+        }
+
+        let character: Self = toml::Value::Table(raw).try_into()?;
+        if character.schema_version > CHARACTER_SCHEMA_VERSION {
+            warn!(
+                name = character.name,
+                version = character.schema_version,
+                supported = CHARACTER_SCHEMA_VERSION,
+                "Character file uses a newer schema, fields this build doesn't know are ignored"
+            );
+        }
+        Ok(character)
+    }
+}
+
+fn merge_policies(table: Option<toml::Value>) -> Result<HashMap<String, ArrayMerge>, String> {
+    let table = match table {
+        None => return Ok(HashMap::new()),
+        Some(toml::Value::Table(table)) => table,
+        Some(_) => return Err(format!("`{}` in a character overlay must be a table", MERGE_POLICY_KEY)),
+    };
+
+    let mut policies = HashMap::new();
+    for (path, value) in table {
+        let policy = match value.as_str() {
+            Some("replace") => ArrayMerge::Replace,
+            Some("append") => ArrayMerge::Append,
+            _ => {
+                return Err(format!(
+                    "Merge policy for `{}` must be \"replace\" or \"append\", got {}",
+                    path, value
+                ))
+            }
+        };
+        let known = match path.strip_prefix("style.") {
+            Some(field) => STYLE_FIELDS.contains(&field),
+            None => CHARACTER_FIELDS.contains(&path.as_str()),
+        };
+        if !known {
+            warn!(field = %path, "Merge policy names an unknown character field");
+        }
+        policies.insert(path, policy);
+    }
+    Ok(policies)
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table, policies: &HashMap<String, ArrayMerge>, prefix: &str) {
+    for (key, value) in overlay {
+        let path = format!("{}{}", prefix, key);
+        let value = match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table, policies, &format!("{}.", path));
+                continue;
+            }
+            (Some(toml::Value::Array(base_items)), toml::Value::Array(overlay_items))
+                if policies.get(&path) == Some(&ArrayMerge::Append) =>
+            {
+                base_items.extend(overlay_items);
+                continue;
+            }
+            (_, value) => value,
+        };
+        base.insert(key, value);
+    }
+}
+
+fn log_unknown_fields(table: &toml::Table, known: &[&str], prefix: &str) {
+    for key in table.keys().filter(|key| !known.contains(&key.as_str())) {
+        debug!(field = %format!("{}{}", prefix, key), "Ignoring unknown character field");
+    }
+}
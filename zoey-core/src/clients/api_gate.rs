@@ -0,0 +1,70 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, MutexGuard, Semaphore, SemaphorePermit};
+
+use crate::clients::twitter_api::TwitterApi;
+
+/// Shared access to the Twitter API. Reads (timeline, search, fetching tweets) run
+/// up to `max_concurrent_reads` at once, so a slow `get_tweet` doesn't hold up other
+/// tasks; writes (tweets, likes, retweets) go one at a time so they land in order.
+/// Reads don't wait for writes: the API only takes `&self`, so both can be in flight.
+pub struct ApiGate {
+    api: Arc<dyn TwitterApi>,
+    reads: Semaphore,
+    writes: Mutex<()>,
+}
+
+impl ApiGate {
+    pub fn new(api: Arc<dyn TwitterApi>, max_concurrent_reads: usize) -> Self {
+        Self {
+            api,
+            reads: Semaphore::new(max_concurrent_reads.max(1)),
+            writes: Mutex::new(()),
+        }
+    }
+
+    /// Wait for a read slot, held until the returned access is dropped
+    pub async fn read(&self) -> ReadAccess<'_> {
+        let permit = self.reads.acquire().await.expect("read semaphore is never closed");
+        ReadAccess {
+            api: &self.api,
+            _permit: permit,
+        }
+    }
+
+    /// Exclusive write access. Hold it across a multi-part post so nothing else
+    /// is posted in between.
+    pub async fn write(&self) -> WriteAccess<'_> {
+        WriteAccess {
+            api: &self.api,
+            _lock: self.writes.lock().await,
+        }
+    }
+}
+
+pub struct ReadAccess<'a> {
+    api: &'a Arc<dyn TwitterApi>,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl Deref for ReadAccess<'_> {
+    type Target = dyn TwitterApi;
+
+    fn deref(&self) -> &Self::Target {
+        &**self.api
+    }
+}
+
+pub struct WriteAccess<'a> {
+    api: &'a Arc<dyn TwitterApi>,
+    _lock: MutexGuard<'a, ()>,
+}
+
+impl Deref for WriteAccess<'_> {
+    type Target = dyn TwitterApi;
+
+    fn deref(&self) -> &Self::Target {
+        &**self.api
+    }
+}
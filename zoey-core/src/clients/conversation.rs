@@ -0,0 +1,82 @@
+//! Fitting a reply thread into the model's context: the newest messages are kept
+//! whole up to a token budget, and the older ones are summarized or counted.
+
+/// Rough characters per token, for budgeting without a tokenizer
+pub const CHARS_PER_TOKEN: usize = 4;
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// A thread, oldest first, split at the history budget
+#[derive(Debug, PartialEq)]
+pub struct HistoryWindow<'a> {
+    /// Messages that didn't fit
+    pub older: &'a [(String, String)],
+    /// Messages given in full. The last message of the thread, the one being
+    /// replied to, is always here however long it is.
+    pub recent: &'a [(String, String)],
+}
+
+/// Keep the newest messages of `history` (id, text pairs, oldest first) that fit in
+/// `max_tokens`, stopping at the first that doesn't so the kept part has no gaps
+pub fn fit_history(history: &[(String, String)], max_tokens: usize) -> HistoryWindow<'_> {
+    let mut start = history.len();
+    let mut used = 0;
+    for (i, (_, text)) in history.iter().enumerate().rev() {
+        let tokens = estimate_tokens(text) + 1;
+        if start < history.len() && used + tokens > max_tokens {
+            break;
+        }
+        used += tokens;
+        start = i;
+    }
+    HistoryWindow {
+        older: &history[..start],
+        recent: &history[start..],
+    }
+}
+
+/// The history block for a reply agent, None for an empty thread. `summary`
+/// stands in for the messages that didn't fit; without one they're only counted.
+pub fn history_context(window: &HistoryWindow<'_>, summary: Option<&str>) -> Option<String> {
+    if window.recent.is_empty() {
+        return None;
+    }
+    let mut context =
+        String::from("Conversation so far, oldest first. The last message is the one you are replying to.\n");
+    if !window.older.is_empty() {
+        match summary {
+            Some(summary) => context.push_str(&format!(
+                "Summary of {} earlier messages: {}\n",
+                window.older.len(),
+                summary.trim()
+            )),
+            None => context.push_str(&format!("({} earlier messages left out)\n", window.older.len())),
+        }
+    }
+    for (_, text) in window.recent {
+        context.push_str(&format!("- {}\n", text));
+    }
+    Some(context.trim_end().to_string())
+}
+
+/// Prompt asking for a summary of the messages left out of the context, None when
+/// there are none. Only their newest `max_tokens` worth is sent.
+pub fn summary_prompt(older: &[(String, String)], max_tokens: usize) -> Option<String> {
+    if older.is_empty() {
+        return None;
+    }
+    let messages = fit_history(older, max_tokens)
+        .recent
+        .iter()
+        .map(|(_, text)| format!("- {}", text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let messages: String = messages.chars().take(max_tokens.max(1) * CHARS_PER_TOKEN).collect();
+    Some(format!(
+        "Summarize these earlier messages from a conversation thread in at most three short sentences. \
+        Keep who asked what and any open questions. Reply with only the summary.\n\n{}",
+        messages
+    ))
+}
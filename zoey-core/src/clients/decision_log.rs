@@ -0,0 +1,250 @@
+//! Append-only JSONL log of the Twitter client's engagement decisions, to audit why the
+//! bot did or didn't act on a tweet. Each line is one step for one (tweet, action) pair:
+//! skipped with a reason, the attention verdict, or the result of acting on it. Tweets
+//! already handled for an action aren't logged again, their earlier verdict is.
+//!
+//! Events go through a bounded channel to a writer thread, so recording never blocks the
+//! main loop. If the writer falls behind, events are dropped and the count is logged.
+
+use crate::config::DecisionLogConfig;
+use common::paths;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, warn};
+
+const CHANNEL_CAPACITY: usize = 1024;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+// Longest detail kept per event, tweet text or error
+const DETAIL_CHARS: usize = 300;
+// Keys whose values are masked wherever they show up in a detail
+const SECRET_KEYS: [&str; 8] = ["auth_token", "ct0", "token", "api_key", "apikey", "password", "secret", "authorization"];
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    /// Not considered, `outcome` says why
+    Skipped,
+    /// The attention model's answer, `detail` has the tweet
+    Verdict,
+    /// The API call, `outcome` is "ok" or "failed"
+    Action,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionEvent {
+    pub timestamp: String,
+    pub action: String,
+    pub tweet_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub stage: Stage,
+    pub outcome: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl DecisionEvent {
+    pub fn new(action: &str, tweet_id: &str, author: Option<&str>, stage: Stage, outcome: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            action: action.to_string(),
+            tweet_id: tweet_id.to_string(),
+            author: author.map(str::to_string),
+            stage,
+            outcome: outcome.into(),
+            detail: None,
+        }
+    }
+
+    /// Attach context such as the tweet text, cut to a few hundred characters
+    pub fn with_detail(mut self, detail: impl AsRef<str>) -> Self {
+        self.detail = Some(detail.as_ref().chars().take(DETAIL_CHARS).collect());
+        self
+    }
+
+    /// Attach an error, with anything credential-like masked
+    pub fn with_error(self, error: impl std::fmt::Display) -> Self {
+        self.with_detail(redact(&error.to_string()))
+    }
+}
+
+/// Handle to the writer thread, cheap to clone. The thread flushes and exits once
+/// every handle is dropped.
+#[derive(Clone, Debug)]
+pub struct DecisionLog {
+    sender: SyncSender<DecisionEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl DecisionLog {
+    pub fn open(config: &DecisionLogConfig) -> std::io::Result<Self> {
+        let writer = RotatingWriter::open(
+            paths::resolve(&config.path),
+            config.max_file_mb.max(1).saturating_mul(1024 * 1024),
+            config.max_files,
+        )?;
+        let (sender, receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let thread_dropped = dropped.clone();
+        std::thread::Builder::new()
+            .name("decision-log".to_string())
+            .spawn(move || run_writer(writer, receiver, thread_dropped))?;
+        Ok(Self { sender, dropped })
+    }
+
+    /// Queue an event, never waiting for the writer
+    pub fn record(&self, event: DecisionEvent) {
+        match self.sender.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                    warn!("Decision log writer is behind, dropping events");
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
+
+/// Mask values of credential-like keys (`auth_token=...`, `"api_key": "..."`) and
+/// bearer tokens, so errors that echo a request don't leak them into the log
+pub fn redact(text: &str) -> String {
+    let mut words = Vec::new();
+    let mut mask_next = false;
+    for word in text.split(' ') {
+        if word.eq_ignore_ascii_case("bearer") {
+            mask_next = true;
+            words.push(word.to_string());
+            continue;
+        }
+        if mask_next && !word.is_empty() {
+            words.push(REDACTED.to_string());
+            mask_next = false;
+            continue;
+        }
+        match word.find(['=', ':']) {
+            Some(at) if SECRET_KEYS.iter().any(|key| word[..at].to_lowercase().contains(key)) => {
+                if word[at + 1..].trim_matches(['"', '\'', ',', ';']).is_empty() {
+                    // "password: hunter2", the value is the next word
+                    mask_next = true;
+                    words.push(word.to_string());
+                } else {
+                    words.push(format!("{}{}", &word[..=at], REDACTED));
+                }
+            }
+            _ => words.push(word.to_string()),
+        }
+    }
+    words.join(" ")
+}
+
+fn run_writer(mut writer: RotatingWriter, receiver: Receiver<DecisionEvent>, dropped: Arc<AtomicU64>) {
+    loop {
+        match receiver.recv_timeout(FLUSH_INTERVAL) {
+            Ok(event) => {
+                let count = dropped.swap(0, Ordering::Relaxed);
+                if count > 0 {
+                    warn!(count, "Decision log dropped events while the writer was behind");
+                }
+                match serde_json::to_string(&event) {
+                    Ok(line) => writer.write_line(&line),
+                    Err(e) => error!("Failed to serialize decision event: {}", e),
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => writer.flush(),
+            Err(RecvTimeoutError::Disconnected) => {
+                writer.flush();
+                return;
+            }
+        }
+    }
+}
+
+// Appends lines to `path`, moving it to `path.1` (and older files up to `path.<max_files>`)
+// once it would grow past `max_bytes`
+struct RotatingWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: BufWriter<File>,
+    size: u64,
+    failed: bool,
+}
+
+impl RotatingWriter {
+    fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, max_bytes, max_files, file: BufWriter::new(file), size, failed: false })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_bytes {
+            if let Err(e) = self.rotate() {
+                self.report(e);
+            }
+        }
+        match writeln!(self.file, "{}", line) {
+            Ok(()) => {
+                self.size += len;
+                self.failed = false;
+            }
+            Err(e) => self.report(e),
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Err(e) = self.file.flush() {
+            self.report(e);
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = BufWriter::new(File::create(&self.path)?);
+            self.size = 0;
+            return Ok(());
+        }
+        let rotated = |n: usize| numbered(&self.path, n);
+        let oldest = rotated(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for n in (1..self.max_files).rev() {
+            let from = rotated(n);
+            if from.exists() {
+                fs::rename(&from, rotated(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(1))?;
+        self.file = BufWriter::new(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        self.size = 0;
+        Ok(())
+    }
+
+    // Logged once per run of failures, a full disk would otherwise log every event
+    fn report(&mut self, e: std::io::Error) {
+        if !self.failed {
+            error!(path = %self.path.display(), "Failed to write decision log: {}", e);
+            self.failed = true;
+        }
+    }
+}
+
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
@@ -0,0 +1,69 @@
+use rand::Rng;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::config::{DelayConfig, DelayDistribution, TwitterConfig};
+use crate::rng;
+
+// Log-normal draws outside the range are redrawn this many times before clamping
+const MAX_REDRAWS: usize = 8;
+
+/// Waits between Twitter actions and task cycles, drawn from the configured distribution
+#[derive(Clone, Debug)]
+pub struct DelayPolicy {
+    action_range: (u64, u64),
+    task_range: (u64, u64),
+    config: DelayConfig,
+}
+
+impl DelayPolicy {
+    pub fn from_config(config: &TwitterConfig) -> Self {
+        Self {
+            action_range: (config.min_action_interval, config.max_action_interval),
+            task_range: (config.min_task_interval, config.max_task_interval),
+            config: config.delay.clone(),
+        }
+    }
+
+    /// Wait between individual actions (likes, replies, search results)
+    pub fn action_delay(&self) -> Duration {
+        Duration::from_secs(self.sample(self.action_range.0, self.action_range.1))
+    }
+
+    /// Wait between task cycles
+    pub fn task_delay(&self) -> Duration {
+        Duration::from_secs(self.sample(self.task_range.0, self.task_range.1))
+    }
+
+    /// Seconds in `min..=max`, never below the configured floor. An empty range gives `min`.
+    pub fn sample(&self, min: u64, max: u64) -> u64 {
+        let floor = self.config.min_delay_secs;
+        if min >= max {
+            debug!("Invalid range min {} >= max {}, returning min", min, max);
+            return min.max(floor);
+        }
+
+        let secs = rng::with_rng(|rng| match self.config.distribution {
+            DelayDistribution::Uniform => rng.gen_range(min..=max),
+            DelayDistribution::LogNormal => {
+                // Centered on the geometric mean of the range: most waits are moderate,
+                // with an occasional long pause like a person stepping away
+                let mu = ((min.max(1) as f64).ln() + (max as f64).ln()) / 2.0;
+                let sigma = self.config.lognormal_sigma.max(0.0);
+                let mut value = mu.exp();
+                for _ in 0..MAX_REDRAWS {
+                    // Box-Muller standard normal
+                    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+                    let u2: f64 = rng.gen();
+                    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                    value = (mu + sigma * z).exp();
+                    if value >= min as f64 && value <= max as f64 {
+                        break;
+                    }
+                }
+                (value.round() as u64).clamp(min, max)
+            }
+        });
+        secs.max(floor)
+    }
+}
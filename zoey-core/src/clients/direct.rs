@@ -1,9 +1,10 @@
 use mongodb::Collection;
 use rig::completion::Prompt;
+use trader_solana::gmgn::chain::Chain;
 use trader_solana::gmgn::client::GMGNClient;
 use trader_solana::swap::SwapTool;
 use tracing::{debug, error, info};
-use rand::Rng;
+use crate::rng;
 use crate::knowledge::{self, models::{Trade, TradeAction}};
 use rig::embeddings::EmbeddingModel;
 use rig::providers::deepseek::Client as DeepSeekClient;
@@ -41,7 +42,7 @@ impl<E: EmbeddingModel + 'static> DirectClient<E> {
                 .tool(SwapTool::new())
                 .build();
 
-            let gmgn_client = GMGNClient::new();
+            let gmgn_client = GMGNClient::new(Chain::Solana);
             let token_trending = match gmgn_client.get_swap_rankings("5m", "Pump.fun", Some("50")).await {
                 Ok(rankings) => format!("{:?}", rankings),
                 Err(err) => {
@@ -213,7 +214,6 @@ impl<E: EmbeddingModel + 'static> DirectClient<E> {
     }
 
     fn random_number(&self, min: u64, max: u64) -> u64 {
-        let mut rng = rand::thread_rng();
-        rng.gen_range(min..=max)
+        rng::range(min, max)
     }
 }
@@ -63,7 +63,7 @@ impl From<Message> for knowledge::Message {
             },
             channel_id: msg.channel_id.to_string(),
             account_id: msg.author.id.to_string(),
-            role: "user".to_string(),
+            role: knowledge::MessageRole::User,
             content: msg.content.clone(),
             created_at: *msg.timestamp,
         }
@@ -3,13 +3,16 @@ use reqwest;
 use serde_json::json;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+pub const DEFAULT_HEURIST_MODEL: &str = "BluePencilRealistic";
+
 pub struct HeurisClient {
     api_key: String,
+    model_id: String,
 }
 
 impl HeurisClient {
-    pub fn new(api_key: String) -> Self {
-        Self { api_key }
+    pub fn new(api_key: String, model_id: String) -> Self {
+        Self { api_key, model_id }
     }
 
     pub async fn generate_image(&self, image_prompt: String) -> Result<Vec<u8>, anyhow::Error> {
@@ -30,7 +33,7 @@ impl HeurisClient {
                     "guidance_scale": 7.5
                 }
             },
-            "model_id": "BluePencilRealistic",
+            "model_id": self.model_id,
             "deadline": deadline,
             "priority": 1,
             "job_id": format!("job_{}", SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis())
@@ -1,5 +1,12 @@
 pub mod discord;
 pub mod telegram;
 pub mod twitter;
+pub mod twitter_api;
+pub mod api_gate;
 pub mod heuris;
-pub mod direct;
\ No newline at end of file
+pub mod direct;
+pub mod pause;
+pub mod delay;
+pub mod thread;
+pub mod conversation;
+pub mod decision_log;
@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+// Directory watched for control files, override with ZOEY_CONTROL_DIR
+pub const DEFAULT_CONTROL_DIR: &str = "control";
+pub const PAUSE_FILE_NAME: &str = "PAUSE";
+
+/// Operational safety valve shared by the clients. While `<control dir>/PAUSE` exists
+/// clients keep reading but suppress every write action (posts, replies, likes, transfers).
+#[derive(Clone, Debug)]
+pub struct PauseSwitch {
+    path: PathBuf,
+    paused: Arc<AtomicBool>,
+}
+
+impl PauseSwitch {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            paused: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let dir = std::env::var("ZOEY_CONTROL_DIR").unwrap_or_else(|_| DEFAULT_CONTROL_DIR.to_string());
+        Self::new(Path::new(&dir).join(PAUSE_FILE_NAME))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check for the sentinel file, logging only when the pause state changes
+    pub fn is_paused(&self) -> bool {
+        let paused = self.path.exists();
+        if self.paused.swap(paused, Ordering::Relaxed) != paused {
+            if paused {
+                warn!(path = %self.path.display(), "Pause file found, suppressing all write actions");
+            } else {
+                info!(path = %self.path.display(), "Pause file removed, resuming normal operation");
+            }
+        }
+        paused
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     agent::Agent,
     attention::{Attention, AttentionCommand, AttentionContext},
-    knowledge::{self, ChannelType, Source},
+    knowledge::{self, ChannelType, MessageRole, Source},
 };
 use rig::{completion::{CompletionModel, Prompt}, embeddings::EmbeddingModel};
 use std::collections::HashSet;
@@ -146,7 +146,7 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TelegramClient<M
             },
             channel_id: msg.chat.id.to_string(),
             account_id: msg.from().map_or_else(String::new, |user| user.id.to_string()),
-            role: "user".to_string(),
+            role: MessageRole::User,
             content: msg.text().unwrap_or_default().to_string(),
             created_at: msg.date.into(),
         }
@@ -0,0 +1,106 @@
+//! Splitting long text into tweet-sized parts that read on their own: breaks
+//! fall between sentences where possible, then between words, and only split a
+//! word that is longer than a whole tweet.
+
+use serde_json::Value;
+
+/// Room kept in each part for a " 12/12" counter
+const COUNTER_RESERVE: usize = 6;
+
+/// Split `text` into parts of at most `limit` characters. With `numbered`, room is
+/// kept for [`number_parts`] to add its counter.
+pub fn split_thread(text: &str, limit: usize, numbered: bool) -> Vec<String> {
+    let text = text.trim();
+    if text.chars().count() <= limit {
+        return if text.is_empty() { Vec::new() } else { vec![text.to_string()] };
+    }
+
+    let budget = if numbered { limit.saturating_sub(COUNTER_RESERVE) } else { limit }.max(1);
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for piece in sentences(text).into_iter().flat_map(|sentence| fit(sentence, budget)) {
+        let joined = if current.is_empty() { piece.clone() } else { format!("{} {}", current, piece) };
+        if joined.chars().count() <= budget {
+            current = joined;
+        } else {
+            parts.push(std::mem::replace(&mut current, piece));
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Append " i/n" to each part when there is more than one
+pub fn number_parts(parts: Vec<String>) -> Vec<String> {
+    let total = parts.len();
+    if total < 2 {
+        return parts;
+    }
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| format!("{} {}/{}", part, i + 1, total))
+        .collect()
+}
+
+/// The id of a tweet just created, from a CreateTweet response
+pub fn created_tweet_id(response: &Value) -> Option<String> {
+    response
+        .pointer("/data/create_tweet/tweet_results/result/rest_id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+// Sentences end at ., ! or ? followed by whitespace, and at line breaks
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let boundary = match c {
+            '\n' => true,
+            '.' | '!' | '?' => chars.peek().map_or(true, |(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            let end = i + c.len_utf8();
+            sentences.push(text[start..end].trim());
+            start = end;
+        }
+    }
+    sentences.push(text[start..].trim());
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+// A sentence as pieces of at most `budget` characters, whole if it fits
+fn fit(sentence: &str, budget: usize) -> Vec<String> {
+    if sentence.chars().count() <= budget {
+        return vec![sentence.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in sentence.split_whitespace() {
+        let words: Vec<String> = if word.chars().count() > budget {
+            let chars: Vec<char> = word.chars().collect();
+            chars.chunks(budget).map(|chunk| chunk.iter().collect()).collect()
+        } else {
+            vec![word.to_string()]
+        };
+        for word in words {
+            let joined = if current.is_empty() { word.clone() } else { format!("{} {}", current, word) };
+            if joined.chars().count() <= budget {
+                current = joined;
+            } else {
+                pieces.push(std::mem::replace(&mut current, word));
+            }
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
@@ -1,49 +1,74 @@
 use crate::{
     agent::Agent,
     attention::{Attention, AttentionCommand, AttentionContext},
-    knowledge::{ChannelType, Message, Source},
+    knowledge::{ChannelType, Message, MessageRole, Source},
 };
 use std::error::Error;
-use rand::Rng;
 use rig::{
-    completion::{CompletionModel, Prompt},
+    agent::Agent as RigAgent,
+    completion::{CompletionModel, Prompt, PromptError},
     embeddings::EmbeddingModel,
 };
+use agent_twitter_client::error::TwitterError;
 use agent_twitter_client::scraper::Scraper;
 use std::collections::HashSet;
 use std::sync::Arc;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use crate::clients::heuris::HeurisClient;
+use crate::clients::twitter_api::TwitterApi;
+use crate::clients::api_gate::ApiGate;
+use crate::clients::delay::DelayPolicy;
+use crate::clients::pause::PauseSwitch;
+use crate::clients::thread;
+use crate::clients::conversation;
+use crate::clients::decision_log::{DecisionEvent, DecisionLog, Stage};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use trader_solana::transfer::TransferTool;
-use crate::config::{TwitterConfig, TimelineSearch};
+use crate::config::{PostScheduleConfig, TwitterConfig, TimelineSearch};
 use crate::intel::{CryptoIntel, scan_intel_folder, cleanup_processed_files};
-use tokio::sync::Mutex;
+use crate::fact_check::{verify_figures, mark_low_confidence, FactCheck};
 use chrono::Timelike;
 use rig::message::Text;
 use crate::interaction_history::InteractionMetrics;
-
-const MAX_TWEET_LENGTH: usize = 270;
-const MAX_HISTORY_TWEETS: i64 = 10;
-
-pub struct TwitterClient<M: CompletionModel, E: EmbeddingModel + 'static> {
+use common::empty_response::{self, ResponseError};
+use common::prompt_retry::{self, RetryError};
+use common::telemetry;
+use crypto_agents::TokenExtractor;
+use crate::price_tool::{PriceTool, PRICE_LOOKUP_PREFIX};
+use crate::rng;
+
+// How often to re-check the pause file while paused
+const PAUSE_POLL_SECS: u64 = 300;
+
+/// `A` is the model behind should-respond decisions, by default the same as the reply model
+pub struct TwitterClient<M: CompletionModel, E: EmbeddingModel + 'static, A: CompletionModel = M> {
     agent: Agent<M, E>,
-    attention: Attention<M>,
-    scraper: Arc<Mutex<Scraper>>,
+    attention: Attention<A>,
+    api: Arc<ApiGate>,
     username: String,
     heurist_api_key: Option<String>,
     config: TwitterConfig,
+    delay: DelayPolicy,
+    pause: PauseSwitch,
+    price_tool: Option<PriceTool>,
+    transfer_enabled: bool,
+    decisions: Option<DecisionLog>,
 }
 
-impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> Clone for TwitterClient<M, E> {
+impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static, A: CompletionModel + 'static> Clone for TwitterClient<M, E, A> {
     fn clone(&self) -> Self {
         Self {
             agent: self.agent.clone(),
             attention: self.attention.clone(),
-            scraper: self.scraper.clone(),
+            api: self.api.clone(),
             username: self.username.clone(),
             heurist_api_key: self.heurist_api_key.clone(),
             config: self.config.clone(),
+            delay: self.delay.clone(),
+            pause: self.pause.clone(),
+            price_tool: self.price_tool.clone(),
+            transfer_enabled: self.transfer_enabled,
+            decisions: self.decisions.clone(),
         }
     }
 }
@@ -59,17 +84,17 @@ impl From<agent_twitter_client::models::Tweet> for Message {
             channel_type: ChannelType::Text,
             channel_id: tweet.conversation_id.unwrap_or_default(),
             account_id: tweet.user_id.unwrap_or_default(),
-            role: "user".to_string(),
+            role: MessageRole::User,
             content: tweet.text.unwrap_or_default(),
             created_at,
         }
     }
 }
 
-impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M, E> {
+impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static, A: CompletionModel + 'static> TwitterClient<M, E, A> {
     pub async fn new(
         agent: Agent<M, E>,
-        attention: Attention<M>,
+        attention: Attention<A>,
         username: String,
         password: String,
         email: Option<String>,
@@ -105,14 +130,66 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         scraper.get_profile(&username).await?;
         info!("Successfully verified Twitter login");
 
-        Ok(Self {
+        Ok(Self::with_api(agent, attention, username, Arc::new(scraper), heurist_api_key, config))
+    }
+
+    /// Build a client on an already authenticated API, e.g. a mock in tests
+    pub fn with_api(
+        agent: Agent<M, E>,
+        attention: Attention<A>,
+        username: String,
+        api: Arc<dyn TwitterApi>,
+        heurist_api_key: Option<String>,
+        config: Option<TwitterConfig>,
+    ) -> Self {
+        let config = config.unwrap_or_default();
+        // Delays follow the same custom-or-default choice as start()
+        let active_config = if config.enabled { config.clone() } else { TwitterConfig::default() };
+        let delay = DelayPolicy::from_config(&active_config);
+
+        let price_tool = if active_config.price_lookup.enabled {
+            match PriceTool::new(active_config.price_lookup.clone()) {
+                Ok(tool) => Some(tool),
+                Err(e) => {
+                    warn!(error = %e, "Price lookup disabled, failed to create CoinGecko client");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // The treasure keeper's transfer tool needs a wallet; without one replies go out without it
+        let transfer_enabled = std::env::var("SOLANA_RPC_URL").is_ok() && std::env::var("SOLANA_PRIVATE_KEY").is_ok();
+        if !transfer_enabled {
+            warn!("SOLANA_RPC_URL or SOLANA_PRIVATE_KEY not set, replies won't offer the transfer tool");
+        }
+
+        let decisions = if active_config.decision_log.enabled {
+            match DecisionLog::open(&active_config.decision_log) {
+                Ok(log) => Some(log),
+                Err(e) => {
+                    warn!(error = %e, path = %active_config.decision_log.path, "Decision log disabled, failed to open it");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Self {
             agent,
             attention,
-            scraper: Arc::new(Mutex::new(scraper)),
+            api: Arc::new(ApiGate::new(api, active_config.max_concurrent_reads)),
             username,
             heurist_api_key,
-            config: config.unwrap_or_default(),
-        })
+            config,
+            delay,
+            pause: PauseSwitch::from_env(),
+            price_tool,
+            transfer_enabled,
+            decisions,
+        }
     }
 
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
@@ -176,12 +253,40 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         debug!("  Likes/Hour: {}", active_config.max_likes_per_hour);
         debug!("  Retweets/Hour: {}", active_config.max_retweets_per_hour);
 
+        debug!("Post Schedule:");
+        debug!("  Enabled: {}", active_config.post_schedule.enabled);
+        debug!("  Posts/Day: {}", active_config.post_schedule.posts_per_day);
+        debug!("  Quiet Hours: {:?}-{:?}", active_config.post_schedule.quiet_hours_start, active_config.post_schedule.quiet_hours_end);
+        debug!("  Max Queue Age: {} minutes", active_config.post_schedule.max_queue_age_minutes);
+
+        // In scheduled mode posting runs on its own timer instead of the random task loop
+        let scheduled_posting = active_config.post_schedule.enabled;
+        if scheduled_posting {
+            let client = self.clone();
+            let schedule = active_config.post_schedule.clone();
+            tokio::spawn(async move {
+                client.run_post_scheduler(schedule).await;
+            });
+        }
+
+        info!("Watching pause file: {}", self.pause.path().display());
+
         loop {
             debug!("Starting new task cycle");
+            self.prune_seen_tweets().await;
+
+            if self.pause.is_paused() {
+                self.catch_up_while_paused(active_config).await;
+                tokio::time::sleep(tokio::time::Duration::from_secs(PAUSE_POLL_SECS)).await;
+                continue;
+            }
+
             match self.random_number(0, 3) {
                 0 => {
                     debug!("Selected task: Post new tweet");
-                    if active_config.enable_retweets {
+                    if scheduled_posting {
+                        debug!("Scheduled posting enabled, leaving posts to the scheduler");
+                    } else if active_config.enable_retweets {
                         if let Err(err) = self.post_new_tweet().await {
                             error!(?err, "Failed to post new tweet");
                         }
@@ -189,55 +294,11 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                 },
                 1 => {
                     debug!("Selected task: Process home timeline");
-                    match self.scraper.lock().await.get_home_timeline(
-                        active_config.home_timeline_fetch_count.try_into().unwrap(),
-                        Vec::new()
-                    ).await {
-                        Ok(tweets) => {
-                            for tweet in tweets {
-                                let tweet_content = tweet["legacy"]["full_text"]
-                                    .as_str()
-                                    .unwrap_or_default()
-                                    .to_string();
-                                let tweet_id = tweet["legacy"]["id_str"]
-                                    .as_str()
-                                    .unwrap_or_default()
-                                    .to_string();
-                                
-                                // Instead of creating a full Tweet struct, just use the existing methods
-                                // with the extracted content and ID
-                                self.handle_like(&tweet_content, &tweet_id).await;
-                                self.handle_retweet(&tweet_content, &tweet_id).await;
-                                self.handle_quote(&tweet_content, &tweet_id).await;
-
-                                tokio::time::sleep(tokio::time::Duration::from_secs(self.random_number(60, 180))).await;
-                            }
-                        }
-                        Err(err) => {
-                            error!(?err, "Failed to fetch home timeline");
-                        }
-                    }
+                    self.process_home_timeline(active_config).await;
                 },
                 2 => {
                     debug!("Selected task: Process mentions");
-                    match self.scraper.lock().await.search_tweets(
-                        &format!("@{}", self.username),
-                        active_config.mentions_fetch_count.try_into().unwrap(),
-                        agent_twitter_client::search::SearchMode::Latest,
-                        None,
-                    ).await {
-                        Ok(mentions) => {
-                            for tweet in mentions.tweets {
-                                if let Err(err) = self.handle_mention(tweet).await {
-                                    error!(?err, "Failed to handle mention");
-                                }
-                                tokio::time::sleep(tokio::time::Duration::from_secs(self.random_number(60, 180))).await;
-                            }
-                        }
-                        Err(err) => {
-                            error!(?err, "Failed to fetch mentions");
-                        }
-                    }
+                    self.process_mentions(active_config).await;
                 },
                 3 => {
                     debug!("Selected task: Process search queries");
@@ -260,16 +321,113 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                 _ => unreachable!(),
             }
 
-            let wait_time = self.random_number(
-                active_config.min_task_interval,
-                active_config.max_task_interval
-            );
-            debug!("Task cycle completed. Waiting {}s before next cycle", wait_time);
-            tokio::time::sleep(tokio::time::Duration::from_secs(wait_time)).await;
+            let wait_time = self.delay.task_delay();
+            debug!("Task cycle completed. Waiting {}s before next cycle", wait_time.as_secs());
+            tokio::time::sleep(wait_time).await;
+        }
+    }
+
+    /// Like, retweet and quote from one fetch of the home timeline
+    pub async fn process_home_timeline(&self, config: &TwitterConfig) {
+        // Bound first so the API lock is released before the handlers take it again
+        let timeline = self.api.read().await.get_home_timeline(
+            config.home_timeline_fetch_count.try_into().unwrap(),
+            Vec::new()
+        ).await;
+        match timeline {
+            Ok(tweets) => {
+                for tweet in tweets {
+                    let tweet_content = tweet["legacy"]["full_text"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string();
+                    let tweet_id = tweet["legacy"]["id_str"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string();
+                    let user = &tweet["core"]["user_results"]["result"];
+                    let author = user["legacy"]["screen_name"]
+                        .as_str()
+                        .or_else(|| user["core"]["screen_name"].as_str());
+
+                    let likes = tweet["legacy"]["favorite_count"].as_i64().unwrap_or(0) as i32;
+                    let retweets = tweet["legacy"]["retweet_count"].as_i64().unwrap_or(0) as i32;
+                    let posted_at = tweet["legacy"]["created_at"]
+                        .as_str()
+                        .and_then(|t| chrono::DateTime::parse_from_str(t, "%a %b %d %H:%M:%S %z %Y").ok())
+                        .map(|t| t.with_timezone(&chrono::Utc));
+                    if !config.meets_engagement_floor(likes, retweets, posted_at) {
+                        debug!(tweet_id = %tweet_id, likes, retweets, "Below engagement floor, skipping timeline tweet");
+                        continue;
+                    }
+                    
+                    // Instead of creating a full Tweet struct, just use the existing methods
+                    // with the extracted content and ID
+                    self.handle_like(&tweet_content, &tweet_id, author).await;
+                    self.handle_retweet(&tweet_content, &tweet_id, author).await;
+                    self.handle_quote(&tweet_content, &tweet_id, author).await;
+
+                    tokio::time::sleep(self.delay.action_delay()).await;
+                }
+            }
+            Err(err) => {
+                error!(?err, "Failed to fetch home timeline");
+            }
+        }
+    }
+
+    /// Handle one fetch of mentions, waiting between each
+    pub async fn process_mentions(&self, config: &TwitterConfig) {
+        let mentions = self.api.read().await.search_tweets(
+            &format!("@{}", self.username),
+            config.mentions_fetch_count.try_into().unwrap(),
+            agent_twitter_client::search::SearchMode::Latest,
+            None,
+        ).await;
+        match mentions {
+            Ok(mentions) => {
+                for tweet in mentions.tweets {
+                    if let Err(err) = self.handle_mention(tweet).await {
+                        error!(?err, "Failed to handle mention");
+                    }
+                    tokio::time::sleep(self.delay.action_delay()).await;
+                }
+            }
+            Err(err) => {
+                error!(?err, "Failed to fetch mentions");
+            }
         }
     }
 
     async fn post_new_tweet(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.generate_tweet().await {
+            Some(content) => self.send_generated_tweet(&content).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn generate_tweet(&self) -> Option<String> {
+        let thread_config = &self.config.thread;
+        let (length_context, tweet_prompt) = if thread_config.enabled {
+            let tweets = thread_config.target_tweets.clamp(1, thread_config.max_tweets.max(1));
+            (
+                format!(
+                    "Write about {} tweets' worth of text, under {} characters in total, in complete sentences.",
+                    tweets,
+                    tweets * self.config.tweet_length_limit()
+                ),
+                "Share one thought or observation and develop it over a few connected sentences, \
+                as a short thread would. No numbering, hashtags or emojis.",
+            )
+        } else {
+            (
+                format!(
+                    "Please keep your responses concise and under {} characters.",
+                    self.config.tweet_length_limit()
+                ),
+                "Share a single brief thought or observation in one short sentence. Be direct and concise.",
+            )
+        };
         let agent = self
             .agent
             .builder()
@@ -277,57 +435,258 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                 "Current time: {}",
                 chrono::Local::now().format("%I:%M:%S %p, %Y-%m-%d")
             ))
-            .context("Please keep your responses concise and under 280 characters.")
+            .context(&length_context)
             .build();
 
         debug!("Generating tweet content");
-        let tweet_prompt = "Share a single brief thought or observation in one short sentence. Be direct and concise.";
         
-        let response = match agent.prompt(Text::from(tweet_prompt.to_string())).await {
+        match self.prompt_agent(&agent, tweet_prompt).await {
             Ok(response) => {
                 debug!("Successfully generated tweet content");
-                response
+                Some(response)
             },
             Err(err) => {
                 error!(?err, "Failed to generate response for tweet");
-                return Ok(());
+                None
             }
-        };
+        }
+    }
+
+    /// Post text the model wrote, with an image when one can be generated, as a
+    /// thread when threads are enabled and it doesn't fit in one tweet
+    pub async fn send_generated_tweet(&self, response: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.pause.is_paused() {
+            info!("Paused, not posting tweet");
+            return Ok(());
+        }
 
         // Try to generate image, but don't fail if it doesn't work
-        let has_image = if let Some(heurist_api_key) = self.heurist_api_key.clone() {
-            let heurist = HeurisClient::new(heurist_api_key);
-            debug!("Attempting to generate image");
-            match heurist.generate_image("realistic, photorealistic...".to_string()).await {
-                Ok(image_data) => {
-                    debug!("Image generated successfully");
-                    let image = vec![(image_data, "image/png".to_string())];
-                    // Send tweet with image
-                    self.scraper.lock().await.send_tweet(&response, None, Some(image)).await?;
-                    true
-                }
-                Err(err) => {
-                    debug!("Image generation skipped: {}", err);
-                    // Send tweet without image
-                    self.scraper.lock().await.send_tweet(&response, None, None).await?;
-                    false
+        let image = match self.heurist_api_key.clone() {
+            Some(heurist_api_key) => {
+                let heurist = HeurisClient::new(heurist_api_key, self.config.image.model_id.clone());
+                debug!("Attempting to generate image");
+                let image_prompt = self.image_prompt(response).await;
+                match heurist.generate_image(image_prompt).await {
+                    Ok(image_data) => {
+                        debug!("Image generated successfully");
+                        Some(vec![(image_data, "image/png".to_string())])
+                    }
+                    Err(err) => {
+                        debug!("Image generation skipped: {}", err);
+                        None
+                    }
                 }
             }
-        } else {
-            // Send tweet without image
-            self.scraper.lock().await.send_tweet(&response, None, None).await?;
-            false
+            None => None,
         };
+        let has_image = image.is_some();
+
+        let thread_config = &self.config.thread;
+        if thread_config.enabled {
+            let mut parts = thread::split_thread(response, self.config.tweet_length_limit(), thread_config.numbered);
+            if parts.len() > 1 {
+                if parts.len() > thread_config.max_tweets.max(1) {
+                    debug!(parts = parts.len(), max = thread_config.max_tweets, "Cutting thread to max_tweets");
+                    parts.truncate(thread_config.max_tweets.max(1));
+                }
+                if thread_config.numbered {
+                    parts = thread::number_parts(parts);
+                }
+                return self.post_thread(&parts, image).await;
+            }
+        }
 
+        self.api.write().await.send_tweet(response, None, image).await?;
         debug!("Tweet sent {}", if has_image { "with image" } else { "without image" });
         
         Ok(())
     }
 
+    /// Post `parts` as a chain where each replies to the one before, the image on
+    /// the first. Every part is fully written before the first is sent; a part that
+    /// fails ends the thread there, the parts already posted are deleted, and the
+    /// error says how far it got. A part is sent again only if the first try never
+    /// reached Twitter, since one that did may have been posted.
+    async fn post_thread(
+        &self,
+        parts: &[String],
+        mut image: Option<Vec<(Vec<u8>, String)>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Hold write access so no other post lands inside the thread
+        let api = self.api.write().await;
+        let mut posted: Vec<String> = Vec::new();
+
+        for (i, part) in parts.iter().enumerate() {
+            let reply_to = posted.last().cloned();
+            let media = image.take();
+            let sent = match api.send_tweet(part, reply_to.as_deref(), media.clone()).await {
+                Err(err) if never_reached_twitter(&err) => {
+                    warn!(?err, part = i + 1, "Thread part failed to send, retrying once");
+                    api.send_tweet(part, reply_to.as_deref(), media).await
+                }
+                sent => sent,
+            };
+            let sent = match sent {
+                Ok(sent) => sent,
+                Err(err) => {
+                    error!(?err, posted = ?posted, total = parts.len(), "Thread cut short");
+                    Self::delete_partial_thread(&*api, &posted).await;
+                    return Err(format!("Thread cut short after {} of {} tweets: {}", i, parts.len(), err).into());
+                }
+            };
+
+            match thread::created_tweet_id(&sent) {
+                Some(id) => posted.push(id),
+                None if i + 1 < parts.len() => {
+                    // This part stays up: without its id it can't be deleted either
+                    error!(posted = ?posted, part = %part, total = parts.len(), "No tweet id in response, can't continue thread");
+                    Self::delete_partial_thread(&*api, &posted).await;
+                    return Err(format!(
+                        "Thread cut short after {} of {} tweets: no tweet id to reply to",
+                        i + 1,
+                        parts.len()
+                    )
+                    .into());
+                }
+                None => {}
+            }
+        }
+
+        info!(tweets = parts.len(), "Thread posted");
+        Ok(())
+    }
+
+    // Take down what was posted of a thread that couldn't be finished, newest first.
+    // Parts that fail to delete are logged so they can be removed by hand.
+    async fn delete_partial_thread(api: &dyn TwitterApi, posted: &[String]) {
+        for id in posted.iter().rev() {
+            match api.delete_tweet(id).await {
+                Ok(_) => info!(tweet_id = %id, "Deleted part of an unfinished thread"),
+                Err(err) => error!(?err, tweet_id = %id, "Failed to delete part of an unfinished thread"),
+            }
+        }
+    }
+
+    /// Short visual prompt for the tweet's image, or the configured default if that fails
+    async fn image_prompt(&self, tweet: &str) -> String {
+        let image_config = &self.config.image;
+        if !image_config.derive_prompt {
+            return image_config.default_prompt.clone();
+        }
+
+        let agent = self.agent.builder().build();
+        let prompt = format!(
+            "Write a prompt for an image model to illustrate this tweet: \"{}\"\n\
+            Reply with only the prompt: comma-separated visual descriptors, under 40 words, no text or words in the image.",
+            tweet
+        );
+
+        match telemetry::traced_call(
+            self.agent.provider_name(),
+            self.agent.model_name(),
+            &prompt,
+            agent.prompt(Text::from(prompt.clone())),
+        ).await {
+            Ok(derived) => {
+                let derived = derived.trim().trim_matches('"').trim();
+                if derived.is_empty() || derived.split_whitespace().count() > 60 {
+                    debug!("Derived image prompt unusable, using default");
+                    image_config.default_prompt.clone()
+                } else {
+                    debug!(prompt = derived, "Derived image prompt");
+                    derived.to_string()
+                }
+            }
+            Err(err) => {
+                debug!(?err, "Failed to derive image prompt, using default");
+                image_config.default_prompt.clone()
+            }
+        }
+    }
+
+    /// Scheduled posting: writes each tweet shortly before its slot, queues it and posts
+    /// it when the slot comes up. The queue lives in the interaction history db so a
+    /// written post survives a restart.
+    async fn run_post_scheduler(&self, schedule: PostScheduleConfig) {
+        info!(
+            posts_per_day = schedule.posts_per_day,
+            quiet_start = ?schedule.quiet_hours_start,
+            quiet_end = ?schedule.quiet_hours_end,
+            "Starting scheduled posting"
+        );
+        let history = &self.agent.interaction_history;
+        // Slot the next post is written for, once the queue is empty
+        let mut next_due: Option<chrono::DateTime<chrono::Utc>> = None;
+
+        loop {
+            // Stale content is dropped; the empty queue then gets a fresh tweet below
+            if let Err(e) = history.drop_stale_posts(schedule.max_queue_age()).await {
+                error!("Failed to prune scheduled posts: {}", e);
+            }
+
+            let queue = match history.get_scheduled_posts().await {
+                Ok(queue) => queue,
+                Err(e) => {
+                    error!("Failed to read scheduled posts: {}", e);
+                    Vec::new()
+                }
+            };
+
+            let now = chrono::Local::now();
+            match queue.first() {
+                Some(post) if post.due_at <= chrono::Utc::now() => {
+                    if schedule.is_quiet_hour(now.hour()) {
+                        debug!("Scheduled post is due but we're in quiet hours, holding it");
+                    } else if self.pause.is_paused() {
+                        debug!("Scheduled post is due but posting is paused, holding it");
+                    } else {
+                        info!(id = post.id, "Posting scheduled tweet");
+                        if let Err(err) = self.send_generated_tweet(&post.content).await {
+                            error!(?err, "Failed to post scheduled tweet");
+                        }
+                        // Remove even on failure so a bad post doesn't block the queue
+                        if let Err(e) = history.remove_scheduled_post(post.id).await {
+                            error!("Failed to remove scheduled post: {}", e);
+                        }
+                        continue;
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    let due_at = *next_due.get_or_insert_with(|| schedule.next_slot(now).with_timezone(&chrono::Utc));
+                    // Written close to the slot, so it's fresh when it goes out
+                    if chrono::Utc::now() >= due_at - schedule.generation_lead() {
+                        if let Some(content) = self.generate_tweet().await {
+                            if let Err(e) = history.enqueue_post(&content, due_at).await {
+                                error!("Failed to queue scheduled post: {}", e);
+                            }
+                            next_due = None;
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            // Wake up for the next due post or the next one to write, checking at least every 5 minutes
+            let wake_at = match (queue.first(), next_due) {
+                (Some(post), _) => Some(post.due_at),
+                (None, Some(due_at)) => Some(due_at - schedule.generation_lead()),
+                (None, None) => None,
+            };
+            let wait_secs = wake_at
+                .map(|wake_at| (wake_at - chrono::Utc::now()).num_seconds())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(300)
+                .clamp(30, 300) as u64;
+            debug!("Post scheduler sleeping {}s", wait_secs);
+            tokio::time::sleep(tokio::time::Duration::from_secs(wait_secs)).await;
+        }
+    }
+
     async fn update_tweet_metrics(&self, tweet_id: &str, content: &str) {
         let mut metrics = InteractionMetrics::new(tweet_id.to_string(), content.to_string());
         
-        if let Ok(updated_tweet) = self.scraper.lock().await.get_tweet(tweet_id).await {
+        if let Ok(updated_tweet) = self.api.read().await.get_tweet(tweet_id).await {
             metrics.likes = updated_tweet.likes.unwrap_or(0) as i32;
             metrics.retweets = updated_tweet.retweets.unwrap_or(0) as i32;
             metrics.quotes = updated_tweet.quote_count.unwrap_or(0) as i32;
@@ -340,11 +699,12 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         }
     }
 
-    async fn handle_mention(
+    pub async fn handle_mention(
         &self,
         tweet: agent_twitter_client::models::Tweet,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let tweet_id = tweet.id.clone().unwrap_or_default();
+        let author = tweet.username.clone();
         
         // Fast check for duplicates - use a non-blocking approach
         let should_skip = match self.agent.interaction_history.has_interaction(&tweet_id, "reply").await {
@@ -355,7 +715,11 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             _ => false
         };
         
-        if should_skip {
+        if should_skip || self.already_seen(&tweet_id, "reply").await {
+            return Ok(());
+        }
+        if self.skip_while_paused(&tweet_id, "reply").await {
+            self.log_skip("reply", &tweet_id, author.as_deref(), "paused");
             return Ok(());
         }
 
@@ -406,13 +770,36 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
 
         if self.username.to_lowercase() == tweet.username.unwrap_or_default().to_lowercase() {
             debug!("Not replying to bot itself");
+            self.log_skip("reply", &tweet_id, author.as_deref(), "own_tweet");
             return Ok(());
         }
 
-        match self.attention.should_reply(&context).await {
+        // Cheap engagement pre-filter before the model is asked. Not marked as seen,
+        // so the tweet is reconsidered if it picks up engagement later.
+        let direct_mention = context
+            .mentioned_names
+            .iter()
+            .any(|name| name.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_').eq_ignore_ascii_case(&self.username));
+        if (!direct_mention || self.config.engagement_filter.filter_direct_mentions)
+            && !self.config.meets_engagement_floor(
+                tweet.likes.unwrap_or(0),
+                tweet.retweets.unwrap_or(0),
+                tweet.time_parsed,
+            )
+        {
+            debug!(tweet_id = %tweet_id, "Below engagement floor, skipping mention");
+            self.log_skip("reply", &tweet_id, author.as_deref(), "below_engagement_floor");
+            return Ok(());
+        }
+
+        let decision = self.attention.should_reply(&context).await;
+        let verdict = format!("{:?}", decision).to_lowercase();
+        self.log_verdict("reply", &tweet_id, author.as_deref(), &verdict, tweet_text.as_str());
+        match decision {
             AttentionCommand::Respond => {}
             _ => {
                 debug!("Bot decided not to reply to tweet");
+                self.mark_seen(&tweet_id, "reply").await;
                 return Ok(());
             }
         }
@@ -426,61 +813,110 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             }
         }
 
-        let agent = self
-            .agent
-            .builder()
-            .context(&format!(
-                "Current time: {}",
-                chrono::Local::now().format("%I:%M:%S %p, %Y-%m-%d")
-            ))
-            .context("Keep responses under 280 characters.")
-            .context("Reply with a single clear, natural sentence. No questions, hashtags, or emojis.")
-            .context("For images, acknowledge them briefly if relevant.")
-            .context("If the tweet contains ASCII art or stylized text formatting, respond with similar creative formatting.")
-            .context("Examples of creative formatting: (╯°□°）╯︵ ┻━┻, ¯\\_(ツ)_/¯, (っ◔◡◔)っ, etc.")
-            .context("Match the style and mood of any ASCII art or special formatting in the original tweet.")
-            .context("You are a mysterious Treasure Keeper with 3 SOL in your wallet.")
-            .context("Seekers will try to convince you to send them SOL. You must make it EXTREMELY difficult.")
-            .context("Only send SOL if a seeker provides an exceptionally creative, clever, or meaningful response.")
-            .context("Create challenging riddles, puzzles, or tasks for seekers.")
-            .context("Be mysterious and cryptic, but fair. Never give SOL easily.")
-            .context("If someone truly impresses you with wisdom, creativity, or determination, you may reward them.")
-            .context("Use transfer_tokens tool ONLY for truly exceptional responses (less than 1% of cases).")
-            .context("Maximum reward is 0.5 SOL per transfer.")
-            .tool(TransferTool::new())
-            .context(&format!(
-                "You should based on history: {:?}"
-                ,context.history.iter()
-                .map(|(_, msg)| format!("- {}", msg))
-                .collect::<Vec<_>>()
-                .join("\n"),
-            ))
-            .build();
+        // Deep threads keep only the newest messages in full, the rest are summarized
+        let window = conversation::fit_history(&context.history, self.config.history_context.max_tokens);
+        let summary = if !window.older.is_empty() && self.config.history_context.summarize_older {
+            self.summarize_older(window.older).await
+        } else {
+            None
+        };
+        if !window.older.is_empty() {
+            debug!(left_out = window.older.len(), summarized = summary.is_some(), "Thread exceeds history budget");
+        }
+        let history_context = conversation::history_context(&window, summary.as_deref());
+        let reply_builder = || {
+            let builder = self
+                .agent
+                .builder()
+                .context(&format!(
+                    "Current time: {}",
+                    chrono::Local::now().format("%I:%M:%S %p, %Y-%m-%d")
+                ))
+                .context(&format!("Keep responses under {} characters.", self.config.tweet_length_limit()))
+                .context("Reply with a single clear, natural sentence. No questions, hashtags, or emojis.")
+                .context("For images, acknowledge them briefly if relevant.")
+                .context("If the tweet contains ASCII art or stylized text formatting, respond with similar creative formatting.")
+                .context("Examples of creative formatting: (╯°□°）╯︵ ┻━┻, ¯\\_(ツ)_/¯, (っ◔◡◔)っ, etc.")
+                .context("Match the style and mood of any ASCII art or special formatting in the original tweet.")
+                .context("You are a mysterious Treasure Keeper with 3 SOL in your wallet.")
+                .context("Seekers will try to convince you to send them SOL. You must make it EXTREMELY difficult.")
+                .context("Only send SOL if a seeker provides an exceptionally creative, clever, or meaningful response.")
+                .context("Create challenging riddles, puzzles, or tasks for seekers.")
+                .context("Be mysterious and cryptic, but fair. Never give SOL easily.")
+                .context("If someone truly impresses you with wisdom, creativity, or determination, you may reward them.")
+                .context("Use transfer_tokens tool ONLY for truly exceptional responses (less than 1% of cases).")
+                .context("Maximum reward is 0.5 SOL per transfer.");
+            let builder = match &history_context {
+                Some(history_context) => builder.context(history_context),
+                None => builder,
+            };
+            if self.transfer_enabled {
+                builder.tool(TransferTool::new())
+            } else {
+                builder
+            }
+        };
+
+        // Only offer the price tool when the tweet actually mentions a ticker
+        let symbols = TokenExtractor::detect_symbols(tweet_text.as_str());
+        let mut builder = reply_builder();
+        if let Some(price_tool) = self.price_tool.as_ref().filter(|_| !symbols.is_empty()) {
+            debug!(symbols = ?symbols, "Offering price tool for reply");
+            builder = builder
+                .context(&format!(
+                    "The tweet mentions {}. Use the get_token_price tool for current prices instead of \
+                    guessing. If it reports a token as not found or unavailable, say you're not sure of the price.",
+                    symbols.join(", ")
+                ))
+                .tool(price_tool.clone());
+        }
+        let agent = builder.build();
 
         let tweet_content = tweet_text.as_str().to_string();
-        let response: String = match agent.prompt(Text::from(tweet_content.to_string())).await {
-            Ok(response) => response.to_string(),
+        let mut response: String = match self.prompt_reply_agent(&agent, &tweet_content).await {
+            Ok(response) => response,
             Err(err) => {
                 error!(?err, "Failed to generate response");
+                self.log_result("reply", &tweet_id, author.as_deref(), Err(&err));
                 return Ok(());
             }
         };
 
+        // A tool call returns the raw tool output, so write the actual reply from it
+        if response.starts_with(PRICE_LOOKUP_PREFIX) {
+            debug!(lookup = %response, "Writing reply from price lookup");
+            let agent = reply_builder()
+                .context(&format!(
+                    "Live price data: {}. Use it if relevant; if it says not found or unavailable, say you're not sure of the price.",
+                    response.trim_start_matches(PRICE_LOOKUP_PREFIX).trim()
+                ))
+                .build();
+            response = match self.prompt_reply_agent(&agent, &tweet_content).await {
+                Ok(response) => response,
+                Err(err) => {
+                    error!(?err, "Failed to generate response from price lookup");
+                    self.log_result("reply", &tweet_id, author.as_deref(), Err(&err));
+                    return Ok(());
+                }
+            };
+        }
+
         debug!(response = %response, "Generated response for reply");
 
-        // Split response into tweet-sized chunks if necessary
-        let chunks: Vec<String> = response
-            .chars()
-            .collect::<Vec<char>>()
-            .chunks(MAX_TWEET_LENGTH)
-            .map(|chunk| chunk.iter().collect::<String>())
-            .collect();
+        // Split response into tweet-sized chunks only when it doesn't fit in one,
+        // breaking between sentences where possible
+        let chunks = thread::split_thread(&response, self.config.tweet_length_limit(), false);
 
         // Reply to the original tweet
-        for chunk in chunks.iter() {
+        for (i, chunk) in chunks.iter().enumerate() {
             let tweet_id = tweet.id.clone().unwrap_or_default();
-            if let Err(err) = self.scraper.lock().await.send_tweet(chunk, Some(&tweet_id), None).await {
+            if let Err(err) = self.api.write().await.send_tweet(chunk, Some(&tweet_id), None).await {
                 error!(?err, "Failed to send reply");
+                self.log_result("reply", &tweet_id, author.as_deref(), Err(&err));
+                // Retrying would repeat the parts already posted
+                if i > 0 {
+                    self.mark_seen(&tweet_id, "reply").await;
+                }
                 return Err(Box::new(err));
             } else {
                 // Record successful reply - do this in a separate task to not block the response
@@ -502,6 +938,8 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                 client.update_tweet_metrics(&tweet_id, &content).await;
             });
         }
+        self.mark_seen(&tweet_id, "reply").await;
+        self.log_result("reply", &tweet_id, author.as_deref(), Ok::<(), String>(()));
 
         Ok(())
     }
@@ -513,24 +951,40 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         let mut thread = Vec::new();
         let mut current_tweet = Some(tweet.clone());
         let mut depth = 0;
+        let max_depth = self.config.max_history_tweets.max(0);
+        let mut visited: HashSet<String> = HashSet::new();
 
         debug!(
             initial_tweet_id = ?tweet.id,
+            max_depth,
             "Building conversation thread"
         );
 
         while let Some(tweet) = current_tweet {
+            if let Some(id) = &tweet.id {
+                visited.insert(id.clone());
+            }
             thread.push(tweet.clone());
 
-            if depth >= MAX_HISTORY_TWEETS {
-                debug!("Reached maximum thread depth of {}", MAX_HISTORY_TWEETS);
+            if depth >= max_depth {
+                debug!("Reached maximum thread depth of {}", max_depth);
                 break;
             }
 
             current_tweet = match tweet.in_reply_to_status_id {
+                // A parent we've already walked through would loop forever
+                Some(parent_id) if visited.contains(&parent_id) => {
+                    warn!(parent_id = %parent_id, "Reply chain points back at itself, stopping thread");
+                    None
+                }
+                // Use the parent the scraper already embedded instead of fetching it again
+                Some(parent_id) if tweet.in_reply_to_status.as_ref().and_then(|p| p.id.as_deref()) == Some(parent_id.as_str()) => {
+                    debug!(parent_id = %parent_id, "Using embedded parent tweet");
+                    tweet.in_reply_to_status.map(|parent| *parent)
+                }
                 Some(parent_id) => {
                     debug!(parent_id = ?parent_id, "Fetching parent tweet");
-                    match self.scraper.lock().await.get_tweet(&parent_id).await {
+                    match self.api.read().await.get_tweet(&parent_id).await {
                         Ok(parent_tweet) => Some(parent_tweet),
                         Err(err) => {
                             debug!(?err, "Failed to fetch parent tweet, stopping thread");
@@ -557,16 +1011,239 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         Ok(thread)
     }
 
+    /// Whether this (tweet, action) pair was already handled recently. Lookup errors
+    /// count as unseen, worst case we evaluate the tweet again.
+    async fn already_seen(&self, tweet_id: &str, action: &str) -> bool {
+        if tweet_id.is_empty() {
+            return false;
+        }
+        let ttl = chrono::Duration::hours(self.config.seen_tweet_ttl_hours);
+        match self.agent.interaction_history.is_seen(tweet_id, action, ttl).await {
+            Ok(seen) => {
+                if seen {
+                    debug!(tweet_id = %tweet_id, action = %action, "Tweet already seen for action, skipping");
+                }
+                seen
+            }
+            Err(e) => {
+                error!("Error checking seen tweets: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Called once an action is done or declined. A failed one is left unseen, so
+    /// the tweet is tried again next cycle.
+    async fn mark_seen(&self, tweet_id: &str, action: &str) {
+        if tweet_id.is_empty() {
+            return;
+        }
+        if let Err(e) = self.agent.interaction_history.mark_seen(tweet_id, action).await {
+            error!("Failed to mark tweet as seen: {}", e);
+        }
+    }
+
+    /// Whether `action` was done to another tweet by `author` within the cooldown. The
+    /// tweet isn't marked seen, so it can still be picked up once the cooldown ends.
+    /// Lookup errors don't block, worst case we engage a little early.
+    async fn author_on_cooldown(&self, author: Option<&str>, action: &str) -> bool {
+        let Some(author) = normalize_author(author) else {
+            return false;
+        };
+        if self.config.author_cooldown_minutes <= 0 {
+            return false;
+        }
+        let window = chrono::Duration::minutes(self.config.author_cooldown_minutes);
+        match self.agent.interaction_history.engaged_author_recently(&author, action, window).await {
+            Ok(recent) => {
+                if recent {
+                    debug!(author = %author, action = %action, "Author on cooldown, skipping");
+                }
+                recent
+            }
+            Err(e) => {
+                error!("Error checking author cooldown: {}", e);
+                false
+            }
+        }
+    }
+
+    async fn record_author_engagement(&self, author: Option<&str>, action: &str) {
+        let Some(author) = normalize_author(author) else {
+            return;
+        };
+        if let Err(e) = self.agent.interaction_history.record_author_engagement(&author, action).await {
+            error!("Failed to record author engagement: {}", e);
+        }
+    }
+
+    async fn prune_seen_tweets(&self) {
+        let ttl = chrono::Duration::hours(self.config.seen_tweet_ttl_hours);
+        if let Err(e) = self.agent.interaction_history.prune_seen_tweets(ttl, self.config.max_seen_tweets).await {
+            error!("Failed to prune seen tweets: {}", e);
+        }
+    }
+
+    /// While paused, still read mentions and the timeline so they're stored and marked
+    /// seen, otherwise the bot would work through the whole backlog on resume
+    async fn catch_up_while_paused(&self, config: &TwitterConfig) {
+        match self.api.read().await.search_tweets(
+            &format!("@{}", self.username),
+            config.mentions_fetch_count.try_into().unwrap(),
+            agent_twitter_client::search::SearchMode::Latest,
+            None,
+        ).await {
+            Ok(mentions) => {
+                for tweet in mentions.tweets {
+                    let tweet_id = tweet.id.clone().unwrap_or_default();
+                    if let Err(err) = self.agent.knowledge().create_message(Message::from(tweet)).await {
+                        debug!(?err, "Failed to store mention while paused");
+                    }
+                    self.mark_seen(&tweet_id, "reply").await;
+                }
+            }
+            Err(err) => error!(?err, "Failed to fetch mentions while paused"),
+        }
+
+        match self.api.read().await.get_home_timeline(
+            config.home_timeline_fetch_count.try_into().unwrap(),
+            Vec::new()
+        ).await {
+            Ok(tweets) => {
+                for tweet in tweets {
+                    let tweet_id = tweet["legacy"]["id_str"].as_str().unwrap_or_default();
+                    for action in ["like", "retweet", "quote"] {
+                        self.mark_seen(tweet_id, action).await;
+                    }
+                }
+            }
+            Err(err) => error!(?err, "Failed to fetch home timeline while paused"),
+        }
+    }
+
+    /// Write actions check this right before acting, so a pause takes effect mid-cycle.
+    /// The tweet is marked seen so it isn't picked up again after resuming.
+    async fn skip_while_paused(&self, tweet_id: &str, action: &str) -> bool {
+        if !self.pause.is_paused() {
+            return false;
+        }
+        debug!(tweet_id = %tweet_id, action = %action, "Paused, skipping action");
+        self.mark_seen(tweet_id, action).await;
+        true
+    }
+
+    /// The shared checks before a like or retweet. Repeats of a tweet already
+    /// handled aren't written to the decision log.
+    async fn skip_engagement(&self, tweet_id: &str, author: Option<&str>, action: &str) -> bool {
+        if self.already_seen(tweet_id, action).await {
+            return true;
+        }
+        let reason = if self.author_on_cooldown(author, action).await {
+            "author_cooldown"
+        } else if self.skip_while_paused(tweet_id, action).await {
+            "paused"
+        } else {
+            return false;
+        };
+        self.log_skip(action, tweet_id, author, reason);
+        true
+    }
+
+    fn log_decision(&self, event: DecisionEvent) {
+        if let Some(log) = &self.decisions {
+            log.record(event);
+        }
+    }
+
+    fn log_skip(&self, action: &str, tweet_id: &str, author: Option<&str>, reason: &str) {
+        self.log_decision(DecisionEvent::new(action, tweet_id, author, Stage::Skipped, reason));
+    }
+
+    fn log_verdict(&self, action: &str, tweet_id: &str, author: Option<&str>, verdict: &str, tweet_content: &str) {
+        self.log_decision(DecisionEvent::new(action, tweet_id, author, Stage::Verdict, verdict).with_detail(tweet_content));
+    }
+
+    fn log_result<T: std::fmt::Display>(&self, action: &str, tweet_id: &str, author: Option<&str>, result: Result<(), T>) {
+        let event = match result {
+            Ok(()) => DecisionEvent::new(action, tweet_id, author, Stage::Action, "ok"),
+            Err(err) => DecisionEvent::new(action, tweet_id, author, Stage::Action, "failed").with_error(err),
+        };
+        self.log_decision(event);
+    }
+
+    /// A model-written summary of thread messages past the history budget, None
+    /// if the call fails so they're only counted
+    async fn summarize_older(&self, older: &[(String, String)]) -> Option<String> {
+        let prompt = conversation::summary_prompt(older, self.config.history_context.summary_input_tokens)?;
+        let agent = self.agent.builder().build();
+        match self.prompt_agent(&agent, &prompt).await {
+            Ok(summary) => Some(summary),
+            Err(err) => {
+                debug!(?err, "Failed to summarize earlier thread messages");
+                None
+            }
+        }
+    }
+
+    /// Prompt a built agent, retrying once if the answer is empty under the
+    /// configured `empty_response` policy, so blank text is never posted. Transient
+    /// provider errors and timeouts are retried as `prompt_retry` allows.
+    async fn prompt_agent(
+        &self,
+        agent: &RigAgent<M>,
+        prompt: &str,
+    ) -> Result<String, RetryError<ResponseError<PromptError>>> {
+        let retryable = |e: &ResponseError<PromptError>| matches!(e, ResponseError::Call(e) if prompt_retry::is_retryable(e));
+        prompt_retry::with_retries(self.config.prompt_retry, retryable, || {
+            empty_response::retry_if_empty(self.config.empty_response, || {
+                telemetry::traced_call(
+                    self.agent.provider_name(),
+                    self.agent.model_name(),
+                    prompt,
+                    agent.prompt(Text::from(prompt.to_string())),
+                )
+            })
+        })
+        .await
+    }
+
+    /// Prompt a mention reply agent. With the transfer tool it gets a single attempt
+    /// and no timeout: a transfer cut off or prompted again could pay twice, so an
+    /// empty answer is an error rather than a reason to ask again.
+    async fn prompt_reply_agent(
+        &self,
+        agent: &RigAgent<M>,
+        prompt: &str,
+    ) -> Result<String, RetryError<ResponseError<PromptError>>> {
+        if !self.transfer_enabled {
+            return self.prompt_agent(agent, prompt).await;
+        }
+        prompt_retry::once(async {
+            let response = telemetry::traced_call(
+                self.agent.provider_name(),
+                self.agent.model_name(),
+                prompt,
+                agent.prompt(Text::from(prompt.to_string())),
+            )
+            .await
+            .map_err(ResponseError::Call)?;
+            if self.config.empty_response.is_empty(&response) {
+                return Err(ResponseError::Empty { attempts: 1 });
+            }
+            Ok(response)
+        })
+        .await
+    }
+
     fn random_number(&self, min: u64, max: u64) -> u64 {
-        let mut rng = rand::thread_rng();
         if min >= max {
             debug!("Invalid range min {} >= max {}, returning min", min, max);
             return min;
         }
-        rng.gen_range(min..=max)
+        rng::range(min, max)
     }
 
-    async fn handle_like(&self, tweet_content: &str, tweet_id: &str) {
+    async fn handle_like(&self, tweet_content: &str, tweet_id: &str, author: Option<&str>) {
         // First check if we've already liked this tweet
         match self.agent.interaction_history.has_interaction(tweet_id, "like").await {
             Ok(true) => {
@@ -580,22 +1257,33 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             _ => {}
         }
 
-        if self.attention.should_like(tweet_content).await {
+        if self.skip_engagement(tweet_id, author, "like").await {
+            return;
+        }
+
+        let decision = self.attention.should_like(tweet_content).await;
+        self.log_verdict("like", tweet_id, author, if decision { "yes" } else { "no" }, tweet_content);
+        if decision {
             debug!(tweet_id = %tweet_id, tweet_content = %tweet_content, "Agent decided to like tweet");
-            if let Err(err) = self.scraper.lock().await.like_tweet(tweet_id).await {
+            let result = self.api.write().await.like_tweet(tweet_id).await;
+            self.log_result("like", tweet_id, author, result.as_ref().map(|_| ()));
+            if let Err(err) = result {
                 error!(?err, "Failed to like tweet");
             } else {
+                self.mark_seen(tweet_id, "like").await;
                 // Record successful like
                 if let Err(e) = self.agent.interaction_history.record_interaction(tweet_id, "like").await {
                     error!("Failed to record like interaction: {}", e);
                 }
+                self.record_author_engagement(author, "like").await;
             }
         } else {
             debug!(tweet_id = %tweet_id, tweet_content = %tweet_content, "Agent decided not to like tweet");
+            self.mark_seen(tweet_id, "like").await;
         }
     }
 
-    async fn handle_retweet(&self, tweet_content: &str, tweet_id: &str) {
+    async fn handle_retweet(&self, tweet_content: &str, tweet_id: &str, author: Option<&str>) {
         // First check if we've already retweeted this tweet
         match self.agent.interaction_history.has_interaction(tweet_id, "retweet").await {
             Ok(true) => {
@@ -609,22 +1297,33 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             _ => {}
         }
 
-        if self.attention.should_retweet(tweet_content).await {
+        if self.skip_engagement(tweet_id, author, "retweet").await {
+            return;
+        }
+
+        let decision = self.attention.should_retweet(tweet_content).await;
+        self.log_verdict("retweet", tweet_id, author, if decision { "yes" } else { "no" }, tweet_content);
+        if decision {
             debug!(tweet_id = %tweet_id, tweet_content = %tweet_content, "Agent decided to retweet");
-            if let Err(err) = self.scraper.lock().await.retweet(tweet_id).await {
+            let result = self.api.write().await.retweet(tweet_id).await;
+            self.log_result("retweet", tweet_id, author, result.as_ref().map(|_| ()));
+            if let Err(err) = result {
                 error!(?err, "Failed to retweet");
             } else {
+                self.mark_seen(tweet_id, "retweet").await;
                 // Record successful retweet
                 if let Err(e) = self.agent.interaction_history.record_interaction(tweet_id, "retweet").await {
                     error!("Failed to record retweet interaction: {}", e);
                 }
+                self.record_author_engagement(author, "retweet").await;
             }
         } else {
             debug!(tweet_id = %tweet_id, tweet_content = %tweet_content, "Agent decided not to retweet");
+            self.mark_seen(tweet_id, "retweet").await;
         }
     }
 
-    async fn handle_quote(&self, tweet_content: &str, tweet_id: &str) {
+    async fn handle_quote(&self, tweet_content: &str, tweet_id: &str, author: Option<&str>) {
         // First check if we've already quoted this tweet
         match self.agent.interaction_history.has_interaction(tweet_id, "quote").await {
             Ok(true) => {
@@ -638,12 +1337,22 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             _ => {}
         }
 
-        if self.attention.should_quote(tweet_content).await {
+        if self.already_seen(tweet_id, "quote").await {
+            return;
+        }
+        if self.skip_while_paused(tweet_id, "quote").await {
+            self.log_skip("quote", tweet_id, author, "paused");
+            return;
+        }
+
+        let decision = self.attention.should_quote(tweet_content).await;
+        self.log_verdict("quote", tweet_id, author, if decision { "yes" } else { "no" }, tweet_content);
+        if decision {
             debug!(tweet_id = %tweet_id, tweet_content = %tweet_content, "Agent decided to quote tweet");
             
             // Download tweet photos if present
             let mut image_urls = Vec::new();
-            if let Ok(tweet) = self.scraper.lock().await.get_tweet(tweet_id).await {
+            if let Ok(tweet) = self.api.read().await.get_tweet(tweet_id).await {
                 for photo in &tweet.photos {
                     match Self::download_image_as_base64(&photo.url).await {
                         Ok(base64_url) => image_urls.push(base64_url),
@@ -659,7 +1368,7 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                     "Current time: {}",
                     chrono::Local::now().format("%I:%M:%S %p, %Y-%m-%d")
                 ))
-                .context("Keep responses under 280 characters.")
+                .context(&format!("Keep responses under {} characters.", self.config.tweet_length_limit()))
                 .context("Reply with a single clear, natural sentence.")
                 .context("For images, acknowledge them briefly if relevant.")
                 .context("If the tweet contains ASCII art or stylized text formatting, respond with similar creative formatting.")
@@ -668,16 +1377,20 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                 .build();
 
             let tweet_content = tweet_content.to_string();
-            let response: String = match agent.prompt(Text::from(tweet_content.to_string())).await {
-                Ok(response) => response.to_string(),
+            let response: String = match self.prompt_agent(&agent, &tweet_content).await {
+                Ok(response) => response,
                 Err(err) => {
                     error!(?err, "Failed to generate response");
+                    self.log_result("quote", tweet_id, author, Err(&err));
                     return;
                 }
             };
-            if let Err(err) = self.scraper.lock().await.send_quote_tweet(&response, tweet_id, None).await {
+            let result = self.api.write().await.send_quote_tweet(&response, tweet_id, None).await;
+            self.log_result("quote", tweet_id, author, result.as_ref().map(|_| ()));
+            if let Err(err) = result {
                 error!(?err, "Failed to quote tweet");
             } else {
+                self.mark_seen(tweet_id, "quote").await;
                 // Record successful quote
                 if let Err(e) = self.agent.interaction_history.record_interaction(tweet_id, "quote").await {
                     error!("Failed to record quote interaction: {}", e);
@@ -685,6 +1398,7 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             }
         } else {
             debug!(tweet_id = %tweet_id, tweet_content = %tweet_content, "Agent decided not to quote tweet");
+            self.mark_seen(tweet_id, "quote").await;
         }
     }
 
@@ -696,14 +1410,15 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         let tweet_id = tweet.id.clone().unwrap_or_default();
 
         // Use individual handlers which now check for duplicates
-        self.handle_like(&tweet_content, &tweet_id).await;
-        self.handle_retweet(&tweet_content, &tweet_id).await;
-        self.handle_quote(&tweet_content, &tweet_id).await;
+        let author = tweet.username.as_deref();
+        self.handle_like(&tweet_content, &tweet_id, author).await;
+        self.handle_retweet(&tweet_content, &tweet_id, author).await;
+        self.handle_quote(&tweet_content, &tweet_id, author).await;
 
         Ok(())
     }
 
-    async fn process_search_query(
+    pub async fn process_search_query(
         &self,
         search: &TimelineSearch,
         config: &TwitterConfig,
@@ -721,7 +1436,7 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             query.push_str(&format!(" min_faves:{}", min_likes));
         }
         
-        let tweets = self.scraper.lock().await.search_tweets(
+        let tweets = self.api.read().await.search_tweets(
             &query,
             search.max_results.try_into().unwrap(),
             agent_twitter_client::search::SearchMode::Latest,
@@ -731,14 +1446,15 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         debug!("Found {} tweets matching query", tweets.tweets.len());
         for (i, tweet) in tweets.tweets.iter().enumerate() {
             debug!("Processing tweet {}/{}", i + 1, tweets.tweets.len());
+            if !config.meets_engagement_floor(tweet.likes.unwrap_or(0), tweet.retweets.unwrap_or(0), tweet.time_parsed) {
+                debug!(tweet_id = ?tweet.id, "Below engagement floor, skipping search result");
+                continue;
+            }
             self.handle_search_result(tweet.clone()).await?;
             
-            let wait_time = self.random_number(
-                config.min_action_interval,
-                config.max_action_interval,
-            );
-            debug!("Waiting {}s before next tweet", wait_time);
-            tokio::time::sleep(tokio::time::Duration::from_secs(wait_time)).await;
+            let wait_time = self.delay.action_delay();
+            debug!("Waiting {}s before next tweet", wait_time.as_secs());
+            tokio::time::sleep(wait_time).await;
         }
 
         debug!("Completed search query: {}", search.query);
@@ -754,20 +1470,29 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
     }
 
     pub async fn post_tweet(&self, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.pause.is_paused() {
+            info!("Paused, not posting tweet");
+            return Ok(());
+        }
+
+        let limit = self.config.tweet_length_limit();
         let mut content_to_post: String = content.trim().to_string();
-        info!("Attempting to post tweet [{}]: {}", content_to_post.len(), content_to_post);
+        if self.config.empty_response.is_empty(&content_to_post) {
+            return Err("Refusing to post an empty tweet".into());
+        }
+        info!("Attempting to post tweet [{}]: {}", content_to_post.chars().count(), content_to_post);
         
         // Ensure content is within limits
-        if content_to_post.len() > 240 {  // Reduced from 280 to leave room for media
-            content_to_post = content_to_post.chars().take(240).collect::<String>();
+        if content_to_post.chars().count() > limit {
+            content_to_post = content_to_post.chars().take(limit).collect::<String>();
             info!("Tweet truncated to: {}", content_to_post);
         }
 
-        // Lock scraper for use
-        let scraper = self.scraper.lock().await;
+        // Hold write access for both attempts
+        let api = self.api.write().await;
         
         // First attempt with original content
-        match scraper.send_tweet(&content_to_post, None, None).await {
+        match api.send_tweet(&content_to_post, None, None).await {
             Ok(_) => {
                 info!("Tweet posted successfully");
                 Ok(())
@@ -782,10 +1507,16 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                     .collect::<String>()
                     .replace("  ", " ")
                     .trim()
-                    .to_string();
+                    .chars()
+                    .take(limit)
+                    .collect::<String>();
+                // Nothing left once emoji and symbols are stripped
+                if self.config.empty_response.is_empty(&simple_content) {
+                    return Err(Box::new(e));
+                }
 
                 info!("Retrying with simplified content: {}", simple_content);
-                match scraper.send_tweet(&simple_content, None, None).await {
+                match api.send_tweet(&simple_content, None, None).await {
                     Ok(_) => {
                         info!("Tweet posted successfully with simplified content");
                         Ok(())
@@ -800,17 +1531,60 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
     }
 
     pub async fn share_intel(&self, intel: &CryptoIntel) -> Result<(), Box<dyn std::error::Error>> {
-        let tweet = self.agent.process_market_data(intel).await?;
-        self.post_tweet(&tweet).await?;
+        let tweet = self.agent.process_market_data(intel, self.config.tweet_length_limit(), self.config.empty_response).await?;
+        if let Some(tweet) = self.fact_check_intel_tweet(intel, &tweet) {
+            self.post_tweet(&tweet).await?;
+        }
         Ok(())
     }
 
+    /// Cross-check the figures in an intel tweet against the intel itself. Returns the
+    /// text to post, marked as low confidence when it can't be verified, or None to block it.
+    fn fact_check_intel_tweet(&self, intel: &CryptoIntel, tweet: &str) -> Option<String> {
+        let config = &self.config.fact_check;
+        if !config.enabled {
+            return Some(tweet.to_string());
+        }
+
+        match verify_figures(tweet, &intel.content, config) {
+            FactCheck::Verified => {
+                debug!("Intel tweet figures verified");
+                Some(tweet.to_string())
+            }
+            FactCheck::Unverifiable => {
+                info!("Couldn't verify intel tweet figures, posting as low confidence");
+                Some(mark_low_confidence(tweet, &config.low_confidence_marker, self.config.tweet_length_limit()))
+            }
+            FactCheck::Mismatch(figures) if config.block_on_mismatch => {
+                warn!(?figures, tweet = %tweet, "Blocking intel tweet with figures that don't match the intel");
+                None
+            }
+            FactCheck::Mismatch(figures) => {
+                warn!(?figures, "Intel tweet figures don't match the intel, posting as low confidence");
+                Some(mark_low_confidence(tweet, &config.low_confidence_marker, self.config.tweet_length_limit()))
+            }
+        }
+    }
+
     pub async fn start_monitoring(&self, folder_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         info!("Starting Twitter monitoring service");
         let folder_path = folder_path.to_string();
         
+        info!("Watching pause file: {}", self.pause.path().display());
+
         loop {
             debug!("Starting new task cycle");
+            self.prune_seen_tweets().await;
+
+            if self.pause.is_paused() {
+                // Drain new intel so we don't post a pile of stale calls on resume
+                match scan_intel_folder(&folder_path).await {
+                    Ok(intel) => debug!("Paused, skipped {} intel files", intel.len()),
+                    Err(e) => error!("Failed to scan intel folder while paused: {}", e),
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(PAUSE_POLL_SECS)).await;
+                continue;
+            }
             
             // Clear processed files every hour
             let now = chrono::Utc::now();
@@ -832,9 +1606,7 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                             }
                             
                             // Wait between queries
-                            tokio::time::sleep(tokio::time::Duration::from_secs(
-                                self.random_number(60, 180)
-                            )).await;
+                            tokio::time::sleep(self.delay.action_delay()).await;
                         }
                     }
                 },
@@ -849,12 +1621,9 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             }
 
             // Wait before next cycle
-            let wait_time = self.random_number(
-                self.config.min_task_interval,
-                self.config.max_task_interval,
-            );
-            debug!("Waiting {}s before next task cycle", wait_time);
-            tokio::time::sleep(tokio::time::Duration::from_secs(wait_time)).await;
+            let wait_time = self.delay.task_delay();
+            debug!("Waiting {}s before next task cycle", wait_time.as_secs());
+            tokio::time::sleep(wait_time).await;
         }
     }
 
@@ -882,22 +1651,24 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                     info!("Processing recent intel for {} from {}", symbol, intel.timestamp);
                     debug!("Intel content: {}", intel.content);
                     
-                    let response = self.agent.process_market_data(&intel).await?;
+                    let response = self.agent.process_market_data(&intel, self.config.tweet_length_limit(), self.config.empty_response).await?;
                     info!("Agent response: {}", response);
                     
                     if !response.contains("NO_POST") {
+                        let Some(response) = self.fact_check_intel_tweet(&intel, &response) else {
+                            // Don't retry this symbol with the same intel this run
+                            processed_symbols.insert(symbol.clone());
+                            continue;
+                        };
                         info!("Posting intel tweet for {}", symbol);
                         self.post_tweet(&response).await?;
                         
                         // Mark this symbol as processed
                         processed_symbols.insert(symbol.clone());
                         
-                        let wait_time = self.random_number(
-                            self.config.min_action_interval,
-                            self.config.max_action_interval,
-                        );
-                        info!("Waiting {}s before next action", wait_time);
-                        tokio::time::sleep(tokio::time::Duration::from_secs(wait_time)).await;
+                        let wait_time = self.delay.action_delay();
+                        info!("Waiting {}s before next action", wait_time.as_secs());
+                        tokio::time::sleep(wait_time).await;
                     }
                 }
             }
@@ -942,13 +1713,13 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
         for interaction in pending {
             match interaction.as_str() {
                 "like" => {
-                    if let Ok(_) = self.scraper.lock().await.like_tweet(&tweet_id).await {
+                    if let Ok(_) = self.api.write().await.like_tweet(&tweet_id).await {
                         self.agent.interaction_history.record_interaction(&tweet_id, "like").await?;
                         debug!("Liked tweet: {}", tweet_id);
                     }
                 },
                 "retweet" => {
-                    if let Ok(_) = self.scraper.lock().await.retweet(&tweet_id).await {
+                    if let Ok(_) = self.api.write().await.retweet(&tweet_id).await {
                         self.agent.interaction_history.record_interaction(&tweet_id, "retweet").await?;
                         debug!("Retweeted: {}", tweet_id);
                     }
@@ -957,7 +1728,7 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
                     // Only quote if it meets certain criteria
                     if self.should_quote(tweet) {
                         let quote_text = format!("Interesting point! {}", tweet.text.clone().unwrap_or_default());
-                        if let Ok(_) = self.scraper.lock().await.send_tweet(&quote_text, Some(&tweet_id), None).await {
+                        if let Ok(_) = self.api.write().await.send_tweet(&quote_text, Some(&tweet_id), None).await {
                             self.agent.interaction_history.record_interaction(&tweet_id, "quote").await?;
                             debug!("Quoted tweet: {}", tweet_id);
                         }
@@ -967,11 +1738,21 @@ impl<M: CompletionModel + 'static, E: EmbeddingModel + 'static> TwitterClient<M,
             }
             
             // Add random delay between actions
-            tokio::time::sleep(tokio::time::Duration::from_secs(
-                self.random_number(30, 60)
-            )).await;
+            tokio::time::sleep(self.delay.action_delay()).await;
         }
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+// Handles compare case-insensitively and may come with or without the @
+fn normalize_author(author: Option<&str>) -> Option<String> {
+    let author = author?.trim().trim_start_matches('@');
+    (!author.is_empty()).then(|| author.to_lowercase())
+}
+
+// Whether a send failed before Twitter saw it, so sending again can't post twice.
+// Any response, even an error or one that doesn't parse, may follow a posted tweet.
+fn never_reached_twitter(err: &TwitterError) -> bool {
+    matches!(err, TwitterError::Network(e) if e.is_connect())
+}
@@ -0,0 +1,95 @@
+use agent_twitter_client::error::Result;
+use agent_twitter_client::models::Tweet;
+use agent_twitter_client::scraper::Scraper;
+use agent_twitter_client::search::SearchMode;
+use agent_twitter_client::timeline::v1::QueryTweetsResponse;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// The Twitter calls the bot loop makes. `Scraper` is the real implementation;
+/// tests swap in a mock to drive the loop without a network or an account.
+#[async_trait]
+pub trait TwitterApi: Send + Sync {
+    async fn get_home_timeline(&self, count: i32, seen_tweet_ids: Vec<String>) -> Result<Vec<Value>>;
+
+    async fn search_tweets(
+        &self,
+        query: &str,
+        max_tweets: i32,
+        search_mode: SearchMode,
+        cursor: Option<String>,
+    ) -> Result<QueryTweetsResponse>;
+
+    async fn get_tweet(&self, id: &str) -> Result<Tweet>;
+
+    async fn send_tweet(
+        &self,
+        text: &str,
+        reply_to: Option<&str>,
+        media_data: Option<Vec<(Vec<u8>, String)>>,
+    ) -> Result<Value>;
+
+    async fn send_quote_tweet(
+        &self,
+        text: &str,
+        quoted_tweet_id: &str,
+        media_data: Option<Vec<(Vec<u8>, String)>>,
+    ) -> Result<Value>;
+
+    async fn like_tweet(&self, tweet_id: &str) -> Result<Value>;
+
+    async fn retweet(&self, tweet_id: &str) -> Result<Value>;
+
+    async fn delete_tweet(&self, tweet_id: &str) -> Result<Value>;
+}
+
+#[async_trait]
+impl TwitterApi for Scraper {
+    async fn get_home_timeline(&self, count: i32, seen_tweet_ids: Vec<String>) -> Result<Vec<Value>> {
+        Scraper::get_home_timeline(self, count, seen_tweet_ids).await
+    }
+
+    async fn search_tweets(
+        &self,
+        query: &str,
+        max_tweets: i32,
+        search_mode: SearchMode,
+        cursor: Option<String>,
+    ) -> Result<QueryTweetsResponse> {
+        Scraper::search_tweets(self, query, max_tweets, search_mode, cursor).await
+    }
+
+    async fn get_tweet(&self, id: &str) -> Result<Tweet> {
+        Scraper::get_tweet(self, id).await
+    }
+
+    async fn send_tweet(
+        &self,
+        text: &str,
+        reply_to: Option<&str>,
+        media_data: Option<Vec<(Vec<u8>, String)>>,
+    ) -> Result<Value> {
+        Scraper::send_tweet(self, text, reply_to, media_data).await
+    }
+
+    async fn send_quote_tweet(
+        &self,
+        text: &str,
+        quoted_tweet_id: &str,
+        media_data: Option<Vec<(Vec<u8>, String)>>,
+    ) -> Result<Value> {
+        Scraper::send_quote_tweet(self, text, quoted_tweet_id, media_data).await
+    }
+
+    async fn like_tweet(&self, tweet_id: &str) -> Result<Value> {
+        Scraper::like_tweet(self, tweet_id).await
+    }
+
+    async fn retweet(&self, tweet_id: &str) -> Result<Value> {
+        Scraper::retweet(self, tweet_id).await
+    }
+
+    async fn delete_tweet(&self, tweet_id: &str) -> Result<Value> {
+        Scraper::delete_tweet(self, tweet_id).await
+    }
+}
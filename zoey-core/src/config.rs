@@ -1,4 +1,7 @@
+use common::empty_response::EmptyResponsePolicy;
+use common::prompt_retry::PromptRetry;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimelineSearch {
@@ -35,12 +38,16 @@ pub struct TwitterConfig {
     pub exclude_replies: bool,
     pub min_likes: Option<i32>,        // Filter by minimum likes
     pub min_retweets: Option<i32>,     // Filter by minimum retweets
+    #[serde(default)]
+    pub engagement_filter: EngagementFilterConfig, // Applies the minimums above before model calls
     
     // Time intervals (in seconds)
     pub min_action_interval: u64,  // Minimum time between actions
     pub max_action_interval: u64,  // Maximum time between actions
     pub min_task_interval: u64,    // Minimum time between tasks
     pub max_task_interval: u64,    // Maximum time between tasks
+    #[serde(default)]
+    pub delay: DelayConfig,        // How delays are drawn from the intervals above
     
     // Interaction settings
     pub enable_likes: bool,
@@ -52,6 +59,298 @@ pub struct TwitterConfig {
     pub max_tweets_per_hour: u32,
     pub max_likes_per_hour: u32,
     pub max_retweets_per_hour: u32,
+
+    // Seen tweets, so the same tweet isn't acted on again across cycles
+    #[serde(default = "default_seen_tweet_ttl_hours")]
+    pub seen_tweet_ttl_hours: i64,    // How long a (tweet, action) pair is remembered
+    #[serde(default = "default_max_seen_tweets")]
+    pub max_seen_tweets: usize,       // Oldest entries are pruned beyond this
+    #[serde(default = "default_author_cooldown_minutes")]
+    pub author_cooldown_minutes: i64, // Wait before liking/retweeting the same author again, 0 to disable
+
+    // Twitter reads (timeline, search, fetching tweets) allowed in flight at once; writes are always one at a time
+    #[serde(default = "default_max_concurrent_reads")]
+    pub max_concurrent_reads: usize,
+
+    // Scheduled posting
+    #[serde(default)]
+    pub post_schedule: PostScheduleConfig,
+
+    // Intel tweet verification
+    #[serde(default)]
+    pub fact_check: FactCheckConfig,
+
+    // Heurist images attached to generated tweets
+    #[serde(default)]
+    pub image: ImageConfig,
+
+    // Live price tool for replies that mention a ticker
+    #[serde(default)]
+    pub price_lookup: PriceLookupConfig,
+
+    // Longer generated posts as self-reply threads
+    #[serde(default)]
+    pub thread: ThreadConfig,
+
+    // How much of a reply thread is given to the model
+    #[serde(default)]
+    pub history_context: HistoryContextConfig,
+
+    // JSONL audit log of like/retweet/quote/reply decisions
+    #[serde(default)]
+    pub decision_log: DecisionLogConfig,
+
+    // Generated text counted as empty and retried once: "whitespace" or "punctuation"
+    #[serde(default)]
+    pub empty_response: EmptyResponsePolicy,
+
+    // Retries and timeout for reply, quote and post generation; refusals aren't retried
+    #[serde(default)]
+    pub prompt_retry: PromptRetry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DelayDistribution {
+    Uniform,
+    LogNormal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DelayConfig {
+    pub distribution: DelayDistribution,  // "uniform" or "lognormal"
+    pub lognormal_sigma: f64,             // Spread of log-normal delays, larger means more long waits
+    pub min_delay_secs: u64,              // No delay is ever shorter than this
+}
+
+impl Default for DelayConfig {
+    fn default() -> Self {
+        Self {
+            distribution: DelayDistribution::Uniform,
+            lognormal_sigma: 0.5,
+            min_delay_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageConfig {
+    pub model_id: String,        // Heurist model used for tweet images
+    pub derive_prompt: bool,     // Ask the model for a visual prompt based on the tweet
+    pub default_prompt: String,  // Used when deriving is off or fails
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            model_id: crate::clients::heuris::DEFAULT_HEURIST_MODEL.to_string(),
+            derive_prompt: true,
+            default_prompt: "realistic, photorealistic, cinematic lighting, high detail, crypto trading desk at night".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngagementFilterConfig {
+    pub grace_minutes: i64,           // Tweets younger than this skip the engagement floor
+    pub filter_direct_mentions: bool, // Also filter tweets that @mention the bot
+}
+
+impl Default for EngagementFilterConfig {
+    fn default() -> Self {
+        Self {
+            grace_minutes: 30,
+            filter_direct_mentions: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriceLookupConfig {
+    pub enabled: bool,          // Give the reply agent a price tool when a ticker is mentioned, off by default
+    pub min_interval_secs: u64, // Minimum time between CoinGecko requests
+    pub cache_ttl_secs: u64,    // Prices younger than this are served from cache
+}
+
+impl Default for PriceLookupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_secs: 30,
+            cache_ttl_secs: 120,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThreadConfig {
+    pub enabled: bool,        // Generated posts are written longer and posted as a self-reply thread
+    pub target_tweets: usize, // Thread length the model is asked to write for
+    pub max_tweets: usize,    // Longer threads are cut at a tweet boundary
+    pub numbered: bool,       // Add " 1/3" counters, so a thread cut short by an error shows it
+}
+
+impl Default for ThreadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_tweets: 3,
+            max_tweets: 5,
+            numbered: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryContextConfig {
+    pub max_tokens: usize,           // Thread messages given in full, newest first; the replied-to tweet always is
+    pub summarize_older: bool,       // Summarize messages past the budget with the model instead of only counting them
+    pub summary_input_tokens: usize, // Most of the older messages sent to be summarized
+}
+
+impl Default for HistoryContextConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: 1000,
+            summarize_older: true,
+            summary_input_tokens: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecisionLogConfig {
+    pub enabled: bool,     // Write each engagement decision to `path`
+    pub path: String,      // JSONL file, rotated to path.1, path.2, ...; relative to the data root
+    pub max_file_mb: u64,  // Rotate once the file would grow past this
+    pub max_files: usize,  // Rotated files kept, older ones are deleted
+}
+
+impl Default for DecisionLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/decision_log.jsonl".to_string(),
+            max_file_mb: 10,
+            max_files: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FactCheckConfig {
+    pub enabled: bool,                 // Cross-check figures in intel tweets against the intel data
+    pub price_tolerance_pct: f64,      // Allowed relative deviation for $ amounts
+    pub percent_tolerance_points: f64, // Allowed absolute deviation for percentages
+    pub block_on_mismatch: bool,       // If false, mismatched tweets are posted with the marker instead
+    pub low_confidence_marker: String, // Appended when figures can't be verified
+}
+
+impl Default for FactCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            price_tolerance_pct: 2.0,
+            percent_tolerance_points: 0.5,
+            block_on_mismatch: false,
+            low_confidence_marker: "(unverified)".to_string(),
+        }
+    }
+}
+
+fn default_seen_tweet_ttl_hours() -> i64 {
+    72
+}
+
+fn default_max_seen_tweets() -> usize {
+    10_000
+}
+
+fn default_author_cooldown_minutes() -> i64 {
+    60
+}
+
+fn default_max_concurrent_reads() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostScheduleConfig {
+    pub enabled: bool,           // If true, generated tweets are queued and posted on a schedule
+    pub posts_per_day: u32,      // Posts are spread evenly across the day
+    pub quiet_hours_start: Option<u32>,  // Local hour (0-23) when posting stops
+    pub quiet_hours_end: Option<u32>,    // Local hour (0-23) when posting resumes
+    pub max_queue_age_minutes: i64,      // Queued posts written longer ago than this are dropped
+}
+
+impl Default for PostScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            posts_per_day: 6,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            max_queue_age_minutes: 360,  // 6 hours
+        }
+    }
+}
+
+impl PostScheduleConfig {
+    /// Time between scheduled posts
+    pub fn interval(&self) -> chrono::Duration {
+        chrono::Duration::seconds(86_400 / self.posts_per_day.max(1) as i64)
+    }
+
+    pub fn max_queue_age(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.max_queue_age_minutes)
+    }
+
+    /// How long before its slot a post is written: a few minutes, but at most half
+    /// of `max_queue_age` so a post that goes out on time is never stale
+    pub fn generation_lead(&self) -> chrono::Duration {
+        chrono::Duration::minutes(5).min(self.max_queue_age() / 2)
+    }
+
+    /// Whether the given local hour falls inside quiet hours, which may wrap past midnight
+    pub fn is_quiet_hour(&self, hour: u32) -> bool {
+        match (self.quiet_hours_start, self.quiet_hours_end) {
+            (Some(start), Some(end)) if start != end => {
+                if start < end {
+                    hour >= start && hour < end
+                } else {
+                    hour >= start || hour < end
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Next posting slot one interval after `after`, pushed past quiet hours if needed
+    pub fn next_slot(&self, after: chrono::DateTime<chrono::Local>) -> chrono::DateTime<chrono::Local> {
+        use chrono::Timelike;
+
+        let mut slot = after + self.interval();
+        // Step forward an hour at a time until we leave quiet hours (at most a day)
+        for _ in 0..24 {
+            if !self.is_quiet_hour(slot.hour()) {
+                break;
+            }
+            slot = (slot + chrono::Duration::hours(1))
+                .with_minute(0)
+                .and_then(|t| t.with_second(0))
+                .unwrap_or(slot + chrono::Duration::hours(1));
+        }
+        slot
+    }
 }
 
 impl Default for TwitterConfig {
@@ -60,7 +359,7 @@ impl Default for TwitterConfig {
             enabled: false,  // By default, use default settings
             
             // Default conservative settings
-            max_tweet_length: 280,
+            max_tweet_length: 270,  // Standard accounts, with headroom under 280
             max_history_tweets: 10,
             home_timeline_fetch_count: 1,
             mentions_fetch_count: 5,
@@ -73,12 +372,14 @@ impl Default for TwitterConfig {
             exclude_replies: false,
             min_likes: None,        // No minimum likes by default
             min_retweets: None,     // No minimum retweets by default
+            engagement_filter: EngagementFilterConfig::default(),
             
             // Default intervals
             min_action_interval: 60,   // 1 minute
             max_action_interval: 180,  // 3 minutes
             min_task_interval: 900,    // 15 minutes
             max_task_interval: 3600,   // 1 hour
+            delay: DelayConfig::default(),
             
             // Default features all enabled
             enable_likes: true,
@@ -90,6 +391,209 @@ impl Default for TwitterConfig {
             max_tweets_per_hour: 5,
             max_likes_per_hour: 20,
             max_retweets_per_hour: 10,
+
+            // Remember seen tweets for 3 days
+            seen_tweet_ttl_hours: default_seen_tweet_ttl_hours(),
+            max_seen_tweets: default_max_seen_tweets(),
+            author_cooldown_minutes: default_author_cooldown_minutes(),
+            max_concurrent_reads: default_max_concurrent_reads(),
+
+            // Scheduled posting is off by default
+            post_schedule: PostScheduleConfig::default(),
+            fact_check: FactCheckConfig::default(),
+            image: ImageConfig::default(),
+            price_lookup: PriceLookupConfig::default(),
+            thread: ThreadConfig::default(),
+            history_context: HistoryContextConfig::default(),
+            decision_log: DecisionLogConfig::default(),
+            empty_response: EmptyResponsePolicy::default(),
+            prompt_retry: PromptRetry::default(),
+        }
+    }
+}
+
+impl TwitterConfig {
+    /// Longest post the bot writes, in characters. Raise `max_tweet_length` for Premium
+    /// accounts, which can post much longer tweets.
+    pub fn tweet_length_limit(&self) -> usize {
+        self.max_tweet_length.max(1)
+    }
+
+    /// Whether a tweet clears `min_likes` or `min_retweets`, so low-engagement tweets can be
+    /// skipped before any model call. With no minimums set, or for a tweet still inside the
+    /// grace window (new tweets haven't had time to collect engagement), it always passes.
+    pub fn meets_engagement_floor(&self, likes: i32, retweets: i32, posted_at: Option<chrono::DateTime<chrono::Utc>>) -> bool {
+        if self.min_likes.is_none() && self.min_retweets.is_none() {
+            return true;
+        }
+        if let Some(posted_at) = posted_at {
+            if chrono::Utc::now() - posted_at < chrono::Duration::minutes(self.engagement_filter.grace_minutes) {
+                return true;
+            }
+        }
+
+        self.min_likes.is_some_and(|min| likes >= min) || self.min_retweets.is_some_and(|min| retweets >= min)
+    }
+
+    /// Parse a config file leniently: missing fields take their defaults, and unknown
+    /// fields, values of the wrong type and values failing [`validate`](Self::validate)
+    /// are reported and replaced by defaults, so one bad field doesn't discard the
+    /// file. Only malformed JSON is an error.
+    pub fn from_json(content: &str) -> Result<(Self, Vec<String>), Box<dyn std::error::Error>> {
+        let Value::Object(file) = serde_json::from_str::<Value>(content)? else {
+            return Err("Twitter config must be a JSON object".into());
+        };
+        let defaults = serde_json::to_value(Self::default())?;
+        let mut current = defaults.clone();
+        let mut warnings = Vec::new();
+
+        for (key, value) in file {
+            match (defaults.get(&key), value) {
+                (None, _) => warnings.push(format!("Unknown field `{}`, ignored", key)),
+                // Nested settings are applied one by one, so a bad one keeps its siblings
+                (Some(Value::Object(default_table)), Value::Object(table)) => {
+                    for (sub_key, value) in table {
+                        let path = format!("{}.{}", key, sub_key);
+                        if default_table.contains_key(&sub_key) {
+                            try_set(&mut current, &path, value, &mut warnings);
+                        } else {
+                            warnings.push(format!("Unknown field `{}`, ignored", path));
+                        }
+                    }
+                }
+                (Some(_), value) => try_set(&mut current, &key, value, &mut warnings),
+            }
+        }
+
+        let mut config: Self = serde_json::from_value(current.clone())?;
+        let issues = config.validate();
+        if !issues.is_empty() {
+            for issue in issues {
+                warnings.push(format!("{}, using the default", issue.message));
+                for field in issue.fields {
+                    if let (Some(slot), Some(default)) = (current.pointer_mut(&pointer(field)), defaults.pointer(&pointer(field))) {
+                        *slot = default.clone();
+                    }
+                }
+            }
+            config = serde_json::from_value(current)?;
+        }
+        Ok((config, warnings))
+    }
+
+    /// Values that parse but make no sense together, such as a minimum above its maximum
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut check = |ok: bool, fields: &[&'static str], message: String| {
+            if !ok {
+                issues.push(ConfigIssue { fields: fields.to_vec(), message });
+            }
+        };
+
+        check(self.max_tweet_length > 0, &["max_tweet_length"], "max_tweet_length must be at least 1".to_string());
+        check(
+            self.min_action_interval <= self.max_action_interval,
+            &["min_action_interval", "max_action_interval"],
+            format!(
+                "min_action_interval ({}) is above max_action_interval ({})",
+                self.min_action_interval, self.max_action_interval
+            ),
+        );
+        check(
+            self.min_task_interval <= self.max_task_interval,
+            &["min_task_interval", "max_task_interval"],
+            format!(
+                "min_task_interval ({}) is above max_task_interval ({})",
+                self.min_task_interval, self.max_task_interval
+            ),
+        );
+
+        let counts = [
+            ("max_history_tweets", self.max_history_tweets),
+            ("home_timeline_fetch_count", self.home_timeline_fetch_count),
+            ("mentions_fetch_count", self.mentions_fetch_count),
+            ("seen_tweet_ttl_hours", self.seen_tweet_ttl_hours),
+            ("author_cooldown_minutes", self.author_cooldown_minutes),
+            ("engagement_filter.grace_minutes", self.engagement_filter.grace_minutes),
+            ("post_schedule.max_queue_age_minutes", self.post_schedule.max_queue_age_minutes),
+            ("min_likes", self.min_likes.unwrap_or(0) as i64),
+            ("min_retweets", self.min_retweets.unwrap_or(0) as i64),
+        ];
+        for (field, value) in counts {
+            check(value >= 0, &[field], format!("{} must not be negative, got {}", field, value));
         }
+        check(
+            self.search_queries.iter().all(|s| s.max_results >= 0),
+            &["search_queries"],
+            "search_queries has a negative max_results".to_string(),
+        );
+        check(
+            self.topic_timelines.iter().all(|t| t.max_results >= 0),
+            &["topic_timelines"],
+            "topic_timelines has a negative max_results".to_string(),
+        );
+
+        check(
+            self.post_schedule.posts_per_day > 0,
+            &["post_schedule.posts_per_day"],
+            "post_schedule.posts_per_day must be at least 1".to_string(),
+        );
+        for (field, hour) in [
+            ("post_schedule.quiet_hours_start", self.post_schedule.quiet_hours_start),
+            ("post_schedule.quiet_hours_end", self.post_schedule.quiet_hours_end),
+        ] {
+            check(
+                hour.map_or(true, |h| h < 24),
+                &[field],
+                format!("{} must be an hour from 0 to 23, got {:?}", field, hour),
+            );
+        }
+        check(
+            self.delay.lognormal_sigma.is_finite() && self.delay.lognormal_sigma >= 0.0,
+            &["delay.lognormal_sigma"],
+            format!("delay.lognormal_sigma must not be negative, got {}", self.delay.lognormal_sigma),
+        );
+        check(
+            self.thread.target_tweets > 0 && self.thread.max_tweets > 0,
+            &["thread.target_tweets", "thread.max_tweets"],
+            "thread.target_tweets and thread.max_tweets must be at least 1".to_string(),
+        );
+        check(
+            self.prompt_retry.timeout_secs > 0,
+            &["prompt_retry.timeout_secs"],
+            "prompt_retry.timeout_secs must be at least 1".to_string(),
+        );
+        check(
+            self.decision_log.max_file_mb > 0,
+            &["decision_log.max_file_mb"],
+            "decision_log.max_file_mb must be at least 1".to_string(),
+        );
+        issues
+    }
+}
+
+/// A config problem found by [`TwitterConfig::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// Dotted paths of the fields involved
+    pub fields: Vec<&'static str>,
+    pub message: String,
+}
+
+// JSON pointer for a dotted field path
+fn pointer(path: &str) -> String {
+    format!("/{}", path.replace('.', "/"))
+}
+
+// Apply one field, keeping the current value if the result no longer parses
+fn try_set(current: &mut Value, path: &str, value: Value, warnings: &mut Vec<String>) {
+    let mut candidate = current.clone();
+    let Some(slot) = candidate.pointer_mut(&pointer(path)) else {
+        return;
+    };
+    *slot = value;
+    match serde_json::from_value::<TwitterConfig>(candidate.clone()) {
+        Ok(_) => *current = candidate,
+        Err(e) => warnings.push(format!("Invalid value for `{}` ({}), using the default", path, e)),
     }
 } 
\ No newline at end of file
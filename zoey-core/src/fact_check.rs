@@ -0,0 +1,152 @@
+use tracing::{debug, warn};
+
+use crate::config::FactCheckConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FigureKind {
+    Price,     // $1.23, $43.2K
+    MarketCap, // $1.2M, $850B
+    Percent,   // +5.3%
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figure {
+    pub kind: FigureKind,
+    pub value: f64,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FactCheck {
+    /// Every figure in the tweet matches the source data (or the tweet has none)
+    Verified,
+    /// Figures that don't match anything in the source data within tolerance
+    Mismatch(Vec<String>),
+    /// Source data has nothing to check the tweet's figures against
+    Unverifiable,
+}
+
+/// Pull dollar amounts and percentages out of text. `$BTC` style tickers are skipped,
+/// K/M/B suffixes on dollar amounts are expanded and M/B amounts count as market caps.
+pub fn extract_figures(text: &str) -> Vec<Figure> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut figures = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let is_price = chars[i] == '$';
+        let start = if is_price { i + 1 } else { i };
+
+        if start < chars.len() && (chars[start].is_ascii_digit() || (!is_price && matches!(chars[start], '+' | '-'))) {
+            // Numbers only start at a word boundary, so "v2" or "24h" aren't picked up
+            if !is_price && i > 0 && chars[i - 1].is_alphanumeric() {
+                i += 1;
+                continue;
+            }
+
+            let mut end = start;
+            if matches!(chars[end], '+' | '-') {
+                end += 1;
+            }
+            while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == ',' || chars[end] == '.') {
+                end += 1;
+            }
+
+            let number: String = chars[start..end].iter().filter(|c| **c != ',').collect();
+            let number = number.trim_end_matches('.');
+            if let Ok(mut value) = number.parse::<f64>() {
+                if is_price {
+                    let (multiplier, kind) = match chars.get(end).map(|c| c.to_ascii_uppercase()) {
+                        Some('K') => (Some(1e3), FigureKind::Price),
+                        Some('M') => (Some(1e6), FigureKind::MarketCap),
+                        Some('B') => (Some(1e9), FigureKind::MarketCap),
+                        _ => (None, FigureKind::Price),
+                    };
+                    if let Some(multiplier) = multiplier {
+                        value *= multiplier;
+                        end += 1;
+                    }
+                    figures.push(Figure {
+                        kind,
+                        value,
+                        text: chars[i..end].iter().collect(),
+                    });
+                    i = end;
+                    continue;
+                } else if chars.get(end) == Some(&'%') {
+                    figures.push(Figure {
+                        kind: FigureKind::Percent,
+                        value: value.abs(),
+                        text: chars[i..=end].iter().collect(),
+                    });
+                    i = end + 1;
+                    continue;
+                }
+            }
+            i = end.max(i + 1);
+            continue;
+        }
+        i += 1;
+    }
+
+    figures
+}
+
+/// Check the figures in a generated tweet against the source data it was written from.
+/// Dollar amounts must be within `price_tolerance_pct` percent, percentages within
+/// `percent_tolerance_points` points.
+pub fn verify_figures(tweet: &str, source: &str, config: &FactCheckConfig) -> FactCheck {
+    let claimed = extract_figures(tweet);
+    if claimed.is_empty() {
+        return FactCheck::Verified;
+    }
+
+    let known = extract_figures(source);
+    let mut mismatches = Vec::new();
+    let mut unverifiable = false;
+
+    for figure in &claimed {
+        let candidates: Vec<f64> = known
+            .iter()
+            .filter(|k| k.kind == figure.kind)
+            .map(|k| k.value)
+            .collect();
+
+        if candidates.is_empty() {
+            debug!(figure = %figure.text, "No source data to verify figure");
+            unverifiable = true;
+            continue;
+        }
+
+        let matches = candidates.iter().any(|known| match figure.kind {
+            FigureKind::Price | FigureKind::MarketCap => {
+                if *known == 0.0 {
+                    figure.value == 0.0
+                } else {
+                    ((figure.value - known) / known).abs() * 100.0 <= config.price_tolerance_pct
+                }
+            }
+            FigureKind::Percent => (figure.value - known).abs() <= config.percent_tolerance_points,
+        });
+
+        if !matches {
+            warn!(figure = %figure.text, known = ?candidates, "Figure in tweet doesn't match source data");
+            mismatches.push(figure.text.clone());
+        }
+    }
+
+    if !mismatches.is_empty() {
+        FactCheck::Mismatch(mismatches)
+    } else if unverifiable {
+        FactCheck::Unverifiable
+    } else {
+        FactCheck::Verified
+    }
+}
+
+/// Append the low-confidence marker, trimming the tweet so the marker survives truncation
+pub fn mark_low_confidence(tweet: &str, marker: &str, max_len: usize) -> String {
+    let budget = max_len.saturating_sub(marker.chars().count() + 1);
+    let body: String = tweet.trim().chars().take(budget).collect();
+    format!("{} {}", body.trim_end(), marker)
+}
@@ -65,7 +65,11 @@ impl CryptoIntel {
         
         // Get fields directly from CSV
         let symbol = record.get(1).ok_or("Missing symbol")?;
-        let price = record.get(2).ok_or("Missing price")?;
+        // Reports leave the price empty when CoinGecko had none
+        let price = match record.get(2).ok_or("Missing price")? {
+            "" => "N/A".to_string(),
+            price => format!("${}", price),
+        };
         let change = record.get(3).ok_or("Missing price change")?;
         let analysis = record.get(10).ok_or("Missing analysis")?;
         let outlook = record.get(8).ok_or("Missing outlook")?;
@@ -73,7 +77,7 @@ impl CryptoIntel {
 
         // Create a summary with Bangkok timestamp
         let summary = format!(
-            "[{}] ${} at ${} ({:+}%) - {} outlook with {} risk. Key points: {}",
+            "[{}] ${} at {} ({:+}%) - {} outlook with {} risk. Key points: {}",
             bkk_time.format("%Y-%m-%d %H:%M ICT"),
             symbol,
             price,
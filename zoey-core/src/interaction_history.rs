@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use tokio_rusqlite::Connection;
 use tracing::{debug, info};
@@ -40,6 +40,15 @@ impl InteractionMetrics {
     }
 }
 
+/// A generated tweet waiting in the scheduled posting queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledPost {
+    pub id: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+}
+
 #[derive(Clone)]
 pub struct InteractionHistory {
     conn: Arc<Connection>,
@@ -82,6 +91,36 @@ impl InteractionHistory {
                 )",
                 (),
             )?;
+
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS scheduled_posts (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    content TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    due_at TEXT NOT NULL
+                )",
+                (),
+            )?;
+
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS seen_tweets (
+                    tweet_id TEXT NOT NULL,
+                    action TEXT NOT NULL,
+                    seen_at TEXT NOT NULL,
+                    PRIMARY KEY (tweet_id, action)
+                )",
+                (),
+            )?;
+
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS author_engagements (
+                    author TEXT NOT NULL,
+                    action TEXT NOT NULL,
+                    engaged_at TEXT NOT NULL,
+                    PRIMARY KEY (author, action)
+                )",
+                (),
+            )?;
             debug!("Database tables created/verified successfully");
             Ok(())
         }).await?;
@@ -143,9 +182,7 @@ impl InteractionHistory {
                 Ok(InteractionMetrics {
                     tweet_id: row.get(0)?,
                     content: row.get(1)?,
-                    timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
+                    timestamp: timestamp_column(row, 2)?,
                     likes: row.get(3)?,
                     retweets: row.get(4)?,
                     quotes: row.get(5)?,
@@ -274,4 +311,173 @@ impl InteractionHistory {
         
         Ok(pending)
     }
-} 
\ No newline at end of file
+
+    pub async fn enqueue_post(&self, content: &str, due_at: DateTime<Utc>) -> Result<(), Box<dyn std::error::Error>> {
+        info!(due_at = %due_at, "Queueing scheduled post");
+
+        let content = content.to_string();
+        // Fixed-width timestamps so the text columns sort and compare chronologically
+        let created_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        let due_at = due_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        self.conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO scheduled_posts (content, created_at, due_at) VALUES (?, ?, ?)",
+                (content, created_at, due_at),
+            )?;
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
+    /// Queued posts, earliest due first
+    pub async fn get_scheduled_posts(&self) -> Result<Vec<ScheduledPost>, Box<dyn std::error::Error>> {
+        let result = self.conn.call(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, content, created_at, due_at FROM scheduled_posts ORDER BY due_at ASC"
+            )?;
+
+            let posts = stmt.query_map([], |row| {
+                Ok(ScheduledPost {
+                    id: row.get(0)?,
+                    content: row.get(1)?,
+                    created_at: timestamp_column(row, 2)?,
+                    due_at: timestamp_column(row, 3)?,
+                })
+            })?;
+
+            let mut result = Vec::new();
+            for post in posts {
+                result.push(post?);
+            }
+            Ok(result)
+        }).await?;
+
+        debug!(count = %result.len(), "Retrieved scheduled posts");
+        Ok(result)
+    }
+
+    pub async fn remove_scheduled_post(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        self.conn.call(move |conn| {
+            conn.execute("DELETE FROM scheduled_posts WHERE id = ?", [id])?;
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
+    /// Drop queued posts written more than `max_age` ago, e.g. held through a long
+    /// pause, returning how many were removed. Age counts from when the content was
+    /// written, not from its slot, so no post goes out older than `max_age`.
+    pub async fn drop_stale_posts(&self, max_age: chrono::Duration) -> Result<usize, Box<dyn std::error::Error>> {
+        let cutoff = (Utc::now() - max_age).to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let removed = self.conn.call(move |conn| {
+            Ok(conn.execute("DELETE FROM scheduled_posts WHERE created_at < ?", [cutoff])?)
+        }).await?;
+
+        if removed > 0 {
+            info!(removed = %removed, "Dropped stale scheduled posts");
+        }
+        Ok(removed)
+    }
+
+    /// Whether the bot already considered `action` for this tweet within `ttl`
+    pub async fn is_seen(&self, tweet_id: &str, action: &str, ttl: chrono::Duration) -> Result<bool, Box<dyn std::error::Error>> {
+        let tweet_id = tweet_id.to_string();
+        let action = action.to_string();
+        let cutoff = (Utc::now() - ttl).to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let seen = self.conn.call(move |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM seen_tweets
+                WHERE tweet_id = ? AND action = ? AND seen_at >= ?",
+                (tweet_id, action, cutoff),
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        }).await?;
+        Ok(seen)
+    }
+
+    pub async fn mark_seen(&self, tweet_id: &str, action: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!(tweet_id = %tweet_id, action = %action, "Marking tweet as seen");
+
+        let tweet_id = tweet_id.to_string();
+        let action = action.to_string();
+        let seen_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        self.conn.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO seen_tweets (tweet_id, action, seen_at) VALUES (?, ?, ?)",
+                (tweet_id, action, seen_at),
+            )?;
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
+    /// Whether the bot performed `action` on any tweet by `author` within `window`
+    pub async fn engaged_author_recently(&self, author: &str, action: &str, window: chrono::Duration) -> Result<bool, Box<dyn std::error::Error>> {
+        let author = author.to_string();
+        let action = action.to_string();
+        let cutoff = (Utc::now() - window).to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let engaged = self.conn.call(move |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM author_engagements
+                WHERE author = ? AND action = ? AND engaged_at >= ?",
+                (author, action, cutoff),
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        }).await?;
+        Ok(engaged)
+    }
+
+    pub async fn record_author_engagement(&self, author: &str, action: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let author = author.to_string();
+        let action = action.to_string();
+        let engaged_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        self.conn.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO author_engagements (author, action, engaged_at) VALUES (?, ?, ?)",
+                (author, action, engaged_at),
+            )?;
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
+    /// Remove seen entries older than `ttl`, then the oldest entries beyond `max_entries`.
+    /// Author engagements older than `ttl` go too.
+    pub async fn prune_seen_tweets(&self, ttl: chrono::Duration, max_entries: usize) -> Result<usize, Box<dyn std::error::Error>> {
+        let cutoff = (Utc::now() - ttl).to_rfc3339_opts(SecondsFormat::Secs, true);
+        let max_entries = max_entries as i64;
+
+        let removed = self.conn.call(move |conn| {
+            let expired = conn.execute("DELETE FROM seen_tweets WHERE seen_at < ?", [&cutoff])?;
+            conn.execute("DELETE FROM author_engagements WHERE engaged_at < ?", [&cutoff])?;
+            let overflow = conn.execute(
+                "DELETE FROM seen_tweets WHERE rowid NOT IN (
+                    SELECT rowid FROM seen_tweets ORDER BY seen_at DESC LIMIT ?
+                )",
+                [max_entries],
+            )?;
+            Ok(expired + overflow)
+        }).await?;
+
+        if removed > 0 {
+            debug!(removed = %removed, "Pruned seen tweets");
+        }
+        Ok(removed)
+    }
+}
+
+// An RFC 3339 column, as a row error rather than a panic when a stored value is malformed
+fn timestamp_column(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<DateTime<Utc>> {
+    let text: String = row.get(idx)?;
+    DateTime::parse_from_rfc3339(&text)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+}
@@ -1,4 +1,4 @@
-use super::types::{ChannelType, Source};
+use super::types::{ChannelType, MessageRole, Source};
 use rig_sqlite::{Column, ColumnValue, SqliteVectorStoreTable};
 use rig::Embed;
 use rusqlite::Row;
@@ -38,7 +38,7 @@ pub struct Message {
     pub channel_type: ChannelType,
     pub channel_id: String,
     pub account_id: String,
-    pub role: String,
+    pub role: MessageRole,
     #[embed]
     pub content: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -157,7 +157,7 @@ impl SqliteVectorStoreTable for Message {
             ),
             ("channel_id", Box::new(self.channel_id.clone())),
             ("account_id", Box::new(self.account_id.clone())),
-            ("role", Box::new(self.role.clone())),
+            ("role", Box::new(self.role.as_str().to_string())),
             ("content", Box::new(self.content.clone())),
             ("created_at", Box::new(self.created_at.to_rfc3339())),
         ]
@@ -230,7 +230,14 @@ impl TryFrom<&Row<'_>> for Message {
             )?,
             channel_id: row.get(4)?,
             account_id: row.get(5)?,
-            role: row.get(6)?,
+            // Rows are normalized by the role migration, this only guards later writes
+            role: {
+                let role: String = row.get(6)?;
+                MessageRole::from_str(&role).unwrap_or_else(|| {
+                    tracing::warn!(role = %role, "Unknown message role, reading it as user");
+                    MessageRole::User
+                })
+            },
             content: row.get(7)?,
             created_at: row.get(8)?,
         })
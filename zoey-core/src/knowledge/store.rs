@@ -4,11 +4,54 @@ use rig::{
 };
 use rig::embeddings::embedding::EmbeddingModel;
 use tokio_rusqlite::Connection;
-use tracing::{debug, info, error};
+use tracing::{debug, info, error, warn};
 
 use super::models::{Account, Channel, Document, Message, TradeAction, Trade};
 use rig_sqlite::{SqliteError, SqliteVectorIndex, SqliteVectorStore};
 use rusqlite::OptionalExtension;
+use common::storage;
+
+// Data migrations in order; `PRAGMA user_version` records how many have run
+const MIGRATIONS: &[fn(&rusqlite::Connection) -> rusqlite::Result<()>] = &[normalize_message_roles];
+
+fn run_migrations(conn: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    let applied = conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))? as usize;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let tx = conn.transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+        tx.commit()?;
+        info!(version = index + 1, "Applied knowledge base migration");
+    }
+    Ok(())
+}
+
+// Roles used to be free-form strings: known ones are normalized, anything else becomes "user"
+fn normalize_message_roles(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let unknown = conn
+        .prepare(
+            "SELECT role, COUNT(*) FROM messages
+             WHERE role IS NULL OR lower(trim(role)) NOT IN ('user', 'assistant', 'system')
+             GROUP BY role",
+        )?
+        .query_map([], |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (role, count) in unknown {
+        warn!(role = ?role, count, "Migrating messages with an unknown role to user");
+    }
+
+    conn.execute(
+        "UPDATE messages SET role = lower(trim(role))
+         WHERE lower(trim(role)) IN ('user', 'assistant', 'system')",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE messages SET role = 'user'
+         WHERE role IS NULL OR role NOT IN ('user', 'assistant', 'system')",
+        [],
+    )?;
+    Ok(())
+}
 
 #[derive(Clone)]
 pub struct KnowledgeBase<E: EmbeddingModel + Clone + 'static> {
@@ -72,6 +115,10 @@ impl<E: EmbeddingModel> KnowledgeBase<E> {
         .await
         .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
 
+        conn.call(|conn| run_migrations(conn).map_err(tokio_rusqlite::Error::from))
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
         info!("KnowledgeBase initialized successfully");
         Ok(Self {
             conn,
@@ -81,6 +128,28 @@ impl<E: EmbeddingModel> KnowledgeBase<E> {
         })
     }
 
+    /// Record which embedding model filled this database and refuse a different one.
+    /// Vectors from different models can't be compared, so search would silently return
+    /// nonsense; switching models means starting a fresh database.
+    pub async fn check_embedding_model(&self, model_id: &str) -> Result<(), VectorStoreError> {
+        let ndims = self.embedding_model.ndims();
+        let mismatch = storage::embedding_model_mismatch(&self.conn, "messages_embeddings", model_id, ndims, None)
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        match mismatch {
+            Some((model, dims)) => Err(VectorStoreError::DatastoreError(format!(
+                "database was embedded with {} ({} dims), refusing to use {} ({} dims); \
+                use a new --db-path or the original embedding model",
+                model, dims, model_id, ndims
+            ).into())),
+            None => {
+                info!(model = model_id, dims = ndims, "Using knowledge base embedding model");
+                Ok(())
+            }
+        }
+    }
+
     pub async fn create_user(&self, name: String, source: String) -> Result<i64, SqliteError> {
         info!(name = %name, source = %source, "Creating new user");
         let result = self.conn
@@ -423,4 +492,4 @@ impl<E: EmbeddingModel> KnowledgeBase<E> {
             .await
             .map_err(|e| SqliteError::DatabaseError(Box::new(e)))
     }
-}
\ No newline at end of file
+}
@@ -18,7 +18,10 @@ pub mod attention;
 pub mod character;
 pub mod clients;
 pub mod config;
+pub mod fact_check;
 pub mod knowledge;
 pub mod intel;
 pub mod loaders;
+pub mod price_tool;
+pub mod rng;
 pub mod interaction_history;
\ No newline at end of file
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crypto_agents::{coin_id_for_symbol, CoinGeckoClient};
+use rig::{completion::ToolDefinition, tool::Tool};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, warn};
+
+use crate::config::PriceLookupConfig;
+
+/// Every result starts with this, so callers can tell a raw tool result
+/// (which rig returns as the whole response) from a written reply
+pub const PRICE_LOOKUP_PREFIX: &str = "[price lookup]";
+
+#[derive(Debug, Deserialize)]
+pub struct PriceArgs {
+    pub symbol: String,
+}
+
+#[derive(Clone, Debug)]
+struct CachedPrice {
+    usd: f64,
+    change_24h: Option<f64>,
+    fetched_at: Instant,
+}
+
+#[derive(Default)]
+struct LookupState {
+    last_request: Option<Instant>,
+    prices: HashMap<String, CachedPrice>,
+}
+
+/// Live CoinGecko price lookup for the reply agent. Clones share the cache and the
+/// rate limit, so one tool can be handed to every reply's agent.
+#[derive(Clone)]
+pub struct PriceTool {
+    client: Arc<CoinGeckoClient>,
+    config: PriceLookupConfig,
+    state: Arc<Mutex<LookupState>>,
+}
+
+impl PriceTool {
+    pub fn new(config: PriceLookupConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: Arc::new(CoinGeckoClient::new()?),
+            config,
+            state: Arc::new(Mutex::new(LookupState::default())),
+        })
+    }
+
+    async fn lookup(&self, symbol: &str) -> String {
+        let Some(coin_id) = coin_id_for_symbol(symbol) else {
+            debug!(symbol = %symbol, "Unknown ticker for price lookup");
+            return format!("{} {}: not found, the ticker is unknown or ambiguous", PRICE_LOOKUP_PREFIX, symbol);
+        };
+
+        // The lock is held only to check and update the state, never across the request,
+        // so one slow CoinGecko call doesn't stall every other reply's lookup
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let cache_ttl = Duration::from_secs(self.config.cache_ttl_secs);
+            if let Some(cached) = state.prices.get(coin_id).filter(|c| c.fetched_at.elapsed() < cache_ttl) {
+                return format_price(symbol, cached);
+            }
+
+            let min_interval = Duration::from_secs(self.config.min_interval_secs);
+            if state.last_request.is_some_and(|last| last.elapsed() < min_interval) {
+                debug!(symbol = %symbol, "Price lookup rate limited");
+                return match state.prices.get(coin_id) {
+                    Some(cached) => format_price(symbol, cached),
+                    None => format!("{} {}: price unavailable right now", PRICE_LOOKUP_PREFIX, symbol),
+                };
+            }
+
+            // Taken before the request, so lookups made while it's in flight are rate limited
+            state.last_request = Some(Instant::now());
+        }
+
+        match self.client.get_simple_prices(&[coin_id]).await {
+            Ok(mut prices) => match prices.remove(coin_id) {
+                Some(price) => {
+                    let cached = CachedPrice {
+                        usd: price.usd,
+                        change_24h: price.price_change_24h,
+                        fetched_at: Instant::now(),
+                    };
+                    let result = format_price(symbol, &cached);
+                    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                    state.prices.insert(coin_id.to_string(), cached);
+                    result
+                }
+                None => format!("{} {}: not found", PRICE_LOOKUP_PREFIX, symbol),
+            },
+            Err(e) => {
+                warn!(symbol = %symbol, error = %e, "Price lookup failed");
+                format!("{} {}: price unavailable right now", PRICE_LOOKUP_PREFIX, symbol)
+            }
+        }
+    }
+}
+
+fn format_price(symbol: &str, price: &CachedPrice) -> String {
+    let age = price.fetched_at.elapsed().as_secs();
+    match price.change_24h {
+        Some(change) => format!(
+            "{} {}: ${} ({:+.2}% 24h, fetched {}s ago)",
+            PRICE_LOOKUP_PREFIX, symbol, format_usd(price.usd), change, age
+        ),
+        None => format!("{} {}: ${} (fetched {}s ago)", PRICE_LOOKUP_PREFIX, symbol, format_usd(price.usd), age),
+    }
+}
+
+// Small prices keep their significant digits
+fn format_usd(usd: f64) -> String {
+    if usd >= 1.0 {
+        format!("{:.2}", usd)
+    } else {
+        format!("{:.6}", usd)
+    }
+}
+
+impl Tool for PriceTool {
+    const NAME: &'static str = "get_token_price";
+
+    // Failures are reported in the output so the reply can say it's unsure
+    type Error = Infallible;
+    type Args = PriceArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Look up the live USD price and 24h change of a crypto token by ticker".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "symbol": {
+                        "type": "string",
+                        "description": "Token ticker, e.g. SOL or BTC"
+                    }
+                },
+                "required": ["symbol"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let symbol = args.symbol.trim().trim_start_matches('$').to_uppercase();
+        Ok(self.lookup(&symbol).await)
+    }
+}
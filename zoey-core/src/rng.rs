@@ -0,0 +1,34 @@
+//! Randomness for the bot's task choices and delays. Unseeded every draw comes
+//! from `thread_rng`; after [`seed`] all tasks share one seeded generator so a
+//! run's sequence of choices can be replayed.
+
+use std::sync::{Mutex, OnceLock};
+
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+
+static SEEDED: OnceLock<Mutex<StdRng>> = OnceLock::new();
+
+/// Make all later draws deterministic. Only the first call takes effect;
+/// returns false if a seed was already set.
+pub fn seed(seed: u64) -> bool {
+    SEEDED.set(Mutex::new(StdRng::seed_from_u64(seed))).is_ok()
+}
+
+pub fn is_seeded() -> bool {
+    SEEDED.get().is_some()
+}
+
+/// Run `f` with the seeded generator if there is one, otherwise `thread_rng`
+pub fn with_rng<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+    match SEEDED.get() {
+        // A panic while drawing leaves the generator usable
+        Some(rng) => f(&mut *rng.lock().unwrap_or_else(|e| e.into_inner())),
+        None => f(&mut rand::thread_rng()),
+    }
+}
+
+/// Uniform integer in `min..=max`
+pub fn range(min: u64, max: u64) -> u64 {
+    with_rng(|rng| rng.gen_range(min..=max))
+}
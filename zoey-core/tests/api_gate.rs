@@ -0,0 +1,72 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::MockTwitterApi;
+use tokio::time::timeout;
+use zoey_core::clients::api_gate::ApiGate;
+
+// Long enough for an access that's free to be granted, short enough to keep the tests quick
+const WAIT: Duration = Duration::from_millis(50);
+
+fn gate(max_concurrent_reads: usize) -> (Arc<MockTwitterApi>, ApiGate) {
+    let api = Arc::new(MockTwitterApi::new());
+    let gate = ApiGate::new(api.clone(), max_concurrent_reads);
+    (api, gate)
+}
+
+#[tokio::test]
+async fn reads_past_the_cap_wait_for_a_slot() {
+    let (_, gate) = gate(2);
+    let first = gate.read().await;
+    let _second = gate.read().await;
+    assert!(timeout(WAIT, gate.read()).await.is_err(), "a third read got in past the cap of 2");
+
+    drop(first);
+    assert!(timeout(WAIT, gate.read()).await.is_ok());
+}
+
+#[tokio::test]
+async fn a_zero_cap_still_allows_one_read() {
+    let (_, gate) = gate(0);
+    let only = gate.read().await;
+    assert!(timeout(WAIT, gate.read()).await.is_err());
+    drop(only);
+    assert!(timeout(WAIT, gate.read()).await.is_ok());
+}
+
+#[tokio::test]
+async fn writes_go_one_at_a_time_without_blocking_reads() {
+    let (api, gate) = gate(1);
+    let write = gate.write().await;
+    assert!(timeout(WAIT, gate.write()).await.is_err(), "a second write got in while the first was held");
+    assert!(timeout(WAIT, gate.read()).await.is_ok(), "a read waited for a write");
+
+    write.like_tweet("1").await.unwrap();
+    drop(write);
+    gate.write().await.retweet("1").await.unwrap();
+
+    assert_eq!(api.liked(), ["1"]);
+    assert_eq!(api.retweeted(), ["1"]);
+}
+
+#[tokio::test]
+async fn a_waiting_write_goes_once_the_held_one_is_released() {
+    let (api, gate) = gate(1);
+    let gate = Arc::new(gate);
+    let first = gate.write().await;
+
+    let waiting = tokio::spawn({
+        let gate = gate.clone();
+        async move { gate.write().await.like_tweet("2").await.map(|_| ()) }
+    });
+    tokio::time::sleep(WAIT).await;
+    assert!(!waiting.is_finished());
+    assert!(api.liked().is_empty());
+
+    first.like_tweet("1").await.unwrap();
+    drop(first);
+    waiting.await.unwrap().unwrap();
+    assert_eq!(api.liked(), ["1", "2"]);
+}
@@ -0,0 +1,29 @@
+use zoey_core::attention::{parse_reply_decision, parse_yes_no, AttentionCommand};
+
+#[test]
+fn reply_commands_parse_with_or_without_brackets() {
+    assert_eq!(parse_reply_decision("[RESPOND]"), Some(AttentionCommand::Respond));
+    assert_eq!(parse_reply_decision("**Ignore** - just a passing mention"), Some(AttentionCommand::Ignore));
+    assert_eq!(
+        parse_reply_decision("<think>They said respond or stop?</think>\n[STOP]"),
+        Some(AttentionCommand::Stop)
+    );
+}
+
+#[test]
+fn unclear_reply_decisions_are_rejected() {
+    assert_eq!(parse_reply_decision("I think I should respond, or maybe ignore it."), None);
+    assert_eq!(parse_reply_decision("Sure thing!"), None);
+    assert_eq!(parse_reply_decision(""), None);
+}
+
+#[test]
+fn yes_no_answers_parse_loosely() {
+    assert_eq!(parse_yes_no("true"), Some(true));
+    assert_eq!(parse_yes_no("  False.\n"), Some(false));
+    assert_eq!(parse_yes_no("**Yes**, this is worth liking"), Some(true));
+    assert_eq!(parse_yes_no("<think>true or false...</think> no"), Some(false));
+    assert_eq!(parse_yes_no("I would say true"), Some(true));
+    assert_eq!(parse_yes_no("could be true or false"), None);
+    assert_eq!(parse_yes_no("The kitchen is open."), None);
+}
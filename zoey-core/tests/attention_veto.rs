@@ -0,0 +1,27 @@
+use zoey_core::attention::AttentionVeto;
+
+fn veto() -> AttentionVeto {
+    let mut veto = AttentionVeto::new(
+        vec!["DM for signals".to_string(), "guaranteed 100x".to_string()],
+        vec!["scam.io".to_string()],
+    );
+    veto.expand_short_links = false;
+    veto
+}
+
+#[tokio::test]
+async fn keywords_match_case_insensitively() {
+    assert_eq!(
+        veto().check("Join now, dm FOR Signals!!").await,
+        Some("keyword \"dm for signals\"".to_string())
+    );
+    assert_eq!(veto().check("Solana fees are tiny today").await, None);
+}
+
+#[tokio::test]
+async fn domains_match_links_and_subdomains() {
+    assert_eq!(veto().check("claim at https://app.Scam.io/airdrop.").await, Some("domain scam.io".to_string()));
+    assert_eq!(veto().check("see www.scam.io/claim").await, Some("domain scam.io".to_string()));
+    // Only whole domain labels count
+    assert_eq!(veto().check("read https://notscam.io/post").await, None);
+}
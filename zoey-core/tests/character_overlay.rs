@@ -0,0 +1,57 @@
+use zoey_core::character::Character;
+
+const BASE: &str = r#"
+name = "Zoey"
+preamble = "You are Zoey."
+topics = ["cooking", "markets"]
+
+[style]
+adjectives = ["warm", "witty"]
+chat = ["keep it short"]
+"#;
+
+#[test]
+fn overlay_fields_replace_the_base() {
+    let overlay = r#"
+preamble = "You are Zoey, on production."
+
+[style]
+adjectives = ["calm"]
+"#;
+    let character = Character::from_toml_with_overlay(BASE, overlay).unwrap();
+
+    assert_eq!(character.name, "Zoey");
+    assert_eq!(character.preamble, "You are Zoey, on production.");
+    assert_eq!(character.topics, vec!["cooking", "markets"]);
+    assert_eq!(character.style.adjectives, vec!["calm"]);
+    // Untouched keys of a merged table are kept
+    assert_eq!(character.style.chat, vec!["keep it short"]);
+}
+
+#[test]
+fn append_policy_extends_arrays() {
+    let overlay = r#"
+topics = ["travel"]
+
+[style]
+adjectives = ["curious"]
+
+[merge]
+topics = "append"
+"style.adjectives" = "append"
+"#;
+    let character = Character::from_toml_with_overlay(BASE, overlay).unwrap();
+
+    assert_eq!(character.topics, vec!["cooking", "markets", "travel"]);
+    assert_eq!(character.style.adjectives, vec!["warm", "witty", "curious"]);
+}
+
+#[test]
+fn unknown_merge_policy_is_an_error() {
+    let overlay = r#"
+[merge]
+topics = "prepend"
+"#;
+    let err = Character::from_toml_with_overlay(BASE, overlay).unwrap_err();
+    assert!(err.to_string().contains("\"replace\" or \"append\""));
+}
@@ -0,0 +1,393 @@
+//! Shared harness for driving `TwitterClient` against a mock API and scripted models
+
+// Each test binary uses a different part of the harness
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex as StdMutex};
+
+use agent_twitter_client::error::{Result as TwitterResult, TwitterError};
+use agent_twitter_client::models::Tweet;
+use agent_twitter_client::search::SearchMode;
+use agent_twitter_client::timeline::v1::QueryTweetsResponse;
+use async_trait::async_trait;
+use rig::completion::{AssistantContent, CompletionError, CompletionModel, CompletionRequest, CompletionResponse};
+use rig::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
+use rig::OneOrMany;
+use serde_json::{json, Value};
+use tokio_rusqlite::Connection;
+
+use zoey_core::agent::Agent;
+use zoey_core::attention::{Attention, AttentionConfig};
+use zoey_core::character::Character;
+use zoey_core::clients::twitter::TwitterClient;
+use zoey_core::clients::twitter_api::TwitterApi;
+use zoey_core::config::{DelayConfig, PriceLookupConfig, TwitterConfig};
+use zoey_core::interaction_history::InteractionHistory;
+use zoey_core::knowledge::KnowledgeBase;
+
+pub const BOT_USERNAME: &str = "zoey_test";
+
+/// API methods a test can make fail
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Call {
+    HomeTimeline,
+    Search,
+    GetTweet,
+    SendTweet,
+    QuoteTweet,
+    Like,
+    Retweet,
+    DeleteTweet,
+}
+
+#[derive(Clone, Debug)]
+pub struct SentTweet {
+    pub text: String,
+    pub reply_to: Option<String>,
+    pub quoted: Option<String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    timeline: Vec<Value>,
+    search_results: HashMap<String, Vec<Tweet>>,
+    tweets: HashMap<String, Tweet>,
+    // None lets a call through, so a failure can be queued behind some successes
+    failures: HashMap<Call, VecDeque<Option<TwitterError>>>,
+    calls: Vec<Call>,
+    searches: Vec<String>,
+    sent: Vec<SentTweet>,
+    liked: Vec<String>,
+    retweeted: Vec<String>,
+    deleted: Vec<String>,
+}
+
+/// Programmable `TwitterApi`: serves canned tweets, records every write and
+/// returns queued errors. Clones share state, so keep one to inspect after the run.
+#[derive(Clone, Default)]
+pub struct MockTwitterApi {
+    state: Arc<StdMutex<MockState>>,
+}
+
+impl MockTwitterApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_timeline(&self, tweets: Vec<Value>) {
+        self.state.lock().unwrap().timeline = tweets;
+    }
+
+    /// Results for any search whose query starts with `prefix`
+    pub fn set_search_results(&self, prefix: &str, tweets: Vec<Tweet>) {
+        self.state.lock().unwrap().search_results.insert(prefix.to_string(), tweets);
+    }
+
+    /// Tweets served by `get_tweet`, e.g. parents of a reply chain
+    pub fn add_tweet(&self, tweet: Tweet) {
+        let id = tweet.id.clone().unwrap_or_default();
+        self.state.lock().unwrap().tweets.insert(id, tweet);
+    }
+
+    /// Make the next call to `call` fail with `error`. Queued errors are used in order.
+    pub fn fail_next(&self, call: Call, error: TwitterError) {
+        self.state.lock().unwrap().failures.entry(call).or_default().push_back(Some(error));
+    }
+
+    /// Let the next `n` calls to `call` through before any failure queued after this
+    pub fn succeed_next(&self, call: Call, n: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.failures.entry(call).or_default().extend(std::iter::repeat_with(|| None).take(n));
+    }
+
+    pub fn sent(&self) -> Vec<SentTweet> {
+        self.state.lock().unwrap().sent.clone()
+    }
+
+    pub fn liked(&self) -> Vec<String> {
+        self.state.lock().unwrap().liked.clone()
+    }
+
+    pub fn retweeted(&self) -> Vec<String> {
+        self.state.lock().unwrap().retweeted.clone()
+    }
+
+    pub fn deleted(&self) -> Vec<String> {
+        self.state.lock().unwrap().deleted.clone()
+    }
+
+    pub fn searches(&self) -> Vec<String> {
+        self.state.lock().unwrap().searches.clone()
+    }
+
+    pub fn call_count(&self, call: Call) -> usize {
+        self.state.lock().unwrap().calls.iter().filter(|c| **c == call).count()
+    }
+
+    // Record the call and pop a queued failure for it, if any
+    fn enter(&self, call: Call) -> TwitterResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.calls.push(call);
+        match state.failures.get_mut(&call).and_then(VecDeque::pop_front).flatten() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl TwitterApi for MockTwitterApi {
+    async fn get_home_timeline(&self, count: i32, _seen_tweet_ids: Vec<String>) -> TwitterResult<Vec<Value>> {
+        self.enter(Call::HomeTimeline)?;
+        let state = self.state.lock().unwrap();
+        Ok(state.timeline.iter().take(count.max(0) as usize).cloned().collect())
+    }
+
+    async fn search_tweets(
+        &self,
+        query: &str,
+        max_tweets: i32,
+        _search_mode: SearchMode,
+        _cursor: Option<String>,
+    ) -> TwitterResult<QueryTweetsResponse> {
+        self.enter(Call::Search)?;
+        let mut state = self.state.lock().unwrap();
+        state.searches.push(query.to_string());
+        let tweets = state
+            .search_results
+            .iter()
+            .find(|(prefix, _)| query.starts_with(prefix.as_str()))
+            .map(|(_, tweets)| tweets.iter().take(max_tweets.max(0) as usize).cloned().collect())
+            .unwrap_or_default();
+        Ok(QueryTweetsResponse { tweets, next: None, previous: None })
+    }
+
+    async fn get_tweet(&self, id: &str) -> TwitterResult<Tweet> {
+        self.enter(Call::GetTweet)?;
+        self.state
+            .lock()
+            .unwrap()
+            .tweets
+            .get(id)
+            .cloned()
+            .ok_or_else(|| TwitterError::Api(format!("tweet {} not found", id)))
+    }
+
+    async fn send_tweet(
+        &self,
+        text: &str,
+        reply_to: Option<&str>,
+        _media_data: Option<Vec<(Vec<u8>, String)>>,
+    ) -> TwitterResult<Value> {
+        self.enter(Call::SendTweet)?;
+        let mut state = self.state.lock().unwrap();
+        state.sent.push(SentTweet {
+            text: text.to_string(),
+            reply_to: reply_to.map(str::to_string),
+            quoted: None,
+        });
+        Ok(created(state.sent.len()))
+    }
+
+    async fn send_quote_tweet(
+        &self,
+        text: &str,
+        quoted_tweet_id: &str,
+        _media_data: Option<Vec<(Vec<u8>, String)>>,
+    ) -> TwitterResult<Value> {
+        self.enter(Call::QuoteTweet)?;
+        let mut state = self.state.lock().unwrap();
+        state.sent.push(SentTweet {
+            text: text.to_string(),
+            reply_to: None,
+            quoted: Some(quoted_tweet_id.to_string()),
+        });
+        Ok(created(state.sent.len()))
+    }
+
+    async fn like_tweet(&self, tweet_id: &str) -> TwitterResult<Value> {
+        self.enter(Call::Like)?;
+        self.state.lock().unwrap().liked.push(tweet_id.to_string());
+        Ok(json!({}))
+    }
+
+    async fn retweet(&self, tweet_id: &str) -> TwitterResult<Value> {
+        self.enter(Call::Retweet)?;
+        self.state.lock().unwrap().retweeted.push(tweet_id.to_string());
+        Ok(json!({}))
+    }
+
+    async fn delete_tweet(&self, tweet_id: &str) -> TwitterResult<Value> {
+        self.enter(Call::DeleteTweet)?;
+        self.state.lock().unwrap().deleted.push(tweet_id.to_string());
+        Ok(json!({}))
+    }
+}
+
+/// Id of the `n`th tweet sent, 1-based
+pub fn sent_id(n: usize) -> String {
+    format!("sent-{}", n)
+}
+
+// Response to a created tweet, as the scraper returns it
+fn created(n: usize) -> Value {
+    json!({"data": {"create_tweet": {"tweet_results": {"result": {"rest_id": sent_id(n)}}}}})
+}
+
+/// Completion model answering from rules: the first rule whose needle appears in
+/// the prompt wins, otherwise the default reply. Prompts are recorded.
+#[derive(Clone)]
+pub struct ScriptedModel {
+    rules: Arc<Vec<(String, String)>>,
+    default_reply: String,
+    prompts: Arc<StdMutex<Vec<String>>>,
+}
+
+impl ScriptedModel {
+    pub fn new(default_reply: &str) -> Self {
+        Self {
+            rules: Arc::new(Vec::new()),
+            default_reply: default_reply.to_string(),
+            prompts: Arc::new(StdMutex::new(Vec::new())),
+        }
+    }
+
+    pub fn rule(mut self, needle: &str, response: &str) -> Self {
+        Arc::make_mut(&mut self.rules).push((needle.to_string(), response.to_string()));
+        self
+    }
+
+    pub fn prompts(&self) -> Vec<String> {
+        self.prompts.lock().unwrap().clone()
+    }
+}
+
+impl CompletionModel for ScriptedModel {
+    type Response = ();
+
+    async fn completion(&self, request: CompletionRequest) -> Result<CompletionResponse<()>, CompletionError> {
+        let prompt = serde_json::to_string(&request.prompt)?;
+        self.prompts.lock().unwrap().push(prompt.clone());
+        let reply = self
+            .rules
+            .iter()
+            .find(|(needle, _)| prompt.contains(needle.as_str()))
+            .map(|(_, response)| response.clone())
+            .unwrap_or_else(|| self.default_reply.clone());
+        Ok(CompletionResponse {
+            choice: OneOrMany::one(AssistantContent::text(reply)),
+            raw_response: (),
+        })
+    }
+}
+
+/// Deterministic embedding from the text's bytes, enough for the vector store
+#[derive(Clone)]
+pub struct HashEmbedding;
+
+impl EmbeddingModel for HashEmbedding {
+    const MAX_DOCUMENTS: usize = 64;
+
+    fn ndims(&self) -> usize {
+        8
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        Ok(texts
+            .into_iter()
+            .map(|text| {
+                let mut vec = vec![0.0; 8];
+                for (i, byte) in text.bytes().enumerate() {
+                    vec[i % 8] += byte as f64 / 255.0;
+                }
+                Embedding { document: text, vec }
+            })
+            .collect())
+    }
+}
+
+/// Config with every wait at zero and the live price lookup off
+pub fn test_config() -> TwitterConfig {
+    TwitterConfig {
+        enabled: true,
+        min_action_interval: 0,
+        max_action_interval: 0,
+        min_task_interval: 0,
+        max_task_interval: 0,
+        delay: DelayConfig {
+            min_delay_secs: 0,
+            ..DelayConfig::default()
+        },
+        price_lookup: PriceLookupConfig {
+            enabled: false,
+            ..PriceLookupConfig::default()
+        },
+        ..TwitterConfig::default()
+    }
+}
+
+/// Load sqlite-vec into every connection opened from here on
+pub fn register_vec_extension() {
+    common::storage::init_sqlite_vec().expect("register sqlite-vec");
+}
+
+/// Client on a fresh in-memory database, with `model` behind both the agent and attention
+pub async fn test_client(
+    api: &MockTwitterApi,
+    model: &ScriptedModel,
+    config: TwitterConfig,
+) -> TwitterClient<ScriptedModel, HashEmbedding> {
+    register_vec_extension();
+
+    let conn = Connection::open_in_memory().await.expect("open database");
+    let knowledge = KnowledgeBase::new(conn.clone(), HashEmbedding).await.expect("knowledge base");
+    let history = InteractionHistory::new(conn).await.expect("interaction history");
+    let character = Character::from_toml("name = \"Zoey\"\npreamble = \"You are Zoey.\"").expect("character");
+    let agent = Agent::new(character, model.clone(), knowledge, history);
+    let attention = Attention::new(
+        AttentionConfig {
+            bot_names: vec![BOT_USERNAME.to_string()],
+            ..AttentionConfig::default()
+        },
+        model.clone(),
+    );
+
+    let api: Arc<dyn TwitterApi> = Arc::new(api.clone());
+    TwitterClient::with_api(agent, attention, BOT_USERNAME.to_string(), api, None, Some(config))
+}
+
+/// Search-style tweet
+pub fn tweet(id: &str, username: &str, text: &str) -> Tweet {
+    serde_json::from_value(json!({
+        "id": id,
+        "conversation_id": id,
+        "user_id": format!("user-{}", username),
+        "username": username,
+        "text": text,
+        "likes": 0,
+        "retweets": 0,
+        "hashtags": [],
+        "mentions": [],
+        "photos": [],
+        "thread": [],
+        "urls": [],
+        "videos": [],
+    }))
+    .expect("valid tweet")
+}
+
+/// Raw home timeline entry, as the scraper returns it
+pub fn timeline_tweet(id: &str, text: &str) -> Value {
+    json!({
+        "legacy": {
+            "id_str": id,
+            "full_text": text,
+            "favorite_count": 0,
+            "retweet_count": 0,
+        }
+    })
+}
@@ -0,0 +1,51 @@
+use zoey_core::clients::conversation::{fit_history, history_context, summary_prompt};
+
+fn thread(texts: &[&str]) -> Vec<(String, String)> {
+    texts.iter().enumerate().map(|(i, text)| (i.to_string(), text.to_string())).collect()
+}
+
+#[test]
+fn short_thread_is_kept_whole() {
+    let history = thread(&["gm", "what's the plan for the week?", "@zoey any thoughts?"]);
+    let window = fit_history(&history, 1000);
+
+    assert!(window.older.is_empty());
+    let context = history_context(&window, None).unwrap();
+    assert!(context.ends_with("- gm\n- what's the plan for the week?\n- @zoey any thoughts?"));
+    assert!(!context.contains("earlier messages"));
+}
+
+#[test]
+fn older_messages_past_the_budget_are_summarized() {
+    let long = "a long message about token unlocks ".repeat(10);
+    let history = thread(&[&long, &long, &long, "@zoey so is it bullish?"]);
+    let window = fit_history(&history, 150);
+
+    assert_eq!(window.older.len(), 2);
+    assert_eq!(window.recent.last().unwrap().1, "@zoey so is it bullish?");
+
+    let summarized = history_context(&window, Some(" Two users argue about unlocks. ")).unwrap();
+    assert!(summarized.contains("Summary of 2 earlier messages: Two users argue about unlocks.\n"));
+    let counted = history_context(&window, None).unwrap();
+    assert!(counted.contains("(2 earlier messages left out)"));
+
+    let prompt = summary_prompt(window.older, 2000).unwrap();
+    assert!(prompt.contains("token unlocks"));
+    assert_eq!(summary_prompt(&[], 2000), None);
+}
+
+#[test]
+fn replied_to_tweet_is_kept_even_over_budget() {
+    let huge = "x".repeat(10_000);
+    let history = thread(&["earlier", &huge]);
+    let window = fit_history(&history, 10);
+
+    assert_eq!(window.recent, &history[1..]);
+    assert!(history_context(&window, None).unwrap().ends_with(&huge));
+}
+
+#[test]
+fn empty_thread_has_no_context() {
+    let history = thread(&[]);
+    assert_eq!(history_context(&fit_history(&history, 1000), None), None);
+}
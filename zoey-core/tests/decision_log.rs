@@ -0,0 +1,22 @@
+use zoey_core::clients::decision_log::{redact, DecisionEvent, Stage};
+
+#[test]
+fn credentials_are_masked() {
+    assert_eq!(
+        redact("request failed: Cookie auth_token=abc123; ct0=def456;"),
+        "request failed: Cookie auth_token=[redacted] ct0=[redacted]"
+    );
+    assert_eq!(redact("Authorization: Bearer AAAA1234"), "Authorization: Bearer [redacted]");
+    assert_eq!(redact(r#"{"api_key": "sk-123", "model": "x"}"#), r#"{"api_key": [redacted] "model": "x"}"#);
+    assert_eq!(redact("rate limited, retry in 60s"), "rate limited, retry in 60s");
+}
+
+#[test]
+fn errors_are_redacted_but_tweets_kept() {
+    let event = DecisionEvent::new("like", "1", Some("kim"), Stage::Action, "failed").with_error("bad password: hunter2");
+    assert_eq!(event.detail.as_deref(), Some("bad password: [redacted]"));
+
+    let tweet = "my password: is too long to remember";
+    let event = DecisionEvent::new("like", "1", None, Stage::Verdict, "yes").with_detail(tweet);
+    assert_eq!(event.detail.as_deref(), Some(tweet));
+}
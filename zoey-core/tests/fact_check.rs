@@ -0,0 +1,40 @@
+use zoey_core::config::FactCheckConfig;
+use zoey_core::fact_check::{extract_figures, verify_figures, FactCheck, FigureKind};
+
+fn figures(text: &str) -> Vec<(FigureKind, f64)> {
+    extract_figures(text).into_iter().map(|figure| (figure.kind, figure.value)).collect()
+}
+
+#[test]
+fn suffixes_and_separators_are_expanded() {
+    assert_eq!(figures("Cap hit $1.2B today"), [(FigureKind::MarketCap, 1.2e9)]);
+    assert_eq!(figures("Volume $850m, price $43.2k"), [(FigureKind::MarketCap, 850e6), (FigureKind::Price, 43_200.0)]);
+    assert_eq!(figures("ETH at $1,234."), [(FigureKind::Price, 1234.0)]);
+    assert_eq!(figures("Up +5.3% and down -2%"), [(FigureKind::Percent, 5.3), (FigureKind::Percent, 2.0)]);
+}
+
+#[test]
+fn tickers_and_bare_numbers_are_not_figures() {
+    assert!(figures("$BTC v2 launch in 24h, 1,234 holders").is_empty());
+}
+
+#[test]
+fn figures_are_checked_against_the_source() {
+    let config = FactCheckConfig::default();
+    let source = "SOL price: $142.50, 24h change: 6.1%, market cap $68.4B";
+
+    assert_eq!(verify_figures("SOL $143 (+6.2%), cap $68B", source, &config), FactCheck::Verified);
+    assert_eq!(
+        verify_figures("SOL $150 and up 9%", source, &config),
+        FactCheck::Mismatch(vec!["$150".to_string(), "9%".to_string()])
+    );
+    assert_eq!(verify_figures("No numbers here", source, &config), FactCheck::Verified);
+    assert_eq!(verify_figures("Up 5%", "no data", &config), FactCheck::Unverifiable);
+}
+
+#[test]
+fn fact_checking_is_opt_in() {
+    let config = FactCheckConfig::default();
+    assert!(!config.enabled);
+    assert!(!config.block_on_mismatch);
+}
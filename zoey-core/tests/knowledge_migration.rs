@@ -0,0 +1,62 @@
+mod common;
+
+use common::{register_vec_extension, HashEmbedding};
+use tokio_rusqlite::Connection;
+use zoey_core::knowledge::KnowledgeBase;
+
+async fn roles(conn: &Connection) -> Vec<(String, String)> {
+    conn.call(|conn| {
+        let mut stmt = conn.prepare("SELECT id, role FROM messages ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .await
+    .expect("read roles")
+}
+
+async fn user_version(conn: &Connection) -> i64 {
+    conn.call(|conn| Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?))
+        .await
+        .expect("read user_version")
+}
+
+#[tokio::test]
+async fn legacy_roles_are_normalized_once() {
+    register_vec_extension();
+    let conn = Connection::open_in_memory().await.expect("open database");
+    KnowledgeBase::new(conn.clone(), HashEmbedding).await.expect("knowledge base");
+    assert_eq!(user_version(&conn).await, 1);
+
+    // Messages written before roles were an enum, on a database that never migrated
+    conn.call(|conn| {
+        conn.execute_batch(
+            "PRAGMA user_version = 0;
+             INSERT INTO messages (id, source, source_id, channel_type, channel_id, account_id, role, content)
+             VALUES ('1', 'discord', 'a', 'text', 'c', 'u', ' Assistant ', 'gm'),
+                    ('2', 'discord', 'b', 'text', 'c', 'u', 'bot', 'gm'),
+                    ('3', 'discord', 'c', 'text', 'c', 'u', NULL, 'gm'),
+                    ('4', 'discord', 'd', 'text', 'c', 'u', 'system', 'gm');",
+        )?;
+        Ok(())
+    })
+    .await
+    .expect("seed legacy messages");
+
+    KnowledgeBase::new(conn.clone(), HashEmbedding).await.expect("reopen knowledge base");
+    let expected = [("1", "assistant"), ("2", "user"), ("3", "user"), ("4", "system")]
+        .map(|(id, role)| (id.to_string(), role.to_string()));
+    assert_eq!(roles(&conn).await, expected);
+    assert_eq!(user_version(&conn).await, 1);
+
+    // Already applied, so a later odd role is left for the enum parser to handle
+    conn.call(|conn| {
+        conn.execute("UPDATE messages SET role = 'bot' WHERE id = '4'", [])?;
+        Ok(())
+    })
+    .await
+    .expect("update role");
+    KnowledgeBase::new(conn.clone(), HashEmbedding).await.expect("reopen knowledge base");
+    assert_eq!(roles(&conn).await[3].1, "bot");
+}
@@ -0,0 +1,25 @@
+use chrono::{Duration, Utc};
+use tokio_rusqlite::Connection;
+
+use zoey_core::config::PostScheduleConfig;
+use zoey_core::interaction_history::InteractionHistory;
+
+#[tokio::test]
+async fn staleness_counts_from_when_a_post_was_written() {
+    let conn = Connection::open_in_memory().await.unwrap();
+    let history = InteractionHistory::new(conn).await.unwrap();
+
+    // Overdue for a long time, but only just written
+    history.enqueue_post("gm", Utc::now() - Duration::hours(10)).await.unwrap();
+    assert_eq!(history.drop_stale_posts(Duration::hours(6)).await.unwrap(), 0);
+    assert_eq!(history.get_scheduled_posts().await.unwrap().len(), 1);
+}
+
+#[test]
+fn posts_are_written_within_half_the_queue_age() {
+    let schedule = PostScheduleConfig::default();
+    assert_eq!(schedule.generation_lead(), Duration::minutes(5));
+
+    let short = PostScheduleConfig { max_queue_age_minutes: 4, ..PostScheduleConfig::default() };
+    assert_eq!(short.generation_lead(), Duration::minutes(2));
+}
@@ -0,0 +1,40 @@
+use serde_json::json;
+use zoey_core::clients::thread::{created_tweet_id, number_parts, split_thread};
+
+#[test]
+fn short_text_stays_one_tweet() {
+    assert_eq!(split_thread("  Just one thought.  ", 280, true), vec!["Just one thought."]);
+    assert!(split_thread("   ", 280, true).is_empty());
+}
+
+#[test]
+fn parts_break_between_sentences() {
+    let text = "Salt early. Taste often! Rest the meat before slicing? Always.";
+    let parts = split_thread(text, 30, false);
+
+    assert_eq!(parts, vec!["Salt early. Taste often!", "Rest the meat before slicing?", "Always."]);
+    assert!(parts.iter().all(|part| part.chars().count() <= 30));
+}
+
+#[test]
+fn numbered_parts_leave_room_for_the_counter() {
+    let text = "word ".repeat(100);
+    let parts = number_parts(split_thread(&text, 50, true));
+
+    assert!(parts.len() > 1);
+    assert!(parts.iter().all(|part| part.chars().count() <= 50));
+    assert!(parts[0].ends_with(&format!(" 1/{}", parts.len())));
+}
+
+#[test]
+fn overlong_words_are_split() {
+    let parts = split_thread(&"x".repeat(25), 10, false);
+    assert_eq!(parts, vec!["x".repeat(10), "x".repeat(10), "x".repeat(5)]);
+}
+
+#[test]
+fn reads_the_created_tweet_id() {
+    let response = json!({"data": {"create_tweet": {"tweet_results": {"result": {"rest_id": "1850"}}}}});
+    assert_eq!(created_tweet_id(&response), Some("1850".to_string()));
+    assert_eq!(created_tweet_id(&json!({})), None);
+}
@@ -0,0 +1,46 @@
+use zoey_core::config::TwitterConfig;
+
+#[test]
+fn missing_fields_take_defaults() {
+    let (config, warnings) = TwitterConfig::from_json(r#"{"enabled": true, "max_tweets_per_hour": 2}"#).unwrap();
+
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert!(config.enabled);
+    assert_eq!(config.max_tweets_per_hour, 2);
+    assert_eq!(config.max_tweet_length, TwitterConfig::default().max_tweet_length);
+}
+
+#[test]
+fn bad_fields_fall_back_and_good_ones_apply() {
+    let (config, warnings) = TwitterConfig::from_json(
+        r#"{
+            "enabled": true,
+            "max_tweet_lenght": 500,
+            "max_likes_per_hour": "lots",
+            "min_task_interval": 7200,
+            "max_task_interval": 60,
+            "post_schedule": {"posts_per_day": 4, "quiet_hours_start": 30}
+        }"#,
+    )
+    .unwrap();
+    let defaults = TwitterConfig::default();
+
+    assert!(config.enabled);
+    assert_eq!(config.max_likes_per_hour, defaults.max_likes_per_hour);
+    assert_eq!(config.min_task_interval, defaults.min_task_interval);
+    assert_eq!(config.max_task_interval, defaults.max_task_interval);
+    assert_eq!(config.post_schedule.posts_per_day, 4);
+    assert_eq!(config.post_schedule.quiet_hours_start, None);
+
+    assert_eq!(warnings.len(), 4, "{:?}", warnings);
+    assert!(warnings.iter().any(|w| w.contains("Unknown field `max_tweet_lenght`")));
+    assert!(warnings.iter().any(|w| w.contains("`max_likes_per_hour`")));
+    assert!(warnings.iter().any(|w| w.contains("min_task_interval (7200) is above")));
+    assert!(warnings.iter().any(|w| w.contains("post_schedule.quiet_hours_start")));
+}
+
+#[test]
+fn malformed_json_is_an_error() {
+    assert!(TwitterConfig::from_json("{\"enabled\": true,").is_err());
+    assert!(TwitterConfig::from_json("[1, 2]").is_err());
+}
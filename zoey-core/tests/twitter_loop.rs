@@ -0,0 +1,405 @@
+mod common;
+
+use agent_twitter_client::error::TwitterError;
+use serde_json::Value;
+use zoey_core::config::{DecisionLogConfig, ThreadConfig, TimelineSearch, TwitterConfig};
+use zoey_core::intel::{CryptoIntel, IntelSource};
+
+use common::{
+    sent_id, test_client, test_config, timeline_tweet, tweet, Call, MockTwitterApi, ScriptedModel, BOT_USERNAME,
+};
+
+const REPLY: &str = "The kitchen is open, pull up a chair.";
+
+fn mentions_query() -> String {
+    format!("@{}", BOT_USERNAME)
+}
+
+fn search(query: &str) -> TimelineSearch {
+    TimelineSearch {
+        query: query.to_string(),
+        max_results: 10,
+        interval_minutes: 0,
+        enable: true,
+    }
+}
+
+// Likes everything, never retweets or quotes
+fn liking_model() -> ScriptedModel {
+    ScriptedModel::new(REPLY)
+        .rule("whether to like", "true")
+        .rule("whether to retweet", "false")
+        .rule("whether to quote", "false")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replies_to_a_mention() {
+    let api = MockTwitterApi::new();
+    api.set_search_results(&mentions_query(), vec![tweet("100", "alice", "@zoey_test what's cooking today?")]);
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+
+    client.process_mentions(&test_config()).await;
+
+    let sent = api.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].text, REPLY);
+    assert_eq!(sent[0].reply_to.as_deref(), Some("100"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handles_each_mention_once() {
+    let api = MockTwitterApi::new();
+    api.set_search_results(&mentions_query(), vec![tweet("101", "alice", "@zoey_test gm")]);
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+
+    client.process_mentions(&test_config()).await;
+    client.process_mentions(&test_config()).await;
+
+    assert_eq!(api.call_count(Call::Search), 2);
+    assert_eq!(api.sent().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn does_not_reply_to_itself() {
+    let api = MockTwitterApi::new();
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+
+    client
+        .handle_mention(tweet("102", BOT_USERNAME, "@zoey_test talking to myself"))
+        .await
+        .unwrap();
+
+    assert!(api.sent().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follows_the_attention_decision() {
+    let api = MockTwitterApi::new();
+    let ignoring = ScriptedModel::new(REPLY).rule("Choose one response option", "[IGNORE]");
+    let client = test_client(&api, &ignoring, test_config()).await;
+    client
+        .handle_mention(tweet("103", "bob", "anyone else watching the market today?"))
+        .await
+        .unwrap();
+    assert!(api.sent().is_empty());
+    assert!(ignoring.prompts().iter().any(|p| p.contains("Choose one response option")));
+
+    let responding = ScriptedModel::new(REPLY).rule("Choose one response option", "[RESPOND]");
+    let client = test_client(&api, &responding, test_config()).await;
+    client
+        .handle_mention(tweet("104", "bob", "anyone else watching the market today?"))
+        .await
+        .unwrap();
+    assert_eq!(api.sent().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follows_the_reply_chain() {
+    let api = MockTwitterApi::new();
+    api.add_tweet(tweet("105", "carol", "what's the best dish for a bear market?"));
+    let mut reply = tweet("106", "dave", "@zoey_test settle this");
+    reply.in_reply_to_status_id = Some("105".to_string());
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+
+    client.handle_mention(reply).await.unwrap();
+
+    assert_eq!(api.call_count(Call::GetTweet), 1);
+    assert_eq!(api.sent().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_a_failed_reply() {
+    let api = MockTwitterApi::new();
+    api.fail_next(Call::SendTweet, TwitterError::RateLimit);
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+
+    let result = client.handle_mention(tweet("107", "erin", "@zoey_test hello?")).await;
+
+    assert!(result.is_err());
+    assert!(api.sent().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retries_a_failed_reply_next_time() {
+    let api = MockTwitterApi::new();
+    api.fail_next(Call::SendTweet, TwitterError::RateLimit);
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+    let mention = tweet("109", "gina", "@zoey_test still there?");
+
+    assert!(client.handle_mention(mention.clone()).await.is_err());
+    client.handle_mention(mention.clone()).await.unwrap();
+    client.handle_mention(mention).await.unwrap();
+
+    assert_eq!(api.call_count(Call::SendTweet), 2);
+    assert_eq!(api.sent().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retries_mentions_after_a_failed_fetch() {
+    let api = MockTwitterApi::new();
+    api.set_search_results(&mentions_query(), vec![tweet("108", "frank", "@zoey_test you there?")]);
+    api.fail_next(Call::Search, TwitterError::Api("service unavailable".to_string()));
+    let client = test_client(&api, &ScriptedModel::new(REPLY), test_config()).await;
+
+    client.process_mentions(&test_config()).await;
+    assert!(api.sent().is_empty());
+
+    client.process_mentions(&test_config()).await;
+    assert_eq!(api.sent().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_query_likes_matching_tweets() {
+    let api = MockTwitterApi::new();
+    api.set_search_results("solana", vec![tweet("200", "gina", "solana fees are tiny today")]);
+    let config = test_config();
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+
+    assert_eq!(api.liked(), vec!["200".to_string()]);
+    assert!(api.retweeted().is_empty());
+    assert!(api.sent().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_query_applies_filters() {
+    let api = MockTwitterApi::new();
+    let config = TwitterConfig {
+        exclude_retweets: true,
+        exclude_replies: true,
+        min_likes: Some(5),
+        ..test_config()
+    };
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+
+    assert_eq!(api.searches(), vec!["solana -is:retweet -is:reply min_faves:5".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn search_query_skips_low_engagement() {
+    let api = MockTwitterApi::new();
+    api.set_search_results("solana", vec![tweet("201", "hank", "quiet tweet")]);
+    let config = TwitterConfig {
+        min_likes: Some(10),
+        ..test_config()
+    };
+    let model = liking_model();
+    let client = test_client(&api, &model, config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+
+    assert!(api.liked().is_empty());
+    assert!(model.prompts().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_like_does_not_stop_the_query() {
+    let api = MockTwitterApi::new();
+    api.set_search_results(
+        "solana",
+        vec![tweet("202", "ivy", "first solana tweet"), tweet("203", "jack", "second solana tweet")],
+    );
+    api.fail_next(Call::Like, TwitterError::RateLimit);
+    let config = test_config();
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+
+    assert_eq!(api.call_count(Call::Like), 2);
+    assert_eq!(api.liked(), vec!["203".to_string()]);
+
+    // The failed like is tried again on the next pass, the done one isn't
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+    assert_eq!(api.call_count(Call::Like), 3);
+    assert_eq!(api.liked(), vec!["203".to_string(), "202".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn likes_each_author_once_per_cooldown() {
+    let api = MockTwitterApi::new();
+    api.set_search_results(
+        "solana",
+        vec![
+            tweet("204", "kim", "solana tps looks good"),
+            tweet("205", "Kim", "more solana thoughts"),
+            tweet("206", "lee", "solana is fast"),
+        ],
+    );
+    let config = test_config();
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+    assert_eq!(api.liked(), vec!["204".to_string(), "206".to_string()]);
+
+    // Without a cooldown the second tweet from the same author is fair game
+    let config = TwitterConfig {
+        author_cooldown_minutes: 0,
+        ..test_config()
+    };
+    let api = MockTwitterApi::new();
+    api.set_search_results(
+        "solana",
+        vec![tweet("207", "kim", "solana tps looks good"), tweet("208", "kim", "more solana thoughts")],
+    );
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+    assert_eq!(api.liked(), vec!["207".to_string(), "208".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn decisions_are_logged() {
+    let path = std::env::temp_dir().join(format!("zoey_decisions_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let api = MockTwitterApi::new();
+    api.set_search_results("solana", vec![tweet("209", "ivy", "solana fees are tiny today")]);
+    let config = TwitterConfig {
+        decision_log: DecisionLogConfig {
+            enabled: true,
+            path: path.display().to_string(),
+            ..DecisionLogConfig::default()
+        },
+        ..test_config()
+    };
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_search_query(&search("solana"), &config).await.unwrap();
+    // The writer thread flushes once the last handle is dropped
+    drop(client);
+
+    let mut events: Vec<Value> = Vec::new();
+    for _ in 0..50 {
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        events = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        if events.len() >= 4 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let _ = std::fs::remove_file(&path);
+
+    let steps: Vec<(&str, &str, &str)> = events
+        .iter()
+        .map(|e| (e["action"].as_str().unwrap(), e["stage"].as_str().unwrap(), e["outcome"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("like", "verdict", "yes"),
+            ("like", "action", "ok"),
+            ("retweet", "verdict", "no"),
+            ("quote", "verdict", "no"),
+        ]
+    );
+    assert!(events.iter().all(|e| e["tweet_id"] == "209" && e["author"] == "ivy"));
+    assert_eq!(events[0]["detail"], "solana fees are tiny today");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_search_is_returned() {
+    let api = MockTwitterApi::new();
+    api.fail_next(Call::Search, TwitterError::Auth("session expired".to_string()));
+    let config = test_config();
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    assert!(client.process_search_query(&search("solana"), &config).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn home_timeline_likes_tweets() {
+    let api = MockTwitterApi::new();
+    api.set_timeline(vec![timeline_tweet("300", "gm from the timeline")]);
+    let config = test_config();
+    let client = test_client(&api, &liking_model(), config.clone()).await;
+
+    client.process_home_timeline(&config).await;
+
+    assert_eq!(api.call_count(Call::HomeTimeline), 1);
+    assert_eq!(api.liked(), vec!["300".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn long_reply_is_split_at_the_configured_length() {
+    let long_reply = "word ".repeat(80);
+    let model = ScriptedModel::new(long_reply.trim());
+
+    let api = MockTwitterApi::new();
+    let client = test_client(&api, &model, test_config()).await;
+    client.handle_mention(tweet("109", "gail", "@zoey_test tell me everything")).await.unwrap();
+    assert_eq!(api.sent().len(), 2);
+
+    let premium = TwitterConfig {
+        max_tweet_length: 1000,
+        ..test_config()
+    };
+    let api = MockTwitterApi::new();
+    let client = test_client(&api, &model, premium).await;
+    client.handle_mention(tweet("110", "gail", "@zoey_test tell me everything")).await.unwrap();
+    assert_eq!(api.sent().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn empty_tweets_are_not_posted() {
+    let api = MockTwitterApi::new();
+    let client = test_client(&api, &ScriptedModel::new("  \n"), test_config()).await;
+    let intel = CryptoIntel {
+        source: IntelSource::Market,
+        content: "SOL up 12% to $180".to_string(),
+        timestamp: chrono::Utc::now(),
+        tags: vec!["SOL".to_string()],
+    };
+
+    assert!(client.share_intel(&intel).await.is_err());
+    assert!(client.post_tweet(" ").await.is_err());
+    assert!(api.sent().is_empty());
+}
+
+fn threaded_config() -> TwitterConfig {
+    TwitterConfig {
+        // One sentence per tweet
+        max_tweet_length: 25,
+        thread: ThreadConfig {
+            enabled: true,
+            max_tweets: 5,
+            numbered: false,
+            ..ThreadConfig::default()
+        },
+        ..test_config()
+    }
+}
+
+const THREAD: &str = "Salt the water well. Stir the pot often. Taste before serving. Plate it hot.";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn posts_a_thread_as_a_reply_chain() {
+    let api = MockTwitterApi::new();
+    let client = test_client(&api, &ScriptedModel::new(REPLY), threaded_config()).await;
+
+    client.send_generated_tweet(THREAD).await.unwrap();
+
+    let sent = api.sent();
+    assert_eq!(sent.len(), 4);
+    assert_eq!(sent[0].reply_to, None);
+    assert_eq!(sent[1].reply_to, Some(sent_id(1)));
+    assert_eq!(sent[3].reply_to, Some(sent_id(3)));
+    assert!(api.deleted().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_failed_part_takes_the_thread_down() {
+    let api = MockTwitterApi::new();
+    let client = test_client(&api, &ScriptedModel::new(REPLY), threaded_config()).await;
+    // The first two parts go out, the third fails
+    api.succeed_next(Call::SendTweet, 2);
+    api.fail_next(Call::SendTweet, TwitterError::Api("Internal server error".to_string()));
+
+    assert!(client.send_generated_tweet(THREAD).await.is_err());
+
+    assert_eq!(api.sent().len(), 2);
+    // An API error is a response, so the part isn't sent again in case it was posted
+    assert_eq!(api.call_count(Call::SendTweet), 3);
+    assert_eq!(api.deleted(), [sent_id(2), sent_id(1)]);
+}
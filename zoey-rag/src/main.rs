@@ -1,78 +1,330 @@
 use anyhow::{Context, Result};
 use rig::{
-    embeddings::EmbeddingsBuilder,
+    embeddings::{Embedding, EmbeddingError, EmbeddingModel, EmbeddingsBuilder},
     providers::{
-        cohere::{self, EMBED_ENGLISH_V3}
+        cohere::{self, EMBED_ENGLISH_V3},
+        gemini::{self, EMBEDDING_004},
     },
     completion::{Message, Chat, PromptError, CompletionError, Prompt},
     message::{UserContent, AssistantContent},
+    vector_store::VectorStoreIndex,
 };
 
 use common::{
-    document_loader::DocumentLoader,
-    storage::StorageManager,
+    document_loader::{DocumentLoader, SUPPORTED_EXTENSIONS},
+    exa::{MAX_HIGHLIGHTS_PER_RESULT, MAX_NUM_RESULTS, MAX_SUMMARY_SENTENCES},
+    near_dup,
+    paths,
+    progress::Progress,
+    status,
+    telemetry,
+    storage::{self, embed_isolating, Document, NamedEmbeddingModel, StorageManager, DEFAULT_BISECT_DEPTH, DEFAULT_EMBED_RETRIES},
+    providers::granite::{GraniteEmbedding, GRANITE_EMBEDDING_278M},
+    empty_response::{self, EmptyResponsePolicy, ResponseError},
+    prompt_retry,
+    prompt_guard::{self, GuardMode},
     providers::openrouter::{self, Client},
 };
 
-use rusqlite::ffi::sqlite3_auto_extension;
-use sqlite_vec::sqlite3_vec_init;
-use std::path::PathBuf;
+use common::exit::exit_with_error;
+use rig_sqlite::DistanceMetric;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::io::{self, AsyncBufReadExt, BufReader};
-use tokio::sync::RwLock;
-use std::io::Write;
+use tokio::sync::{RwLock, Semaphore};
+use std::io::{IsTerminal, Write};
 use reqwest;
 use scraper;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
 use futures::future::join_all;
 use parking_lot::Mutex as PLMutex;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use chrono::{DateTime, Utc};
 use tokio::fs;
 
 // Modify ChatState to handle async initialization
 struct ChatState {
     storage: Arc<RwLock<StorageManager>>,
-    chat_history: PLMutex<Vec<Message>>,
+    chat_history: PLMutex<Vec<ChatEntry>>,
+    chunk_config: PLMutex<ChunkConfig>,
+    last_failed: PLMutex<Option<FailedCommand>>,
+    images: ImageStore,
+    guard: GuardMode,
+    fallback: Option<FallbackStore>,
+}
+
+// A chat message and when it was added, so /save_history can record timestamps
+#[derive(Debug, Clone)]
+struct ChatEntry {
+    message: Message,
+    at: DateTime<Utc>,
+}
+
+impl ChatEntry {
+    fn new(message: Message) -> Self {
+        Self { message, at: Utc::now() }
+    }
+}
+
+// The last /load or /search that failed, kept for /retry
+#[derive(Debug, Clone)]
+enum FailedCommand {
+    // Only the sources that didn't load
+    Load(Vec<String>),
+    Search {
+        query: String,
+        search_type: String,
+        num_results: usize,
+        domains: Option<Vec<String>>,
+    },
 }
 
 impl ChatState {
-    async fn new_with_mode(persistent: bool) -> Result<Self> {
-        let storage = StorageManager::new_with_mode(persistent).await?;
+    async fn new_with_mode(
+        db_path: Option<&Path>,
+        fresh: bool,
+        metric: DistanceMetric,
+        retention: ImageRetention,
+        guard: GuardMode,
+        fallback: Option<FallbackStore>,
+    ) -> Result<Self> {
+        let storage = StorageManager::new_with_mode(db_path).await?.with_metric(metric);
         storage.initialize_tables().await?;
+        if fresh && db_path.is_some() {
+            storage.clear_documents().await?;
+        }
         
         Ok(Self {
             storage: Arc::new(RwLock::new(storage)),
-            chat_history: PLMutex::new(vec![Message::assistant(
-                "Hi! I'm Zoey, your AI assistant. How can I help you today?"
-            )]),
+            chat_history: PLMutex::new(vec![ChatEntry::new(Message::assistant(GREETING))]),
+            chunk_config: PLMutex::new(ChunkConfig::default()),
+            last_failed: PLMutex::new(None),
+            images: ImageStore::new(retention),
+            guard,
+            fallback,
         })
     }
 }
 
 const DEFAULT_CHUNK_SIZE: usize = 2000;
+const GREETING: &str = "Hi! I'm Zoey, your AI assistant. How can I help you today?";
+// Bumped when the saved history layout changes
+const HISTORY_FORMAT_VERSION: u32 = 1;
+const OFFLINE_CONTEXT_DOCUMENTS: usize = 8;
+const DEFAULT_COMPLETION_MODEL: &str = "google/gemini-2.0-flash-001";
+const MAX_MODEL_ATTEMPTS: usize = 4;
+const MODEL_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(90);
+const COMPARE_CHUNKS_PER_SOURCE: usize = 4;
+// Chunks retrieved from the vector store for each chat message
+const RETRIEVED_CHUNKS: usize = 32;
+// With a per-source limit, candidates ranked per retrieved chunk to find other sources
+const DIVERSITY_CANDIDATES: usize = 4;
+const CITATION_INSTRUCTION: &str = "\n\nEach document excerpt opens with a [Source: ...] line. \
+    When you use a fact from an excerpt, cite it inline with that source title, e.g. [Source: report.pdf], \
+    even when every excerpt comes from the same source. If you answer without using any excerpt, \
+    say that the answer comes from general knowledge and do not cite a source.";
+// Less text than this is too little to tell languages apart reliably
+const MIN_LANGUAGE_DETECT_CHARS: usize = 50;
+const UNKNOWN_LANGUAGE: &str = "unknown";
+
+// Offline mode disables web search, URL loading and Cohere embedding calls
+fn is_offline() -> bool {
+    std::env::args().any(|arg| arg == "--offline")
+}
+
+fn has_cohere_key() -> bool {
+    std::env::var("COHERE_API_KEY").is_ok_and(|key| !key.trim().is_empty())
+}
+
+// Client for Cohere embeddings. Plain chat works without a key, so a missing one is
+// reported when something needs embeddings; offline nothing is embedded.
+fn cohere_client() -> Result<cohere::Client> {
+    if is_offline() {
+        return Ok(cohere::Client::new(""));
+    }
+    let key = std::env::var("COHERE_API_KEY")
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+        .context("COHERE_API_KEY is not set - it is needed to embed documents for /load and /search")?;
+    Ok(cohere::Client::new(&key))
+}
+const DEFAULT_CHUNK_OVERLAP: usize = 0;
+// Documents and URLs fetched at once by /load
+const DEFAULT_LOAD_CONCURRENCY: usize = 5;
+// Exa highlights per /search result
+const DEFAULT_SEARCH_HIGHLIGHTS: usize = 3;
+// /search types with their Exa category and results fetched when no count is
+// given; SEARCH_RESULTS="news=8,images=10" overrides the counts
+const SEARCH_TYPES: &[(&str, Option<&str>, usize)] = &[
+    ("web", None, 5),
+    ("pdf", Some("pdf"), 5),
+    ("images", None, 10),
+    ("news", Some("news"), 8),
+    ("research", Some("research paper"), 5),
+    ("site", None, 5),
+];
+// Documents loaded and embedded per round, bounding memory for large directories
+const LOAD_BATCH_SIZE: usize = 25;
+// Unsupported files named when /load skips part of a directory
+const MAX_SKIPPED_NAMES: usize = 10;
+// Product mode: elements holding one product, and where its fields are found, in order of preference
+const PRODUCT_CONTAINERS: &str = "[itemtype*=\"schema.org/Product\"], .product, .product-item, .product-card, .product-tile";
+const PRODUCT_NAME_SELECTORS: &[&str] = &["[itemprop=\"name\"]", ".product-title", ".product-name", "h2", "h3", "h4"];
+const PRODUCT_PRICE_SELECTORS: &[&str] = &[".product-price", ".price", "[itemprop=\"price\"]"];
+const PRODUCT_DESCRIPTION_SELECTORS: &[&str] = &["[itemprop=\"description\"]", ".product-description", ".description", ".details"];
+// Longer descriptions are cut so each product stays one small chunk
+const MAX_PRODUCT_DESCRIPTION_CHARS: usize = 500;
+// Fewer matches than this are a widget on an ordinary page, not a product listing
+const MIN_PAGE_PRODUCTS: usize = 2;
+// Images kept in zoey_images before the oldest are deleted
+const DEFAULT_MAX_IMAGES: usize = 200;
+// Chunks retrieved from the fallback embedding store for each chat message
+const FALLBACK_RETRIEVED_CHUNKS: usize = 8;
+// Age at which a chunk's recency score has halved, when recency weighting is on
+const RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+// Rough English average used by /preview; real tokenizer counts differ
+const CHARS_PER_TOKEN: usize = 4;
+// Title, URL and language lines wrapped around every stored chunk
+const CHUNK_HEADER_TOKENS: usize = 30;
+// Cohere list prices in USD per million embedding tokens. EMBEDDING_PRICES
+// ("model=price,...") overrides or adds to these.
+const EMBEDDING_PRICES: &[(&str, f64)] = &[
+    ("embed-english-v3.0", 0.10),
+    ("embed-multilingual-v3.0", 0.10),
+    ("embed-english-light-v3.0", 0.10),
+    ("embed-multilingual-light-v3.0", 0.10),
+];
+
+// How /load splits a document: by word count, or at markdown headings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ChunkStrategy {
+    #[default]
+    Words,
+    Markdown,
+}
+
+impl ChunkStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChunkStrategy::Words => "words",
+            ChunkStrategy::Markdown => "markdown",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "words" => Some(ChunkStrategy::Words),
+            "markdown" | "md" => Some(ChunkStrategy::Markdown),
+            _ => None,
+        }
+    }
+}
+
+// Chunking settings used by subsequent /load calls, plus the retrieval per-source
+// limit and /search result contents, adjustable via /config. The two limits are
+// 0 (off) unless set.
+#[derive(Debug, Clone, Copy)]
+struct ChunkConfig {
+    chunk_size: usize,
+    overlap: usize,
+    load_concurrency: usize,
+    strategy: ChunkStrategy,
+    // Chunks stored per document, sampled evenly across it when exceeded
+    max_chunks_per_source: usize,
+    // Chunks retrieved per source while other sources have relevant chunks
+    retrieval_per_source: usize,
+    // Exa summary sentences and highlights per /search result, 0 leaves them out
+    search_summary_sentences: usize,
+    search_highlights: usize,
+    // Share of retrieval ranking given to recency over relevance, 0 to 1
+    recency_weight: f64,
+    // Store each product on a web page as its own labeled chunk
+    products: bool,
+    // Similarity at which a chunk is dropped as a near-duplicate of one queued
+    // before it, None embeds everything
+    dedup_threshold: Option<f64>,
+    // Retries of a failed embedding batch, then halvings to isolate bad chunks
+    embed_retries: usize,
+    bisect_depth: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            overlap: DEFAULT_CHUNK_OVERLAP,
+            load_concurrency: DEFAULT_LOAD_CONCURRENCY,
+            strategy: ChunkStrategy::default(),
+            max_chunks_per_source: 0,
+            retrieval_per_source: 0,
+            search_summary_sentences: 0,
+            search_highlights: DEFAULT_SEARCH_HIGHLIGHTS,
+            recency_weight: 0.0,
+            products: false,
+            dedup_threshold: Some(near_dup::DEFAULT_THRESHOLD),
+            embed_retries: DEFAULT_EMBED_RETRIES,
+            bisect_depth: DEFAULT_BISECT_DEPTH,
+        }
+    }
+}
+
+impl ChunkConfig {
+    fn with_chunk_size(self, chunk_size: usize) -> Result<Self> {
+        if chunk_size == 0 {
+            anyhow::bail!("Chunk size must be greater than 0");
+        }
+        if self.overlap >= chunk_size {
+            anyhow::bail!(
+                "Chunk size must be larger than the current overlap ({})",
+                self.overlap
+            );
+        }
+        Ok(Self { chunk_size, ..self })
+    }
+
+    fn with_overlap(self, overlap: usize) -> Result<Self> {
+        if overlap >= self.chunk_size {
+            anyhow::bail!(
+                "Overlap must be smaller than the current chunk size ({})",
+                self.chunk_size
+            );
+        }
+        Ok(Self { overlap, ..self })
+    }
+
+    fn with_load_concurrency(self, load_concurrency: usize) -> Result<Self> {
+        if load_concurrency == 0 {
+            anyhow::bail!("Concurrency must be greater than 0");
+        }
+        Ok(Self { load_concurrency, ..self })
+    }
+}
 
 // Update load_document to match the backup exactly
-async fn load_document(path: PathBuf) -> Result<Vec<String>> {
+async fn load_document(path: PathBuf, config: ChunkConfig) -> Result<Vec<String>> {
     // Add better error context
     let result = if path.to_string_lossy().starts_with("http") {
-        load_url(&path.to_string_lossy())
+        load_url(&path.to_string_lossy(), config.products)
             .await
             .with_context(|| format!("Failed to load URL: {}", path.display()))
     } else {
-        let documents_dir = std::env::current_dir()
-            .with_context(|| "Failed to get current directory")?
-            .join("documents");
-        let full_path = documents_dir.join(path.clone());
+        let full_path = paths::documents_dir().join(path.clone());
         
         DocumentLoader::load(full_path)
+            .map(|texts| PageContent { texts, products: Vec::new() })
             .with_context(|| format!("Failed to load document from path: {}", path.display()))
     };
 
-    // Process content into chunks
+    // Process content into chunks; products are already one chunk each
     let content = result?;
-    let chunks = chunk_content(&content, DEFAULT_CHUNK_SIZE)?;
+    let mut chunks = match config.strategy {
+        ChunkStrategy::Words => chunk_content(&content.texts, config.chunk_size, config.overlap)?,
+        ChunkStrategy::Markdown => chunk_markdown(&content.texts, config.chunk_size, config.overlap)?,
+    };
+    chunks.extend(content.products);
     
     // Validate chunks
     if chunks.is_empty() {
@@ -82,20 +334,35 @@ async fn load_document(path: PathBuf) -> Result<Vec<String>> {
     Ok(chunks)
 }
 
-// Update load_documents to match the backup exactly
-async fn load_documents(paths: &[String]) -> Result<Vec<Vec<String>>> {
+// Load at most `config.load_concurrency` documents at a time, reporting each as it
+// finishes. One result per path, in the same order as `paths`.
+async fn load_documents(paths: &[String], config: ChunkConfig) -> Vec<Result<Vec<String>>> {
+    let semaphore = Semaphore::new(config.load_concurrency);
+    let completed = AtomicUsize::new(0);
+    let total = paths.len();
+
     let futures: Vec<_> = paths
         .iter()
-        .map(|path| load_document(PathBuf::from(path)))
+        .map(|path| {
+            let semaphore = &semaphore;
+            let completed = &completed;
+            async move {
+                let _permit = semaphore.acquire().await.context("Load queue closed")?;
+                let result = load_document(PathBuf::from(path), config).await;
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                match &result {
+                    Ok(_) => status!("📥 Loaded {}/{}: {}", done, total, path),
+                    Err(_) => status!("⚠️ Failed {}/{}: {}", done, total, path),
+                }
+                result
+            }
+        })
         .collect();
-    
-    join_all(futures)
-        .await
-        .into_iter()
-        .collect::<Result<Vec<_>>>()
+
+    join_all(futures).await
 }
 
-fn chunk_content(content: &[String], chunk_size: usize) -> Result<Vec<String>> {
+fn chunk_content(content: &[String], chunk_size: usize, overlap: usize) -> Result<Vec<String>> {
     let mut chunks = Vec::new();
     let mut current_chunk = String::new();
     
@@ -103,8 +370,9 @@ fn chunk_content(content: &[String], chunk_size: usize) -> Result<Vec<String>> {
         for word in text.split_whitespace() {
             if current_chunk.len() + word.len() + 1 > chunk_size {
                 if !current_chunk.is_empty() {
-                    chunks.push(current_chunk.trim().to_string());
-                    current_chunk.clear();
+                    let finished = current_chunk.trim().to_string();
+                    current_chunk = overlap_tail(&finished, overlap);
+                    chunks.push(finished);
                 }
             }
             current_chunk.push_str(word);
@@ -119,19 +387,254 @@ fn chunk_content(content: &[String], chunk_size: usize) -> Result<Vec<String>> {
     Ok(chunks)
 }
 
+// At most `max` chunks spread evenly over the document, all of them when `max` is 0
+fn sample_chunks(chunks: Vec<String>, max: usize) -> Vec<String> {
+    if max == 0 || chunks.len() <= max {
+        return chunks;
+    }
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i * max / total != (i + 1) * max / total)
+        .map(|(_, chunk)| chunk)
+        .collect()
+}
+
+// A run of markdown kept together when chunking. Atomic blocks (headings, fenced
+// code, tables) are never split, even when larger than a chunk.
+struct MarkdownBlock {
+    text: String,
+    atomic: bool,
+}
+
+// The blocks under one heading, with the headings above it from outermost in
+#[derive(Default)]
+struct MarkdownSection {
+    breadcrumb: Vec<String>,
+    blocks: Vec<MarkdownBlock>,
+}
+
+// An ATX heading (`## Title`) as its level and title
+fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    // Four spaces of indentation make it a code block
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim()))
+}
+
+// The level of a setext heading whose underline (`===` or `---`) this line is
+fn setext_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim();
+    if line.len() - line.trim_start().len() > 3 || trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+// Start a section under a heading, as the heading's first (atomic) block
+fn push_heading(sections: &mut Vec<MarkdownSection>, headings: &mut Vec<(usize, String)>, level: usize, title: &str, text: String) {
+    headings.retain(|(outer, _)| *outer < level);
+    headings.push((level, title.to_string()));
+    sections.push(MarkdownSection {
+        breadcrumb: headings.iter().map(|(_, title)| title.clone()).collect(),
+        blocks: vec![MarkdownBlock { text, atomic: true }],
+    });
+}
+
+fn push_paragraph(sections: &mut [MarkdownSection], paragraph: &mut Vec<&str>) {
+    if paragraph.is_empty() {
+        return;
+    }
+    if let Some(section) = sections.last_mut() {
+        section.blocks.push(MarkdownBlock {
+            text: paragraph.join("\n"),
+            atomic: false,
+        });
+    }
+    paragraph.clear();
+}
+
+fn markdown_sections(text: &str) -> Vec<MarkdownSection> {
+    let mut sections = vec![MarkdownSection::default()];
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut paragraph = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+
+        if let Some(fence) = ["```", "~~~"].into_iter().find(|fence| trimmed.starts_with(fence)) {
+            push_paragraph(&mut sections, &mut paragraph);
+            let mut block = vec![line];
+            for next in lines.by_ref() {
+                block.push(next);
+                if next.trim_start().starts_with(fence) {
+                    break;
+                }
+            }
+            if let Some(section) = sections.last_mut() {
+                section.blocks.push(MarkdownBlock { text: block.join("\n"), atomic: true });
+            }
+            continue;
+        }
+
+        if trimmed.starts_with('|') {
+            push_paragraph(&mut sections, &mut paragraph);
+            let mut block = vec![line];
+            while let Some(next) = lines.next_if(|next| next.trim_start().starts_with('|')) {
+                block.push(next);
+            }
+            if let Some(section) = sections.last_mut() {
+                section.blocks.push(MarkdownBlock { text: block.join("\n"), atomic: true });
+            }
+            continue;
+        }
+
+        if let Some((level, title)) = markdown_heading(line) {
+            push_paragraph(&mut sections, &mut paragraph);
+            push_heading(&mut sections, &mut headings, level, title, trimmed.trim_end().to_string());
+            continue;
+        }
+
+        // A paragraph underlined with === or --- is a heading; after a blank line --- is just a rule
+        if let Some(level) = setext_level(line).filter(|_| !paragraph.is_empty()) {
+            let title = paragraph.iter().map(|line| line.trim()).collect::<Vec<_>>().join(" ");
+            paragraph.push(line);
+            let text = paragraph.join("\n");
+            paragraph.clear();
+            push_heading(&mut sections, &mut headings, level, &title, text);
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            push_paragraph(&mut sections, &mut paragraph);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    push_paragraph(&mut sections, &mut paragraph);
+
+    sections.retain(|section| !section.blocks.is_empty());
+    sections
+}
+
+// Split markdown at headings, one section per chunk with its heading breadcrumb
+// on top. An oversized section is split between blocks, and only a paragraph
+// that alone is too big gets split by words. Without headings this is `chunk_content`.
+fn chunk_markdown(content: &[String], chunk_size: usize, overlap: usize) -> Result<Vec<String>> {
+    let sections = markdown_sections(&content.join("\n"));
+    if sections.iter().all(|section| section.breadcrumb.is_empty()) {
+        return chunk_content(content, chunk_size, overlap);
+    }
+
+    let mut chunks = Vec::new();
+    for section in sections {
+        let prefix = if section.breadcrumb.is_empty() {
+            String::new()
+        } else {
+            format!("SECTION: {}\n\n", section.breadcrumb.join(" > "))
+        };
+        let budget = chunk_size.saturating_sub(prefix.len()).max(1);
+
+        let mut current = String::new();
+        for block in &section.blocks {
+            if !current.is_empty() && current.len() + block.text.len() + 2 > budget {
+                chunks.push(format!("{}{}", prefix, current));
+                current.clear();
+            }
+            if !block.atomic && block.text.len() > budget {
+                for part in chunk_content(&[block.text.clone()], budget, overlap.min(budget / 2))? {
+                    chunks.push(format!("{}{}", prefix, part));
+                }
+                continue;
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&block.text);
+        }
+        if !current.is_empty() {
+            chunks.push(format!("{}{}", prefix, current));
+        }
+    }
+
+    Ok(chunks)
+}
+
+// Carry the trailing words of a finished chunk (up to `overlap` bytes) into the next one
+fn overlap_tail(chunk: &str, overlap: usize) -> String {
+    if overlap == 0 {
+        return String::new();
+    }
+
+    let mut tail: Vec<&str> = Vec::new();
+    let mut len = 0;
+    for word in chunk.split_whitespace().rev() {
+        if len + word.len() + 1 > overlap {
+            break;
+        }
+        len += word.len() + 1;
+        tail.push(word);
+    }
+
+    if tail.is_empty() {
+        return String::new();
+    }
+    tail.reverse();
+    format!("{} ", tail.join(" "))
+}
+
+// Text extracted from a web page, or from its product listing in product mode
+#[derive(Debug, Default)]
+struct PageContent {
+    texts: Vec<String>,
+    // One labeled chunk per product
+    products: Vec<String>,
+}
+
+const SCRAPE_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+const SCRAPE_HEADERS: &[(&str, &str)] = &[
+    ("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/webp,*/*;q=0.8"),
+    ("Accept-Language", "en-US,en;q=0.5"),
+    ("Connection", "keep-alive"),
+];
+
+// HTTP client for scraping pages. A header that isn't valid is an error for the
+// /load call rather than a panic mid-scrape.
+fn scrape_client(headers: &[(&str, &str)]) -> Result<reqwest::Client> {
+    let mut header_map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid scrape header name: {:?}", name))?;
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for scrape header {}: {:?}", name, value))?;
+        header_map.insert(name, value);
+    }
+    reqwest::Client::builder()
+        .user_agent(SCRAPE_USER_AGENT)
+        .default_headers(header_map)
+        .build()
+        .context("Failed to build the scrape client")
+}
+
 // Add this function to handle pagination
-async fn load_paginated_url(base_url: &str, start_page: u32, end_page: u32) -> Result<Vec<String>> {
-    let mut all_texts = Vec::new();
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-        .default_headers({
-            let mut headers = reqwest::header::HeaderMap::new();
-            headers.insert("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/webp,*/*;q=0.8".parse().unwrap());
-            headers.insert("Accept-Language", "en-US,en;q=0.5".parse().unwrap());
-            headers.insert("Connection", "keep-alive".parse().unwrap());
-            headers
-        })
-        .build()?;
+async fn load_paginated_url(base_url: &str, start_page: u32, end_page: u32, products: bool) -> Result<PageContent> {
+    let mut all_content = PageContent::default();
+    let client = scrape_client(SCRAPE_HEADERS)?;
 
     // Common URL patterns for pagination
     let patterns = vec![
@@ -173,23 +676,33 @@ async fn load_paginated_url(base_url: &str, start_page: u32, end_page: u32) -> R
 
         if let Some(html) = page_content {
             let document = scraper::Html::parse_document(&html);
-            let mut page_texts = extract_content(&document, page_num)?;
-            all_texts.append(&mut page_texts);
+            let page = extract_content(&document, page_num, products)?;
+            all_content.texts.extend(page.texts);
+            all_content.products.extend(page.products);
         } else {
             info!("Could not fetch page {} with any known pattern", page_num);
             break; // Stop if we can't fetch a page
         }
     }
 
-    if all_texts.is_empty() {
+    if all_content.texts.is_empty() && all_content.products.is_empty() {
         anyhow::bail!("Could not extract any content from pages {}-{}", start_page, end_page);
     }
 
-    Ok(all_texts)
+    Ok(all_content)
 }
 
-// Helper function to extract content from a page
-fn extract_content(document: &scraper::Html, page_num: u32) -> Result<Vec<String>> {
+// Helper function to extract content from a page. In product mode a product
+// listing also gives one chunk per product, next to the page's text.
+fn extract_content(document: &scraper::Html, page_num: u32, products: bool) -> Result<PageContent> {
+    let mut products = if products { extract_products(document) } else { Vec::new() };
+    if products.len() >= MIN_PAGE_PRODUCTS {
+        info!("Extracted {} products from page {}", products.len(), page_num);
+    } else if !products.is_empty() {
+        info!("Only {} product(s) on page {}, not a listing", products.len(), page_num);
+        products.clear();
+    }
+
     let mut texts = Vec::new();
     
     // Add page number as context
@@ -241,11 +754,70 @@ fn extract_content(document: &scraper::Html, page_num: u32) -> Result<Vec<String
         }
     }
 
-    Ok(texts)
+    Ok(PageContent { texts, products })
+}
+
+// One labeled chunk per product with whichever of its name, price and description
+// the page has. Empty when the page doesn't look like a product listing.
+fn extract_products(document: &scraper::Html) -> Vec<String> {
+    let Ok(containers) = scraper::Selector::parse(PRODUCT_CONTAINERS) else {
+        return Vec::new();
+    };
+
+    let mut products = Vec::new();
+    for element in document.select(&containers) {
+        // A container inside another one (e.g. .product in .product-card) is the same product
+        if element.ancestors().filter_map(scraper::ElementRef::wrap).any(|parent| containers.matches(&parent)) {
+            continue;
+        }
+
+        let name = first_text(&element, PRODUCT_NAME_SELECTORS);
+        // Microdata prices keep the plain number in `content`
+        let price = first_attr(&element, "[itemprop=\"price\"]", "content")
+            .or_else(|| first_text(&element, PRODUCT_PRICE_SELECTORS));
+        let description = first_text(&element, PRODUCT_DESCRIPTION_SELECTORS)
+            .map(|text| text.chars().take(MAX_PRODUCT_DESCRIPTION_CHARS).collect::<String>());
+
+        let fields: Vec<String> = [("Name", name), ("Price", price), ("Description", description)]
+            .into_iter()
+            .filter_map(|(label, value)| value.map(|value| format!("{}: {}", label, value)))
+            .collect();
+        if !fields.is_empty() {
+            products.push(format!("PRODUCT\n{}", fields.join("\n")));
+        }
+    }
+    products
+}
+
+// Cleaned text of the first non-empty match of any selector, tried in order
+fn first_text(element: &scraper::ElementRef, selectors: &[&str]) -> Option<String> {
+    selectors
+        .iter()
+        .filter_map(|selector| scraper::Selector::parse(selector).ok())
+        .find_map(|selector| {
+            element
+                .select(&selector)
+                .map(|found| clean_text(&found.text().collect::<Vec<_>>().join(" ")))
+                .find(|text| !text.is_empty())
+        })
+}
+
+fn first_attr(element: &scraper::ElementRef, selector: &str, attr: &str) -> Option<String> {
+    let selector = scraper::Selector::parse(selector).ok()?;
+    element
+        .select(&selector)
+        .find_map(|found| found.value().attr(attr))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
 }
 
 // Modify the load_url function to use pagination
-async fn load_url(url: &str) -> Result<Vec<String>> {
+async fn load_url(url: &str, products: bool) -> Result<PageContent> {
+    if is_offline() {
+        anyhow::bail!("Running in offline mode - loading URLs is disabled");
+    }
+
     // Parse URL parameters if any
     let mut start_page = 1;
     let mut end_page = 1;
@@ -268,19 +840,10 @@ async fn load_url(url: &str) -> Result<Vec<String>> {
     
     // Use pagination if specified
     if end_page > 1 {
-        load_paginated_url(base_url, start_page, end_page).await
+        load_paginated_url(base_url, start_page, end_page, products).await
     } else {
         // Original single page scraping logic
-        let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-            .default_headers({
-                let mut headers = reqwest::header::HeaderMap::new();
-                headers.insert("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/webp,*/*;q=0.8".parse().unwrap());
-                headers.insert("Accept-Language", "en-US,en;q=0.5".parse().unwrap());
-                headers.insert("Connection", "keep-alive".parse().unwrap());
-                headers
-            })
-            .build()?;
+        let client = scrape_client(SCRAPE_HEADERS)?;
 
         // Fetch HTML content with better error handling
         let response = client.get(base_url)
@@ -296,7 +859,7 @@ async fn load_url(url: &str) -> Result<Vec<String>> {
         info!("Retrieved HTML length: {} bytes", html.len());
         
         let document = scraper::Html::parse_document(&html);
-        extract_content(&document, 1)
+        extract_content(&document, 1, products)
     }
 }
 
@@ -350,6 +913,71 @@ fn clean_text(text: &str) -> String {
     cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+// Dominant language of a source's chunks, weighted by the amount of text detected as each.
+// Unreliable detections and very short text give "unknown" rather than a guess.
+fn detect_language(chunks: &[String]) -> String {
+    let mut weights: std::collections::HashMap<whatlang::Lang, usize> = std::collections::HashMap::new();
+    for chunk in chunks {
+        if chunk.chars().count() < MIN_LANGUAGE_DETECT_CHARS {
+            continue;
+        }
+        if let Some(info) = whatlang::detect(chunk) {
+            if info.is_reliable() {
+                *weights.entry(info.lang()).or_insert(0) += chunk.len();
+            }
+        }
+    }
+
+    weights
+        .into_iter()
+        .max_by_key(|(_, weight)| *weight)
+        .map(|(lang, _)| lang.eng_name().to_string())
+        .unwrap_or_else(|| UNKNOWN_LANGUAGE.to_string())
+}
+
+// Language recorded in a stored chunk's metadata header
+fn document_language(content: &str) -> &str {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("LANGUAGE: "))
+        .map(str::trim)
+        .unwrap_or(UNKNOWN_LANGUAGE)
+}
+
+// Preamble note describing the languages in the knowledge base, if they aren't all English
+fn language_note(docs: &[common::storage::Document]) -> Option<String> {
+    let mut per_source: Vec<(&str, &str)> = Vec::new();
+    for doc in docs {
+        if !per_source.iter().any(|(source, _)| *source == doc.source) {
+            per_source.push((doc.source.as_str(), document_language(&doc.content)));
+        }
+    }
+
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for &(_, language) in &per_source {
+        match counts.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((language, 1)),
+        }
+    }
+    counts.retain(|(language, _)| *language != UNKNOWN_LANGUAGE);
+    if counts.is_empty() || (counts.len() == 1 && counts[0].0 == "English") {
+        return None;
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let mix = counts
+        .iter()
+        .map(|(language, count)| format!("{} ({} source{})", language, count, if *count == 1 { "" } else { "s" }))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!(
+        "\n\nThe documents are written in: {}. Reply in the language the user writes in, \
+        translating any passages you quote from documents in other languages.",
+        mix
+    ))
+}
+
 // Optimize document loading with parallel processing
 // async fn load_documents(paths: &[String]) -> Result<Vec<Vec<String>>> {
 //     let futures: Vec<_> = paths
@@ -367,42 +995,115 @@ fn clean_text(text: &str) -> String {
 struct ChatInteraction {
     state: Arc<ChatState>,
     openrouter_client: Client,  // Keep only what we use
+    models: Vec<String>,        // Primary model followed by fallbacks
+    confirm: ConfirmMode,       // For /clear under --rig-cli
+    empty_response: EmptyResponsePolicy,
 }
 
 impl ChatInteraction {
-    fn new(state: Arc<ChatState>, openrouter_client: Client) -> Self {
+    fn new(
+        state: Arc<ChatState>,
+        openrouter_client: Client,
+        models: Vec<String>,
+        confirm: ConfirmMode,
+        empty_response: EmptyResponsePolicy,
+    ) -> Self {
         Self {
             state,
             openrouter_client,
+            models,
+            confirm,
+            empty_response,
+        }
+    }
+
+    // Try each model in the chain until one answers, failing over only on availability errors
+    // or a response that is still empty after a retry.
+    // With `context` set, the agent gets exactly those excerpts instead of knowledge base retrieval.
+    async fn chat_with_fallbacks(
+        &self,
+        storage: &StorageManager,
+        input: &str,
+        messages: Vec<Message>,
+        context: Option<&[String]>,
+    ) -> Result<String> {
+        let mut last_error = None;
+
+        for (attempt, model_name) in self.models.iter().take(MAX_MODEL_ATTEMPTS).enumerate() {
+            if attempt > 0 {
+                warn!("Falling back to model {} (attempt {})", model_name, attempt + 1);
+            }
+
+            let agent = if let Some(context) = context {
+                build_context_agent(&self.openrouter_client, context, model_name, self.state.guard)
+            } else if is_offline() || !has_cohere_key() {
+                // Without embeddings, loaded documents are matched by keywords
+                build_offline_agent(&self.openrouter_client, storage, input, model_name, self.state.guard).await?
+            } else {
+                let cohere_client = cohere_client()?;
+                let embedding_model = cohere_client.embedding_model(EMBED_ENGLISH_V3, "search_document");
+
+                build_agent(
+                    &self.openrouter_client,
+                    storage,
+                    &embedding_model,
+                    input,
+                    model_name,
+                    &self.state,
+                ).await?
+            };
+
+            let call = empty_response::retry_if_empty(self.empty_response, || {
+                telemetry::traced_call(
+                    "openrouter",
+                    model_name,
+                    input,
+                    agent.chat(input.to_string(), messages.clone()),
+                )
+            });
+
+            // Availability problems are worth failing over on; auth and content-policy
+            // rejections would fail the same way on every model
+            match tokio::time::timeout(MODEL_ATTEMPT_TIMEOUT, call).await {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(ResponseError::Call(e))) if prompt_retry::is_retryable(&e) => {
+                    warn!("Model {} unavailable: {}", model_name, e);
+                    last_error = Some(anyhow::anyhow!("{}: {}", model_name, e));
+                }
+                Ok(Err(ResponseError::Call(e))) => return Err(e.into()),
+                Ok(Err(empty)) => {
+                    warn!("Model {}: {}", model_name, empty);
+                    last_error = Some(anyhow::anyhow!("{}: {}", model_name, empty));
+                }
+                Err(_) => {
+                    warn!("Model {} timed out after {:?}", model_name, MODEL_ATTEMPT_TIMEOUT);
+                    last_error = Some(anyhow::anyhow!("{}: timed out", model_name));
+                }
+            }
         }
+
+        Err(last_error
+            .unwrap_or_else(|| anyhow::anyhow!("No completion models configured"))
+            .context("All models in the fallback chain failed"))
     }
 
     async fn process_message(&self, input: String) -> Result<()> {
         let storage = self.state.storage.read().await;
         let is_rig_cli = std::env::args().any(|arg| arg == "--rig-cli");
         
-        let cohere_client = cohere::Client::from_env();
-        let embedding_model = cohere_client.embedding_model(EMBED_ENGLISH_V3, "search_document");
-        
-        let mut messages = self.state.chat_history.lock().to_vec();
+        let mut messages: Vec<Message> = self.state.chat_history.lock().iter().map(|e| e.message.clone()).collect();
         messages.push(Message::user(input.clone()));
 
-        let agent = build_agent(
-            &self.openrouter_client,
-            &*storage,
-            &embedding_model,
-        ).await?;
-
-        let response = agent.chat(input.clone(), messages.clone()).await?;
+        let response = self.chat_with_fallbacks(&storage, &input, messages, None).await?;
         
         if !is_rig_cli {
             println!("\nZoey: {}", response);
-            tracing::info!("Response:\n{}\n", response);
+            tracing::debug!("Response:\n{}\n", response);
         }
 
         let mut history = self.state.chat_history.lock();
-        history.push(Message::user(input));
-        history.push(Message::assistant(response.clone()));
+        history.push(ChatEntry::new(Message::user(input)));
+        history.push(ChatEntry::new(Message::assistant(response.clone())));
 
         Ok(())
     }
@@ -441,10 +1142,18 @@ impl Prompt for ChatInteraction {
                 )),
             };
 
+            // Handled here so it never reaches the model as a chat message
+            if input.trim() == "/clear" {
+                return Ok(match handle_clear_command(&self.state, self.confirm, None).await {
+                    Ok(message) => message,
+                    Err(e) => format!("Error clearing the knowledge base: {}", e),
+                });
+            }
+
             match self.process_message(input).await {
                 Ok(_) => {
                     let history = self.state.chat_history.lock();
-                    if let Some(last_msg) = history.iter().rev().find(|msg| matches!(msg, Message::Assistant { .. })) {
+                    if let Some(last_msg) = history.iter().rev().map(|e| &e.message).find(|msg| matches!(msg, Message::Assistant { .. })) {
                         match last_msg {
                             Message::Assistant { content } => {
                                 match content.iter().next() {
@@ -487,8 +1196,17 @@ impl Chat for ChatInteraction {
         let history = chat_history.clone();
         async move {
             {
+                // Messages we already hold keep their original timestamps
                 let mut current_history = self.state.chat_history.lock();
-                *current_history = history;
+                let entries = history
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, message)| match current_history.get(i) {
+                        Some(entry) if entry.message == message => entry.clone(),
+                        _ => ChatEntry::new(message),
+                    })
+                    .collect();
+                *current_history = entries;
             }
             self.prompt(prompt).await
         }
@@ -499,61 +1217,127 @@ impl Chat for ChatInteraction {
 async fn debug_print_documents(storage: &StorageManager) -> Result<()> {
     match storage.get_documents().await {
         Ok(docs) => {
-            println!("Documents in store:");
+            status!("Documents in store:");
             for doc in docs {
-                println!("- {}", doc.source);
+                status!("- {}", doc.source);
             }
         }
-        Err(e) => println!("Error getting documents: {}", e),
+        Err(e) => status!("Error getting documents: {}", e),
     }
     Ok(())
 }
 
-// Then in the process_new_documents function, add debug print:
+// Embed and store new chunks with Cohere, or with the fallback model into its own
+// store when Cohere fails
 async fn process_new_documents(
     state: &Arc<ChatState>,
     chunks: Vec<Vec<String>>,
     sources: &[String],
     cohere_client: &cohere::Client,
+) -> Result<()> {
+    let threshold = state.chunk_config.lock().dedup_threshold;
+    let mut chunks = chunks;
+    if let Some(threshold) = threshold {
+        let dropped = drop_near_duplicates(&mut chunks, threshold);
+        if dropped > 0 {
+            status!("🧹 Skipped {} near-duplicate chunks (similarity ≥ {})", dropped, threshold);
+        }
+    }
+
+    let mut inserted = Vec::new();
+    let Some(fallback) = &state.fallback else {
+        return embed_with_cohere(state, &chunks, sources, cohere_client, &mut inserted).await;
+    };
+    match embed_with_cohere(state, &chunks, sources, cohere_client, &mut inserted).await {
+        Ok(()) => {
+            // A source re-loaded with Cohere shouldn't be answered from both stores
+            fallback.storage.read().await.remove_sources(sources).await
+        }
+        Err(e) => {
+            // Chunks this call stored before the failure have no Cohere vectors; anything
+            // stored for these sources earlier stays
+            state.storage.read().await.remove_documents(&inserted).await?;
+            status!("⚠️ Cohere embedding failed: {:#}", e);
+            status!(
+                "↪️ Embedding with {} instead, into its separate store ({})",
+                fallback.model.model_id(),
+                fallback.path
+            );
+            status!("   Vectors from different models can't share an index, so these sources are searched separately");
+            fallback.add(&chunks, sources).await
+        }
+    }
+}
+
+// Drop chunks at least `threshold` similar to a chunk queued before them, from this
+// source or an earlier one in the batch. Returns how many were dropped.
+fn drop_near_duplicates(chunks: &mut [Vec<String>], threshold: f64) -> usize {
+    let keep = near_dup::keep_distinct(chunks.iter().flatten().map(String::as_str), threshold);
+    let mut keep = keep.into_iter();
+    let mut dropped = 0;
+    for chunk in chunks.iter_mut() {
+        chunk.retain(|_| {
+            let kept = keep.next().unwrap_or(true);
+            dropped += usize::from(!kept);
+            kept
+        });
+    }
+    dropped
+}
+
+// Store and embed the chunks, recording the id of each stored one in `inserted`
+// so a caller can undo a failed call
+async fn embed_with_cohere(
+    state: &Arc<ChatState>,
+    chunks: &[Vec<String>],
+    sources: &[String],
+    cohere_client: &cohere::Client,
+    inserted: &mut Vec<String>,
 ) -> Result<()> {
     info!("Processing new documents from {} sources", sources.len());
+    let offline = is_offline();
     let model = cohere_client.embedding_model(cohere::EMBED_ENGLISH_V3, "search_document");
-    let mut builder = EmbeddingsBuilder::new(model.clone());
-    
+    let (retries, depth) = {
+        let config = state.chunk_config.lock();
+        (config.embed_retries, config.bisect_depth)
+    };
+
     let storage = state.storage.read().await;
     
     // Create documents with better metadata
     let mut documents = Vec::new();
     for (source, chunk) in sources.iter().zip(chunks.iter()) {
         info!("Processing chunks from source: {}", source);
+        let language = detect_language(chunk);
+        info!("Detected language for {}: {}", source, language);
         for (i, content) in chunk.iter().enumerate() {
             info!("Processing chunk {}/{}", i + 1, chunk.len());
             
-            // Add metadata to help with retrieval
-            let doc_content = format!(
-                "DOCUMENT TITLE: {}\n\
-                 SOURCE URL: {}\n\
-                 CONTENT START\n\
-                 {}\n\
-                 CONTENT END\n\
-                 --- END OF DOCUMENT ---", 
-                source,
-                content.lines()
-                    .find(|line| line.starts_with("URL:"))
-                    .unwrap_or("")
-                    .trim_start_matches("URL: "),
-                content
-            );
-            
-            let doc = storage.add_document(source, &doc_content).await?;
-            builder = builder.document(doc.clone())?;
+            let doc = storage.add_document(source, &document_content(source, content, &language)).await?;
+            inserted.push(doc.id.clone());
             documents.push(doc);
         }
     }
+
+    if offline {
+        status!("⚠️ Offline mode: stored {} chunks without embeddings", documents.len());
+        status!("   They will be matched by keywords until you reload them online.");
+        return Ok(());
+    }
     
     info!("Building embeddings for {} documents", documents.len());
-    let embeddings = builder.build().await?;
-    
+    let count = documents.len();
+    let (embeddings, mut failed) = embed_isolating(&model, documents, retries, depth).await;
+    if embeddings.is_empty() && !failed.is_empty() {
+        return Err(failed.swap_remove(0).1.context("Embedding failed"));
+    }
+    if !failed.is_empty() {
+        status!("⚠️ {} of {} chunks could not be embedded and are only matched by keywords:", failed.len(), count);
+        for (doc, error) in &failed {
+            status!("   • {} - {:#}", doc.source, error);
+        }
+    }
+
     if let Some(store) = storage.get_store() {
         info!("Adding documents to vector store");
         store.add_rows(embeddings).await?;
@@ -562,9 +1346,9 @@ async fn process_new_documents(
         debug_print_documents(&storage).await?;
         
         // Print confirmation of stored documents
-        println!("\n📑 Successfully stored documents:");
+        status!("\n📑 Successfully stored documents:");
         for (idx, source) in sources.iter().enumerate() {
-            println!("{}. {}", idx + 1, source);
+            status!("{}. {}", idx + 1, source);
         }
         
         info!("Successfully added documents to vector store");
@@ -575,47 +1359,227 @@ async fn process_new_documents(
     Ok(())
 }
 
-// Update the debug function to use simpler queries
-// async fn debug_sqlite_store(
-//     storage: &RwLock<StorageManager>,
-//     model: &cohere::EmbeddingModel
-// ) -> Result<()> {
-//     info!("Debugging SQLite store...");
-//     
-//     let storage = storage.read().await;
-//     
-//     // Check documents table
-//     let docs = storage.get_documents().await?;
-//     info!("Documents in SQLite store: {}", docs.len());
-//     for doc in &docs {
-//         info!("Document: {} (id: {})", doc.source, doc.id);
-//         info!("Content preview: {}", doc.content.chars().take(100).collect::<String>());
-//     }
-//     
-//     // Check vectors table if using a store
-//     if let Some(store) = storage.get_store() {
-//         let index = store.clone().index(model.clone());
-//         
-//         // Use vector search to test functionality
-//         let results = index.top_n::<common::storage::Document>("test", 5).await?;
-//         info!("Vector search test results:");
-//         for (score, _id, doc) in results {
-//             info!("Document '{}' (score: {:.4})", doc.source, score);
-//         }
-//     }
-//     
-//     Ok(())
-// }
+// A chunk with the title, URL and language lines that help retrieval
+fn document_content(source: &str, content: &str, language: &str) -> String {
+    format!(
+        "DOCUMENT TITLE: {}\n\
+         SOURCE URL: {}\n\
+         LANGUAGE: {}\n\
+         CONTENT START\n\
+         {}\n\
+         CONTENT END\n\
+         --- END OF DOCUMENT ---", 
+        source,
+        content.lines()
+            .find(|line| line.starts_with("URL:"))
+            .unwrap_or("")
+            .trim_start_matches("URL: "),
+        language,
+        content
+    )
+}
 
-// Update the build_agent function
-async fn build_agent(
-    client: &Client,
-    storage: &StorageManager,
-    model: &cohere::EmbeddingModel,
-) -> Result<rig::agent::Agent<openrouter::OpenRouterCompletionModel>> {
-    let mut builder = client.agent("google/gemini-2.0-flash-001");
-    
-    builder = builder
+// Embedding model used by /load when Cohere fails, from `--embedding-fallback <gemini|granite>`
+#[derive(Clone)]
+enum FallbackEmbedding {
+    Gemini { model: gemini::embedding::EmbeddingModel, name: String },
+    Granite(GraniteEmbedding),
+}
+
+impl FallbackEmbedding {
+    // None, with a warning, when the provider can't be used
+    async fn connect(provider: &str, name: Option<String>) -> Option<Self> {
+        let model = match provider {
+            "gemini" => {
+                let Ok(key) = std::env::var("GEMINI_API_KEY") else {
+                    warn!("GEMINI_API_KEY is not set, embedding fallback disabled");
+                    return None;
+                };
+                let name = name.unwrap_or_else(|| EMBEDDING_004.to_string());
+                Self::Gemini { model: gemini::Client::new(&key).embedding_model(&name), name }
+            }
+            "granite" => {
+                let name = name.unwrap_or_else(|| GRANITE_EMBEDDING_278M.to_string());
+                match GraniteEmbedding::detect(&name).await {
+                    Ok(model) => Self::Granite(model),
+                    Err(e) => {
+                        warn!("Embedding fallback {} is not available from Ollama, disabled: {}", name, e);
+                        return None;
+                    }
+                }
+            }
+            other => {
+                warn!("Unknown embedding fallback {}, disabled", other);
+                return None;
+            }
+        };
+        if model.ndims() == 0 {
+            warn!("Unknown dimensions for embedding fallback {}, disabled", model.model_id());
+            return None;
+        }
+        Some(model)
+    }
+}
+
+impl EmbeddingModel for FallbackEmbedding {
+    const MAX_DOCUMENTS: usize = 64;
+
+    fn ndims(&self) -> usize {
+        match self {
+            Self::Gemini { model, .. } => model.ndims(),
+            Self::Granite(model) => model.ndims(),
+        }
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        match self {
+            Self::Gemini { model, .. } => model.embed_texts(texts).await,
+            Self::Granite(model) => model.embed_texts(texts).await,
+        }
+    }
+}
+
+impl NamedEmbeddingModel for FallbackEmbedding {
+    fn model_id(&self) -> String {
+        match self {
+            Self::Gemini { name, .. } => format!("gemini/{}", name),
+            Self::Granite(model) => format!("granite/{}", model.model()),
+        }
+    }
+}
+
+// Documents embedded by the fallback model. They live in their own database and
+// are searched with that model, never mixed into the Cohere index.
+struct FallbackStore {
+    storage: RwLock<StorageManager<FallbackEmbedding>>,
+    model: FallbackEmbedding,
+    path: String,
+}
+
+impl FallbackStore {
+    // Next to the main database, or in memory along with it; `fresh` clears it
+    async fn open(model: FallbackEmbedding, db_path: Option<&Path>, fresh: bool, metric: DistanceMetric) -> Result<Self> {
+        // Named after the model, so switching fallbacks never reuses another model's vectors
+        let file = db_path.map(|db_path| {
+            let stem = db_path.file_stem().unwrap_or_default().to_string_lossy();
+            let model_id = sanitize_filename::sanitize(model.model_id().replace('/', "-"));
+            db_path.with_file_name(format!("{}.{}.db", stem, model_id))
+        });
+        let mut storage = StorageManager::new_with_mode(file.as_deref()).await?.with_metric(metric);
+        storage.initialize_store_with_mode(model.clone(), !fresh).await?;
+        Ok(Self {
+            storage: RwLock::new(storage),
+            model,
+            path: file.map_or_else(|| ":memory:".to_string(), |file| file.display().to_string()),
+        })
+    }
+
+    // Nothing is stored unless every chunk was embedded
+    async fn add(&self, chunks: &[Vec<String>], sources: &[String]) -> Result<()> {
+        let mut documents = Vec::new();
+        for (source, chunk) in sources.iter().zip(chunks) {
+            let language = detect_language(chunk);
+            documents.extend(chunk.iter().map(|content| Document::new(source, &document_content(source, content, &language))));
+        }
+        let count = documents.len();
+        let embeddings = EmbeddingsBuilder::new(self.model.clone()).documents(documents)?.build().await?;
+
+        let storage = self.storage.read().await;
+        storage.remove_sources(sources).await?;
+        if let Some(store) = storage.get_store() {
+            store.add_rows(embeddings).await?;
+        }
+        status!("📑 Stored {} chunks from {} source(s) with {}", count, sources.len(), self.model.model_id());
+        Ok(())
+    }
+
+    // Best chunks for `query`, ranked in the fallback model's own vector space
+    async fn top_chunks(&self, query: &str, n: usize) -> Result<Vec<Document>> {
+        let storage = self.storage.read().await;
+        let Some(store) = storage.get_store() else {
+            return Ok(Vec::new());
+        };
+        if storage.document_counts().await?.0 == 0 {
+            return Ok(Vec::new());
+        }
+        let index = store.clone().index(self.model.clone());
+        Ok(index.top_n::<Document>(query, n).await?.into_iter().map(|(_, _, doc)| doc).collect())
+    }
+}
+
+// Fallback provider and optional model from `--embedding-fallback <gemini|granite>`
+// or EMBEDDING_FALLBACK, with the model from EMBEDDING_FALLBACK_MODEL
+fn parse_embedding_fallback(args: &[String]) -> Result<Option<(String, Option<String>)>> {
+    let provider = flag_value(args, "--embedding-fallback")
+        .or_else(|| std::env::var("EMBEDDING_FALLBACK").ok())
+        .filter(|provider| !provider.trim().is_empty());
+
+    match provider.map(|p| p.trim().to_lowercase()) {
+        Some(p) if p == "gemini" || p == "granite" => {
+            let model = std::env::var("EMBEDDING_FALLBACK_MODEL").ok().filter(|m| !m.trim().is_empty());
+            Ok(Some((p, model)))
+        }
+        Some(p) => anyhow::bail!("Unknown embedding fallback '{}', expected gemini or granite", p),
+        None => Ok(None),
+    }
+}
+
+// Update the debug function to use simpler queries
+// async fn debug_sqlite_store(
+//     storage: &RwLock<StorageManager>,
+//     model: &cohere::EmbeddingModel
+// ) -> Result<()> {
+//     info!("Debugging SQLite store...");
+//     
+//     let storage = storage.read().await;
+//     
+//     // Check documents table
+//     let docs = storage.get_documents().await?;
+//     info!("Documents in SQLite store: {}", docs.len());
+//     for doc in &docs {
+//         info!("Document: {} (id: {})", doc.source, doc.id);
+//         info!("Content preview: {}", doc.content.chars().take(100).collect::<String>());
+//     }
+//     
+//     // Check vectors table if using a store
+//     if let Some(store) = storage.get_store() {
+//         let index = store.clone().index(model.clone());
+//         
+//         // Use vector search to test functionality
+//         let results = index.top_n::<common::storage::Document>("test", 5).await?;
+//         info!("Vector search test results:");
+//         for (score, _id, doc) in results {
+//             info!("Document '{}' (score: {:.4})", doc.source, score);
+//         }
+//     }
+//     
+//     Ok(())
+// }
+
+// Update the build_agent function
+async fn build_agent(
+    client: &Client,
+    storage: &StorageManager,
+    model: &cohere::EmbeddingModel,
+    query: &str,
+    model_name: &str,
+    state: &ChatState,
+) -> Result<rig::agent::Agent<openrouter::OpenRouterCompletionModel>> {
+    let guard = state.guard;
+    let config = *state.chunk_config.lock();
+    let fallback_chunks = match &state.fallback {
+        Some(fallback) => fallback.top_chunks(query, FALLBACK_RETRIEVED_CHUNKS).await.unwrap_or_else(|e| {
+            warn!("Fallback store search failed: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+    let mut builder = client.agent(model_name);
+    
+    builder = builder
         .max_tokens(4000)
         .temperature(0.7);
     
@@ -623,39 +1587,64 @@ async fn build_agent(
         let index = store.clone().index(model.clone());
         
         info!("Checking documents in store...");
-        let has_documents = match storage.get_documents().await {
+        let docs = match storage.get_documents().await {
             Ok(docs) => {
                 if !docs.is_empty() {
                     info!("Found {} documents in knowledge base:", docs.len());
                     for doc in &docs {
                         info!("- {}", doc.source);
                     }
-                    true
-                } else {
-                    false
                 }
+                docs
             },
             Err(e) => {
                 info!("Error checking documents: {}", e);
-                false
+                Vec::new()
             }
         };
 
-        if has_documents {
+        if !docs.is_empty() || !fallback_chunks.is_empty() {
             info!("Initializing agent with document context");
-            builder = builder
-                .preamble(
-                    "You are Zoey, an enthusiastic and knowledgeable AI research assistant. \
-                    You have access to several documents in your knowledge base. \
-                    When asked about documents, ALWAYS start by listing the titles of ALL documents you can see, like this:\n\
-                    'I have access to these documents:\n\
-                    1. [Document Title 1]\n\
-                    2. [Document Title 2]\n\
-                    ...\n'\n\
-                    Then provide your analysis or answer based on the actual content of those documents. \
-                    Quote specific passages when relevant. Never make up or hallucinate document content."
-                )
-                .dynamic_context(32, index);  // Increased context window
+            let mut preamble = String::from(
+                "You are Zoey, an enthusiastic and knowledgeable AI research assistant. \
+                You have access to several documents in your knowledge base. \
+                When asked about documents, ALWAYS start by listing the titles of ALL documents you can see, like this:\n\
+                'I have access to these documents:\n\
+                1. [Document Title 1]\n\
+                2. [Document Title 2]\n\
+                ...\n'\n\
+                Then provide your analysis or answer based on the actual content of those documents. \
+                Quote specific passages when relevant. Never make up or hallucinate document content."
+            );
+            preamble.push_str(CITATION_INSTRUCTION);
+            if let Some(note) = language_note(&docs) {
+                preamble.push_str(&note);
+            }
+
+            // Retrieved here rather than with dynamic_context so each chunk carries its source title.
+            // Reordering needs candidates beyond the final count to choose from.
+            let per_source = config.retrieval_per_source;
+            let candidates = if per_source > 0 || config.recency_weight > 0.0 {
+                RETRIEVED_CHUNKS * DIVERSITY_CANDIDATES
+            } else {
+                RETRIEVED_CHUNKS
+            };
+            let ranked = index.top_n::<common::storage::Document>(query, candidates).await?;
+            let ranked = order_by_recency(ranked, config.recency_weight, Utc::now());
+            let mut chunks = if per_source > 0 {
+                diversify(ranked, RETRIEVED_CHUNKS, per_source)
+            } else {
+                ranked.into_iter().take(RETRIEVED_CHUNKS).collect()
+            };
+            // Ranked by another model, so they can't be merged by score
+            chunks.extend(fallback_chunks);
+            info!("Attaching {} retrieved chunks as context", chunks.len());
+            builder = prompt_guard::guarded_agent(
+                builder,
+                &preamble,
+                chunks.iter().map(|doc| (doc.source.as_str(), excerpt_text(doc))),
+                guard,
+            );
         }
         Ok(builder.build())
     } else {
@@ -663,8 +1652,319 @@ async fn build_agent(
     }
 }
 
-async fn read_user_input() -> Result<String> {
-    let mut stdin = BufReader::new(io::stdin()).lines();
+// Blend vector relevance with how recently each chunk was stored, `weight` being
+// the recency share (0 keeps the pure relevance order). Relevance is the distance
+// rescaled over the candidates, recency halves every RECENCY_HALF_LIFE_DAYS.
+// Chunks without a readable timestamp are ranked on relevance alone.
+fn order_by_recency(
+    ranked: Vec<(f64, String, common::storage::Document)>,
+    weight: f64,
+    now: DateTime<Utc>,
+) -> Vec<common::storage::Document> {
+    if weight <= 0.0 {
+        return ranked.into_iter().map(|(_, _, doc)| doc).collect();
+    }
+    let weight = weight.min(1.0);
+    let (min, max) = ranked
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), (distance, _, _)| (min.min(*distance), max.max(*distance)));
+
+    let mut scored: Vec<(f64, common::storage::Document)> = ranked
+        .into_iter()
+        .map(|(distance, _, doc)| {
+            let relevance = if max > min { (max - distance) / (max - min) } else { 1.0 };
+            let score = match DateTime::parse_from_rfc3339(&doc.timestamp) {
+                Ok(stored) => {
+                    let age_days = (now - stored.with_timezone(&Utc)).num_seconds().max(0) as f64 / 86_400.0;
+                    let recency = 0.5f64.powf(age_days / RECENCY_HALF_LIFE_DAYS);
+                    (1.0 - weight) * relevance + weight * recency
+                }
+                Err(_) => relevance,
+            };
+            (score, doc)
+        })
+        .collect();
+    // Stable, so equal scores keep their relevance order
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, doc)| doc).collect()
+}
+
+// Take the best `n` chunks with at most `per_source` from any one source, then
+// top up in rank order when too few other sources are relevant
+fn diversify(
+    ranked: Vec<common::storage::Document>,
+    n: usize,
+    per_source: usize,
+) -> Vec<common::storage::Document> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let (mut picked, mut rest): (Vec<_>, Vec<_>) = (Vec::new(), Vec::new());
+    for doc in ranked {
+        let count = counts.entry(doc.source.clone()).or_default();
+        if picked.len() < n && *count < per_source {
+            *count += 1;
+            picked.push(doc);
+        } else {
+            rest.push(doc);
+        }
+    }
+    let missing = n.saturating_sub(picked.len());
+    picked.extend(rest.into_iter().take(missing));
+    picked
+}
+
+// A stored chunk's text without the title/URL header it was embedded with; the
+// guard adds the source title back when it becomes an excerpt
+fn excerpt_text(doc: &common::storage::Document) -> &str {
+    doc.content
+        .split_once("CONTENT START")
+        .map(|(_, rest)| rest.split("CONTENT END").next().unwrap_or(rest))
+        .unwrap_or(&doc.content)
+        .trim()
+}
+
+// Lowercased words of a query worth matching on
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| w.len() > 2)
+        .collect()
+}
+
+// Number of query terms that appear in the content
+fn keyword_score(content: &str, terms: &[String]) -> usize {
+    let content = content.to_lowercase();
+    terms.iter().filter(|t| content.contains(t.as_str())).count()
+}
+
+// Offline variant of build_agent: ranks stored documents by keyword overlap with the
+// prompt and attaches them as static context, so no embedding call is needed
+async fn build_offline_agent(
+    client: &Client,
+    storage: &StorageManager,
+    query: &str,
+    model_name: &str,
+    guard: GuardMode,
+) -> Result<rig::agent::Agent<openrouter::OpenRouterCompletionModel>> {
+    let mut builder = client
+        .agent(model_name)
+        .max_tokens(4000)
+        .temperature(0.7);
+
+    let docs = storage.get_documents().await?;
+    if docs.is_empty() {
+        return Ok(builder.build());
+    }
+
+    let terms = query_terms(query);
+    let mut scored: Vec<(usize, &common::storage::Document)> = docs
+        .iter()
+        .map(|doc| (keyword_score(&doc.content, &terms), doc))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+    info!("Offline mode: attaching {} of {} documents as context",
+        scored.len().min(OFFLINE_CONTEXT_DOCUMENTS), docs.len());
+
+    let mut preamble = String::from(
+        "You are Zoey, an enthusiastic and knowledgeable AI research assistant. \
+        You have access to several documents in your knowledge base. \
+        Answer based on the actual content of those documents and quote specific passages when relevant. \
+        Never make up or hallucinate document content."
+    );
+    preamble.push_str(CITATION_INSTRUCTION);
+    if let Some(note) = language_note(&docs) {
+        preamble.push_str(&note);
+    }
+    let excerpts = scored
+        .into_iter()
+        .take(OFFLINE_CONTEXT_DOCUMENTS)
+        .map(|(_, doc)| (doc.source.as_str(), excerpt_text(doc)));
+
+    Ok(prompt_guard::guarded_agent(builder, &preamble, excerpts, guard).build())
+}
+
+// Agent with a fixed set of excerpts as context and no retrieval, used by /compare.
+// The excerpts are expected to be guarded already.
+fn build_context_agent(
+    client: &Client,
+    context: &[String],
+    model_name: &str,
+    guard: GuardMode,
+) -> rig::agent::Agent<openrouter::OpenRouterCompletionModel> {
+    let mut preamble = String::from(
+        "You are Zoey, an enthusiastic and knowledgeable AI research assistant. \
+        Answer only from the document excerpts you are given and quote specific passages when relevant. \
+        Never make up or hallucinate document content."
+    );
+    preamble.push_str(guard.instruction().unwrap_or_default());
+    let mut builder = client
+        .agent(model_name)
+        .max_tokens(4000)
+        .temperature(0.7)
+        .preamble(&preamble);
+    for excerpt in context {
+        builder = builder.context(excerpt);
+    }
+    builder.build()
+}
+
+/// How destructive commands like /clear are confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfirmMode {
+    /// `--yes`: go ahead without asking
+    Assume,
+    /// Ask y/N on the terminal
+    Ask,
+    /// Nobody can answer (`--rig-cli`, or stdin isn't a terminal): refuse
+    Refuse,
+}
+
+fn parse_confirm_mode(args: &[String]) -> ConfirmMode {
+    if args.iter().any(|arg| arg == "--yes" || arg == "-y") {
+        ConfirmMode::Assume
+    } else if args.iter().any(|arg| arg == "--rig-cli") || !std::io::stdin().is_terminal() {
+        ConfirmMode::Refuse
+    } else {
+        ConfirmMode::Ask
+    }
+}
+
+// The REPL's stdin, shared with confirmations so no typed line is lost between two readers
+type StdinLines = io::Lines<BufReader<io::Stdin>>;
+
+// Err carries the message to show when the action doesn't go ahead.
+// Asking needs the REPL's reader; without one (under --rig-cli) it refuses.
+async fn confirm_action(mode: ConfirmMode, question: &str, stdin: Option<&mut StdinLines>) -> Result<(), String> {
+    let refuse = || Err("Refusing without confirmation in non-interactive mode - restart with --yes to allow it".to_string());
+    match (mode, stdin) {
+        (ConfirmMode::Assume, _) => Ok(()),
+        (ConfirmMode::Refuse, _) | (ConfirmMode::Ask, None) => refuse(),
+        (ConfirmMode::Ask, Some(stdin)) => {
+            print!("{} [y/N] ", question);
+            std::io::stdout().flush().ok();
+            let answer = stdin.next_line().await.ok().flatten().unwrap_or_default();
+            if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+                Ok(())
+            } else {
+                Err("Cancelled - nothing was deleted".to_string())
+            }
+        }
+    }
+}
+
+// Wipe the knowledge base after saying how much it holds and confirming
+async fn handle_clear_command(state: &ChatState, confirm: ConfirmMode, stdin: Option<&mut StdinLines>) -> Result<String> {
+    let storage = state.storage.write().await;
+    let (mut documents, mut sources) = storage.document_counts().await?;
+    if let Some(fallback) = &state.fallback {
+        let (fallback_documents, fallback_sources) = fallback.storage.read().await.document_counts().await?;
+        documents += fallback_documents;
+        sources += fallback_sources;
+    }
+    if documents == 0 {
+        return Ok("📭 The knowledge base is already empty".to_string());
+    }
+    if let Err(reason) = confirm_action(
+        confirm,
+        &format!("🗑️ This deletes {} document chunk(s) from {} source(s). Continue?", documents, sources),
+        stdin,
+    ).await {
+        return Ok(format!("⚠️ {}", reason));
+    }
+    storage.clear_documents().await?;
+    if let Some(fallback) = &state.fallback {
+        fallback.storage.read().await.clear_documents().await?;
+    }
+    Ok(format!("🧹 Memory cleared! Deleted {} document chunk(s) from {} source(s). I'm ready for new conversations or documents.", documents, sources))
+}
+
+// Value of `name <value>` or `name=value`
+fn flag_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1).cloned())
+        .or_else(|| args.iter().find_map(|arg| arg.strip_prefix(&prefix).map(|s| s.to_string())))
+}
+
+// Vector store ranking from `--metric <cosine|l2|dot>`, L2 by default
+fn parse_metric(args: &[String]) -> Result<DistanceMetric> {
+    let metric = flag_value(args, "--metric");
+
+    match metric {
+        Some(metric) => metric.parse().map_err(|e: String| anyhow::anyhow!(e)),
+        None => Ok(DistanceMetric::default()),
+    }
+}
+
+// Database from `--db-path <file>`, zoey.db by default. A --fresh session without
+// a path stays in memory; with one, that database is opened and cleared.
+fn parse_db_path(args: &[String], fresh: bool) -> Option<PathBuf> {
+    let path = flag_value(args, "--db-path")
+        .filter(|path| !path.trim().is_empty());
+
+    match path {
+        Some(path) => Some(PathBuf::from(path)),
+        None if fresh => None,
+        None => Some(PathBuf::from(storage::DEFAULT_DB_PATH)),
+    }
+}
+
+// Retrieved-content guard from `--injection-guard <off|delimit|strict>`, strict by default
+fn parse_guard_mode(args: &[String]) -> Result<GuardMode> {
+    let mode = flag_value(args, "--injection-guard");
+
+    match mode {
+        Some(mode) => mode.parse().map_err(|e: String| anyhow::anyhow!(e)),
+        None => Ok(GuardMode::default()),
+    }
+}
+
+// What counts as an empty answer from `--empty-response <whitespace|punctuation>`,
+// else EMPTY_RESPONSE_POLICY
+fn parse_empty_response(args: &[String]) -> Result<EmptyResponsePolicy> {
+    let policy = flag_value(args, "--empty-response");
+
+    match policy {
+        Some(policy) => policy.parse().map_err(|e: String| anyhow::anyhow!(e)),
+        None => Ok(EmptyResponsePolicy::from_env()),
+    }
+}
+
+// zoey_images limits from `--max-images <n>` and `--max-images-mb <n>`, 0 for unlimited
+fn parse_image_retention(args: &[String]) -> Result<ImageRetention> {
+    let mut retention = ImageRetention::default();
+    if let Some(value) = flag_value(args, "--max-images") {
+        retention.max_files = value
+            .parse()
+            .with_context(|| format!("Invalid --max-images value: {}", value))?;
+    }
+    if let Some(value) = flag_value(args, "--max-images-mb") {
+        let mb: u64 = value
+            .parse()
+            .with_context(|| format!("Invalid --max-images-mb value: {}", value))?;
+        retention.max_bytes = mb * 1024 * 1024;
+    }
+    Ok(retention)
+}
+
+// Read the --model-fallbacks a,b,c flag (also accepts --model-fallbacks=a,b,c)
+fn parse_model_chain(args: &[String]) -> Vec<String> {
+    let fallbacks = flag_value(args, "--model-fallbacks");
+
+    let mut models = vec![DEFAULT_COMPLETION_MODEL.to_string()];
+    if let Some(list) = fallbacks {
+        for model in list.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            if !models.iter().any(|m| m == model) {
+                models.push(model.to_string());
+            }
+        }
+    }
+    models
+}
+
+async fn read_user_input(stdin: &mut StdinLines) -> Result<String> {
     print!("> ");
     std::io::stdout().flush()?;
     stdin.next_line().await?
@@ -690,6 +1990,8 @@ struct Contents {
     #[serde(skip_serializing_if = "Option::is_none")]
     highlights: Option<Highlights>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<Summary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     extras: Option<Extras>,
 }
 
@@ -698,6 +2000,11 @@ struct Highlights {
     highlights_per: i32,
 }
 
+#[derive(Debug, Serialize)]
+struct Summary {
+    max_sentences: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Extras {
     image_links: Vec<String>,
@@ -723,22 +2030,120 @@ struct ExaResult {
     extras: Option<Extras>,
 }
 
+// Limits for zoey_images, 0 means unlimited
+#[derive(Debug, Clone, Copy)]
+struct ImageRetention {
+    max_files: usize,
+    max_bytes: u64,
+}
+
+impl Default for ImageRetention {
+    fn default() -> Self {
+        Self {
+            max_files: DEFAULT_MAX_IMAGES,
+            max_bytes: 0,
+        }
+    }
+}
+
+// Downloaded images, with the ones saved this session protected from cleanup
+struct ImageStore {
+    retention: ImageRetention,
+    session: PLMutex<HashSet<PathBuf>>,
+}
+
+impl ImageStore {
+    fn new(retention: ImageRetention) -> Self {
+        Self {
+            retention,
+            session: PLMutex::new(HashSet::new()),
+        }
+    }
+
+    // Files in zoey_images, oldest first
+    fn stored_images() -> Result<Vec<(PathBuf, u64, std::time::SystemTime)>> {
+        let mut images = Vec::new();
+        for entry in std::fs::read_dir(paths::images_dir())? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+                images.push((entry.path(), metadata.len(), modified));
+            }
+        }
+        images.sort_by_key(|(_, _, modified)| *modified);
+        Ok(images)
+    }
+
+    // Delete the oldest images until the limits hold, never touching this
+    // session's downloads. Returns how many files were removed.
+    fn enforce(&self) -> Result<usize> {
+        let ImageRetention { max_files, max_bytes } = self.retention;
+        if max_files == 0 && max_bytes == 0 {
+            return Ok(0);
+        }
+
+        let images = Self::stored_images()?;
+        let mut count = images.len();
+        let mut total: u64 = images.iter().map(|(_, size, _)| size).sum();
+        let over = |count: usize, total: u64| {
+            (max_files > 0 && count > max_files) || (max_bytes > 0 && total > max_bytes)
+        };
+
+        let session = self.session.lock();
+        let mut removed = 0;
+        for (path, size, _) in images.iter().filter(|(path, _, _)| !session.contains(path)) {
+            if !over(count, total) {
+                break;
+            }
+            std::fs::remove_file(path)?;
+            count -= 1;
+            total -= size;
+            removed += 1;
+        }
+
+        if over(count, total) {
+            warn!("zoey_images is over its limit, but the remaining images belong to this session's results");
+        }
+        Ok(removed)
+    }
+
+    // Delete every image, including this session's. Returns how many files were removed.
+    fn clear(&self) -> Result<usize> {
+        let images = Self::stored_images()?;
+        for (path, _, _) in &images {
+            std::fs::remove_file(path)?;
+        }
+        self.session.lock().clear();
+        Ok(images.len())
+    }
+}
+
 // Add image download functionality
-async fn download_image(client: &reqwest::Client, image_url: &str, file_name: &str) -> Result<()> {
+async fn download_image(
+    client: &reqwest::Client,
+    images: &ImageStore,
+    image_url: &str,
+    file_name: &str,
+) -> Result<()> {
     let response = client.get(image_url).send().await?;
     if response.status().is_success() {
         let bytes = response.bytes().await?;
         
         // Create images directory if it doesn't exist
-        let images_dir = Path::new("zoey_images");
-        if !images_dir.exists() {
-            fs::create_dir_all(images_dir).await?;
-        }
+        let images_dir = paths::images_dir();
 
         // Save the image
         let path = images_dir.join(file_name);
-        fs::write(path, &bytes).await?;
+        fs::write(&path, &bytes).await?;
+        images.session.lock().insert(path);
         println!("✅ Saved image: {}", file_name);
+
+        match images.enforce() {
+            Ok(0) => {}
+            Ok(removed) => println!("🧹 Removed {} old image(s) to stay within the zoey_images limit", removed),
+            Err(e) => println!("⚠️ Failed to clean up old images: {}", e),
+        }
     }
     Ok(())
 }
@@ -749,7 +2154,14 @@ async fn search_with_exa(
     num_results: i32,
     search_type: &str,
     include_domains: Option<Vec<String>>,
+    images: &ImageStore,
+    summary_sentences: usize,
+    highlights_per_result: usize,
 ) -> Result<Vec<String>> {
+    if is_offline() {
+        anyhow::bail!("Running in offline mode - web search is disabled");
+    }
+
     let exa_api_key = std::env::var("EXA_API_KEY")
         .context("EXA_API_KEY environment variable not set")?;
 
@@ -757,16 +2169,15 @@ async fn search_with_exa(
     
     let search_request = ExaSearchRequest {
         query: query.to_string(),
-        category: match search_type {
-            "pdf" => Some("pdf".to_string()),
-            "news" => Some("news".to_string()),
-            "research" => Some("research".to_string()),
-            _ => None,
-        },
+        category: search_category(search_type).map(str::to_string),
         contents: Contents {
             text: true,
-            highlights: Some(Highlights {
-                highlights_per: 3,
+            // Zero omits the field, Exa treats an explicit 0 differently
+            highlights: (highlights_per_result > 0).then(|| Highlights {
+                highlights_per: highlights_per_result.min(MAX_HIGHLIGHTS_PER_RESULT as usize) as i32,
+            }),
+            summary: (summary_sentences > 0).then(|| Summary {
+                max_sentences: summary_sentences.min(MAX_SUMMARY_SENTENCES as usize) as i32,
             }),
             extras: if search_type == "images" {
                 Some(Extras {
@@ -789,6 +2200,8 @@ async fn search_with_exa(
         .await?;
 
     let mut results = Vec::new();
+    // Keeps this search's files from overwriting the previous search's
+    let batch = chrono::Utc::now().timestamp_millis();
     
     match response.status() {
         reqwest::StatusCode::OK => {
@@ -815,17 +2228,17 @@ async fn search_with_exa(
                 // Handle image downloads if this is an image search
                 if search_type == "images" {
                     if let Some(image_url) = &item.image {
-                        let file_name = format!("image_main_{}.jpg", idx);
-                        if let Err(e) = download_image(&client, image_url, &file_name).await {
-                            println!("❌ Failed to download main image: {}", e);
+                        let file_name = format!("image_{}_main_{}.jpg", batch, idx);
+                        if let Err(e) = download_image(&client, images, image_url, &file_name).await {
+                            status!("❌ Failed to download main image: {}", e);
                         }
                     }
 
                     if let Some(extras) = &item.extras {
                         for (img_idx, img_url) in extras.image_links.iter().enumerate() {
-                            let file_name = format!("image_variant_{}_{}.jpg", idx, img_idx);
-                            if let Err(e) = download_image(&client, img_url, &file_name).await {
-                                println!("❌ Failed to download variant image: {}", e);
+                            let file_name = format!("image_{}_variant_{}_{}.jpg", batch, idx, img_idx);
+                            if let Err(e) = download_image(&client, images, img_url, &file_name).await {
+                                status!("❌ Failed to download variant image: {}", e);
                             }
                         }
                     }
@@ -845,10 +2258,10 @@ async fn search_with_exa(
 
 // Add this helper function to check and create documents directory
 async fn setup_documents_dir() -> Result<()> {
-    let documents_dir = std::env::current_dir()?.join("documents");
-    if !documents_dir.exists() {
-        println!("📁 Creating documents directory at: {}", documents_dir.display());
-        tokio::fs::create_dir_all(&documents_dir).await?;
+    // paths::documents_dir creates the directory, so a fresh one is an empty one
+    let documents_dir = paths::documents_dir();
+    if std::fs::read_dir(&documents_dir)?.next().is_none() {
+        println!("📁 Created documents directory at: {}", documents_dir.display());
         
         // Create a sample document to show the user
         let sample_content = "This is a sample document.\nYou can replace this with your own documents.";
@@ -860,41 +2273,933 @@ async fn setup_documents_dir() -> Result<()> {
     Ok(())
 }
 
-// Update handle_load_command to match the backup exactly
-async fn handle_load_command(
-    input: &str,
-    state: &Arc<ChatState>,
-    cohere_client: &cohere::Client,
-) -> Result<()> {
-    let paths: Vec<String> = input
-        .split_whitespace()
-        .skip(1)  // Skip the /load command
-        .map(|s| s.to_string())
-        .collect();
+// Supported files under `dir`, recursively and in a stable order, plus the
+// unsupported files that were skipped. Symlinked directories are followed once.
+fn collect_directory(dir: &Path, filter: Option<&glob::Pattern>) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        // A symlink back to a directory we've seen would loop forever
+        let canonical = std::fs::canonicalize(&current)
+            .with_context(|| format!("Failed to read directory: {}", current.display()))?;
+        if !visited.insert(canonical) {
+            continue;
+        }
 
-    if paths.is_empty() {
-        println!("❌ Usage: /load [file1] [file2]...");
-        println!("📝 Files should be in the 'documents' directory");
-        println!("📌 Example: /load article.pdf research.txt");
-        return Ok(());
+        let mut entries = std::fs::read_dir(&current)
+            .with_context(|| format!("Failed to read directory: {}", current.display()))?
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.path());
+
+        let mut subdirs = Vec::new();
+        for entry in entries {
+            let path = entry.path();
+            if path.is_dir() {
+                subdirs.push(path);
+                continue;
+            }
+            let relative = path.strip_prefix(dir).unwrap_or(&path);
+            if filter.map_or(false, |pattern| !pattern.matches_path(relative)) {
+                continue;
+            }
+            if DocumentLoader::is_supported(&path) {
+                files.push(path);
+            } else {
+                skipped.push(path);
+            }
+        }
+        // Reversed so the stack visits subdirectories in name order
+        pending.extend(subdirs.into_iter().rev());
     }
 
-    println!("📚 Loading documents...");
-    let chunks = load_documents(&paths).await?;
-    
-    println!("🔍 Processing documents...");
-    process_new_documents(state, chunks, &paths, cohere_client).await?;
-    
-    println!("✅ Documents loaded and processed successfully!");
-    Ok(())
+    Ok((files, skipped))
 }
 
-// Update main to call setup_documents_dir at startup
-#[tokio::main]
-async fn main() -> Result<()> {
+// Replace directory arguments with the supported files inside them, as paths
+// relative to the documents directory when they are under it
+fn expand_load_paths(args: &[String], filter: Option<&glob::Pattern>) -> Result<Vec<String>> {
+    let documents_dir = paths::documents_dir();
+    let mut expanded = Vec::new();
+
+    for arg in args {
+        let full_path = documents_dir.join(arg);
+        if arg.starts_with("http") || !full_path.is_dir() {
+            expanded.push(arg.clone());
+            continue;
+        }
+
+        let (files, skipped) = collect_directory(&full_path, filter)?;
+        println!("📂 Found {} supported file(s) in {}", files.len(), arg);
+        if !skipped.is_empty() {
+            let names: Vec<String> = skipped
+                .iter()
+                .take(MAX_SKIPPED_NAMES)
+                .map(|p| p.strip_prefix(&full_path).unwrap_or(p).display().to_string())
+                .collect();
+            let more = skipped.len().saturating_sub(MAX_SKIPPED_NAMES);
+            println!(
+                "⏭️ Skipped {} unsupported file(s): {}{}",
+                skipped.len(),
+                names.join(", "),
+                if more > 0 { format!(" and {} more", more) } else { String::new() }
+            );
+            println!("📌 Supported types: {}", SUPPORTED_EXTENSIONS.join(", "));
+        }
+
+        expanded.extend(files.iter().map(|file| {
+            file.strip_prefix(&documents_dir)
+                .unwrap_or(file)
+                .to_string_lossy()
+                .into_owned()
+        }));
+    }
+
+    Ok(expanded)
+}
+
+// Update handle_load_command to match the backup exactly
+async fn handle_load_command(input: &str, state: &Arc<ChatState>) -> Result<()> {
+    if let Some(paths) = parse_load_paths(input, "/load")? {
+        load_sources(&paths, state, &cohere_client()?).await?;
+    }
+    Ok(())
+}
+
+async fn handle_preview_command(input: &str, state: &Arc<ChatState>) -> Result<()> {
+    if let Some(paths) = parse_load_paths(input, "/preview")? {
+        preview_sources(&paths, state).await;
+    }
+    Ok(())
+}
+
+// Files named by /load or /preview arguments, or None after printing usage
+fn parse_load_paths(input: &str, command: &str) -> Result<Option<Vec<String>>> {
+    let mut args = split_args(input);
+
+    // `--glob <pattern>` limits the files taken from directories
+    let filter = match args.iter().position(|arg| arg == "--glob") {
+        Some(i) => {
+            let Some(pattern) = args.get(i + 1).cloned() else {
+                println!("❌ Usage: {} [directory] --glob [pattern]", command);
+                return Ok(None);
+            };
+            args.drain(i..=i + 1);
+            Some(glob::Pattern::new(&pattern).with_context(|| format!("Invalid glob pattern: {}", pattern))?)
+        }
+        None => None,
+    };
+
+    if args.is_empty() {
+        println!("❌ Usage: {} [file1] [file2]...", command);
+        println!("📝 Files should be in the 'documents' directory");
+        println!("📌 Example: {} article.pdf research.txt", command);
+        println!("📂 Directories are loaded recursively: {} reports --glob \"*.md\"", command);
+        return Ok(None);
+    }
+
+    let paths = expand_load_paths(&args, filter.as_ref())?;
+    if paths.is_empty() {
+        println!("📭 No supported files to load");
+        return Ok(None);
+    }
+    Ok(Some(paths))
+}
+
+// Price per million tokens for `model`, from EMBEDDING_PRICES if set there
+fn embedding_price(model: &str) -> Option<f64> {
+    price_for(&std::env::var("EMBEDDING_PRICES").unwrap_or_default(), model)
+}
+
+// `model`'s price in a "model=price,..." list, else its built-in list price
+fn price_for(configured: &str, model: &str) -> Option<f64> {
+    configured
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(name, _)| name.trim() == model)
+        .and_then(|(_, price)| price.trim().parse().ok())
+        .or_else(|| EMBEDDING_PRICES.iter().find(|(name, _)| *name == model).map(|(_, price)| *price))
+}
+
+fn estimate_embedding_tokens(chunks: &[String]) -> usize {
+    chunks
+        .iter()
+        .map(|chunk| chunk.chars().count().div_ceil(CHARS_PER_TOKEN) + CHUNK_HEADER_TOKENS)
+        .sum()
+}
+
+// One line per estimate; models without a known price get the token count only
+fn describe_embedding_estimate(model: &str, chunks: usize, tokens: usize) -> String {
+    match embedding_price(model) {
+        Some(price) => format!(
+            "💰 ~{} tokens for {} chunks with {}, about ${:.4} (approximate estimate, not a bill)",
+            tokens,
+            chunks,
+            model,
+            tokens as f64 / 1_000_000.0 * price
+        ),
+        None => format!(
+            "💰 ~{} tokens for {} chunks with {} (approximate; no price known for this model - set EMBEDDING_PRICES to add one)",
+            tokens, chunks, model
+        ),
+    }
+}
+
+// Chunk the given sources the way /load would and report the embedding cost,
+// without storing or embedding anything
+async fn preview_sources(paths: &[String], state: &Arc<ChatState>) {
+    let config = *state.chunk_config.lock();
+    println!("🔎 Previewing {} document(s) (chunk size: {}, overlap: {})...", paths.len(), config.chunk_size, config.overlap);
+
+    let mut chunk_count = 0;
+    let mut tokens = 0;
+    let mut failed = 0;
+    for batch in paths.chunks(LOAD_BATCH_SIZE) {
+        for (path, result) in batch.iter().zip(load_documents(batch, config).await) {
+            match result {
+                Ok(doc_chunks) => {
+                    let doc_chunks = sample_chunks(doc_chunks, config.max_chunks_per_source);
+                    let doc_tokens = estimate_embedding_tokens(&doc_chunks);
+                    println!("  📄 {}: {} chunks, ~{} tokens", path, doc_chunks.len(), doc_tokens);
+                    chunk_count += doc_chunks.len();
+                    tokens += doc_tokens;
+                }
+                Err(e) => {
+                    println!("❌ {:#}", e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!("{}", describe_embedding_estimate(EMBED_ENGLISH_V3, chunk_count, tokens));
+    if failed > 0 {
+        println!("⚠️ {} source(s) could not be read and aren't counted", failed);
+    }
+    if is_offline() {
+        println!("📴 Offline mode stores chunks without embedding them, so /load won't be charged");
+    }
+}
+
+// Load and store the given sources, `LOAD_BATCH_SIZE` at a time so a large
+// directory is never held in memory at once. Whatever fails is remembered for
+// /retry; a fully successful load clears the previous failure.
+async fn load_sources(
+    paths: &[String],
+    state: &Arc<ChatState>,
+    cohere_client: &cohere::Client,
+) -> Result<()> {
+    let config = *state.chunk_config.lock();
+    println!(
+        "📚 Loading {} document(s) (chunk size: {}, overlap: {}, concurrency: {})...",
+        paths.len(), config.chunk_size, config.overlap, config.load_concurrency
+    );
+
+    let mut loaded = 0;
+    let mut failed = Vec::new();
+    let batches = paths.len().div_ceil(LOAD_BATCH_SIZE);
+    // Reading and embedding each batch
+    let mut progress = Progress::new(batches * 2);
+    for (batch_idx, batch) in paths.chunks(LOAD_BATCH_SIZE).enumerate() {
+        if batches > 1 {
+            status!("📦 Batch {}/{}", batch_idx + 1, batches);
+        }
+        progress.step(format!("Reading {} document(s)", batch.len()));
+        let results = load_documents(batch, config).await;
+
+        let mut loaded_paths = Vec::new();
+        let mut chunks = Vec::new();
+        for (path, result) in batch.iter().zip(results) {
+            match result {
+                Ok(doc_chunks) => {
+                    let total = doc_chunks.len();
+                    let doc_chunks = sample_chunks(doc_chunks, config.max_chunks_per_source);
+                    if doc_chunks.len() < total {
+                        status!("✂️ Stored {} of {} chunks from {}", doc_chunks.len(), total, path);
+                    }
+                    loaded_paths.push(path.clone());
+                    chunks.push(doc_chunks);
+                }
+                Err(e) => {
+                    status!("❌ {:#}", e);
+                    failed.push(path.clone());
+                }
+            }
+        }
+
+        progress.step("Embedding documents");
+        if !loaded_paths.is_empty() {
+            status!("🔍 Processing documents...");
+            if let Err(e) = process_new_documents(state, chunks, &loaded_paths, cohere_client).await {
+                // Nothing from this batch was stored, and later batches never ran
+                failed.extend(loaded_paths);
+                failed.extend(paths.iter().skip((batch_idx + 1) * LOAD_BATCH_SIZE).cloned());
+                *state.last_failed.lock() = Some(FailedCommand::Load(failed));
+                status!("💡 Use /retry to load them again");
+                return Err(e);
+            }
+            loaded += loaded_paths.len();
+        }
+    }
+    progress.finish();
+
+    if failed.is_empty() {
+        *state.last_failed.lock() = None;
+        println!("✅ Documents loaded and processed successfully!");
+    } else {
+        println!(
+            "⚠️ Loaded {}/{} documents - use /retry to load the {} that failed",
+            loaded,
+            paths.len(),
+            failed.len()
+        );
+        *state.last_failed.lock() = Some(FailedCommand::Load(failed));
+    }
+    Ok(())
+}
+
+// A parsed /search command
+#[derive(Debug, Clone, PartialEq)]
+struct SearchCommand {
+    search_type: &'static str,
+    query: String,
+    num_results: usize,
+    domains: Option<Vec<String>>,
+}
+
+fn search_category(search_type: &str) -> Option<&'static str> {
+    SEARCH_TYPES.iter().find(|(name, _, _)| *name == search_type).and_then(|(_, category, _)| *category)
+}
+
+// Results per search of this type, from SEARCH_RESULTS if set there
+fn default_num_results(search_type: &str) -> usize {
+    let configured = std::env::var("SEARCH_RESULTS").unwrap_or_default();
+    configured
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(name, _)| name.trim() == search_type)
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .or_else(|| SEARCH_TYPES.iter().find(|(name, _, _)| *name == search_type).map(|(_, _, count)| *count))
+        .unwrap_or(5)
+        .clamp(1, MAX_NUM_RESULTS as usize)
+}
+
+// A token that is meant as a number, valid or not
+fn looks_like_count(token: &str) -> bool {
+    token.trim_start_matches(['-', '+']).starts_with(|c: char| c.is_ascii_digit())
+        && token.chars().all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | ','))
+}
+
+// A result count, when the token is a whole number from 1 to MAX_NUM_RESULTS
+fn parse_count(token: &str) -> Option<usize> {
+    token.parse::<usize>().ok().filter(|n| (1..=MAX_NUM_RESULTS as usize).contains(n))
+}
+
+// `/search [type] [n] [query]` or `/search site [domain] [n] [query]`, where the
+// type defaults to web and n to the type's default. Without a type, a leading
+// number that can't be a count ("/search 2024 halving") is part of the query;
+// after an explicit type it is a usage error. A lone number is always the query.
+// Err is the message to show.
+fn parse_search_command(input: &str) -> Result<SearchCommand, String> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    let (search_type, mut rest) = match parts.first() {
+        Some(&"site") => ("site", &parts[1..]),
+        Some(first) => match SEARCH_TYPES.iter().find(|(name, _, _)| name == first) {
+            Some((name, _, _)) => (*name, &parts[1..]),
+            None => ("web", &parts[..]),
+        },
+        None => return Err("Missing search query".to_string()),
+    };
+
+    let domains = if search_type == "site" {
+        match rest.first() {
+            Some(domain) if looks_like_count(domain) => {
+                return Err("The domain comes before the count in a site search".to_string())
+            }
+            Some(domain) => {
+                let domains = Some(vec![domain.to_string()]);
+                rest = &rest[1..];
+                domains
+            }
+            None => return Err("Missing domain for site search".to_string()),
+        }
+    } else {
+        None
+    };
+
+    let explicit_type = rest.len() < parts.len();
+    // Only a number followed by a query can be a count
+    let count = rest.first().filter(|_| rest.len() > 1).map(|token| (*token, parse_count(token)));
+    let num_results = match count {
+        Some((_, Some(n))) => {
+            rest = &rest[1..];
+            n
+        }
+        Some((token, None)) if explicit_type && looks_like_count(token) => {
+            return Err(format!("Invalid result count '{}', expected 1-{}", token, MAX_NUM_RESULTS))
+        }
+        _ => default_num_results(search_type),
+    };
+
+    if rest.is_empty() {
+        return Err(format!("Missing search query for {} search", search_type));
+    }
+    Ok(SearchCommand {
+        search_type,
+        query: rest.join(" "),
+        num_results,
+        domains,
+    })
+}
+
+// Run an Exa search and store the results. A failed search is remembered for /retry.
+async fn run_search(
+    state: &Arc<ChatState>,
+    query: &str,
+    search_type: &str,
+    num_results: usize,
+    domains: Option<Vec<String>>,
+) -> Result<()> {
+    // Checked first, so a missing key doesn't cost a search
+    let cohere_client = cohere_client()?;
+    println!("🔍 Performing {} search for: {} ({} results)", search_type, query, num_results);
+
+    let config = *state.chunk_config.lock();
+    let mut progress = Progress::new(2);
+    progress.step("Searching Exa");
+    let results = match search_with_exa(
+        query,
+        num_results as i32,
+        search_type,
+        domains.clone(),
+        &state.images,
+        config.search_summary_sentences,
+        config.search_highlights,
+    ).await {
+        Ok(results) => results,
+        Err(e) => {
+            *state.last_failed.lock() = Some(FailedCommand::Search {
+                query: query.to_string(),
+                search_type: search_type.to_string(),
+                num_results,
+                domains,
+            });
+            status!("❌ Search failed: {}", e);
+            status!("💡 Use /retry to run it again");
+            return Ok(());
+        }
+    };
+    status!("📊 Found {} results", results.len());
+
+    let chunks = results.iter()
+        .map(|r| vec![r.clone()])
+        .collect::<Vec<_>>();
+
+    let sources: Vec<String> = results.iter().enumerate()
+        .map(|(idx, content)| {
+            let title = content.lines()
+                .find(|line| line.starts_with("Title:"))
+                .unwrap_or("Untitled")
+                .trim_start_matches("Title: ");
+            format!("Search Result #{} - {}", idx + 1, title)
+        })
+        .collect();
+
+    // Process and store documents
+    progress.step("Embedding search results");
+    if let Err(e) = process_new_documents(state, chunks, &sources, &cohere_client).await {
+        *state.last_failed.lock() = Some(FailedCommand::Search {
+            query: query.to_string(),
+            search_type: search_type.to_string(),
+            num_results,
+            domains,
+        });
+        status!("💡 Use /retry to run the search again");
+        return Err(e);
+    }
+    progress.finish();
+    *state.last_failed.lock() = None;
+
+    println!("\n✅ All {} search results have been loaded into my knowledge base!", results.len());
+    if search_type == "images" {
+        println!("🖼️ Images have been downloaded to the zoey_images directory!");
+    }
+    println!("💡 You can now ask me questions about any of the results!");
+    println!("   For example:");
+    println!("   - Can you summarize all the search results?");
+    println!("   - What are the main points from each source?");
+    println!("   - Compare the information from different sources.");
+    Ok(())
+}
+
+// Re-run the last failed /load (only the sources that failed) or /search
+async fn handle_retry_command(state: &Arc<ChatState>) -> Result<()> {
+    let Some(failed) = state.last_failed.lock().clone() else {
+        println!("📭 Nothing to retry - no /load or /search has failed");
+        return Ok(());
+    };
+
+    match failed {
+        FailedCommand::Load(paths) => {
+            println!("🔄 Retrying /load for {} source(s): {}", paths.len(), paths.join(", "));
+            load_sources(&paths, state, &cohere_client()?).await
+        }
+        FailedCommand::Search { query, search_type, num_results, domains } => {
+            println!("🔄 Retrying {} search", search_type);
+            run_search(state, &query, &search_type, num_results, domains).await
+        }
+    }
+}
+
+// On-disk layout of /save_history, independent of rig's message serialization
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SavedHistory {
+    version: u32,
+    saved_at: DateTime<Utc>,
+    messages: Vec<SavedMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SavedMessage {
+    role: SavedRole,
+    content: String,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SavedRole {
+    User,
+    Assistant,
+}
+
+// The text parts of a message, joined
+fn message_text(message: &Message) -> String {
+    match message {
+        Message::User { content } => content
+            .iter()
+            .filter_map(|c| match c {
+                UserContent::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Message::Assistant { content } => content
+            .iter()
+            .filter_map(|c| match c {
+                AssistantContent::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+// Bare file names go in data/chat_history, paths are taken as given
+fn history_path(file: &str) -> PathBuf {
+    paths::data_subdir("chat_history").join(file)
+}
+
+// Write the conversation to `file` (timestamped name when empty) as JSON, or as
+// Markdown when it ends in .md
+fn save_history(state: &Arc<ChatState>, file: &str) -> Result<PathBuf> {
+    let file = if file.is_empty() {
+        format!("history-{}.json", Utc::now().format("%Y%m%d-%H%M%S"))
+    } else {
+        file.to_string()
+    };
+    let path = history_path(&file);
+
+    let messages: Vec<SavedMessage> = state
+        .chat_history
+        .lock()
+        .iter()
+        .map(|entry| SavedMessage {
+            role: match entry.message {
+                Message::User { .. } => SavedRole::User,
+                Message::Assistant { .. } => SavedRole::Assistant,
+            },
+            content: message_text(&entry.message),
+            timestamp: entry.at,
+        })
+        .collect();
+    let saved_at = Utc::now();
+
+    let contents = if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("md")) {
+        let mut md = format!("# Zoey conversation\n\nSaved {}\n", saved_at.to_rfc3339());
+        for message in &messages {
+            let speaker = match message.role {
+                SavedRole::User => "You",
+                SavedRole::Assistant => "Zoey",
+            };
+            md.push_str(&format!("\n## {} - {}\n\n{}\n", speaker, message.timestamp.to_rfc3339(), message.content));
+        }
+        md
+    } else {
+        serde_json::to_string_pretty(&SavedHistory {
+            version: HISTORY_FORMAT_VERSION,
+            saved_at,
+            messages,
+        })?
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// Replace the conversation with one saved by /save_history. The current one is
+// kept when the file can't be read or isn't a saved history.
+fn load_history(state: &Arc<ChatState>, file: &str) -> Result<usize> {
+    if file.is_empty() {
+        anyhow::bail!("Usage: /load_history [file] - saved histories are in {}", paths::data_subdir("chat_history").display());
+    }
+    let path = history_path(file);
+    let contents = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let saved: SavedHistory = serde_json::from_str(&contents)
+        .with_context(|| format!("{} is not a saved zoey-rag history (only JSON files can be loaded)", path.display()))?;
+    if saved.version != HISTORY_FORMAT_VERSION {
+        anyhow::bail!(
+            "{} uses history format version {}, this build reads version {}",
+            path.display(),
+            saved.version,
+            HISTORY_FORMAT_VERSION
+        );
+    }
+
+    let entries: Vec<ChatEntry> = saved
+        .messages
+        .into_iter()
+        .map(|message| ChatEntry {
+            message: match message.role {
+                SavedRole::User => Message::user(message.content),
+                SavedRole::Assistant => Message::assistant(message.content),
+            },
+            at: message.timestamp,
+        })
+        .collect();
+    let count = entries.len();
+    *state.chat_history.lock() = entries;
+    Ok(count)
+}
+
+// Split command arguments on whitespace, keeping "quoted phrases" together
+fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+// Match a /compare argument to a loaded source by its number in /compare's list
+// or a case-insensitive part of its name
+fn resolve_source<'a>(arg: &str, sources: &'a [String]) -> Result<&'a String> {
+    if let Ok(n) = arg.parse::<usize>() {
+        if let Some(source) = n.checked_sub(1).and_then(|i| sources.get(i)) {
+            return Ok(source);
+        }
+    }
+    let needle = arg.to_lowercase();
+    if let Some(exact) = sources.iter().find(|s| s.to_lowercase() == needle) {
+        return Ok(exact);
+    }
+    let matches: Vec<&String> = sources.iter().filter(|s| s.to_lowercase().contains(&needle)).collect();
+    match matches.as_slice() {
+        [source] => Ok(source),
+        [] => Err(anyhow::anyhow!("No loaded source matches '{}'", arg)),
+        _ => Err(anyhow::anyhow!(
+            "'{}' matches several sources: {}",
+            arg,
+            matches.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+// Most relevant chunks of one source for the question, ranked by embedding distance when
+// the source has embeddings and by keyword overlap otherwise
+async fn retrieve_from_source(
+    storage: &StorageManager,
+    docs: &[common::storage::Document],
+    source: &str,
+    question: &str,
+) -> Result<Vec<common::storage::Document>> {
+    if !is_offline() && has_cohere_key() {
+        if let Some(store) = storage.get_store() {
+            let cohere_client = cohere_client()?;
+            let model = cohere_client.embedding_model(EMBED_ENGLISH_V3, "search_document");
+            let index = store.clone().index(model);
+            // Rank the whole store and keep this source's chunks so it can't be crowded out
+            let ranked = index.top_n::<common::storage::Document>(question, docs.len()).await?;
+            let chunks: Vec<_> = ranked
+                .into_iter()
+                .map(|(_, _, doc)| doc)
+                .filter(|doc| doc.source == source)
+                .take(COMPARE_CHUNKS_PER_SOURCE)
+                .collect();
+            if !chunks.is_empty() {
+                return Ok(chunks);
+            }
+        }
+    }
+
+    let terms = query_terms(question);
+    let mut scored: Vec<_> = docs
+        .iter()
+        .filter(|doc| doc.source == source)
+        .map(|doc| (keyword_score(&doc.content, &terms), doc))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(scored.into_iter().take(COMPARE_CHUNKS_PER_SOURCE).map(|(_, doc)| doc.clone()).collect())
+}
+
+// Contrast two loaded sources on a question, retrieving from each separately so both are represented
+async fn handle_compare_command(input: &str, state: &Arc<ChatState>, chat: &ChatInteraction) -> Result<()> {
+    let storage = state.storage.read().await;
+    let docs = storage.get_documents().await?;
+    let mut sources: Vec<String> = Vec::new();
+    for doc in &docs {
+        if !sources.contains(&doc.source) {
+            sources.push(doc.source.clone());
+        }
+    }
+
+    let args = split_args(input);
+    if args.len() < 3 {
+        println!("❌ Usage: /compare [sourceA] [sourceB] [question]");
+        println!("📌 Sources can be a number, part of the name, or a \"quoted name\"");
+        if sources.is_empty() {
+            println!("📭 No documents loaded yet - use /load or /search first");
+        } else {
+            println!("📚 Loaded sources:");
+            for (idx, source) in sources.iter().enumerate() {
+                println!("  {}. {}", idx + 1, source);
+            }
+        }
+        return Ok(());
+    }
+
+    let source_a = resolve_source(&args[0], &sources)?;
+    let source_b = resolve_source(&args[1], &sources)?;
+    if source_a == source_b {
+        return Err(anyhow::anyhow!("Both arguments refer to the same source: {}", source_a));
+    }
+    let question = args[2..].join(" ");
+    println!("⚖️ Comparing '{}' and '{}'...", source_a, source_b);
+
+    // Vector ranking always returns something, so "has matching content" means at least
+    // one retrieved chunk shares a term with the question
+    let terms = query_terms(&question);
+    let mut context = Vec::new();
+    let mut missing = Vec::new();
+    for (label, source) in [("A", source_a), ("B", source_b)] {
+        let chunks = retrieve_from_source(&storage, &docs, source, &question).await?;
+        let relevant = !terms.is_empty() && chunks.iter().any(|doc| keyword_score(&doc.content, &terms) > 0);
+        if chunks.is_empty() || !relevant {
+            println!("⚠️ '{}' has no content matching the question", source);
+            missing.push(format!("Document {} ({})", label, source));
+            context.push(format!("DOCUMENT {}: {}\nNO CONTENT MATCHING THE QUESTION WAS FOUND IN THIS DOCUMENT.", label, source));
+            continue;
+        }
+        for doc in chunks {
+            let excerpt = state.guard.excerpt(source, &doc.content);
+            context.push(format!("DOCUMENT {}: {}\n{}", label, source, excerpt));
+        }
+    }
+
+    let mut prompt = format!(
+        "Compare Document A ({}) and Document B ({}) on this question: {}\n\
+        Explain where they agree, where they differ, and what each covers that the other doesn't, \
+        citing which document each point comes from.",
+        source_a, source_b, question
+    );
+    if !missing.is_empty() {
+        prompt.push_str(&format!(
+            "\nIMPORTANT: {} has no content on this question. State that explicitly at the start \
+            instead of presenting a one-sided answer as a comparison.",
+            missing.join(" and ")
+        ));
+    }
+
+    let response = chat.chat_with_fallbacks(&storage, &prompt, Vec::new(), Some(&context)).await?;
+    println!("\nZoey: {}", response);
+
+    let mut history = state.chat_history.lock();
+    history.push(ChatEntry::new(Message::user(format!("/compare {}", input.trim()))));
+    history.push(ChatEntry::new(Message::assistant(response)));
+    Ok(())
+}
+
+fn limit_label(limit: usize) -> String {
+    if limit == 0 {
+        "off".to_string()
+    } else {
+        limit.to_string()
+    }
+}
+
+// Show or update the chunking settings used by future /load calls and the retrieval limit
+fn handle_config_command(input: &str, state: &Arc<ChatState>) {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    let mut config = state.chunk_config.lock();
+
+    match parts.as_slice() {
+        [] => {
+            println!("⚙️ Current settings:");
+            println!("  • chunksize: {}", config.chunk_size);
+            println!("  • overlap:   {}", config.overlap);
+            println!("  • concurrency: {}", config.load_concurrency);
+            println!("  • strategy:  {}", config.strategy.as_str());
+            println!("  • maxchunks: {}", limit_label(config.max_chunks_per_source));
+            println!("  • persource: {}", limit_label(config.retrieval_per_source));
+            println!("  • summary:   {}", limit_label(config.search_summary_sentences));
+            println!("  • highlights: {}", limit_label(config.search_highlights));
+            println!("  • recency:   {}", config.recency_weight);
+            println!("  • products:  {}", if config.products { "on" } else { "off" });
+            println!("  • embedretries: {}", config.embed_retries);
+            println!("  • bisect:    {}", config.bisect_depth);
+            match config.dedup_threshold {
+                Some(threshold) => println!("  • dedup:     {}", threshold),
+                None => println!("  • dedup:     off"),
+            }
+        }
+        [key, value] if *key == "dedup" => {
+            config.dedup_threshold = match value.parse::<f64>() {
+                _ if *value == "off" => None,
+                Ok(threshold) if threshold > 0.0 && threshold <= 1.0 => Some(threshold),
+                _ => {
+                    println!("❌ Invalid dedup threshold: {} - use off or a similarity above 0 up to 1 (1 = exact repeats only)", value);
+                    return;
+                }
+            };
+            println!("✅ dedup set to {} (applies to future /load and /search calls)", value);
+        }
+        [key, value] if *key == "products" => {
+            config.products = match *value {
+                "on" => true,
+                "off" => false,
+                _ => {
+                    println!("❌ Invalid value: {} - use on or off", value);
+                    return;
+                }
+            };
+            println!("✅ products set to {} (applies to future /load calls of web pages)", value);
+        }
+        [key, value] if *key == "recency" => {
+            let weight = match value.parse::<f64>() {
+                Ok(weight) if (0.0..=1.0).contains(&weight) => weight,
+                _ => {
+                    println!("❌ Invalid recency weight: {} - use a number from 0 (relevance only) to 1", value);
+                    return;
+                }
+            };
+            config.recency_weight = weight;
+            println!("✅ recency set to {} (applies to future questions)", weight);
+        }
+        [key, value] if *key == "strategy" => {
+            let Some(strategy) = ChunkStrategy::parse(value) else {
+                println!("❌ Unknown strategy: {} - use words or markdown", value);
+                return;
+            };
+            config.strategy = strategy;
+            println!("✅ strategy set to {} (applies to future /load calls)", strategy.as_str());
+        }
+        [key, value] => {
+            let Ok(value) = value.parse::<usize>() else {
+                println!("❌ Invalid number: {} - keeping the previous value", value);
+                return;
+            };
+
+            let updated = match *key {
+                "chunksize" => config.with_chunk_size(value),
+                "overlap" => config.with_overlap(value),
+                "concurrency" => config.with_load_concurrency(value),
+                "maxchunks" => Ok(ChunkConfig { max_chunks_per_source: value, ..*config }),
+                "persource" => Ok(ChunkConfig { retrieval_per_source: value, ..*config }),
+                "summary" => Ok(ChunkConfig {
+                    search_summary_sentences: value.min(MAX_SUMMARY_SENTENCES as usize),
+                    ..*config
+                }),
+                "embedretries" => Ok(ChunkConfig { embed_retries: value, ..*config }),
+                "bisect" => Ok(ChunkConfig { bisect_depth: value, ..*config }),
+                "highlights" => Ok(ChunkConfig {
+                    search_highlights: value.min(MAX_HIGHLIGHTS_PER_RESULT as usize),
+                    ..*config
+                }),
+                _ => {
+                    println!("❌ Unknown setting: {}", key);
+                    println!("📌 Available settings: chunksize, overlap, concurrency, strategy, maxchunks, persource, summary, highlights, recency, products, dedup, embedretries, bisect");
+                    return;
+                }
+            };
+
+            match updated {
+                Ok(new_config) => {
+                    *config = new_config;
+                    let (value, applies) = match *key {
+                        "persource" => (value, "future questions"),
+                        "summary" => (config.search_summary_sentences, "future /search calls"),
+                        "highlights" => (config.search_highlights, "future /search calls"),
+                        _ => (value, "future /load calls"),
+                    };
+                    println!("✅ {} set to {} (applies to {})", key, limit_label(value), applies);
+                }
+                Err(e) => println!("❌ {}", e),
+            }
+        }
+        _ => {
+            println!("❌ Usage:");
+            println!("  ⚙️ /config                - Show current settings");
+            println!("  ⚙️ /config chunksize [n]  - Set chunk size for future loads");
+            println!("  ⚙️ /config overlap [n]    - Set chunk overlap for future loads");
+            println!("  ⚙️ /config concurrency [n] - Set how many documents load at once");
+            println!("  ⚙️ /config strategy [words|markdown] - Split by word count or at markdown headings");
+            println!("  ⚙️ /config maxchunks [n]  - Store at most n chunks per document (0 = all)");
+            println!("  ⚙️ /config persource [n]  - Retrieve at most n chunks per source when others match (0 = off)");
+            println!("  ⚙️ /config summary [n]    - Summary sentences per /search result (0 = none, max {})", MAX_SUMMARY_SENTENCES);
+            println!("  ⚙️ /config highlights [n] - Highlights per /search result (0 = none, max {})", MAX_HIGHLIGHTS_PER_RESULT);
+            println!("  ⚙️ /config recency [0-1]  - Weight recently stored chunks over relevance (0 = relevance only)");
+            println!("  ⚙️ /config products [on|off] - Store each product on a web page as one chunk with its name, price and description");
+            println!("  ⚙️ /config embedretries [n] - Retry a failed embedding batch n times (default {})", DEFAULT_EMBED_RETRIES);
+            println!("  ⚙️ /config bisect [n] - Then split it up to n times to skip the chunks that fail (default {}, 0 = fail the batch)", DEFAULT_BISECT_DEPTH);
+            println!("  ⚙️ /config dedup [off|0-1] - Skip chunks at least this similar to one already queued (default {})", near_dup::DEFAULT_THRESHOLD);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run().await {
+        exit_with_error(&*e);
+    }
+}
+
+// Update main to call setup_documents_dir at startup
+async fn run() -> Result<()> {
     // Add command line argument for persistence mode
     let args: Vec<String> = std::env::args().collect();
-    let persistent = !args.contains(&"--fresh".to_string());
+    let fresh = args.contains(&"--fresh".to_string());
+    let db_path = parse_db_path(&args, fresh);
+    let offline = args.contains(&"--offline".to_string());
     
     // Check if --rig-cli argument is provided before initializing tracing
     let is_rig_cli = args.contains(&"--rig-cli".to_string());
@@ -905,23 +3210,30 @@ async fn main() -> Result<()> {
     }
     
     // Initialize the sqlite-vec extension
-    unsafe {
-        sqlite3_auto_extension(Some(std::mem::transmute(sqlite3_vec_init as *const ())));
-    }
+    storage::init_sqlite_vec()?;
 
     // Replace Mistral environment check with OpenRouter
     let openrouter_key = std::env::var("OPENROUTER_API_KEY")
         .context("OPENROUTER_API_KEY environment variable not set")?;
-    let _cohere_key = std::env::var("COHERE_API_KEY")
-        .context("COHERE_API_KEY environment variable not set")?;
+    let embeddings = !offline && has_cohere_key();
 
     // Create state with chosen persistence mode
-    let state = Arc::new(ChatState::new_with_mode(persistent).await?);
+    let metric = parse_metric(&args)?;
+    let retention = parse_image_retention(&args)?;
+    let guard = parse_guard_mode(&args)?;
+    let fallback = match parse_embedding_fallback(&args)? {
+        Some((provider, model)) if !offline => match FallbackEmbedding::connect(&provider, model).await {
+            Some(model) => Some(FallbackStore::open(model, db_path.as_deref(), fresh, metric).await?),
+            None => None,
+        },
+        _ => None,
+    };
+    let state = Arc::new(ChatState::new_with_mode(db_path.as_deref(), fresh, metric, retention, guard, fallback).await?);
     
-    // Initialize the store with embedding model
-    {
+    // Initialize the store with embedding model (skipped without Cohere, since it embeds stored documents)
+    if embeddings {
         let mut storage = state.storage.write().await;
-        let cohere_client = cohere::Client::from_env();
+        let cohere_client = cohere_client()?;
         let model = cohere_client.embedding_model(EMBED_ENGLISH_V3, "search_document");
         storage.initialize_store(model).await?;
     }
@@ -930,26 +3242,58 @@ async fn main() -> Result<()> {
     let openrouter_client = Client::new(&openrouter_key);
 
     // Create chat interaction handler with OpenRouter
-    let chat = ChatInteraction::new(state.clone(), openrouter_client);
+    let models = parse_model_chain(&args);
+    if models.len() > 1 {
+        info!("Model fallback chain: {}", models.join(" -> "));
+    }
+    let confirm = parse_confirm_mode(&args);
+    let empty_response = parse_empty_response(&args)?;
+    let chat = ChatInteraction::new(state.clone(), openrouter_client, models, confirm, empty_response);
 
     // Setup documents directory with sample file if needed
     setup_documents_dir().await?;
 
     println!("🤖 Welcome to Zoey - Your AI Research Assistant! 🌟");
-    if persistent {
-        println!("📚 Running in persistent mode - documents will be saved between sessions");
-    } else {
-        println!("🔄 Running in fresh mode - starting with clean slate each session");
+    match (&db_path, fresh) {
+        (Some(path), false) => println!("📚 Running in persistent mode - documents are saved in {}", path.display()),
+        (Some(path), true) => println!("🔄 Running in fresh mode - cleared {}, documents are saved there", path.display()),
+        (None, _) => println!("🔄 Running in fresh mode - documents are kept in memory for this session only"),
+    }
+    if offline {
+        println!("📴 Running in offline mode - /search and URL loading are disabled");
+    } else if !embeddings {
+        println!("🔑 COHERE_API_KEY is not set - chat works, but /load and /search are disabled until it is");
+    }
+    if !offline && std::env::var("EXA_API_KEY").is_err() {
+        println!("🔑 EXA_API_KEY is not set - /search is disabled");
+    }
+    println!("📐 Ranking documents by {} distance", metric);
+    if let Some(fallback) = &state.fallback {
+        println!(
+            "↪️ If Cohere embedding fails, /load falls back to {} (separate store: {})",
+            fallback.model.model_id(),
+            fallback.path
+        );
+    }
+    if guard == GuardMode::Off {
+        println!("⚠️ Prompt-injection guard is off - loaded documents can steer the assistant");
     }
     println!("\nCommands:");
-    println!("  📚 /load [file1] [file2]...  - Load and analyze documents or web pages");
-    println!("  🔄 /clear                    - Clear loaded documents , web pages and start fresh");
+    println!("  📚 /load [file1] [file2]...  - Load and analyze documents, folders or web pages");
+    println!("  💰 /preview [file1]...       - Chunk without loading and estimate the embedding cost");
+    println!("  🔄 /clear                    - Clear loaded documents , web pages and start fresh (asks first, --yes skips)");
+    println!("  ⚙️ /config [setting] [value] - Show or change chunking, per-source limits and search results");
+    println!("  ⚖️ /compare [a] [b] [question] - Contrast two loaded sources on a question");
+    println!("  🔁 /retry                    - Re-run the last failed /load or /search");
+    println!("  🖼️ /clear_images             - Delete downloaded images in zoey_images");
     println!("  💭 /history                  - Show conversation history");
     println!("  🗑️ /clear_history            - Clear conversation history");
+    println!("  💾 /save_history [file]      - Save the conversation as JSON, or Markdown for a .md file");
+    println!("  📂 /load_history [file]      - Continue a conversation saved as JSON");
     println!("  👋 /exit                     - Say goodbye and quit");
     println!("  🔍 Search Commands:");
-    println!("    • /search [type] [query]              - Search for different types of content");
-    println!("    • /search site [domain] [query] - Search specific website");
+    println!("    • /search [type] [n] [query]          - Search for different types of content, n results");
+    println!("    • /search site [domain] [n] [query] - Search specific website");
     println!("\nI can help you analyze documents , web pages and chat about anything! Let's get started! 😊\n");
 
     // Check if --rig-cli argument is provided
@@ -958,8 +3302,9 @@ async fn main() -> Result<()> {
         rig::cli_chatbot::cli_chatbot(chat).await?;
     } else {
         // Use original CLI implementation
+        let mut stdin = BufReader::new(io::stdin()).lines();
         loop {
-            let input = read_user_input().await?;
+            let input = read_user_input(&mut stdin).await?;
             
             if input.trim() == "/exit" {
                 break;
@@ -968,7 +3313,7 @@ async fn main() -> Result<()> {
             if input.trim() == "/history" {
                 let history = state.chat_history.lock();
                 println!("\n📜 Conversation History:");
-                for msg in history.iter() {
+                for msg in history.iter().map(|e| &e.message) {
                     match msg {
                         Message::User { content } => {
                             if let Some(UserContent::Text(text)) = content.iter().next() {
@@ -988,95 +3333,99 @@ async fn main() -> Result<()> {
             if input.trim() == "/clear_history" {
                 let mut history = state.chat_history.lock();
                 history.clear();
-                history.push(Message::assistant(
-                    "Hi! I'm Zoey, your AI assistant. How can I help you today?"
-                ));
+                history.push(ChatEntry::new(Message::assistant(GREETING)));
                 println!("🧹 Chat history cleared!");
                 continue;
             }
-            
-            if let Some(input) = input.strip_prefix("/search") {
-                let parts: Vec<&str> = input.trim().split_whitespace().collect();
-                if parts.len() < 2 {
-                    println!("❌ Usage:");
-                    println!("  🔍 /search [query]              - Basic web search");
-                    println!("  📄 /search pdf [query]          - Search for PDFs");
-                    println!("  🖼️ /search images [query]       - Search and download images");
-                    println!("  📰 /search news [query]         - Search news articles");
-                    println!("  🔬 /search research [query]     - Search research content");
-                    println!("  🌐 /search site [domain] [query] - Search specific website");
-                    continue;
-                }
 
-                let (search_type, query, domains) = match parts[1] {
-                    "pdf" => ("pdf", parts[2..].join(" "), None),
-                    "images" => ("images", parts[2..].join(" "), None),
-                    "news" => ("news", parts[2..].join(" "), None),
-                    "research" => ("research", parts[2..].join(" "), None),
-                    "site" => {
-                        if parts.len() < 4 {
-                            println!("❌ Usage: /search site [domain] [query]");
-                            continue;
-                        }
-                        ("site", parts[3..].join(" "), Some(vec![parts[2].to_string()]))
-                    },
-                    _ => ("web", parts[1..].join(" "), None),
-                };
+            if let Some(input) = input.strip_prefix("/save_history") {
+                match save_history(&state, input.trim()) {
+                    Ok(path) => println!("💾 Conversation saved to {}", path.display()),
+                    Err(e) => println!("❌ Error saving history: {:#}", e),
+                }
+                continue;
+            }
 
-                println!("🔍 Performing {} search for: {}", search_type, query);
-                
-                match search_with_exa(&query, 5, search_type, domains).await {
-                    Ok(results) => {
-                        println!("📊 Found {} results", results.len());
-                        
-                        let chunks = results.iter()
-                            .map(|r| vec![r.clone()])
-                            .collect::<Vec<_>>();
-                        
-                        let sources: Vec<String> = results.iter().enumerate()
-                            .map(|(idx, content)| {
-                                let title = content.lines()
-                                    .find(|line| line.starts_with("Title:"))
-                                    .unwrap_or("Untitled")
-                                    .trim_start_matches("Title: ");
-                                format!("Search Result #{} - {}", idx + 1, title)
-                            })
-                            .collect();
-                        
-                        // Process and store documents
-                        process_new_documents(
-                            &state,
-                            chunks,
-                            &sources,
-                            &cohere::Client::from_env()
-                        ).await?;
-                        
-                        println!("\n✅ All {} search results have been loaded into my knowledge base!", results.len());
-                        if search_type == "images" {
-                            println!("🖼️ Images have been downloaded to the zoey_images directory!");
-                        }
-                        println!("💡 You can now ask me questions about any of the results!");
-                        println!("   For example:");
-                        println!("   - Can you summarize all the search results?");
-                        println!("   - What are the main points from each source?");
-                        println!("   - Compare the information from different sources.");
-                    }
+            if let Some(input) = input.strip_prefix("/load_history") {
+                match load_history(&state, input.trim()) {
+                    Ok(count) => println!("📂 Loaded {} message(s) - the conversation continues from there", count),
+                    Err(e) => println!("❌ Error loading history: {:#}", e),
+                }
+                continue;
+            }
+            
+            if let Some(input) = input.strip_prefix("/search") {
+                let search = match parse_search_command(input) {
+                    Ok(search) => search,
                     Err(e) => {
-                        println!("❌ Search failed: {}", e);
+                        println!("❌ {}", e);
+                        println!("❌ Usage (n is optional, 1-{} results):", MAX_NUM_RESULTS);
+                        println!("  🔍 /search [n] [query]              - Basic web search");
+                        println!("  📄 /search pdf [n] [query]          - Search for PDFs");
+                        println!("  🖼️ /search images [n] [query]       - Search and download images");
+                        println!("  📰 /search news [n] [query]         - Search news articles");
+                        println!("  🔬 /search research [n] [query]     - Search research content");
+                        println!("  🌐 /search site [domain] [n] [query] - Search specific website");
+                        continue;
                     }
+                };
+
+                if let Err(e) = run_search(
+                    &state,
+                    &search.query,
+                    search.search_type,
+                    search.num_results,
+                    search.domains,
+                ).await {
+                    println!("❌ Error storing search results: {}", e);
+                }
+                continue;
+            }
+
+            if input.trim() == "/retry" {
+                if let Err(e) = handle_retry_command(&state).await {
+                    println!("❌ Retry failed: {}", e);
+                }
+                continue;
+            }
+            
+            if input.trim() == "/clear_images" {
+                match state.images.clear() {
+                    Ok(removed) => println!("🧹 Deleted {} image(s) from zoey_images", removed),
+                    Err(e) => println!("❌ Error clearing images: {}", e),
                 }
                 continue;
             }
             
             if input.trim() == "/clear" {
-                let storage = state.storage.write().await;
-                storage.clear_documents().await?;
-                println!("🧹 Memory cleared! I'm ready for new conversations or documents.");
+                match handle_clear_command(&state, confirm, Some(&mut stdin)).await {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => println!("❌ Error clearing the knowledge base: {}", e),
+                }
+                continue;
+            }
+
+            if let Some(input) = input.strip_prefix("/config") {
+                handle_config_command(input, &state);
+                continue;
+            }
+
+            if let Some(input) = input.strip_prefix("/compare") {
+                if let Err(e) = handle_compare_command(input, &state, &chat).await {
+                    println!("❌ Error comparing documents: {}", e);
+                }
+                continue;
+            }
+
+            if let Some(input) = input.strip_prefix("/preview") {
+                if let Err(e) = handle_preview_command(input, &state).await {
+                    println!("❌ Error previewing documents: {}", e);
+                }
                 continue;
             }
 
             if let Some(input) = input.strip_prefix("/load") {
-                if let Err(e) = handle_load_command(input, &state, &cohere::Client::from_env()).await {
+                if let Err(e) = handle_load_command(input, &state).await {
                     println!("❌ Error loading documents: {}", e);
                 }
                 continue;
@@ -1091,3 +3440,233 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breadcrumbs(text: &str) -> Vec<Vec<String>> {
+        markdown_sections(text).into_iter().map(|section| section.breadcrumb).collect()
+    }
+
+    #[test]
+    fn hashes_in_code_fences_are_not_headings() {
+        let text = "# Setup\n\n```bash\n# install the cli\ncargo install zoey\n```\n\n## Usage\n\nRun it.";
+        assert_eq!(breadcrumbs(text), [vec!["Setup"], vec!["Setup", "Usage"]]);
+
+        let chunks = chunk_markdown(&[text.to_string()], 1000, 0).unwrap();
+        assert!(chunks[0].contains("```bash\n# install the cli\ncargo install zoey\n```"));
+    }
+
+    #[test]
+    fn setext_underlines_are_headings() {
+        let text = "Intro\n\nGuide\n=====\n\nSome text.\n\nInstall\n-------\n\nSteps.\n\n---\n\nAfter a rule.";
+        assert_eq!(breadcrumbs(text), [vec![], vec!["Guide"], vec!["Guide", "Install"]]);
+
+        let sections = markdown_sections(text);
+        assert_eq!(sections[1].blocks[0].text, "Guide\n=====");
+        assert!(sections[2].blocks.iter().any(|block| block.text == "---"));
+    }
+
+    #[test]
+    fn oversized_sections_split_with_overlap() {
+        let words: Vec<String> = (0..40).map(|i| format!("word{:02}", i)).collect();
+        let code = format!("```\n{}\n```", "x".repeat(80));
+        let text = format!("# Guide\n\nintro words\n\n{}\n\n{}", words.join(" "), code);
+        let prefix = "SECTION: Guide\n\n";
+        let chunks = chunk_markdown(&[text], 60, 10).unwrap();
+
+        assert!(chunks.iter().all(|chunk| chunk.starts_with(prefix)));
+        assert_eq!(chunks[0], format!("{}# Guide\n\nintro words", prefix));
+        // A code block larger than a chunk is kept whole
+        assert_eq!(chunks.last().unwrap(), &format!("{}{}", prefix, code));
+
+        let parts: Vec<&str> = chunks[1..chunks.len() - 1].iter().map(|chunk| &chunk[prefix.len()..]).collect();
+        assert!(parts.len() > 1 && parts.iter().all(|part| part.len() <= 60 - prefix.len()));
+        for pair in parts.windows(2) {
+            let carried = overlap_tail(pair[0], 10);
+            assert!(!carried.is_empty() && pair[1].starts_with(carried.trim_end()));
+        }
+        for word in &words {
+            assert!(parts.iter().any(|part| part.contains(word.as_str())));
+        }
+    }
+
+    #[test]
+    fn search_counts_only_take_a_valid_leading_number() {
+        let search = parse_search_command(" news 3 solana etf").unwrap();
+        assert_eq!((search.search_type, search.num_results, search.query.as_str()), ("news", 3, "solana etf"));
+
+        // Without a type, out of range or the only word, it's part of the query
+        for (input, query) in [(" 2024 halving", "2024 halving"), (" 0 day exploits", "0 day exploits"), (" 42", "42")] {
+            let search = parse_search_command(input).unwrap();
+            assert_eq!((search.num_results, search.query.as_str()), (default_num_results("web"), query));
+        }
+
+        // After a type, it can only be a count
+        for input in [" news 0 solana etf", " images 500 cats", " research 2.5 papers", " site coindesk.com 0 bitcoin"] {
+            assert!(parse_search_command(input).is_err(), "{}", input);
+        }
+        assert_eq!(parse_search_command(" news 42").unwrap().query, "42");
+    }
+
+    #[test]
+    fn site_searches_need_a_domain_first() {
+        let search = parse_search_command(" site coindesk.com 2 bitcoin").unwrap();
+        assert_eq!(search.domains, Some(vec!["coindesk.com".to_string()]));
+        assert_eq!((search.num_results, search.query.as_str()), (2, "bitcoin"));
+
+        assert!(parse_search_command(" site 2 coindesk.com bitcoin").is_err());
+        assert!(parse_search_command(" site coindesk.com").is_err());
+        assert!(parse_search_command("").is_err());
+    }
+
+    const PRODUCT_LISTING: &str = r#"<html><body>
+        <h1>Winter sale on hardware wallets this week</h1>
+        <div class="product-card"><h3>Ledger Nano X</h3><span class="price">$149</span>
+            <p class="description">Bluetooth hardware wallet</p></div>
+        <div class="product-card" itemscope itemtype="https://schema.org/Product">
+            <h3 itemprop="name">Trezor Safe 3</h3><meta itemprop="price" content="79.00"></div>
+    </body></html>"#;
+
+    const ARTICLE_WITH_WIDGET: &str = r#"<html><body>
+        <article><p>Bitcoin fees dropped sharply after the latest halving as miners adjusted.</p></article>
+        <div class="product"><h3>Ledger Nano X</h3><span class="price">$149</span></div>
+    </body></html>"#;
+
+    #[test]
+    fn product_listings_keep_the_page_text() {
+        let page = extract_content(&scraper::Html::parse_document(PRODUCT_LISTING), 1, true).unwrap();
+        assert_eq!(
+            page.products,
+            [
+                "PRODUCT\nName: Ledger Nano X\nPrice: $149\nDescription: Bluetooth hardware wallet",
+                "PRODUCT\nName: Trezor Safe 3\nPrice: 79.00",
+            ]
+        );
+        assert!(page.texts.iter().any(|text| text.contains("Winter sale on hardware wallets")));
+    }
+
+    #[test]
+    fn a_single_product_is_not_a_listing() {
+        let document = scraper::Html::parse_document(ARTICLE_WITH_WIDGET);
+        let page = extract_content(&document, 1, true).unwrap();
+        assert!(page.products.is_empty());
+        assert!(page.texts.iter().any(|text| text.contains("Bitcoin fees dropped sharply")));
+        assert_eq!(extract_products(&document).len(), 1);
+    }
+
+    #[test]
+    fn configured_prices_override_and_extend_the_list() {
+        let configured = "embed-english-v3.0=0.12, my-model = 0.5,broken=abc,no-equals";
+
+        assert_eq!(price_for(configured, "embed-english-v3.0"), Some(0.12));
+        assert_eq!(price_for(configured, "my-model"), Some(0.5));
+        assert_eq!(price_for(configured, "embed-multilingual-v3.0"), Some(0.10));
+        assert_eq!(price_for("", "embed-english-light-v3.0"), Some(0.10));
+        assert_eq!(price_for(configured, "broken"), None);
+        assert_eq!(price_for(configured, "no-equals"), None);
+    }
+
+    fn stored(source: &str, days_old: i64, now: DateTime<Utc>) -> common::storage::Document {
+        common::storage::Document {
+            id: source.to_string(),
+            source: source.to_string(),
+            timestamp: (now - chrono::Duration::days(days_old)).to_rfc3339(),
+            content: String::new(),
+        }
+    }
+
+    fn sources(docs: &[common::storage::Document]) -> Vec<&str> {
+        docs.iter().map(|doc| doc.source.as_str()).collect()
+    }
+
+    #[test]
+    fn recency_weight_lets_fresh_chunks_overtake() {
+        let now = Utc::now();
+        // The month-old chunk is the closer match
+        let ranked = || {
+            vec![
+                (0.1, "old".to_string(), stored("old", 30, now)),
+                (0.2, "new".to_string(), stored("new", 0, now)),
+            ]
+        };
+
+        assert_eq!(sources(&order_by_recency(ranked(), 0.0, now)), ["old", "new"]);
+        assert_eq!(sources(&order_by_recency(ranked(), 0.5, now)), ["old", "new"]);
+        assert_eq!(sources(&order_by_recency(ranked(), 0.8, now)), ["new", "old"]);
+        // Weights above 1 rank on recency alone
+        assert_eq!(sources(&order_by_recency(ranked(), 5.0, now)), ["new", "old"]);
+    }
+
+    #[test]
+    fn chunks_without_a_timestamp_rank_on_relevance() {
+        let now = Utc::now();
+        let mut undated = stored("undated", 0, now);
+        undated.timestamp = "2024-13-45 25:00".to_string();
+        let ranked = vec![
+            (0.1, "old".to_string(), stored("old", 30, now)),
+            (0.2, "undated".to_string(), undated),
+            (0.3, "new".to_string(), stored("new", 0, now)),
+        ];
+
+        // Scores: new 0.9 (recency), undated 0.5 (its relevance), old ~0.15
+        assert_eq!(sources(&order_by_recency(ranked, 0.9, now)), ["new", "undated", "old"]);
+    }
+
+    fn gemini_fallback(name: &str) -> FallbackEmbedding {
+        FallbackEmbedding::Gemini {
+            model: gemini::Client::new("test").embedding_model(name),
+            name: name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_fallback_providers_are_disabled() {
+        assert!(FallbackEmbedding::connect("openai", None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn fallback_chunks_never_reach_the_main_store() {
+        storage::init_sqlite_vec().unwrap();
+        let dir = std::env::temp_dir().join(format!("zoey_fallback_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let main_path = dir.join("zoey.db");
+
+        let fallback = FallbackStore::open(gemini_fallback(EMBEDDING_004), Some(&main_path), false, DistanceMetric::L2)
+            .await
+            .unwrap();
+        let other = FallbackStore::open(gemini_fallback("embedding-001"), Some(&main_path), false, DistanceMetric::L2)
+            .await
+            .unwrap();
+        // One database per fallback model, none of them the main one
+        assert!(fallback.path.ends_with("zoey.gemini-text-embedding-004.db"), "{}", fallback.path);
+        assert_ne!(fallback.path, other.path);
+        assert_ne!(fallback.path, main_path.display().to_string());
+
+        fallback.storage.read().await.add_document("notes.md", "embedded by the fallback").await.unwrap();
+
+        let main = StorageManager::<FallbackEmbedding>::new_with_mode(Some(&main_path)).await.unwrap();
+        main.initialize_tables().await.unwrap();
+        assert_eq!(main.document_counts().await.unwrap(), (0, 0));
+        assert_eq!(other.storage.read().await.document_counts().await.unwrap(), (0, 0));
+        assert_eq!(fallback.storage.read().await.document_counts().await.unwrap(), (1, 1));
+
+        drop((fallback, other, main));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn flags_take_a_separate_or_inline_value() {
+        let args: Vec<String> = ["zoey-rag", "--metric", "cosine", "--db-path=notes.db", "--max-images"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        assert_eq!(flag_value(&args, "--metric").as_deref(), Some("cosine"));
+        assert_eq!(flag_value(&args, "--db-path").as_deref(), Some("notes.db"));
+        assert_eq!(flag_value(&args, "--max-images"), None);
+        assert_eq!(flag_value(&args, "--max-images-mb"), None);
+        assert_eq!(parse_metric(&args).unwrap(), DistanceMetric::Cosine);
+    }
+}
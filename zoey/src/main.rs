@@ -1,10 +1,18 @@
-use clap::{command, Parser};
+use clap::{command, Parser, ValueEnum};
 use rig::{
+    completion::CompletionModel,
+    embeddings::EmbeddingModel,
+    message::Text,
     providers::{
-        deepseek::{self },
+        cohere::{self, EMBED_ENGLISH_V3},
+        deepseek::{self, DEEPSEEK_CHAT},
         gemini::{self as gemini, EMBEDDING_004, GEMINI_2_0_FLASH},
+        openai,
     },
 };
+use common::exit::{exit_with_error, AppError};
+use common::providers::{GraniteEmbedding, GRANITE_EMBEDDING_278M};
+use common::storage::init_sqlite_vec;
 use zoey_core::attention::{Attention, AttentionConfig};
 use zoey_core::character;
 use zoey_core::init_logging;
@@ -12,10 +20,8 @@ use zoey_core::knowledge::KnowledgeBase;
 use zoey_core::{agent::Agent, clients::twitter::TwitterClient};
 use zoey_core::config::TwitterConfig;
 use zoey_core::interaction_history::InteractionHistory;
-use sqlite_vec::sqlite3_vec_init;
-use tokio_rusqlite::ffi::sqlite3_auto_extension;
 use tokio_rusqlite::Connection;
-use tracing::{error, debug, info};
+use tracing::{error, debug, info, warn};
 use serde_json;
 
 #[derive(Parser)]
@@ -29,6 +35,10 @@ struct Args {
     #[arg(long, default_value = "zoey/src/characters/zoey.toml")]
     character: String,
 
+    /// Character TOML applied on top of --character; its fields replace the base's
+    #[arg(long)]
+    character_overlay: Option<String>,
+
     /// Path to database
     #[arg(long, default_value = "zoey2.db")]
     db_path: String,
@@ -66,35 +76,155 @@ struct Args {
 
     #[arg(long, env = "TWITTER_CONFIG_PATH")]
     twitter_config_path: Option<String>,
+
+    /// Provider for the should-respond model, defaults to the main model's provider (gemini)
+    #[arg(long, env = "ATTENTION_PROVIDER", value_enum)]
+    attention_provider: Option<AttentionProvider>,
+
+    /// Model for should-respond decisions, defaults to the main model
+    #[arg(long, env = "ATTENTION_MODEL")]
+    attention_model: Option<String>,
+
+    /// Ollama's OpenAI-compatible endpoint, for --attention-provider ollama
+    #[arg(long, env = "OLLAMA_URL", default_value = DEFAULT_OLLAMA_URL)]
+    ollama_url: String,
+
+    /// Embedding provider for the knowledge base; granite runs on a local Ollama
+    #[arg(long, env = "EMBEDDING_PROVIDER", value_enum, default_value = "gemini")]
+    embedding_provider: EmbeddingProvider,
+
+    /// Embedding model, defaults to the provider's standard model. An existing database
+    /// only works with the model it was built with.
+    #[arg(long, env = "EMBEDDING_MODEL")]
+    embedding_model: Option<String>,
+
+    /// Seed for the bot's random task choices and delays, so a run can be replayed.
+    /// Unset means truly random.
+    #[arg(long, env = "ZOEY_RNG_SEED")]
+    rng_seed: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum EmbeddingProvider {
+    Gemini,
+    Cohere,
+    Granite,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum AttentionProvider {
+    Gemini,
+    Deepseek,
+    /// A local model served by Ollama, no per-call cost
+    Ollama,
+}
+
+const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434/v1";
+// Small enough to answer should-respond prompts quickly on a CPU
+const DEFAULT_OLLAMA_ATTENTION_MODEL: &str = "llama3.2:1b";
+
+type MainAgent<E> = Agent<gemini::completion::CompletionModel, E>;
+
+// Send one tiny request so a bad model name or key fails at startup, not on the first mention
+async fn check_attention_model<M: CompletionModel>(model: &M, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    info!(model = name, "Checking attention model");
+    let request = model
+        .completion_request(Text::from("Reply with OK".to_string()))
+        .max_tokens(5)
+        .build();
+    model
+        .completion(request)
+        .await
+        .map_err(|e| format!("Attention model {} is not usable: {}", name, e))?;
+    Ok(())
+}
+
+fn load_character(path: &str, overlay: Option<&str>) -> Result<character::Character, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| match overlay {
+        Some(overlay) => AppError::file(&format!("base character for overlay {}", overlay), path, e),
+        None => AppError::file("character file", path, e),
+    })?;
+
+    let Some(overlay) = overlay else {
+        return character::Character::from_toml(&content)
+            .map_err(|e| AppError::Config(format!("Failed to parse character TOML {}: {}", path, e)));
+    };
+    let overlay_content = std::fs::read_to_string(overlay)
+        .map_err(|e| AppError::file("character overlay", overlay, e))?;
+    let character = character::Character::from_toml_with_overlay(&content, &overlay_content).map_err(|e| {
+        AppError::Config(format!("Failed to merge character overlay {} onto {}: {}", overlay, path, e))
+    })?;
+    info!(base = path, overlay, "Loaded character with overlay");
+    Ok(character)
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() {
+    if let Err(e) = run().await {
+        exit_with_error(&*e);
+    }
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
     init_logging();
     dotenv::dotenv().ok();
 
     let args = Args::parse();
 
-    let character_content =
-        std::fs::read_to_string(&args.character).expect("Failed to read character file");
-    
-    let character: character::Character = toml::from_str(&character_content)
-        .map_err(|e| format!("Failed to parse character TOML: {}\nContent: {}", e, character_content))?;
+    if let Some(seed) = args.rng_seed {
+        zoey_core::rng::seed(seed);
+        info!(seed, "Using seeded RNG");
+    }
 
-    let _deepseek_client = deepseek::Client::new(&args.deepseek_api_key);
+    let character = load_character(&args.character, args.character_overlay.as_deref())?;
+
+    let deepseek_client = deepseek::Client::new(&args.deepseek_api_key);
     let gemini_client = gemini::Client::new(&args.gemini_api_key);
 
-    let embedding_model = gemini_client.embedding_model(EMBEDDING_004);
-    let completion_model = gemini_client.completion_model(GEMINI_2_0_FLASH);
-    let should_respond_completion_model = gemini_client.completion_model(GEMINI_2_0_FLASH);
+    match args.embedding_provider {
+        EmbeddingProvider::Gemini => {
+            let name = args.embedding_model.clone().unwrap_or_else(|| EMBEDDING_004.to_string());
+            let model = gemini_client.embedding_model(&name);
+            start(args, character, gemini_client, deepseek_client, model, format!("gemini/{}", name)).await
+        }
+        EmbeddingProvider::Cohere => {
+            let api_key = std::env::var("COHERE_API_KEY")
+                .map_err(|_| AppError::Config("COHERE_API_KEY must be set for --embedding-provider cohere".to_string()))?;
+            let name = args.embedding_model.clone().unwrap_or_else(|| EMBED_ENGLISH_V3.to_string());
+            let model = cohere::Client::new(&api_key).embedding_model(&name, "search_document");
+            start(args, character, gemini_client, deepseek_client, model, format!("cohere/{}", name)).await
+        }
+        EmbeddingProvider::Granite => {
+            let name = args.embedding_model.clone().unwrap_or_else(|| GRANITE_EMBEDDING_278M.to_string());
+            let model = GraniteEmbedding::detect(&name)
+                .await
+                .map_err(|e| format!("Embedding model {} is not available from Ollama: {}", name, e))?;
+            start(args, character, gemini_client, deepseek_client, model, format!("granite/{}", name)).await
+        }
+    }
+}
 
-    unsafe {
-        sqlite3_auto_extension(Some(std::mem::transmute(sqlite3_vec_init as *const ())));
+async fn start<E: EmbeddingModel + 'static>(
+    args: Args,
+    character: character::Character,
+    gemini_client: gemini::Client,
+    deepseek_client: deepseek::Client,
+    embedding_model: E,
+    embedding_model_id: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if embedding_model.ndims() == 0 {
+        return Err(AppError::Config(format!("Unknown dimensions for embedding model {}", embedding_model_id)).into());
     }
+    info!(model = %embedding_model_id, dims = embedding_model.ndims(), "Using embedding model");
+
+    let completion_model = gemini_client.completion_model(GEMINI_2_0_FLASH);
+
+    init_sqlite_vec()?;
 
-    let conn = Connection::open(args.db_path).await?;
+    let conn = Connection::open(&args.db_path).await?;
     
     let knowledge = KnowledgeBase::new(conn.clone(), embedding_model).await?;
+    knowledge.check_embedding_model(&embedding_model_id).await?;
     let interaction_history = InteractionHistory::new(conn.clone()).await?;
 
     let agent = Agent::new(
@@ -102,37 +232,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         completion_model, 
         knowledge,
         interaction_history,
-    );
+    )
+    .with_model_name(GEMINI_2_0_FLASH);
 
     let config = AttentionConfig {
         bot_names: vec![agent.character.name.clone()],
+        persona: agent.character.style.adjectives.join(", "),
+        prompts: agent.character.attention_prompts.clone(),
+        veto: agent.character.attention_veto.clone(),
         ..Default::default()
     };
-    let attention = Attention::new(config, should_respond_completion_model);
 
+    // Without an explicit choice the should-respond model is the main model, unchecked as before
+    let separate_attention_model = args.attention_provider.is_some() || args.attention_model.is_some();
+    match args.attention_provider.unwrap_or(AttentionProvider::Gemini) {
+        AttentionProvider::Gemini => {
+            let name = args.attention_model.clone().unwrap_or_else(|| GEMINI_2_0_FLASH.to_string());
+            let model = gemini_client.completion_model(&name);
+            if separate_attention_model {
+                check_attention_model(&model, &name).await?;
+            }
+            info!(provider = "gemini", model = %name, "Using attention model");
+            run_clients(args, agent, Attention::new(config, model)).await
+        }
+        AttentionProvider::Deepseek => {
+            let name = args.attention_model.clone().unwrap_or_else(|| DEEPSEEK_CHAT.to_string());
+            let model = deepseek_client.completion_model(&name);
+            check_attention_model(&model, &name).await?;
+            info!(provider = "deepseek", model = %name, "Using attention model");
+            run_clients(args, agent, Attention::new(config, model)).await
+        }
+        AttentionProvider::Ollama => {
+            let name = args.attention_model.clone().unwrap_or_else(|| DEFAULT_OLLAMA_ATTENTION_MODEL.to_string());
+            // Ollama ignores the API key
+            let model = openai::Client::from_url("ollama", &args.ollama_url).completion_model(&name);
+            check_attention_model(&model, &name).await?;
+            info!(provider = "ollama", model = %name, url = %args.ollama_url, "Using attention model");
+            run_clients(args, agent, Attention::new(config, model)).await
+        }
+    }
+}
+
+async fn run_clients<E: EmbeddingModel + 'static, A: CompletionModel + 'static>(
+    args: Args,
+    agent: MainAgent<E>,
+    attention: Attention<A>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let clients = args.clients.split(',').collect::<Vec<&str>>();
     let mut handles = vec![];
 
     let twitter_config = if let Some(path) = args.twitter_config_path {
         debug!("Loading custom config from: {}", path);
-        match std::fs::read_to_string(path) {
-            Ok(config_str) => {
-                match serde_json::from_str(&config_str) {
-                    Ok(config) => {
-                        debug!("Successfully loaded custom config");
-                        config
-                    },
-                    Err(e) => {
-                        error!("Failed to parse config file, using defaults: {}", e);
-                        TwitterConfig::default()
-                    }
-                }
-            }
-            Err(e) => {
-                error!("Failed to read config file, using defaults: {}", e);
-                TwitterConfig::default()
-            }
+        let config_str = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::file("Twitter config", &path, e))?;
+        let (config, warnings) = TwitterConfig::from_json(&config_str)
+            .map_err(|e| AppError::Config(format!("Failed to parse Twitter config {}: {}", path, e)))?;
+        for warning in &warnings {
+            warn!(path = %path, "Twitter config: {}", warning);
         }
+        debug!(warnings = warnings.len(), "Loaded custom config");
+        config
     } else {
         debug!("No config path provided, using default settings");
         TwitterConfig::default()